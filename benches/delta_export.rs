@@ -0,0 +1,72 @@
+//! Compares the delta export against the full-frame CSV and JSON exports: bytes written and
+//! readings per second, for a day of 1 Hz readings from 20 zones.
+//!
+//! ```bash
+//! cargo bench --bench delta_export
+//! ```
+
+use agri_iot_simulator::export::{CsvExporter, DataExporter, DeltaExporter, JsonExporter};
+use agri_iot_simulator::{DeviceState, SensorId, SensorReading, Tick};
+use std::cell::Cell;
+use std::hint::black_box;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::time::Instant;
+
+const ZONES: u8 = 20;
+const TICKS: u64 = 24 * 60 * 60;
+
+/// Zones drying at slightly different rates, each watered back up every six hours
+fn readings() -> Vec<SensorReading> {
+    let sensor_ids: Vec<SensorId> = (0..ZONES).map(SensorId::for_zone).collect();
+    (0..TICKS)
+        .flat_map(|tick| {
+            sensor_ids.iter().enumerate().map(move |(zone, sensor_id)| SensorReading {
+                sensor_id: sensor_id.clone(),
+                tick: Tick(tick),
+                state: DeviceState::Monitoring,
+                moisture_level: 70.0 - (tick % 21_600) as f32 * (0.0015 + zone as f32 * 0.0001),
+            })
+        })
+        .collect()
+}
+
+/// Counts the bytes written to it and keeps none, so the formats are timed without disk I/O
+struct ByteCounter(Rc<Cell<usize>>);
+
+impl Write for ByteCounter {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.set(self.0.get() + bytes.len());
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes every reading through the exporter `make` returns and prints the size and throughput
+fn run<E: DataExporter>(name: &str, readings: &[SensorReading], make: impl FnOnce(ByteCounter) -> E) -> usize {
+    let written = Rc::new(Cell::new(0));
+    let mut exporter = make(ByteCounter(Rc::clone(&written)));
+    let started = Instant::now();
+    for reading in readings {
+        exporter.write(black_box(reading)).unwrap();
+    }
+    exporter.flush().unwrap();
+    let per_second = readings.len() as f64 / started.elapsed().as_secs_f64();
+    println!("{name:>6}: {:>10} bytes, {:>8.2} M readings/s", written.get(), per_second / 1e6);
+    written.get()
+}
+
+fn main() {
+    let readings = readings();
+    let raw = readings.len() * size_of::<f32>();
+    println!("{} readings, {raw} bytes as raw f32", readings.len());
+    let csv = run("csv", &readings, |out| CsvExporter::new(out).unwrap());
+    let json = run("json", &readings, JsonExporter::new);
+    let delta = run("delta", &readings, DeltaExporter::new);
+    println!("delta is {:.1}% smaller than raw f32", 100.0 * (1.0 - delta as f64 / raw as f64));
+    println!("delta is {:.1}% smaller than csv", 100.0 * (1.0 - delta as f64 / csv as f64));
+    println!("delta is {:.1}% smaller than json", 100.0 * (1.0 - delta as f64 / json as f64));
+}
@@ -0,0 +1,13 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // The gRPC server is part of the std build only
+    if std::env::var_os("CARGO_FEATURE_STD").is_none() {
+        return Ok(());
+    }
+    // Use the bundled protoc so building doesn't require a system install
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    // The client lets library users and the integration tests call the service
+    tonic_prost_build::configure()
+        .build_client(true)
+        .compile_protos(&["proto/agri.proto"], &["proto"])?;
+    Ok(())
+}
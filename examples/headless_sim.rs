@@ -0,0 +1,23 @@
+//! Runs a four-zone simulation without the TUI and prints its report.
+//!
+//! ```bash
+//! cargo run --example headless_sim
+//! ```
+
+use agri_iot_simulator::Simulation;
+
+fn main() {
+    let mut simulation = Simulation::new(4, 30.0, 42);
+    let report = simulation.run_for(1000);
+
+    for zone in &report.final_states {
+        println!("Zone {}: {:?} at {:.1}% moisture", zone.zone_id, zone.state, zone.moisture_level);
+    }
+    println!("Water used: {:.0} L", report.total_water_used_l);
+
+    let mut transitions: Vec<_> = report.transition_counts.iter().collect();
+    transitions.sort_by_key(|&(_, count)| std::cmp::Reverse(*count));
+    for ((from, to), count) in transitions {
+        println!("{:?} -> {:?}: {}", from, to, count);
+    }
+}
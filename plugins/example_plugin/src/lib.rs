@@ -0,0 +1,73 @@
+//! Example plugin adding a leaf wetness sensor
+//!
+//! Build it with `cargo build -p example_plugin` and load it with:
+//!
+//! ```toml
+//! [[plugins]]
+//! path = "target/debug/libexample_plugin.so"
+//!
+//! [plugins.config]
+//! drying_pct_per_tick = 0.5 # Wetness lost per tick
+//! dew_interval_ticks = 86400 # Dew wets the leaves again this often
+//! ```
+
+use agri_iot_simulator::plugin::{Plugin, Sensor};
+
+/// Share of the leaf surface that is wet, in %; dries steadily and is wetted again by dew
+pub struct LeafWetnessSensor {
+    pub wetness_pct: f32,
+    pub drying_pct_per_tick: f32,
+    pub dew_interval_ticks: u64,
+    ticks: u64,
+}
+
+impl LeafWetnessSensor {
+    /// Starts with the leaves wet from the last dew
+    pub fn new(drying_pct_per_tick: f32, dew_interval_ticks: u64) -> Self {
+        Self { wetness_pct: 100.0, drying_pct_per_tick, dew_interval_ticks, ticks: 0 }
+    }
+}
+
+impl Sensor<f32> for LeafWetnessSensor {
+    fn name(&self) -> &str {
+        "Leaf wetness"
+    }
+
+    fn unit(&self) -> &str {
+        "%"
+    }
+
+    fn read(&mut self) -> f32 {
+        self.ticks += 1;
+        if self.dew_interval_ticks > 0 && self.ticks.is_multiple_of(self.dew_interval_ticks) {
+            self.wetness_pct = 100.0;
+        } else {
+            self.wetness_pct = (self.wetness_pct - self.drying_pct_per_tick).max(0.0);
+        }
+        self.wetness_pct
+    }
+}
+
+pub struct LeafWetnessPlugin;
+
+impl Plugin for LeafWetnessPlugin {
+    fn name(&self) -> &str {
+        "leaf-wetness"
+    }
+
+    fn version(&self) -> (u32, u32, u32) {
+        (0, 1, 0)
+    }
+
+    fn create_sensor(&self, config: &toml::Value) -> Box<dyn Sensor<f32>> {
+        let drying_pct_per_tick = config.get("drying_pct_per_tick").and_then(toml::Value::as_float).unwrap_or(0.5);
+        let dew_interval_ticks = config.get("dew_interval_ticks").and_then(toml::Value::as_integer).unwrap_or(86_400);
+        Box::new(LeafWetnessSensor::new(drying_pct_per_tick as f32, dew_interval_ticks.max(0) as u64))
+    }
+}
+
+/// Entry point the simulator looks up in the library
+#[no_mangle]
+pub fn agri_plugin_entry() -> Box<dyn Plugin> {
+    Box::new(LeafWetnessPlugin)
+}
@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use agri_iot_simulator::plugin::PluginLoader;
+
+/// The `cdylib` cargo built next to this test's `deps` directory
+fn library_path() -> PathBuf {
+    let exe = std::env::current_exe().expect("test executable path");
+    let profile_dir = exe.parent().and_then(|deps| deps.parent()).expect("target/<profile>/deps");
+    profile_dir.join(libloading::library_filename("example_plugin"))
+}
+
+#[test]
+fn loads_the_library_and_reads_its_sensor() {
+    let config: toml::Value = toml::from_str("drying_pct_per_tick = 10.0\ndew_interval_ticks = 3").unwrap();
+    let mut loader = PluginLoader::new();
+    let plugin = loader.load(&library_path(), &config).expect("example plugin loads");
+    assert_eq!(plugin.label(), "leaf-wetness 0.1.0");
+    assert_eq!(plugin.sensor.name(), "Leaf wetness");
+    assert_eq!(plugin.sensor.unit(), "%");
+    let readings: Vec<f32> = (0..4).map(|_| plugin.read()).collect();
+    assert_eq!(readings, [90.0, 80.0, 100.0, 90.0]);
+    assert_eq!(plugin.last_reading, Some(90.0));
+}
+
+#[test]
+fn defaults_apply_when_the_config_is_empty() {
+    let mut loader = PluginLoader::new();
+    let empty = toml::Value::Table(toml::Table::new());
+    let plugin = loader.load(&library_path(), &empty).expect("example plugin loads");
+    assert_eq!(plugin.read(), 99.5);
+}
@@ -0,0 +1,141 @@
+//! Barometric pressure probe with a three-hour pressure tendency
+
+use super::history::ReadingHistory;
+
+/// Hours the tendency is averaged over
+pub const TREND_WINDOW_HOURS: usize = 3;
+/// Tendencies smaller than this, in hPa per hour, count as steady
+pub const STABLE_TREND_HPA_PER_H: f32 = 0.3;
+/// Pressure falling faster than this, in hPa per hour, signals an approaching low and rain
+pub const RAIN_FORECAST_TREND_HPA_PER_H: f32 = -1.0;
+
+/// Which way the pressure is heading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureTrend {
+    Rising, // Anticyclone building: dry weather
+    Stable,
+    Falling, // Low approaching: rain likely
+}
+
+impl PressureTrend {
+    pub fn name(self) -> &'static str {
+        match self {
+            PressureTrend::Rising => "Rising",
+            PressureTrend::Stable => "Stable",
+            PressureTrend::Falling => "Falling",
+        }
+    }
+}
+
+/// Air pressure as last sampled, with its tendency over the last [`TREND_WINDOW_HOURS`]
+#[derive(Debug, Clone, Default)]
+pub struct BarometricPressureSensor {
+    pub pressure_hpa: f32,
+    pub trend_hpa_per_h: f32, // Mean rate of change over the window; negative when falling
+    hourly: ReadingHistory<(f32, f32), { TREND_WINDOW_HOURS + 1 }>, // (hours, hPa), one per hour
+    hours: f32, // Since the first sample
+    hours_to_next_sample: f32,
+}
+
+impl BarometricPressureSensor {
+    /// Takes a new sample `elapsed_hours` after the previous one. The pressure is logged
+    /// once an hour, and the tendency is the change across the logged hours divided by
+    /// the time they span: the rolling mean of the hourly rates.
+    pub fn record(&mut self, pressure_hpa: f32, elapsed_hours: f32) {
+        self.pressure_hpa = pressure_hpa;
+        self.hours += elapsed_hours;
+        self.hours_to_next_sample -= elapsed_hours;
+        if self.hours_to_next_sample > 0.0 {
+            return;
+        }
+        self.hours_to_next_sample = 1.0;
+        self.hourly.push((self.hours, pressure_hpa));
+        let oldest = self.hourly.iter().next();
+        if let (Some((start_h, start_hpa)), Some((end_h, end_hpa))) = (oldest, self.hourly.latest()) {
+            if end_h > start_h {
+                self.trend_hpa_per_h = (end_hpa - start_hpa) / (end_h - start_h);
+            }
+        }
+    }
+
+    pub fn trend(&self) -> PressureTrend {
+        if self.trend_hpa_per_h >= STABLE_TREND_HPA_PER_H {
+            PressureTrend::Rising
+        } else if self.trend_hpa_per_h <= -STABLE_TREND_HPA_PER_H {
+            PressureTrend::Falling
+        } else {
+            PressureTrend::Stable
+        }
+    }
+
+    /// Whether the pressure is falling fast enough to expect rain
+    pub fn rain_likely(&self) -> bool {
+        self.trend_hpa_per_h < RAIN_FORECAST_TREND_HPA_PER_H
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A sensor fed one sample an hour, the first at hour 0
+    fn hourly(pressures_hpa: &[f32]) -> BarometricPressureSensor {
+        let mut sensor = BarometricPressureSensor::default();
+        for (hour, &pressure_hpa) in pressures_hpa.iter().enumerate() {
+            sensor.record(pressure_hpa, if hour == 0 { 0.0 } else { 1.0 });
+        }
+        sensor
+    }
+
+    fn assert_trend(sensor: &BarometricPressureSensor, expected_hpa_per_h: f32) {
+        let trend = sensor.trend_hpa_per_h;
+        assert!((trend - expected_hpa_per_h).abs() < 1e-4, "{trend} != {expected_hpa_per_h}");
+    }
+
+    #[test]
+    fn falling_pressure_forecasts_rain() {
+        // 4 hPa lost over the three hours of the window
+        let sensor = hourly(&[1013.0, 1012.0, 1010.5, 1009.0]);
+        assert_trend(&sensor, -4.0 / 3.0);
+        assert_eq!(sensor.trend(), PressureTrend::Falling);
+        assert!(sensor.rain_likely());
+        // Falling, but not fast enough to expect rain
+        let sensor = hourly(&[1013.0, 1012.5, 1012.0, 1011.5]);
+        assert_trend(&sensor, -0.5);
+        assert_eq!(sensor.trend(), PressureTrend::Falling);
+        assert!(!sensor.rain_likely());
+    }
+
+    #[test]
+    fn rising_and_steady_pressure() {
+        let sensor = hourly(&[1000.0, 1001.0, 1002.0]);
+        assert_trend(&sensor, 1.0);
+        assert_eq!(sensor.trend(), PressureTrend::Rising);
+        let sensor = hourly(&[1013.0, 1013.2]);
+        assert_trend(&sensor, 0.2);
+        assert_eq!(sensor.trend(), PressureTrend::Stable);
+        assert_eq!(hourly(&[1013.0]).trend(), PressureTrend::Stable);
+    }
+
+    #[test]
+    fn trend_rolls_over_the_last_three_hours() {
+        let mut sensor = hourly(&[1020.0, 1010.0, 1010.0, 1010.0]);
+        assert_trend(&sensor, -10.0 / 3.0);
+        // The drop in the first hour leaves the window
+        sensor.record(1010.0, 1.0);
+        assert_trend(&sensor, 0.0);
+        assert_eq!(sensor.trend(), PressureTrend::Stable);
+    }
+
+    #[test]
+    fn samples_within_the_hour_are_not_logged() {
+        let mut sensor = hourly(&[1013.0]);
+        for pressure_hpa in [1012.5, 1012.0, 1011.5] {
+            sensor.record(pressure_hpa, 0.25);
+            assert_eq!(sensor.pressure_hpa, pressure_hpa);
+            assert_trend(&sensor, 0.0);
+        }
+        sensor.record(1011.0, 0.25);
+        assert_trend(&sensor, -2.0);
+    }
+}
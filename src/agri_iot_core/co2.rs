@@ -0,0 +1,14 @@
+//! Non-dispersive infrared CO2 probe
+
+/// CO2 concentration of the air as last sampled, in ppm
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CO2Sensor {
+    pub co2_ppm: f32,
+}
+
+impl CO2Sensor {
+    /// Takes a new sample
+    pub fn record(&mut self, co2_ppm: f32) {
+        self.co2_ppm = co2_ppm.max(0.0);
+    }
+}
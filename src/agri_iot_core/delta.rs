@@ -0,0 +1,194 @@
+//! Delta encoding with run-length compression for sensor time series
+//!
+//! Readings are quantized to multiples of `precision` and stored as the
+//! difference from the previous reading. Each record starts with a tag byte:
+//!
+//! | Tag                | Meaning                                              |
+//! |--------------------|------------------------------------------------------|
+//! | `0b0ddddddd`       | Delta of -64..=63 steps, packed into the tag         |
+//! | `0b10nnnnnn`       | The previous value repeated `n + 1` times (1..=64)   |
+//! | `0xC0` + `i16` LE  | Delta that needs 16 bits                             |
+//! | `0xC1` + `i16` LE  | Reset: the full quantized value, when a delta overflows |
+//!
+//! Slowly changing moisture readings mostly take one byte each, or less in
+//! runs, against four for a raw `f32`.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+const RUN_TAG: u8 = 0b1000_0000;
+const WIDE_DELTA_TAG: u8 = 0xC0;
+const RESET_TAG: u8 = 0xC1;
+const MAX_RUN: u8 = 64;
+
+/// Compresses a stream of readings pushed one at a time
+#[derive(Debug, Clone)]
+pub struct DeltaEncoder {
+    last_value: i16, // Quantized previous reading
+    precision: f32,  // Value of one quantization step, e.g. 0.01 percentage points
+    run_length: u8,  // Repeats of `last_value` not yet written
+    started: bool,
+    bytes: Vec<u8>,
+}
+
+impl DeltaEncoder {
+    /// An empty encoder; readings are rounded to the nearest multiple of `precision`
+    pub fn new(precision: f32) -> Self {
+        Self { last_value: 0, precision, run_length: 0, started: false, bytes: Vec::new() }
+    }
+
+    /// Appends one reading, clamped to what 16 bits of `precision` steps can hold
+    pub fn push(&mut self, value: f32) {
+        let quantized = libm::roundf(value / self.precision).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+        if self.started && quantized == self.last_value {
+            if self.run_length == MAX_RUN {
+                self.flush_run();
+            }
+            self.run_length += 1;
+            return;
+        }
+        self.flush_run();
+        let delta = i32::from(quantized) - i32::from(self.last_value);
+        match i16::try_from(delta) {
+            _ if !self.started => self.write_wide(RESET_TAG, quantized),
+            Ok(delta @ -64..=63) => self.bytes.push(delta as u8 & 0x7F),
+            Ok(delta) => self.write_wide(WIDE_DELTA_TAG, delta),
+            Err(_) => self.write_wide(RESET_TAG, quantized),
+        }
+        self.last_value = quantized;
+        self.started = true;
+    }
+
+    /// Bytes written so far, not counting a run still being counted
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Whether no reading has been written yet
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty() && self.run_length == 0
+    }
+
+    /// Ends the stream and returns the encoded bytes
+    pub fn finish(mut self) -> Vec<u8> {
+        self.flush_run();
+        self.bytes
+    }
+
+    fn flush_run(&mut self) {
+        if self.run_length > 0 {
+            self.bytes.push(RUN_TAG | (self.run_length - 1));
+            self.run_length = 0;
+        }
+    }
+
+    fn write_wide(&mut self, tag: u8, value: i16) {
+        self.bytes.push(tag);
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// Returned when encoded bytes are truncated or corrupt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeError {
+    pub offset: usize, // Byte where the bad record starts
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid delta-encoded record at byte {}", self.offset)
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+/// Expands bytes from [`DeltaEncoder::finish`] back into readings
+pub fn decode(bytes: &[u8], precision: f32) -> Result<Vec<f32>, DecodeError> {
+    let mut values = Vec::new();
+    let mut last: Option<i16> = None;
+    let mut offset = 0;
+    while let Some(&tag) = bytes.get(offset) {
+        let error = DecodeError { offset };
+        let wide = || bytes.get(offset + 1..offset + 3).map(|b| i16::from_le_bytes([b[0], b[1]])).ok_or(error);
+        let (value, repeats, len) = match tag {
+            0x00..=0x7F => {
+                // Sign-extend the 7-bit delta
+                let delta = ((tag << 1) as i8 >> 1) as i16;
+                (last.ok_or(error)?.wrapping_add(delta), 1, 1)
+            }
+            0x80..=0xBF => (last.ok_or(error)?, usize::from(tag & 0x3F) + 1, 1),
+            WIDE_DELTA_TAG => (last.ok_or(error)?.wrapping_add(wide()?), 1, 3),
+            RESET_TAG => (wide()?, 1, 3),
+            _ => return Err(error),
+        };
+        values.extend(core::iter::repeat_n(f32::from(value) * precision, repeats));
+        last = Some(value);
+        offset += len;
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    const PRECISION: f32 = 0.01;
+
+    fn round_trip(values: &[f32]) -> Vec<u8> {
+        let mut encoder = DeltaEncoder::new(PRECISION);
+        for &value in values {
+            encoder.push(value);
+        }
+        let bytes = encoder.finish();
+        let decoded = decode(&bytes, PRECISION).unwrap();
+        assert_eq!(decoded.len(), values.len());
+        for (tick, (&value, decoded)) in values.iter().zip(&decoded).enumerate() {
+            assert!((value - decoded).abs() <= PRECISION / 2.0 + 1e-4, "tick {tick}: {value} came back as {decoded}");
+        }
+        bytes
+    }
+
+    #[test]
+    fn monotone_sequences_round_trip() {
+        let fast: Vec<f32> = (0..1000).map(|tick| 80.0 - tick as f32 * 0.05).collect();
+        round_trip(&fast);
+        // Soil read at 1 Hz dries far slower than one step a tick, so most readings are repeats
+        let drying: Vec<f32> = (0..10_000).map(|tick| 80.0 - tick as f32 * 0.002).collect();
+        let bytes = round_trip(&drying);
+        let raw_len = drying.len() * size_of::<f32>();
+        assert!(bytes.len() * 5 <= raw_len, "{} bytes is not 80% under {raw_len}", bytes.len());
+    }
+
+    #[test]
+    fn oscillating_sequences_round_trip() {
+        let diurnal: Vec<f32> = (0..1000).map(|tick| 40.0 + 5.0 * libm::sinf(tick as f32 / 20.0)).collect();
+        round_trip(&diurnal);
+        let alternating: Vec<f32> = (0..100).map(|tick| if tick % 2 == 0 { 30.0 } else { 31.5 }).collect();
+        round_trip(&alternating);
+    }
+
+    #[test]
+    fn spike_sequences_round_trip() {
+        let mut values = vec![35.0; 500];
+        values[100] = 95.0; // A watering
+        values[101] = 0.0; // A sensor dropout
+        values[300] = -300.0; // Past what one 16-bit delta can reach, so a reset
+        round_trip(&values);
+    }
+
+    #[test]
+    fn runs_collapse_to_a_byte_per_64_repeats() {
+        let bytes = round_trip(&[50.0; 129]);
+        assert_eq!(bytes.len(), 3 + 2);
+    }
+
+    #[test]
+    fn truncated_input_is_an_error() {
+        let mut encoder = DeltaEncoder::new(PRECISION);
+        encoder.push(12.34);
+        let bytes = encoder.finish();
+        assert_eq!(decode(&bytes[..2], PRECISION), Err(DecodeError { offset: 0 }));
+        assert_eq!(decode(&[0x05], PRECISION), Err(DecodeError { offset: 0 }));
+    }
+}
@@ -0,0 +1,47 @@
+//! Fixed-capacity history of recent readings
+
+use heapless::HistoryBuffer;
+
+/// The last `N` readings, overwriting the oldest once full
+#[derive(Debug, Clone)]
+pub struct ReadingHistory<T, const N: usize> {
+    buffer: HistoryBuffer<T, N>,
+}
+
+impl<T: Copy, const N: usize> ReadingHistory<T, N> {
+    /// An empty history
+    pub const fn new() -> Self {
+        Self { buffer: HistoryBuffer::new() }
+    }
+
+    /// Records `reading`, dropping the oldest if the history is full
+    pub fn push(&mut self, reading: T) {
+        self.buffer.write(reading);
+    }
+
+    /// Number of readings held, at most `N`
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Whether nothing has been recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// The newest reading
+    pub fn latest(&self) -> Option<T> {
+        self.buffer.recent().copied()
+    }
+
+    /// Readings from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.buffer.oldest_ordered().copied()
+    }
+}
+
+impl<T: Copy, const N: usize> Default for ReadingHistory<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
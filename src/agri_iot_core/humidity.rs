@@ -0,0 +1,14 @@
+//! Relative humidity probe
+
+/// Relative humidity as last sampled, in percent
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HumiditySensor {
+    pub relative_humidity_pct: f32,
+}
+
+impl HumiditySensor {
+    /// Takes a new sample, clamped to 0-100%
+    pub fn record(&mut self, relative_humidity_pct: f32) {
+        self.relative_humidity_pct = relative_humidity_pct.clamp(0.0, 100.0);
+    }
+}
@@ -0,0 +1,14 @@
+//! Pyranometer for the solar radiation reaching the crop
+
+/// Global radiation as last sampled, in W/m²
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LightSensor {
+    pub solar_radiation_w_m2: f32,
+}
+
+impl LightSensor {
+    /// Takes a new sample
+    pub fn record(&mut self, solar_radiation_w_m2: f32) {
+        self.solar_radiation_w_m2 = solar_radiation_w_m2.max(0.0);
+    }
+}
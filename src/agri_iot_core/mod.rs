@@ -0,0 +1,39 @@
+//! Simulation core that runs without `std`, for microcontroller firmware
+//!
+//! Only `core` and `alloc` are used here; floating-point functions come from
+//! `libm` and status messages are fixed-capacity `heapless` strings.
+
+pub mod barometer;
+pub mod co2;
+pub mod delta;
+pub mod history;
+pub mod humidity;
+pub mod light;
+pub mod moisture_profile;
+pub mod ph;
+pub mod rainfall;
+pub mod root_zone;
+pub mod sensor;
+pub mod sensor_id;
+pub mod soil_temperature;
+pub mod temperature;
+pub mod tensiometer;
+pub mod tick;
+pub mod trend;
+pub mod van_genuchten;
+pub mod water_level;
+pub mod wind;
+
+pub use barometer::BarometricPressureSensor;
+pub use co2::CO2Sensor;
+pub use humidity::HumiditySensor;
+pub use light::LightSensor;
+pub use ph::PHSensor;
+pub use rainfall::RainfallSensor;
+pub use sensor_id::SensorId;
+pub use soil_temperature::SoilTemperatureSensor;
+pub use temperature::TemperatureSensor;
+pub use tensiometer::TensiometerSensor;
+pub use tick::Tick;
+pub use water_level::WaterLevelSensor;
+pub use wind::WindSensor;
@@ -0,0 +1,85 @@
+//! Soil moisture down the profile, from sensors buried at several depths
+
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use super::root_zone::{RootZoneModel, WettingFront};
+use super::sensor::SoilMoistureSensor;
+
+/// Depth over which a layer's share of surface watering and drying falls to 1/e
+pub const LAYER_DECAY_DEPTH_CM: f32 = 30.0;
+
+/// Moisture sensors at several depths under one zone, each reached by surface water only after
+/// its own wetting front's delay. They only report: the zone's own sensor runs the pump.
+#[derive(Debug)]
+pub struct MoistureProfiler {
+    pub sensors: Vec<(u8, SoilMoistureSensor)>, // (depth in cm, sensor), in profile order
+}
+
+impl MoistureProfiler {
+    /// One sensor per layer, in the order given, starting at `moisture_level`, with each tick
+    /// lasting `tick_interval`
+    pub fn new(layers: &[RootZoneModel], moisture_level: f32, tick_interval: Duration) -> Self {
+        let sensors = layers
+            .iter()
+            .map(|&layer| {
+                let mut sensor = SoilMoistureSensor::new(0.0, moisture_level)
+                    .with_wetting_front(WettingFront::new(layer, tick_interval));
+                sensor.irrigation_blocked = true;
+                (layer.depth_cm as u8, sensor)
+            })
+            .collect();
+        Self { sensors }
+    }
+
+    /// Share of surface watering and drying a layer at `depth_cm` sees, as roots and evaporation
+    /// thin out and infiltrating water spreads with depth
+    pub fn layer_share(depth_cm: u8) -> f32 {
+        libm::expf(-(depth_cm as f32) / LAYER_DECAY_DEPTH_CM)
+    }
+
+    /// Applies `moisture_points` of watering at the surface; each layer gets its share once the
+    /// wetting front arrives
+    pub fn infiltrate(&mut self, moisture_points: f32) {
+        for (depth_cm, sensor) in &mut self.sensors {
+            sensor.infiltrate(moisture_points * Self::layer_share(*depth_cm));
+        }
+    }
+
+    /// Advances every layer one tick, drying each by its share of the surface's `drop`
+    pub fn step(&mut self, drop: f32) {
+        for (depth_cm, sensor) in &mut self.sensors {
+            let new_moisture = sensor.moisture_level - drop * Self::layer_share(*depth_cm);
+            sensor.step(new_moisture);
+        }
+    }
+
+    /// Moisture at `depth_cm`, if a sensor sits there
+    pub fn moisture_at(&self, depth_cm: u8) -> Option<f32> {
+        self.sensors.iter().find(|&&(depth, _)| depth == depth_cm).map(|(_, sensor)| sensor.moisture_level)
+    }
+
+    /// Moisture of the sensor nearest `depth_cm`, if there are any
+    pub fn moisture_near(&self, depth_cm: f32) -> Option<f32> {
+        let distance = |depth: u8| libm::fabsf(depth as f32 - depth_cm);
+        self.sensors
+            .iter()
+            .min_by(|(a, _), (b, _)| distance(*a).total_cmp(&distance(*b)))
+            .map(|(_, sensor)| sensor.moisture_level)
+    }
+
+    /// Plant-available moisture as the mean of the layers weighted by `root_distribution`, the
+    /// share of roots in each layer in profile order; missing weights count as none, and
+    /// without any weight every layer counts the same
+    pub fn root_weighted_moisture(&self, root_distribution: &[f32]) -> f32 {
+        let weight = |layer: usize| root_distribution.get(layer).copied().unwrap_or(0.0).max(0.0);
+        let total_weight: f32 = (0..self.sensors.len()).map(weight).sum();
+        if total_weight <= 0.0 {
+            let total: f32 = self.sensors.iter().map(|(_, sensor)| sensor.moisture_level).sum();
+            return total / self.sensors.len().max(1) as f32;
+        }
+        let weighted: f32 =
+            self.sensors.iter().enumerate().map(|(layer, (_, sensor))| weight(layer) * sensor.moisture_level).sum();
+        weighted / total_weight
+    }
+}
@@ -0,0 +1,20 @@
+//! Glass-electrode soil pH probe
+
+/// pH of the soil solution as last sampled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PHSensor {
+    pub ph_level: f32,
+}
+
+impl Default for PHSensor {
+    fn default() -> Self {
+        Self { ph_level: 7.0 }
+    }
+}
+
+impl PHSensor {
+    /// Takes a new sample
+    pub fn record(&mut self, ph_level: f32) {
+        self.ph_level = ph_level.clamp(0.0, 14.0);
+    }
+}
@@ -0,0 +1,19 @@
+//! Tipping-bucket rain gauge
+
+/// Rain gauge that accumulates precipitation until it is read
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RainfallSensor {
+    pending_mm: f32,
+}
+
+impl RainfallSensor {
+    /// Adds `mm` of rain that fell on the gauge
+    pub fn record(&mut self, mm: f32) {
+        self.pending_mm += mm.max(0.0);
+    }
+
+    /// Precipitation since the last read, in mm; 0 when it has stayed dry
+    pub fn take_precipitation_mm(&mut self) -> f32 {
+        core::mem::take(&mut self.pending_mm)
+    }
+}
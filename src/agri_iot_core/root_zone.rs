@@ -0,0 +1,114 @@
+//! Delay between applying water at the surface and the sensor seeing it at depth
+
+use alloc::collections::VecDeque;
+use core::time::Duration;
+
+use super::tick::Tick;
+
+/// Where the sensor sits in the soil profile and how fast infiltrating water moves down
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RootZoneModel {
+    pub depth_cm: f32,
+    pub wetting_front_velocity_cm_per_h: f32,
+}
+
+impl RootZoneModel {
+    /// Simulated time the wetting front takes to reach the sensor
+    pub fn travel_time(&self) -> Duration {
+        Duration::from_secs_f32(self.depth_cm / self.wetting_front_velocity_cm_per_h * 3600.0)
+    }
+
+    /// Whole ticks the wetting front takes to reach the sensor, rounded up
+    pub fn delay_ticks(&self, tick_interval: Duration) -> Tick {
+        Tick(libm::ceil(self.travel_time().as_secs_f64() / tick_interval.as_secs_f64()) as u64)
+    }
+}
+
+/// Water applied at the surface that has not yet reached the sensor
+#[derive(Debug, Clone)]
+pub struct WettingFront {
+    delay: Tick,
+    pending: VecDeque<(Tick, f32)>, // (arrival tick, moisture percentage points)
+}
+
+impl WettingFront {
+    /// Delays water by the model's travel time, with each tick lasting `tick_interval` of simulated time
+    pub fn new(model: RootZoneModel, tick_interval: Duration) -> Self {
+        Self {
+            delay: model.delay_ticks(tick_interval),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Ticks between applying water and the sensor seeing it
+    pub fn delay(&self) -> Tick {
+        self.delay
+    }
+
+    /// Applies `moisture` percentage points at the surface on tick `now`
+    pub fn apply(&mut self, now: Tick, moisture: f32) {
+        self.pending.push_back((now + self.delay, moisture));
+    }
+
+    /// Removes and sums the water that has reached the sensor by tick `now`
+    pub fn arrived(&mut self, now: Tick) -> f32 {
+        let mut total = 0.0;
+        while let Some(&(arrival, moisture)) = self.pending.front() {
+            if arrival > now {
+                break;
+            }
+            total += moisture;
+            self.pending.pop_front();
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SoilMoistureSensor;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    /// First tick the sensor reads the water, which is applied before tick 1
+    fn ticks_until_seen(depth_cm: f32) -> u64 {
+        let model = RootZoneModel { depth_cm, wetting_front_velocity_cm_per_h: 10.0 };
+        let mut sensor = SoilMoistureSensor::new(10.0, 50.0).with_wetting_front(WettingFront::new(model, MINUTE));
+        sensor.infiltrate(15.0);
+        (1..=1_000)
+            .find(|_| {
+                sensor.step(sensor.moisture_level);
+                sensor.moisture_level > 50.0
+            })
+            .expect("water reaches the sensor")
+    }
+
+    #[test]
+    fn delay_is_depth_over_velocity() {
+        let model = RootZoneModel { depth_cm: 30.0, wetting_front_velocity_cm_per_h: 10.0 };
+        assert_eq!(model.travel_time(), Duration::from_secs(3 * 3600));
+        assert_eq!(model.delay_ticks(MINUTE), Tick(180));
+        // Part of a tick still has to be waited out
+        assert_eq!(model.delay_ticks(Duration::from_secs(7 * 60)), Tick(26));
+    }
+
+    #[test]
+    fn deep_sensors_see_irrigation_only_after_the_delay() {
+        assert_eq!(ticks_until_seen(5.0), 30);
+        assert_eq!(ticks_until_seen(30.0), 180);
+        assert_eq!(ticks_until_seen(60.0), 360);
+    }
+
+    #[test]
+    fn queued_water_arrives_in_order_and_only_once() {
+        let model = RootZoneModel { depth_cm: 10.0, wetting_front_velocity_cm_per_h: 10.0 };
+        let mut front = WettingFront::new(model, MINUTE);
+        front.apply(Tick(0), 5.0);
+        front.apply(Tick(30), 7.0);
+        assert_eq!(front.arrived(Tick(59)), 0.0);
+        assert_eq!(front.arrived(Tick(60)), 5.0);
+        assert_eq!(front.arrived(Tick(60)), 0.0);
+        assert_eq!(front.arrived(Tick(200)), 7.0);
+    }
+}
@@ -0,0 +1,288 @@
+//! Soil moisture sensor state machine
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::str::FromStr;
+
+use super::root_zone::WettingFront;
+use super::sensor_id::SensorId;
+use super::tick::Tick;
+
+/// Status text produced by a state change, sized for microcontroller RAM
+pub type StatusMessage = heapless::String<64>;
+
+/// Formats a status message, truncating anything beyond its capacity
+macro_rules! status {
+    ($($arg:tt)*) => {{
+        let mut message = StatusMessage::new();
+        let _ = write!(message, $($arg)*);
+        message
+    }};
+}
+
+/// Moisture percentage points added by one full watering cycle
+pub const WATERING_BOOST: f32 = 15.0;
+
+/// Represents the possible states of the soil moisture sensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(any(feature = "std", feature = "wasm"), derive(serde::Serialize, serde::Deserialize))]
+pub enum DeviceState {
+    Monitoring,  // Checking environmental conditions
+    Activating,  // Initiating watering
+    Adjusting,   // Watering in progress
+    Idle,        // Optimal moisture level
+    Error,       // System error
+}
+
+impl DeviceState {
+    /// Every state, in state machine order
+    pub const ALL: [DeviceState; 5] = [
+        DeviceState::Monitoring,
+        DeviceState::Activating,
+        DeviceState::Adjusting,
+        DeviceState::Idle,
+        DeviceState::Error,
+    ];
+
+    /// Plain variant name, as used in config files and machine-readable APIs
+    pub fn name(&self) -> &'static str {
+        match self {
+            DeviceState::Monitoring => "Monitoring",
+            DeviceState::Activating => "Activating",
+            DeviceState::Adjusting => "Adjusting",
+            DeviceState::Idle => "Idle",
+            DeviceState::Error => "Error",
+        }
+    }
+
+    /// Emoji shown after the name in user-facing text
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            DeviceState::Monitoring => "🔍",
+            DeviceState::Activating => "💧",
+            DeviceState::Adjusting => "🌊",
+            DeviceState::Idle => "✅",
+            DeviceState::Error => "❌",
+        }
+    }
+}
+
+impl fmt::Display for DeviceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name(), self.emoji())
+    }
+}
+
+/// Returned when a string names no [`DeviceState`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDeviceStateError(String);
+
+impl fmt::Display for ParseDeviceStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown device state {:?}", self.0)
+    }
+}
+
+impl core::error::Error for ParseDeviceStateError {}
+
+impl FromStr for DeviceState {
+    type Err = ParseDeviceStateError;
+
+    /// Accepts the variant name in any case, optionally followed by its emoji as printed by `Display`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        DeviceState::ALL
+            .into_iter()
+            .find(|state| match words[..] {
+                [name] => name.eq_ignore_ascii_case(state.name()),
+                [name, emoji] => name.eq_ignore_ascii_case(state.name()) && emoji == state.emoji(),
+                _ => false,
+            })
+            .ok_or_else(|| ParseDeviceStateError(s.to_string()))
+    }
+}
+
+/// What a sensor reported at a given tick
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorReading {
+    pub sensor_id: SensorId,
+    pub tick: Tick,
+    pub state: DeviceState,
+    pub moisture_level: f32,
+}
+
+/// Simulates a soil moisture sensor with state and animation tracking
+#[derive(Debug)]
+pub struct SoilMoistureSensor {
+    pub sensor_id: SensorId,
+    pub state: DeviceState,
+    pub moisture_level: f32, // Percentage (0.0 to 100.0)
+    pub threshold: f32,      // Water if below this level
+    pub watering_boost: f32, // Percentage points one watering cycle adds
+    pub irrigation_blocked: bool, // Keeps the pump off, e.g. while a backflow preventer is faulty
+    just_watered: bool,      // Prevents immediate moisture drop after watering
+    pub animation_frame: usize, // Tracks blinking frames (0 or 1)
+    pub current_tick: Tick,     // Steps taken since the sensor was created
+    wetting_front: Option<WettingFront>, // Delays watering until it reaches the sensor depth
+}
+
+impl SoilMoistureSensor {
+    /// Creates a new sensor with a given moisture threshold and starting moisture level
+    pub fn new(threshold: f32, moisture_level: f32) -> Self {
+        Self {
+            sensor_id: SensorId::default(),
+            state: DeviceState::Monitoring,
+            moisture_level,
+            threshold,
+            watering_boost: WATERING_BOOST,
+            irrigation_blocked: false,
+            just_watered: false,
+            animation_frame: 0,
+            current_tick: Tick::ZERO,
+            wetting_front: None,
+        }
+    }
+
+    /// Labels the sensor with `sensor_id` instead of `zone-0`
+    pub fn with_sensor_id(mut self, sensor_id: SensorId) -> Self {
+        self.sensor_id = sensor_id;
+        self
+    }
+
+    /// Makes watering reach the sensor only after the wetting front's delay
+    pub fn with_wetting_front(mut self, wetting_front: WettingFront) -> Self {
+        self.wetting_front = Some(wetting_front);
+        self
+    }
+
+    /// Adds `moisture` percentage points of water at the surface, which the sensor sees after the
+    /// wetting front's delay, or at once without one
+    pub fn infiltrate(&mut self, moisture: f32) {
+        match &mut self.wetting_front {
+            Some(front) => front.apply(self.current_tick, moisture),
+            None => self.moisture_level += moisture,
+        }
+    }
+
+    /// The sensor's state and moisture at the current tick
+    pub fn reading(&self) -> SensorReading {
+        SensorReading {
+            sensor_id: self.sensor_id.clone(),
+            tick: self.current_tick,
+            state: self.state,
+            moisture_level: self.moisture_level,
+        }
+    }
+
+    /// Transitions the sensor state based on moisture levels without waiting
+    pub fn step(&mut self, new_moisture: f32) -> Option<StatusMessage> {
+        if !self.just_watered {
+            self.moisture_level = new_moisture.max(0.0);
+        }
+        self.just_watered = false;
+        self.current_tick = self.current_tick + Tick(1);
+        if let Some(front) = &mut self.wetting_front {
+            self.moisture_level += front.arrived(self.current_tick);
+        }
+        match self.state {
+            DeviceState::Monitoring => {
+                self.animation_frame = (self.animation_frame + 1) % 2;
+                if self.moisture_level < self.threshold && !self.irrigation_blocked {
+                    self.state = DeviceState::Activating;
+                    self.animation_frame = 0;
+                    Some(status!("Moisture low ({:.1}%), activating...", self.moisture_level))
+                } else {
+                    None
+                }
+            }
+            DeviceState::Activating if self.irrigation_blocked => {
+                self.state = DeviceState::Monitoring;
+                Some(status!("Irrigation blocked, watering cancelled"))
+            }
+            DeviceState::Activating => {
+                match &mut self.wetting_front {
+                    Some(front) => {
+                        front.apply(self.current_tick, self.watering_boost);
+                        self.moisture_level += front.arrived(self.current_tick);
+                    }
+                    None => self.moisture_level += self.watering_boost,
+                }
+                self.state = DeviceState::Adjusting;
+                self.just_watered = true;
+                self.animation_frame = (self.animation_frame + 1) % 2;
+                Some(status!("Watering... Moisture now {:.1}%", self.moisture_level))
+            }
+            DeviceState::Adjusting => {
+                self.animation_frame = 0;
+                if self.moisture_level >= self.threshold + 10.0 {
+                    self.state = DeviceState::Idle;
+                    Some(status!("Moisture optimal ({:.1}%), going idle", self.moisture_level))
+                } else if self.moisture_level < self.threshold {
+                    self.state = DeviceState::Monitoring;
+                    Some(status!("Moisture still low ({:.1}%), back to monitoring", self.moisture_level))
+                } else {
+                    None
+                }
+            }
+            DeviceState::Idle => {
+                self.animation_frame = 0;
+                if self.moisture_level < self.threshold {
+                    self.state = DeviceState::Monitoring;
+                    Some(status!("Moisture dropping, back to monitoring"))
+                } else {
+                    None
+                }
+            }
+            DeviceState::Error => {
+                self.animation_frame = (self.animation_frame + 1) % 2;
+                Some(status!("Error state, no transitions"))
+            }
+        }
+    }
+
+    /// Starts a watering cycle ahead of the threshold, e.g. when a forecast says
+    /// moisture will drop below it soon. Returns whether it could start, which
+    /// needs the sensor idle or monitoring and irrigation not blocked.
+    pub fn start_watering(&mut self) -> bool {
+        let can_start = !self.irrigation_blocked && matches!(self.state, DeviceState::Monitoring | DeviceState::Idle);
+        if can_start {
+            self.state = DeviceState::Activating;
+            self.animation_frame = 0;
+        }
+        can_start
+    }
+
+    /// Whether the water pump is running (watering startup or in progress)
+    pub fn water_running(&self) -> bool {
+        matches!(self.state, DeviceState::Activating | DeviceState::Adjusting)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_is_the_name_and_emoji() {
+        let shown: Vec<String> = DeviceState::ALL.iter().map(ToString::to_string).collect();
+        assert_eq!(shown, ["Monitoring 🔍", "Activating 💧", "Adjusting 🌊", "Idle ✅", "Error ❌"]);
+    }
+
+    #[test]
+    fn every_state_round_trips_through_display_and_from_str() {
+        for state in DeviceState::ALL {
+            assert_eq!(state.to_string().parse(), Ok(state));
+            assert_eq!(state.name().parse(), Ok(state));
+            assert_eq!(state.name().to_lowercase().parse(), Ok(state));
+        }
+    }
+
+    #[test]
+    fn unknown_states_are_rejected() {
+        for text in ["", "Watering", "Idle ❌", "Idle ✅ extra"] {
+            assert_eq!(text.parse::<DeviceState>(), Err(ParseDeviceStateError(text.to_string())));
+        }
+        assert_eq!("Sleeping".parse::<DeviceState>().unwrap_err().to_string(), "unknown device state \"Sleeping\"");
+    }
+}
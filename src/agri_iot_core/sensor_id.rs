@@ -0,0 +1,168 @@
+//! Validated labels naming individual sensors
+
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::str::FromStr;
+
+/// Longest label accepted, in bytes
+const MAX_LEN: usize = 32;
+
+/// Label of one sensor, matching `^[a-z][a-z0-9_-]{0,31}$` so it is safe in
+/// MQTT topics and URL paths
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "std",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct SensorId(String);
+
+impl SensorId {
+    /// `zone-N`, the label a zone's sensor gets unless configured otherwise
+    pub fn for_zone(zone_id: u8) -> Self {
+        Self(format!("zone-{zone_id}"))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether `label` is a valid sensor id
+    pub fn is_valid(label: &str) -> bool {
+        let mut bytes = label.bytes();
+        bytes.next().is_some_and(|first| first.is_ascii_lowercase())
+            && label.len() <= MAX_LEN
+            && bytes.all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'_' || byte == b'-')
+    }
+}
+
+impl Default for SensorId {
+    fn default() -> Self {
+        Self::for_zone(0)
+    }
+}
+
+impl fmt::Display for SensorId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Returned when a label is not a valid [`SensorId`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidSensorId(String);
+
+impl fmt::Display for InvalidSensorId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid sensor id {:?}: use a lowercase letter then up to 31 lowercase letters, digits, `_` or `-`",
+            self.0
+        )
+    }
+}
+
+impl core::error::Error for InvalidSensorId {}
+
+impl FromStr for SensorId {
+    type Err = InvalidSensorId;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if Self::is_valid(s) {
+            Ok(Self(s.to_string()))
+        } else {
+            Err(InvalidSensorId(s.to_string()))
+        }
+    }
+}
+
+impl TryFrom<String> for SensorId {
+    type Error = InvalidSensorId;
+
+    fn try_from(label: String) -> Result<Self, Self::Error> {
+        if Self::is_valid(&label) {
+            Ok(Self(label))
+        } else {
+            Err(InvalidSensorId(label))
+        }
+    }
+}
+
+impl From<SensorId> for String {
+    fn from(id: SensorId) -> Self {
+        id.0
+    }
+}
+
+/// Returned when two sensors share one [`SensorId`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateSensorId(pub SensorId);
+
+impl fmt::Display for DuplicateSensorId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sensor id {} is used more than once", self.0)
+    }
+}
+
+impl core::error::Error for DuplicateSensorId {}
+
+/// Checks that no id appears twice, returning the first repeat
+pub fn ensure_unique<'a>(ids: impl IntoIterator<Item = &'a SensorId>) -> Result<(), DuplicateSensorId> {
+    let mut seen = BTreeSet::new();
+    for id in ids {
+        if !seen.insert(id) {
+            return Err(DuplicateSensorId(id.clone()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(label: &str) -> SensorId {
+        label.parse().unwrap()
+    }
+
+    #[test]
+    fn valid_labels_parse() {
+        for label in ["a", "zone-0", "north_field-3", "x1234567890123456789012345678901"] {
+            assert_eq!(id(label).as_str(), label);
+        }
+        assert_eq!(SensorId::for_zone(7), id("zone-7"));
+        assert_eq!(SensorId::default().to_string(), "zone-0");
+    }
+
+    #[test]
+    fn invalid_labels_are_rejected() {
+        let too_long = "x12345678901234567890123456789012";
+        for label in ["", "Zone-1", "1zone", "-zone", "_zone", "zone 1", "zone/1", "zoné", too_long] {
+            assert_eq!(label.parse::<SensorId>(), Err(InvalidSensorId(label.to_string())), "{label:?}");
+            assert!(SensorId::try_from(label.to_string()).is_err());
+        }
+        assert!("Zone".parse::<SensorId>().unwrap_err().to_string().starts_with("invalid sensor id \"Zone\""));
+    }
+
+    #[test]
+    fn ensure_unique_finds_the_first_repeat() {
+        let ids = [id("north"), id("south"), id("north")];
+        assert_eq!(ensure_unique(&ids), Err(DuplicateSensorId(id("north"))));
+        assert_eq!(ensure_unique(&ids[..2]), Ok(()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn zone_construction_rejects_repeated_ids() {
+        use crate::simulation::Simulation;
+
+        let ids = [id("north"), id("south"), id("north")];
+        let duplicated = Simulation::with_sensor_ids(ids.to_vec(), 30.0, 1);
+        assert_eq!(duplicated.err(), Some(DuplicateSensorId(id("north"))));
+        let simulation = Simulation::with_sensor_ids(ids[..2].to_vec(), 30.0, 1).unwrap();
+        let labels: Vec<&str> = simulation.zones.iter().map(|zone| zone.sensor.sensor_id.as_str()).collect();
+        assert_eq!(labels, ["north", "south"]);
+    }
+}
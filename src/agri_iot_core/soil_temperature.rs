@@ -0,0 +1,26 @@
+//! Soil temperature probe, which lags the air more the deeper it sits
+
+/// Hours the soil's temperature takes to follow the air's per cm of depth
+pub const LAG_HOURS_PER_CM: f32 = 0.5;
+
+/// Soil temperature at `depth_cm` as last sampled, in °C
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SoilTemperatureSensor {
+    pub depth_cm: f32,
+    pub temperature_c: f32,
+}
+
+impl SoilTemperatureSensor {
+    /// A probe at `depth_cm` in soil at `temperature_c`
+    pub fn new(depth_cm: f32, temperature_c: f32) -> Self {
+        Self { depth_cm, temperature_c }
+    }
+
+    /// Lets the soil follow air at `air_temp_c` for `hours` and returns the new reading
+    pub fn update(&mut self, air_temp_c: f32, hours: f32) -> f32 {
+        let lag_hours = self.depth_cm.max(0.0) * LAG_HOURS_PER_CM;
+        let response = if lag_hours > 0.0 { 1.0 - libm::expf(-hours.max(0.0) / lag_hours) } else { 1.0 };
+        self.temperature_c += (air_temp_c - self.temperature_c) * response;
+        self.temperature_c
+    }
+}
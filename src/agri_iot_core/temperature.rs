@@ -0,0 +1,14 @@
+//! Air temperature probe
+
+/// Air temperature as last sampled, in °C
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TemperatureSensor {
+    pub temperature_c: f32,
+}
+
+impl TemperatureSensor {
+    /// Takes a new sample
+    pub fn record(&mut self, temperature_c: f32) {
+        self.temperature_c = temperature_c;
+    }
+}
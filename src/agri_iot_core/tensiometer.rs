@@ -0,0 +1,125 @@
+//! Tensiometer reading the soil's matric potential through a porous ceramic cup
+
+use super::van_genuchten::VanGenuchten;
+
+/// Soil water status by matric potential, in the bands irrigators schedule by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TensiometerState {
+    WetSoil,               // Above -10 kPa: draining, air-poor
+    FieldCapacity,         // -10 to -33 kPa: what the soil holds after drainage
+    StressOnset,           // -33 to -100 kPa: time to irrigate
+    SevereStress,          // -100 to -1500 kPa: past a water-filled tensiometer's range
+    PermanentWiltingPoint, // Below -1500 kPa: plants can no longer take up water
+}
+
+impl TensiometerState {
+    /// Band that `matric_potential_kpa` falls in
+    pub fn from_potential(matric_potential_kpa: f32) -> Self {
+        match matric_potential_kpa {
+            kpa if kpa > -10.0 => TensiometerState::WetSoil,
+            kpa if kpa > -33.0 => TensiometerState::FieldCapacity,
+            kpa if kpa > -100.0 => TensiometerState::StressOnset,
+            kpa if kpa > -1500.0 => TensiometerState::SevereStress,
+            _ => TensiometerState::PermanentWiltingPoint,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TensiometerState::WetSoil => "wet soil",
+            TensiometerState::FieldCapacity => "field capacity",
+            TensiometerState::StressOnset => "stress onset",
+            TensiometerState::SevereStress => "severe stress",
+            TensiometerState::PermanentWiltingPoint => "permanent wilting point",
+        }
+    }
+}
+
+/// Matric potential at the cup's depth, lagging the soil's as water seeps through the ceramic
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TensiometerSensor {
+    pub depth_cm: f32,
+    pub ceramic_cup_conductance: f32, // Rate the reading closes on the soil's potential, per hour
+    pub matric_potential_kpa: f32,    // As last read; negative
+    pub retention: VanGenuchten,      // Of the soil around the cup
+}
+
+impl TensiometerSensor {
+    /// A cup at `depth_cm` in equilibrium with soil at `moisture_pct` volumetric water content
+    pub fn new(depth_cm: f32, ceramic_cup_conductance: f32, retention: VanGenuchten, moisture_pct: f32) -> Self {
+        Self {
+            depth_cm,
+            ceramic_cup_conductance,
+            matric_potential_kpa: retention.matric_potential_kpa(moisture_pct / 100.0),
+            retention,
+        }
+    }
+
+    /// Matric potential of the soil itself at `moisture_pct` volumetric water content
+    pub fn soil_potential_kpa(&self, moisture_pct: f32) -> f32 {
+        self.retention.matric_potential_kpa(moisture_pct / 100.0)
+    }
+
+    /// Lets the cup equilibrate with soil at `moisture_pct` for `hours` and returns the reading
+    pub fn update(&mut self, moisture_pct: f32, hours: f32) -> f32 {
+        let response = 1.0 - libm::expf(-self.ceramic_cup_conductance * hours.max(0.0));
+        self.matric_potential_kpa += (self.soil_potential_kpa(moisture_pct) - self.matric_potential_kpa) * response;
+        self.matric_potential_kpa
+    }
+
+    pub fn state(&self) -> TensiometerState {
+        TensiometerState::from_potential(self.matric_potential_kpa)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::van_genuchten::CM_PER_KPA;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn potential_matches_van_genuchten_for_loam() {
+        // psi = -((Se^(-1/m) - 1)^(1/n) / alpha) / 10.197, with Carsel and Parrish loam
+        let cup = TensiometerSensor::new(30.0, 1.0, VanGenuchten::loam(), 30.0);
+        for (moisture_pct, expected_kpa) in [(40.0, -1.2091), (30.0, -5.0402), (20.0, -17.4599), (12.0, -121.12)] {
+            assert_close(cup.soil_potential_kpa(moisture_pct), expected_kpa, 1e-3 * -expected_kpa);
+        }
+        assert_close(cup.matric_potential_kpa, -5.0402, 5e-3);
+    }
+
+    #[test]
+    fn band_edges_fall_at_the_tabulated_moisture() {
+        // Water content the loam holds at each band edge, from theta(h) at h = kPa × 10.197 cm
+        let cup = TensiometerSensor::new(30.0, 1.0, VanGenuchten::loam(), 30.0);
+        for (moisture_pct, edge_kpa) in [(24.0558, -10.0), (16.4447, -33.0), (12.4742, -100.0), (8.8272, -1500.0)] {
+            assert_close(cup.soil_potential_kpa(moisture_pct), edge_kpa, 2e-3 * -edge_kpa);
+            let water_content_pct = VanGenuchten::loam().water_content(-edge_kpa * CM_PER_KPA) * 100.0;
+            assert_close(water_content_pct, moisture_pct, 1e-4 * moisture_pct);
+        }
+    }
+
+    #[test]
+    fn states_follow_the_potential_bands() {
+        let state = |moisture_pct| TensiometerSensor::new(30.0, 1.0, VanGenuchten::loam(), moisture_pct).state();
+        assert_eq!(state(30.0), TensiometerState::WetSoil);
+        assert_eq!(state(20.0), TensiometerState::FieldCapacity);
+        assert_eq!(state(14.0), TensiometerState::StressOnset);
+        assert_eq!(state(10.0), TensiometerState::SevereStress);
+        assert_eq!(state(8.5), TensiometerState::PermanentWiltingPoint);
+        assert_eq!(TensiometerState::from_potential(-10.0), TensiometerState::FieldCapacity);
+        assert_eq!(TensiometerState::from_potential(-33.0), TensiometerState::StressOnset);
+    }
+
+    #[test]
+    fn reading_lags_the_soil_through_the_cup() {
+        let mut cup = TensiometerSensor::new(30.0, 0.5, VanGenuchten::loam(), 30.0);
+        let (start, target) = (cup.matric_potential_kpa, cup.soil_potential_kpa(20.0));
+        // After 2 h at 0.5 per hour the reading has closed 1 - e^-1 of the gap
+        let reading = cup.update(20.0, 2.0);
+        assert_close(reading, start + (target - start) * (1.0 - libm::expf(-1.0)), 1e-4 * -target);
+        assert_eq!(cup.update(20.0, 0.0), reading);
+        cup.update(20.0, 100.0);
+        assert_close(cup.matric_potential_kpa, target, 1e-4 * -target);
+    }
+}
@@ -0,0 +1,79 @@
+//! Simulation time measured in ticks rather than wall-clock instants
+
+use core::fmt;
+use core::ops::{Add, Sub};
+use core::time::Duration;
+
+/// Number of state machine steps since a sensor or simulation started
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tick(pub u64);
+
+impl Tick {
+    /// The first tick of a run
+    pub const ZERO: Tick = Tick(0);
+
+    /// Simulated time elapsed by this tick when each tick lasts `tick_interval`
+    pub fn to_simulated_duration(self, tick_interval: Duration) -> Duration {
+        let nanos = tick_interval.as_nanos() * u128::from(self.0);
+        Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+    }
+}
+
+impl From<u64> for Tick {
+    fn from(tick: u64) -> Self {
+        Tick(tick)
+    }
+}
+
+impl Add for Tick {
+    type Output = Tick;
+
+    fn add(self, rhs: Tick) -> Tick {
+        Tick(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Tick {
+    type Output = Tick;
+
+    fn sub(self, rhs: Tick) -> Tick {
+        Tick(self.0 - rhs.0)
+    }
+}
+
+impl fmt::Display for Tick {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tick {}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn arithmetic_and_ordering() {
+        assert_eq!(Tick(3) + Tick(4), Tick(7));
+        assert_eq!(Tick(10) - Tick(4), Tick(6));
+        assert!(Tick(1) < Tick(2));
+        assert_eq!(Tick::from(9), Tick(9));
+        assert_eq!(Tick::default(), Tick::ZERO);
+    }
+
+    #[test]
+    fn displays_as_a_tick_number() {
+        assert_eq!(Tick(42).to_string(), "tick 42");
+    }
+
+    #[test]
+    fn simulated_duration_scales_with_the_interval() {
+        assert_eq!(Tick::ZERO.to_simulated_duration(Duration::from_secs(60)), Duration::ZERO);
+        assert_eq!(Tick(90).to_simulated_duration(Duration::from_secs(1)), Duration::from_secs(90));
+        assert_eq!(Tick(3).to_simulated_duration(Duration::from_millis(400)), Duration::from_millis(1_200));
+        // A year of one-minute ticks
+        let minutes = 365 * 24 * 60;
+        assert_eq!(Tick(minutes).to_simulated_duration(Duration::from_secs(60)), Duration::from_secs(minutes * 60));
+    }
+}
@@ -0,0 +1,135 @@
+//! Moisture trend detection by least-squares regression over recent readings
+
+use core::time::Duration;
+
+use super::history::ReadingHistory;
+
+/// Readings the regression is fitted over
+pub const TREND_WINDOW: usize = 30;
+/// Slopes smaller than this, in percentage points per hour, count as stable
+pub const STABLE_SLOPE_PCT_PER_H: f32 = 0.05;
+/// Slopes at least this steep, in percentage points per hour, are shown as steep arrows
+pub const STEEP_SLOPE_PCT_PER_H: f32 = 0.5;
+
+/// Direction moisture is heading, with the slope in percentage points per hour
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trend {
+    Rising(f32),
+    Falling(f32), // Slope is negative
+    Stable,
+}
+
+impl Trend {
+    /// Slope in percentage points per hour; zero when stable
+    pub fn slope(self) -> f32 {
+        match self {
+            Trend::Rising(slope) | Trend::Falling(slope) => slope,
+            Trend::Stable => 0.0,
+        }
+    }
+
+    /// Whether the slope is at least [`STEEP_SLOPE_PCT_PER_H`]
+    pub fn is_steep(self) -> bool {
+        self.slope().abs() >= STEEP_SLOPE_PCT_PER_H
+    }
+
+    /// Hours until moisture at `current` reaches `level` if the trend holds,
+    /// or `None` if it is heading away from `level`
+    pub fn hours_to_reach(self, current: f32, level: f32) -> Option<f32> {
+        match self {
+            Trend::Falling(slope) if current > level => Some((current - level) / -slope),
+            Trend::Rising(slope) if current < level => Some((level - current) / slope),
+            _ => None,
+        }
+    }
+}
+
+/// Fits a line through the last [`TREND_WINDOW`] moisture readings
+#[derive(Debug, Clone)]
+pub struct TrendDetector {
+    history: ReadingHistory<f32, TREND_WINDOW>,
+    tick_hours: f32, // Simulated hours between readings
+}
+
+impl TrendDetector {
+    /// A detector for readings taken every `tick_interval`
+    pub fn new(tick_interval: Duration) -> Self {
+        Self { history: ReadingHistory::new(), tick_hours: tick_interval.as_secs_f32() / 3600.0 }
+    }
+
+    /// Adds a reading; call once per tick
+    pub fn record(&mut self, moisture_level: f32) {
+        self.history.push(moisture_level);
+    }
+
+    /// The trend given by the ordinary least-squares slope; stable until two readings exist
+    pub fn detect(&self) -> Trend {
+        let n = self.history.len() as f32;
+        if self.history.len() < 2 || self.tick_hours <= 0.0 {
+            return Trend::Stable;
+        }
+        let mean_x = (n - 1.0) / 2.0;
+        let mean_y = self.history.iter().sum::<f32>() / n;
+        let (covariance, variance) = self.history.iter().enumerate().fold((0.0, 0.0), |(cov, var), (i, y)| {
+            let dx = i as f32 - mean_x;
+            (cov + dx * (y - mean_y), var + dx * dx)
+        });
+        let slope = covariance / variance / self.tick_hours;
+        if slope.abs() < STABLE_SLOPE_PCT_PER_H {
+            Trend::Stable
+        } else if slope > 0.0 {
+            Trend::Rising(slope)
+        } else {
+            Trend::Falling(slope)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A detector fed `readings` taken a minute apart
+    fn detector(readings: impl IntoIterator<Item = f32>) -> TrendDetector {
+        let mut detector = TrendDetector::new(Duration::from_secs(60));
+        for reading in readings {
+            detector.record(reading);
+        }
+        detector
+    }
+
+    #[test]
+    fn rising_readings_give_the_slope_per_hour() {
+        // 0.1 points a minute is 6 points an hour
+        let Trend::Rising(slope) = detector((0..30).map(|i| 40.0 + 0.1 * i as f32)).detect() else {
+            panic!("not rising");
+        };
+        assert!((slope - 6.0).abs() < 1e-3, "{slope}");
+    }
+
+    #[test]
+    fn falling_readings_give_a_negative_slope() {
+        let trend = detector((0..30).map(|i| 60.0 - 0.02 * i as f32)).detect();
+        let Trend::Falling(slope) = trend else { panic!("{trend:?}") };
+        assert!((slope + 1.2).abs() < 1e-3, "{slope}");
+        assert!(trend.is_steep());
+        assert!((trend.hours_to_reach(36.0, 30.0).unwrap() - 5.0).abs() < 1e-3);
+        assert_eq!(trend.hours_to_reach(25.0, 30.0), None);
+    }
+
+    #[test]
+    fn flat_or_noisy_readings_are_stable() {
+        assert_eq!(detector([]).detect(), Trend::Stable);
+        assert_eq!(detector([50.0]).detect(), Trend::Stable);
+        assert_eq!(detector([50.0; 30]).detect(), Trend::Stable);
+        // Alternating jitter around a level has no slope
+        assert_eq!(detector((0..30).map(|i| if i % 2 == 0 { 50.1 } else { 49.9 })).detect(), Trend::Stable);
+    }
+
+    #[test]
+    fn only_the_latest_window_counts() {
+        // A long fall followed by a full window of steady readings
+        let detector = detector((0..60).map(|i| if i < 30 { 80.0 - i as f32 } else { 40.0 }));
+        assert_eq!(detector.detect(), Trend::Stable);
+    }
+}
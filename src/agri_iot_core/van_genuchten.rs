@@ -0,0 +1,50 @@
+//! van Genuchten (1980) soil water retention curve
+
+/// Height of a water column exerting 1 kPa, in cm
+pub const CM_PER_KPA: f32 = 10.197;
+
+/// Lowest effective saturation [`VanGenuchten::suction_cm`] works from, which keeps the suction
+/// finite as the soil dries to its residual water content
+const MIN_EFFECTIVE_SATURATION: f32 = 1e-4;
+
+/// Parameters of the retention curve `theta(h) = theta_r + (theta_s - theta_r) / (1 + (alpha * h)^n)^(1 - 1/n)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VanGenuchten {
+    pub theta_r: f32,      // Residual volumetric water content
+    pub theta_s: f32,      // Saturated volumetric water content
+    pub alpha_per_cm: f32, // Inverse of the air-entry suction
+    pub n: f32,            // Pore-size distribution index, > 1
+}
+
+impl VanGenuchten {
+    /// Loam from Carsel and Parrish (1988)
+    pub fn loam() -> Self {
+        Self {
+            theta_r: 0.078,
+            theta_s: 0.43,
+            alpha_per_cm: 0.036,
+            n: 1.56,
+        }
+    }
+
+    /// Volumetric water content held at `suction_cm` of matric suction
+    pub fn water_content(&self, suction_cm: f32) -> f32 {
+        let m = 1.0 - 1.0 / self.n;
+        let effective_saturation = libm::powf(1.0 + libm::powf(self.alpha_per_cm * suction_cm.max(0.0), self.n), -m);
+        self.theta_r + (self.theta_s - self.theta_r) * effective_saturation
+    }
+
+    /// Matric suction in cm at which the soil holds `water_content`, the inverse of
+    /// [`Self::water_content`]; 0 at or above saturation
+    pub fn suction_cm(&self, water_content: f32) -> f32 {
+        let m = 1.0 - 1.0 / self.n;
+        let effective_saturation = ((water_content - self.theta_r) / (self.theta_s - self.theta_r))
+            .clamp(MIN_EFFECTIVE_SATURATION, 1.0);
+        libm::powf(libm::powf(effective_saturation, -1.0 / m) - 1.0, 1.0 / self.n) / self.alpha_per_cm
+    }
+
+    /// Matric potential in kPa, negative as in soil physics, at which the soil holds `water_content`
+    pub fn matric_potential_kpa(&self, water_content: f32) -> f32 {
+        -self.suction_cm(water_content) / CM_PER_KPA
+    }
+}
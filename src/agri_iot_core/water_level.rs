@@ -0,0 +1,37 @@
+//! Level sensor on a water storage tank
+
+/// Tank fill level as a float switch or pressure transducer would report it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaterLevelSensor {
+    pub capacity_liters: f32,
+    pub current_volume_liters: f32,
+}
+
+impl WaterLevelSensor {
+    /// An empty tank holding up to `capacity_liters`
+    pub fn new(capacity_liters: f32) -> Self {
+        Self { capacity_liters, current_volume_liters: 0.0 }
+    }
+
+    /// Fill level as a percentage of capacity
+    pub fn level_pct(&self) -> f32 {
+        if self.capacity_liters <= 0.0 {
+            return 0.0;
+        }
+        self.current_volume_liters / self.capacity_liters * 100.0
+    }
+
+    /// Adds up to `liters` and returns what overflowed
+    pub fn fill(&mut self, liters: f32) -> f32 {
+        let stored = liters.min(self.capacity_liters - self.current_volume_liters).max(0.0);
+        self.current_volume_liters += stored;
+        liters - stored
+    }
+
+    /// Takes up to `liters` out and returns what the tank could give
+    pub fn draw(&mut self, liters: f32) -> f32 {
+        let drawn = liters.clamp(0.0, self.current_volume_liters);
+        self.current_volume_liters -= drawn;
+        drawn
+    }
+}
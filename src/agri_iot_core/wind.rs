@@ -0,0 +1,17 @@
+//! Cup anemometer and wind vane
+
+/// Wind as last sampled
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WindSensor {
+    pub speed_m_s: f32,
+    pub direction_deg: f32, // Where the wind blows from, clockwise from north
+}
+
+impl WindSensor {
+    /// Takes a new sample; negative speeds read as calm and the direction wraps to 0-360°
+    pub fn record(&mut self, speed_m_s: f32, direction_deg: f32) {
+        self.speed_m_s = speed_m_s.max(0.0);
+        let direction_deg = libm::fmodf(direction_deg, 360.0);
+        self.direction_deg = if direction_deg < 0.0 { direction_deg + 360.0 } else { direction_deg };
+    }
+}
@@ -0,0 +1,189 @@
+//! Discord alerts posted through a channel webhook as embeds.
+
+use serde_json::{json, Value};
+
+use super::{unix_timestamp, Acknowledgements};
+use crate::event_bus::Subscriber;
+use crate::events::{AgriEvent, AlertSeverity};
+
+/// Discord rejects embeds with more fields than this
+const MAX_EMBED_FIELDS: usize = 25;
+
+/// Discord rejects messages with more embeds than this
+const MAX_EMBEDS_PER_MESSAGE: usize = 10;
+
+/// Posts alerts to a Discord channel, batching simultaneous ones into as few embeds as Discord allows
+pub struct DiscordAlerter {
+    webhook_url: String,
+    client: reqwest::Client,
+    acknowledgements: Acknowledgements,
+}
+
+impl DiscordAlerter {
+    /// Creates an alerter posting to `webhook_url`
+    pub fn new(webhook_url: String) -> Self {
+        Self { webhook_url, client: reqwest::Client::new(), acknowledgements: Acknowledgements::new() }
+    }
+
+    /// Whether `event` should be posted: a warning or worse that no operator has acknowledged
+    fn is_alert(&mut self, event: &AgriEvent) -> bool {
+        !self.acknowledgements.silences(event) && event.severity() >= AlertSeverity::Warning
+    }
+
+    /// Builds one embed for `chunk`, part `part` of `parts`, colored by its most severe event
+    fn embed(chunk: &[AgriEvent], part: usize, parts: usize, timestamp: u64) -> Value {
+        let severity = chunk.iter().map(AgriEvent::severity).max().unwrap_or(AlertSeverity::Info);
+        let color = match severity {
+            AlertSeverity::Critical => 0xE0_2B_2B,
+            AlertSeverity::Warning => 0xF2_8C_28,
+            AlertSeverity::Info => 0x3B_A5_5C,
+        };
+        let (mut title, description) = match chunk {
+            [event] => (
+                format!("{} in zone {}", event.title(), event.zone_id()),
+                format!("{:?} severity", event.severity()),
+            ),
+            _ => (format!("{} alerts", chunk.len()), format!("Highest severity: {:?}", severity)),
+        };
+        if parts > 1 {
+            title.push_str(&format!(" ({part}/{parts})"));
+        }
+        let fields: Vec<Value> = chunk
+            .iter()
+            .map(|event| {
+                json!({
+                    "name": format!("Zone {} · {}", event.zone_id(), event.title()),
+                    "value": format!(
+                        "Moisture: {:.1}%\nState: {:?}\nTime: <t:{}:T>",
+                        event.moisture_level(),
+                        event.state(),
+                        timestamp
+                    ),
+                })
+            })
+            .collect();
+        json!({
+            "title": title,
+            "description": description,
+            "color": color,
+            "fields": fields,
+        })
+    }
+
+    /// Builds the messages for `batch`: one embed per [`MAX_EMBED_FIELDS`] events, and one message
+    /// per [`MAX_EMBEDS_PER_MESSAGE`] embeds, so no event is dropped
+    fn payloads(batch: &[AgriEvent], timestamp: u64) -> Vec<Value> {
+        let parts = batch.len().div_ceil(MAX_EMBED_FIELDS);
+        let embeds: Vec<Value> = batch
+            .chunks(MAX_EMBED_FIELDS)
+            .enumerate()
+            .map(|(index, chunk)| Self::embed(chunk, index + 1, parts, timestamp))
+            .collect();
+        embeds.chunks(MAX_EMBEDS_PER_MESSAGE).map(|embeds| json!({ "embeds": embeds })).collect()
+    }
+
+    /// Posts alerts from `events` until the simulation shuts down
+    pub async fn run(mut self, mut events: Subscriber) {
+        while let Some(event) = events.recv().await {
+            // Events published during the same tick are already queued; send them together
+            let mut batch = vec![event];
+            while let Some(event) = events.try_recv() {
+                batch.push(event);
+            }
+            batch.retain(|event| self.is_alert(event));
+            for payload in Self::payloads(&batch, unix_timestamp()) {
+                // Delivery failures cannot be reported while the TUI owns the terminal
+                let _ = self.client.post(&self.webhook_url).json(&payload).send().await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_bus::EventBus;
+    use crate::DeviceState;
+    use mockito::Matcher;
+
+    fn sensor_error(zone_id: u8) -> AgriEvent {
+        AgriEvent::SensorError { zone_id, moisture_level: 12.34, state: DeviceState::Error }
+    }
+
+    fn still_low(zone_id: u8) -> AgriEvent {
+        AgriEvent::MoistureStillLow { zone_id, moisture_level: 20.0, state: DeviceState::Monitoring }
+    }
+
+    #[test]
+    fn single_alert_embed_is_titled_and_colored_by_severity() {
+        let payloads = DiscordAlerter::payloads(&[sensor_error(1)], 1_700_000_000);
+        assert_eq!(payloads.len(), 1);
+        let embed = &payloads[0]["embeds"][0];
+        assert_eq!(embed["title"], "Sensor error in zone 1");
+        assert_eq!(embed["description"], "Critical severity");
+        assert_eq!(embed["color"], 0xE0_2B_2B);
+        assert_eq!(embed["fields"][0]["name"], "Zone 1 · Sensor error");
+        assert_eq!(embed["fields"][0]["value"], "Moisture: 12.3%\nState: Error\nTime: <t:1700000000:T>");
+        let warning = DiscordAlerter::payloads(&[still_low(0)], 0);
+        assert_eq!(warning[0]["embeds"][0]["color"], 0xF2_8C_28);
+    }
+
+    #[test]
+    fn simultaneous_alerts_share_an_embed() {
+        let payloads = DiscordAlerter::payloads(&[still_low(0), sensor_error(1), still_low(2)], 0);
+        let embeds = payloads[0]["embeds"].as_array().unwrap();
+        assert_eq!(embeds.len(), 1);
+        assert_eq!(embeds[0]["title"], "3 alerts");
+        assert_eq!(embeds[0]["description"], "Highest severity: Critical");
+        assert_eq!(embeds[0]["color"], 0xE0_2B_2B);
+        assert_eq!(embeds[0]["fields"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn large_batches_are_split_within_discord_limits() {
+        let batch: Vec<AgriEvent> = (0..=255).map(still_low).chain((0..4).map(sensor_error)).collect();
+        let payloads = DiscordAlerter::payloads(&batch, 0);
+        // 260 events need 11 embeds of at most 25 fields, so two messages
+        assert_eq!(payloads.len(), 2);
+        let embeds: Vec<&Value> = payloads.iter().flat_map(|payload| payload["embeds"].as_array().unwrap()).collect();
+        assert_eq!(embeds.len(), 11);
+        assert_eq!(payloads[0]["embeds"].as_array().unwrap().len(), MAX_EMBEDS_PER_MESSAGE);
+        assert!(embeds.iter().all(|embed| embed["fields"].as_array().unwrap().len() <= MAX_EMBED_FIELDS));
+        let fields: usize = embeds.iter().map(|embed| embed["fields"].as_array().unwrap().len()).sum();
+        assert_eq!(fields, batch.len());
+        assert_eq!(embeds[0]["title"], "25 alerts (1/11)");
+        assert_eq!(embeds[10]["title"], "10 alerts (11/11)");
+    }
+
+    #[tokio::test]
+    async fn queued_alerts_are_posted_in_one_message() {
+        let mut server = mockito::Server::new_async().await;
+        let webhook = server
+            .mock("POST", "/api/webhooks/1/token")
+            .match_header("content-type", "application/json")
+            .match_body(Matcher::PartialJson(json!({
+                "embeds": [{
+                    "title": "2 alerts",
+                    "color": 0xE0_2B_2B,
+                    "fields": [{ "name": "Zone 0 · Moisture still low" }, { "name": "Zone 1 · Sensor error" }],
+                }],
+            })))
+            .with_status(204)
+            .expect(1)
+            .create_async()
+            .await;
+        let other_posts = server.mock("POST", Matcher::Any).expect(0).create_async().await;
+
+        let bus = EventBus::new(16);
+        let events = bus.subscribe();
+        bus.publish(still_low(0));
+        // Info events are not alerts
+        bus.publish(AgriEvent::WateringStarted { zone_id: 3, moisture_level: 25.0, state: DeviceState::Activating });
+        bus.publish(sensor_error(1));
+        drop(bus);
+        DiscordAlerter::new(format!("{}/api/webhooks/1/token", server.url())).run(events).await;
+
+        webhook.assert_async().await;
+        other_posts.assert_async().await;
+    }
+}
@@ -0,0 +1,195 @@
+//! Email alerts sent over SMTP for the events an operator must act on.
+
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::error::Error;
+use std::mem::{self, Discriminant};
+use tokio::time::{Duration, Instant};
+
+use super::{Acknowledgements, Cooldown};
+use crate::event_bus::Subscriber;
+use crate::events::AgriEvent;
+
+/// Minimum time between two emails for the same event type
+const RATE_LIMIT: Duration = Duration::from_secs(60 * 60);
+
+/// SMTP relay and recipients for alert emails
+#[derive(Debug, Clone)]
+pub struct AlertConfig {
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub from: String,
+    pub to: Vec<String>,
+    pub username: String, // Empty to connect without authentication
+    pub password: String,
+}
+
+/// Whether `event` is worth an email: a sensor failure, a backflow fault shutting a zone off, frost,
+/// a used-up water budget or maintenance falling due
+pub fn is_emailed(event: &AgriEvent) -> bool {
+    matches!(
+        event,
+        AgriEvent::SensorError { .. }
+            | AgriEvent::BackflowFault { .. }
+            | AgriEvent::FrostAlert { .. }
+            | AgriEvent::BudgetExceeded { .. }
+            | AgriEvent::MaintenanceWarning { .. }
+    )
+}
+
+/// Emails the events [`is_emailed`] picks, at most once per event type per hour
+pub struct EmailAlerter {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    cooldown: Cooldown<Discriminant<AgriEvent>>,
+    acknowledgements: Acknowledgements,
+}
+
+impl EmailAlerter {
+    /// Validates the addresses and prepares a STARTTLS connection to the relay
+    pub fn new(config: AlertConfig) -> Result<Self, Box<dyn Error>> {
+        let mut transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)?
+            .port(config.smtp_port);
+        if !config.username.is_empty() {
+            transport = transport.credentials(Credentials::new(config.username, config.password));
+        }
+        Ok(Self {
+            transport: transport.build(),
+            from: config.from.parse()?,
+            to: config.to.iter().map(|to| to.parse()).collect::<Result<_, _>>()?,
+            cooldown: Cooldown::new(RATE_LIMIT),
+            acknowledgements: Acknowledgements::new(),
+        })
+    }
+
+    /// Formats `event` as a plain-text email to every recipient
+    fn build_message(&self, event: &AgriEvent) -> Result<Message, lettre::error::Error> {
+        let subject = format!("[Agri-IoT] {} in zone {}", event.title(), event.zone_id());
+        let body = format!(
+            "Zone: {}\nState: {:?}\nMoisture: {:.1}%\n",
+            event.zone_id(),
+            event.state(),
+            event.moisture_level()
+        );
+        let mut builder = Message::builder().from(self.from.clone()).subject(subject);
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+        builder.body(body)
+    }
+
+    /// Emails the events [`is_emailed`] picks from `events` until the simulation shuts down
+    pub async fn run(mut self, mut events: Subscriber) {
+        while let Some(event) = events.recv().await {
+            if self.acknowledgements.silences(&event) {
+                continue;
+            }
+            if !is_emailed(&event) || !self.cooldown.try_acquire(mem::discriminant(&event), Instant::now()) {
+                continue;
+            }
+            // Delivery failures cannot be reported while the TUI owns the terminal
+            if let Ok(message) = self.build_message(&event) {
+                let _ = self.transport.send(message).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_bus::EventBus;
+    use crate::DeviceState;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// Speaks just enough SMTP to accept mail, keeping every message's DATA
+    async fn mock_smtp() -> (u16, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let received = Arc::clone(&messages);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                writer.write_all(b"220 mock ESMTP\r\n").await.unwrap();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let reply: &[u8] = match line.split(' ').next().unwrap_or_default() {
+                        "EHLO" => b"250 mock\r\n",
+                        "DATA" => {
+                            writer.write_all(b"354 end with .\r\n").await.unwrap();
+                            let mut data = Vec::new();
+                            while let Ok(Some(line)) = lines.next_line().await {
+                                if line == "." {
+                                    break;
+                                }
+                                data.push(line);
+                            }
+                            received.lock().unwrap().push(data.join("\n"));
+                            b"250 queued\r\n"
+                        }
+                        "QUIT" => b"221 bye\r\n",
+                        _ => b"250 ok\r\n",
+                    };
+                    writer.write_all(reply).await.unwrap();
+                }
+            }
+        });
+        (port, messages)
+    }
+
+    /// An alerter for the mock relay, which speaks plain SMTP without STARTTLS
+    fn alerter(port: u16) -> EmailAlerter {
+        EmailAlerter {
+            transport: AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous("127.0.0.1").port(port).build(),
+            from: "sim@farm.example".parse().unwrap(),
+            to: vec!["ops@farm.example".parse().unwrap(), "agronomist@farm.example".parse().unwrap()],
+            cooldown: Cooldown::new(RATE_LIMIT),
+            acknowledgements: Acknowledgements::new(),
+        }
+    }
+
+    fn frost(zone_id: u8) -> AgriEvent {
+        AgriEvent::FrostAlert { zone_id, moisture_level: 41.25, state: DeviceState::Activating, air_temp_c: -1.5 }
+    }
+
+    #[test]
+    fn only_operator_events_are_emailed() {
+        assert!(is_emailed(&frost(0)));
+        assert!(is_emailed(&AgriEvent::BudgetExceeded {
+            zone_id: 0,
+            moisture_level: 30.0,
+            state: DeviceState::Monitoring,
+            used_mm: 510.0,
+            limit_mm: 500.0,
+        }));
+        let watering =
+            AgriEvent::WateringStarted { zone_id: 0, moisture_level: 25.0, state: DeviceState::Activating };
+        assert!(!is_emailed(&watering));
+    }
+
+    #[tokio::test]
+    async fn email_reaches_the_relay_in_the_alert_format() {
+        let (port, messages) = mock_smtp().await;
+        let bus = EventBus::new(16);
+        let events = bus.subscribe();
+        bus.publish(AgriEvent::WateringStarted { zone_id: 1, moisture_level: 25.0, state: DeviceState::Activating });
+        bus.publish(frost(2));
+        // Within the hour another frost alert is not emailed, even for another zone
+        bus.publish(frost(3));
+        drop(bus);
+        alerter(port).run(events).await;
+
+        let messages = messages.lock().unwrap();
+        assert_eq!(messages.len(), 1, "{messages:?}");
+        let message = &messages[0];
+        assert!(message.contains("From: sim@farm.example"));
+        assert!(message.contains("To: ops@farm.example, agronomist@farm.example"));
+        assert!(message.contains("Subject: [Agri-IoT] Frost alert in zone 2"));
+        assert!(message.contains("Zone: 2\nState: Activating\nMoisture: 41.2%"));
+    }
+}
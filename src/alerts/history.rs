@@ -0,0 +1,267 @@
+//! Record of every alert raised and whether an operator has acknowledged it
+//!
+//! Alerts are events of warning severity or above. With a database they survive restarts: the
+//! history is kept in SQLite and each change is written as it is made.
+
+use rusqlite::types::Type;
+use rusqlite::{params, Connection};
+use std::collections::VecDeque;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::events::{AgriEvent, AlertSeverity};
+use crate::Tick;
+
+/// Alerts kept by default; the oldest acknowledged ones go first beyond this
+pub const DEFAULT_CAPACITY: usize = 100;
+
+/// One alert as raised
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub id: Uuid,
+    pub severity: AlertSeverity,
+    pub message: String,
+    pub zone: u8,
+    pub fired_at: Tick,
+    pub acknowledged: bool,
+    pub acknowledged_at: Option<Tick>,
+}
+
+impl Alert {
+    /// Marks the alert acknowledged at `tick`; acknowledging it again changes nothing
+    pub fn acknowledge(&mut self, tick: Tick) {
+        if !self.acknowledged {
+            self.acknowledged = true;
+            self.acknowledged_at = Some(tick);
+        }
+    }
+}
+
+/// Alerts oldest first, stored in `store` when there is one
+#[derive(Debug)]
+pub struct AlertHistory {
+    pub alerts: VecDeque<Alert>,
+    pub capacity: usize,
+    store: Option<Connection>,
+}
+
+impl Default for AlertHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl AlertHistory {
+    /// An empty history kept only in memory
+    pub fn new(capacity: usize) -> Self {
+        Self { alerts: VecDeque::new(), capacity, store: None }
+    }
+
+    /// Opens the database at `db_path`, creating it if needed, and picks up the alerts an
+    /// earlier run left there
+    pub fn open(db_path: &Path, capacity: usize) -> rusqlite::Result<Self> {
+        let connection = Connection::open(db_path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS alerts (
+                id TEXT PRIMARY KEY,
+                severity TEXT NOT NULL,
+                message TEXT NOT NULL,
+                zone INTEGER NOT NULL,
+                fired_at INTEGER NOT NULL,
+                acknowledged_at INTEGER
+            )",
+        )?;
+        let alerts = connection
+            .prepare("SELECT id, severity, message, zone, fired_at, acknowledged_at FROM alerts ORDER BY rowid")?
+            .query_map([], |row| {
+                let id: String = row.get(0)?;
+                let id = Uuid::parse_str(&id)
+                    .map_err(|error| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(error)))?;
+                let severity: String = row.get(1)?;
+                let acknowledged_at: Option<i64> = row.get(5)?;
+                Ok(Alert {
+                    id,
+                    severity: parse_severity(&severity),
+                    message: row.get(2)?,
+                    zone: row.get(3)?,
+                    fired_at: Tick(row.get::<_, i64>(4)? as u64),
+                    acknowledged: acknowledged_at.is_some(),
+                    acknowledged_at: acknowledged_at.map(|tick| Tick(tick as u64)),
+                })
+            })?
+            .collect::<rusqlite::Result<VecDeque<_>>>()?;
+        let mut history = Self { alerts, capacity, store: Some(connection) };
+        history.trim()?;
+        Ok(history)
+    }
+
+    /// Raises an alert for `event` at `tick` if it is a warning or worse
+    pub fn record(&mut self, event: &AgriEvent, tick: Tick) -> rusqlite::Result<Option<Alert>> {
+        if event.severity() < AlertSeverity::Warning {
+            return Ok(None);
+        }
+        let alert = Alert {
+            id: Uuid::new_v4(),
+            severity: event.severity(),
+            message: event.title().to_string(),
+            zone: event.zone_id(),
+            fired_at: tick,
+            acknowledged: false,
+            acknowledged_at: None,
+        };
+        self.save(&alert)?;
+        self.alerts.push_back(alert.clone());
+        self.trim()?;
+        Ok(Some(alert))
+    }
+
+    /// Alerts still waiting for an operator, oldest first
+    pub fn unacknowledged(&self) -> impl Iterator<Item = &Alert> {
+        self.alerts.iter().filter(|alert| !alert.acknowledged)
+    }
+
+    /// Acknowledges the oldest unacknowledged alert at `tick` and returns it, if there is one
+    pub fn acknowledge_oldest(&mut self, tick: Tick) -> rusqlite::Result<Option<Alert>> {
+        let Some(alert) = self.alerts.iter_mut().find(|alert| !alert.acknowledged) else {
+            return Ok(None);
+        };
+        alert.acknowledge(tick);
+        let alert = alert.clone();
+        self.save(&alert)?;
+        Ok(Some(alert))
+    }
+
+    /// Drops alerts beyond the capacity, acknowledged ones first and oldest first among those
+    fn trim(&mut self) -> rusqlite::Result<()> {
+        while self.alerts.len() > self.capacity {
+            let index = self.alerts.iter().position(|alert| alert.acknowledged).unwrap_or(0);
+            if let Some(alert) = self.alerts.remove(index) {
+                if let Some(store) = &self.store {
+                    store.execute("DELETE FROM alerts WHERE id = ?1", params![alert.id.to_string()])?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn save(&self, alert: &Alert) -> rusqlite::Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+        // Updated in place, so the rows keep the order the alerts were raised in
+        store.execute(
+            "INSERT INTO alerts (id, severity, message, zone, fired_at, acknowledged_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT (id) DO UPDATE SET acknowledged_at = excluded.acknowledged_at",
+            params![
+                alert.id.to_string(),
+                severity_name(alert.severity),
+                alert.message,
+                alert.zone,
+                alert.fired_at.0 as i64,
+                alert.acknowledged_at.map(|tick| tick.0 as i64),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+fn severity_name(severity: AlertSeverity) -> &'static str {
+    match severity {
+        AlertSeverity::Info => "info",
+        AlertSeverity::Warning => "warning",
+        AlertSeverity::Critical => "critical",
+    }
+}
+
+/// Severity stored as `severity_name` wrote it; anything else reads as a warning
+fn parse_severity(name: &str) -> AlertSeverity {
+    match name {
+        "info" => AlertSeverity::Info,
+        "critical" => AlertSeverity::Critical,
+        _ => AlertSeverity::Warning,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DeviceState;
+
+    fn sensor_error(zone_id: u8) -> AgriEvent {
+        AgriEvent::SensorError { zone_id, moisture_level: 20.0, state: DeviceState::Error }
+    }
+
+    fn still_low(zone_id: u8) -> AgriEvent {
+        AgriEvent::MoistureStillLow { zone_id, moisture_level: 25.0, state: DeviceState::Monitoring }
+    }
+
+    #[test]
+    fn only_warnings_and_worse_raise_alerts() {
+        let mut history = AlertHistory::default();
+        let watering = AgriEvent::WateringStarted { zone_id: 0, moisture_level: 28.0, state: DeviceState::Activating };
+        assert_eq!(history.record(&watering, Tick(1)).unwrap(), None);
+        let alert = history.record(&sensor_error(2), Tick(5)).unwrap().unwrap();
+        assert_eq!((alert.severity, alert.zone, alert.fired_at), (AlertSeverity::Critical, 2, Tick(5)));
+        assert_eq!(alert.message, "Sensor error");
+        assert!(!alert.acknowledged && alert.acknowledged_at.is_none());
+        assert_ne!(history.record(&still_low(0), Tick(6)).unwrap().unwrap().id, alert.id);
+    }
+
+    #[test]
+    fn acknowledgement_goes_oldest_first_and_sticks() {
+        let mut history = AlertHistory::default();
+        let oldest = history.record(&sensor_error(0), Tick(1)).unwrap().unwrap().id;
+        let newest = history.record(&still_low(1), Tick(2)).unwrap().unwrap().id;
+        assert_eq!(history.unacknowledged().count(), 2);
+        let first = history.acknowledge_oldest(Tick(10)).unwrap().unwrap();
+        assert_eq!((first.id, first.acknowledged, first.acknowledged_at), (oldest, true, Some(Tick(10))));
+        let ids: Vec<Uuid> = history.unacknowledged().map(|alert| alert.id).collect();
+        assert_eq!(ids, [newest]);
+        assert_eq!(history.acknowledge_oldest(Tick(11)).unwrap().unwrap().id, newest);
+        assert_eq!(history.acknowledge_oldest(Tick(12)).unwrap(), None);
+        // A second acknowledgement keeps the first tick
+        let mut alert = history.alerts[0].clone();
+        alert.acknowledge(Tick(99));
+        assert_eq!(alert.acknowledged_at, Some(Tick(10)));
+    }
+
+    #[test]
+    fn trimming_drops_acknowledged_alerts_first() {
+        let mut history = AlertHistory::new(2);
+        let first = history.record(&sensor_error(0), Tick(1)).unwrap().unwrap().id;
+        history.record(&sensor_error(1), Tick(2)).unwrap();
+        history.alerts[1].acknowledge(Tick(3));
+        let third = history.record(&sensor_error(2), Tick(4)).unwrap().unwrap().id;
+        let ids: Vec<Uuid> = history.alerts.iter().map(|alert| alert.id).collect();
+        assert_eq!(ids, [first, third]);
+        // With none acknowledged the oldest goes
+        let fourth = history.record(&sensor_error(3), Tick(5)).unwrap().unwrap().id;
+        let ids: Vec<Uuid> = history.alerts.iter().map(|alert| alert.id).collect();
+        assert_eq!(ids, [third, fourth]);
+    }
+
+    #[test]
+    fn acknowledgements_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!("agri-alert-history-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let stored = {
+            let mut history = AlertHistory::open(&path, DEFAULT_CAPACITY).unwrap();
+            history.record(&sensor_error(0), Tick(1)).unwrap();
+            history.record(&still_low(1), Tick(2)).unwrap();
+            history.acknowledge_oldest(Tick(7)).unwrap();
+            history.alerts.iter().map(|alert| alert.id).collect::<Vec<_>>()
+        };
+        let mut history = AlertHistory::open(&path, DEFAULT_CAPACITY).unwrap();
+        // Acknowledging updated the row in place, so the order they were raised in is kept
+        let ids: Vec<Uuid> = history.alerts.iter().map(|alert| alert.id).collect();
+        assert_eq!(ids, stored);
+        assert_eq!((history.alerts[0].acknowledged, history.alerts[0].acknowledged_at), (true, Some(Tick(7))));
+        assert_eq!(history.alerts[1].severity, AlertSeverity::Warning);
+        assert_eq!(history.unacknowledged().count(), 1);
+        let id = history.record(&sensor_error(2), Tick(8)).unwrap().unwrap().id;
+        assert!(!stored.contains(&id));
+        drop(history);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
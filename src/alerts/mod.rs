@@ -0,0 +1,120 @@
+//! Delivery of critical events to operators who aren't watching the TUI
+
+pub mod discord;
+pub mod email;
+pub mod history;
+pub mod ntfy;
+pub mod slack;
+pub mod telegram;
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::events::AgriEvent;
+
+/// Seconds since the Unix epoch, for alert timestamps
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Suppresses repeat alerts for the same key within a cooldown period
+struct Cooldown<K> {
+    period: Duration,
+    last_sent: HashMap<K, Instant>,
+}
+
+/// Alerts an operator has acknowledged, by id, with the title and zone their repeats carry;
+/// an acknowledged alert is never sent again
+struct Acknowledgements {
+    acknowledged: HashMap<Uuid, (String, u8)>,
+}
+
+impl Acknowledgements {
+    fn new() -> Self {
+        Self { acknowledged: HashMap::new() }
+    }
+
+    /// Notes an `AlertAcknowledged` event and returns whether `event` was one; otherwise whether
+    /// `event` repeats an acknowledged alert, so it should not be sent again
+    fn silences(&mut self, event: &AgriEvent) -> bool {
+        if let AgriEvent::AlertAcknowledged { zone_id, alert_id, alert_title, .. } = event {
+            self.acknowledged.insert(*alert_id, (alert_title.clone(), *zone_id));
+            return true;
+        }
+        let (title, zone_id) = (event.title(), event.zone_id());
+        self.acknowledged.values().any(|(acknowledged, zone)| acknowledged == title && *zone == zone_id)
+    }
+}
+
+impl<K: Eq + Hash> Cooldown<K> {
+    fn new(period: Duration) -> Self {
+        Self { period, last_sent: HashMap::new() }
+    }
+
+    /// Records a send for `key` unless one already went out within the period
+    fn try_acquire(&mut self, key: K, now: Instant) -> bool {
+        match self.last_sent.get(&key) {
+            Some(&sent) if now.duration_since(sent) < self.period => false,
+            _ => {
+                self.last_sent.insert(key, now);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DeviceState;
+
+    #[test]
+    fn an_acknowledged_alert_is_not_sent_again() {
+        let mut acknowledgements = Acknowledgements::new();
+        let fault = AgriEvent::SensorError { zone_id: 1, moisture_level: 20.0, state: DeviceState::Error };
+        assert!(!acknowledgements.silences(&fault));
+        let acknowledged = AgriEvent::AlertAcknowledged {
+            zone_id: 1,
+            moisture_level: 20.0,
+            state: DeviceState::Error,
+            alert_id: Uuid::new_v4(),
+            alert_title: fault.title().to_string(),
+        };
+        // The acknowledgement itself is never sent
+        assert!(acknowledgements.silences(&acknowledged));
+        assert!(acknowledgements.silences(&fault));
+        let other_zone = AgriEvent::SensorError { zone_id: 2, moisture_level: 20.0, state: DeviceState::Error };
+        assert!(!acknowledgements.silences(&other_zone));
+        // Acknowledging the same alert twice is one acknowledgement
+        assert!(acknowledgements.silences(&acknowledged));
+        assert_eq!(acknowledgements.acknowledged.len(), 1);
+    }
+
+    #[test]
+    fn an_acknowledged_alert_stays_silenced_past_the_cooldown() {
+        // How an alerter decides, with the email and Slack alerters' hour between sends
+        let mut acknowledgements = Acknowledgements::new();
+        let mut cooldown = Cooldown::new(Duration::from_secs(60 * 60));
+        let mut sent =
+            |event: &AgriEvent, now| !acknowledgements.silences(event) && cooldown.try_acquire(event.zone_id(), now);
+        let fault = AgriEvent::SensorError { zone_id: 1, moisture_level: 20.0, state: DeviceState::Error };
+        let other_zone = AgriEvent::SensorError { zone_id: 2, moisture_level: 20.0, state: DeviceState::Error };
+        let now = Instant::now();
+        assert!(sent(&fault, now) && sent(&other_zone, now));
+        let acknowledged = AgriEvent::AlertAcknowledged {
+            zone_id: 1,
+            moisture_level: 20.0,
+            state: DeviceState::Error,
+            alert_id: Uuid::new_v4(),
+            alert_title: fault.title().to_string(),
+        };
+        assert!(!sent(&acknowledged, now));
+        let later = now + Duration::from_secs(2 * 60 * 60);
+        assert!(sent(&other_zone, later));
+        assert!(!sent(&fault, later));
+        assert!(!sent(&fault, later + Duration::from_secs(24 * 60 * 60)));
+    }
+}
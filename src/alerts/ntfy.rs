@@ -0,0 +1,132 @@
+//! Push notifications through an ntfy server (ntfy.sh or self-hosted).
+
+use clap::ValueEnum;
+
+use crate::event_bus::Subscriber;
+use crate::events::AlertSeverity;
+
+/// ntfy message priority, sent as the `X-Priority` header (1–5)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum NtfyPriority {
+    Min = 1,
+    Low = 2,
+    Default = 3,
+    High = 4,
+    Urgent = 5,
+}
+
+impl From<AlertSeverity> for NtfyPriority {
+    fn from(severity: AlertSeverity) -> Self {
+        match severity {
+            AlertSeverity::Critical => NtfyPriority::Urgent,
+            AlertSeverity::Warning => NtfyPriority::Default,
+            AlertSeverity::Info => NtfyPriority::Low,
+        }
+    }
+}
+
+/// Publishes events to an ntfy topic, skipping those below a minimum priority
+pub struct NtfyAlerter {
+    server: String,
+    topic: String,
+    priority: NtfyPriority, // Lowest priority that is published
+    client: reqwest::Client,
+}
+
+impl NtfyAlerter {
+    /// Creates an alerter publishing to `topic` on `server` (e.g. `https://ntfy.sh`)
+    pub fn new(server: String, topic: String, priority: NtfyPriority) -> Self {
+        Self { server, topic, priority, client: reqwest::Client::new() }
+    }
+
+    /// Emoji shortcodes ntfy shows in front of the title
+    fn tags(severity: AlertSeverity) -> &'static str {
+        match severity {
+            AlertSeverity::Critical => "rotating_light,seedling",
+            AlertSeverity::Warning => "warning,seedling",
+            AlertSeverity::Info => "droplet,seedling",
+        }
+    }
+
+    /// Publishes qualifying events from `events` until the simulation shuts down
+    pub async fn run(self, mut events: Subscriber) {
+        let url = format!("{}/{}", self.server.trim_end_matches('/'), self.topic);
+        while let Some(event) = events.recv().await {
+            let priority = NtfyPriority::from(event.severity());
+            if priority < self.priority {
+                continue;
+            }
+            let message = format!(
+                "Zone {}: {} at {:.1}% moisture ({:?})",
+                event.zone_id(),
+                event.title().to_lowercase(),
+                event.moisture_level(),
+                event.state()
+            );
+            let request = self
+                .client
+                .put(&url)
+                .header("X-Title", format!("Agri-IoT zone {}", event.zone_id()))
+                .header("X-Priority", (priority as u8).to_string())
+                .header("X-Tags", Self::tags(event.severity()))
+                .body(message);
+            // Delivery failures cannot be reported while the TUI owns the terminal
+            let _ = request.send().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_bus::EventBus;
+    use crate::events::AgriEvent;
+    use crate::DeviceState;
+    use mockito::Matcher;
+
+    #[test]
+    fn severities_map_to_ntfy_priorities() {
+        assert_eq!(NtfyPriority::from(AlertSeverity::Critical) as u8, 5);
+        assert_eq!(NtfyPriority::from(AlertSeverity::Warning) as u8, 3);
+        assert_eq!(NtfyPriority::from(AlertSeverity::Info) as u8, 2);
+    }
+
+    #[tokio::test]
+    async fn events_are_put_to_the_topic_with_ntfy_headers() {
+        let mut server = mockito::Server::new_async().await;
+        let critical = server
+            .mock("PUT", "/farm-alerts")
+            .match_header("X-Title", "Agri-IoT zone 1")
+            .match_header("X-Priority", "5")
+            .match_header("X-Tags", "rotating_light,seedling")
+            .match_body("Zone 1: sensor error at 12.3% moisture (Error)")
+            .expect(1)
+            .create_async()
+            .await;
+        let warning = server
+            .mock("PUT", "/farm-alerts")
+            .match_header("X-Title", "Agri-IoT zone 0")
+            .match_header("X-Priority", "3")
+            .match_header("X-Tags", "warning,seedling")
+            .match_body("Zone 0: moisture still low at 20.0% moisture (Monitoring)")
+            .expect(1)
+            .create_async()
+            .await;
+        let other_requests = server.mock("PUT", Matcher::Any).expect(0).create_async().await;
+
+        let bus = EventBus::new(16);
+        let events = bus.subscribe();
+        bus.publish(AgriEvent::SensorError { zone_id: 1, moisture_level: 12.34, state: DeviceState::Error });
+        bus.publish(AgriEvent::MoistureStillLow { zone_id: 0, moisture_level: 20.0, state: DeviceState::Monitoring });
+        // Below the minimum priority
+        bus.publish(AgriEvent::WateringStarted { zone_id: 2, moisture_level: 25.0, state: DeviceState::Activating });
+        drop(bus);
+        // A trailing slash on a self-hosted server's URL is allowed
+        let server_url = format!("{}/", server.url());
+        NtfyAlerter::new(server_url, "farm-alerts".into(), NtfyPriority::Default).run(events).await;
+
+        critical.assert_async().await;
+        warning.assert_async().await;
+        other_requests.assert_async().await;
+    }
+}
@@ -0,0 +1,159 @@
+//! Slack alerts posted through an incoming webhook as Block Kit messages.
+
+use serde_json::{json, Value};
+use tokio::time::{Duration, Instant};
+
+use super::{unix_timestamp, Acknowledgements, Cooldown};
+use crate::event_bus::Subscriber;
+use crate::events::{AgriEvent, AlertSeverity};
+
+/// Minimum time between two messages about the same zone
+const RATE_LIMIT: Duration = Duration::from_secs(60);
+
+/// Posts events at or above a severity to a Slack channel, at most once per minute per zone
+pub struct SlackAlerter {
+    webhook_url: String,
+    channel: String, // Empty to post to the webhook's default channel
+    severity_threshold: AlertSeverity,
+    client: reqwest::Client,
+    cooldown: Cooldown<u8>,
+    acknowledgements: Acknowledgements,
+}
+
+impl SlackAlerter {
+    /// Creates an alerter posting to `webhook_url`
+    pub fn new(webhook_url: String, channel: String, severity_threshold: AlertSeverity) -> Self {
+        Self {
+            webhook_url,
+            channel,
+            severity_threshold,
+            client: reqwest::Client::new(),
+            cooldown: Cooldown::new(RATE_LIMIT),
+            acknowledgements: Acknowledgements::new(),
+        }
+    }
+
+    /// Builds the webhook payload: a colored attachment holding the Block Kit blocks
+    fn payload(&self, event: &AgriEvent, timestamp: u64) -> Value {
+        let color = match event.severity() {
+            AlertSeverity::Critical => "#d50200",
+            AlertSeverity::Warning => "#ecb22e",
+            AlertSeverity::Info => "#2eb67d",
+        };
+        let title = format!("{} in zone {}", event.title(), event.zone_id());
+        let mut payload = json!({
+            "text": title,
+            "attachments": [{
+                "color": color,
+                "blocks": [
+                    {
+                        "type": "header",
+                        "text": { "type": "plain_text", "text": title }
+                    },
+                    {
+                        "type": "section",
+                        "fields": [
+                            { "type": "mrkdwn", "text": format!("*Zone*\n{}", event.zone_id()) },
+                            { "type": "mrkdwn", "text": format!("*Event*\n{}", event.title()) },
+                            { "type": "mrkdwn", "text": format!("*Moisture*\n{:.1}%", event.moisture_level()) },
+                            { "type": "mrkdwn", "text": format!("*State*\n{:?}", event.state()) }
+                        ]
+                    },
+                    {
+                        "type": "context",
+                        "elements": [{
+                            "type": "mrkdwn",
+                            "text": format!("<!date^{}^{{date_short_pretty}} {{time_secs}}|{}>", timestamp, timestamp)
+                        }]
+                    }
+                ]
+            }]
+        });
+        if !self.channel.is_empty() {
+            payload["channel"] = json!(self.channel);
+        }
+        payload
+    }
+
+    /// Posts qualifying events from `events` until the simulation shuts down
+    pub async fn run(mut self, mut events: Subscriber) {
+        while let Some(event) = events.recv().await {
+            if self.acknowledgements.silences(&event) {
+                continue;
+            }
+            if event.severity() < self.severity_threshold || !self.cooldown.try_acquire(event.zone_id(), Instant::now()) {
+                continue;
+            }
+            let payload = self.payload(&event, unix_timestamp());
+            // Delivery failures cannot be reported while the TUI owns the terminal
+            let _ = self.client.post(&self.webhook_url).json(&payload).send().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_bus::EventBus;
+    use crate::DeviceState;
+    use mockito::Matcher;
+
+    fn sensor_error(zone_id: u8) -> AgriEvent {
+        AgriEvent::SensorError { zone_id, moisture_level: 12.34, state: DeviceState::Error }
+    }
+
+    #[test]
+    fn payload_is_a_colored_block_kit_attachment() {
+        let alerter = SlackAlerter::new(String::new(), "#farm".into(), AlertSeverity::Warning);
+        let payload = alerter.payload(&sensor_error(1), 1_700_000_000);
+        assert_eq!(payload["text"], "Sensor error in zone 1");
+        assert_eq!(payload["channel"], "#farm");
+        let attachment = &payload["attachments"][0];
+        assert_eq!(attachment["color"], "#d50200");
+        let blocks = &attachment["blocks"];
+        assert_eq!(blocks[0]["text"]["text"], "Sensor error in zone 1");
+        let fields = blocks[1]["fields"].as_array().unwrap();
+        let fields: Vec<&str> = fields.iter().map(|field| field["text"].as_str().unwrap()).collect();
+        assert_eq!(fields, ["*Zone*\n1", "*Event*\nSensor error", "*Moisture*\n12.3%", "*State*\nError"]);
+        assert_eq!(blocks[2]["elements"][0]["text"], "<!date^1700000000^{date_short_pretty} {time_secs}|1700000000>");
+
+        let warning = AgriEvent::MoistureStillLow { zone_id: 0, moisture_level: 20.0, state: DeviceState::Monitoring };
+        let payload = SlackAlerter::new(String::new(), String::new(), AlertSeverity::Warning).payload(&warning, 0);
+        assert_eq!(payload["attachments"][0]["color"], "#ecb22e");
+        assert!(payload.get("channel").is_none());
+    }
+
+    #[tokio::test]
+    async fn run_posts_each_zone_at_most_once_a_minute() {
+        let mut server = mockito::Server::new_async().await;
+        let zone_1 = server
+            .mock("POST", "/hook")
+            .match_header("content-type", "application/json")
+            .match_body(Matcher::PartialJson(json!({ "text": "Sensor error in zone 1", "channel": "#farm" })))
+            .expect(1)
+            .create_async()
+            .await;
+        let zone_2 = server
+            .mock("POST", "/hook")
+            .match_body(Matcher::PartialJson(json!({ "text": "Sensor error in zone 2" })))
+            .expect(1)
+            .create_async()
+            .await;
+        let other_posts = server.mock("POST", Matcher::Any).expect(0).create_async().await;
+
+        let bus = EventBus::new(16);
+        let events = bus.subscribe();
+        bus.publish(sensor_error(1));
+        bus.publish(sensor_error(1));
+        bus.publish(sensor_error(2));
+        // Below the threshold
+        bus.publish(AgriEvent::WateringStarted { zone_id: 3, moisture_level: 25.0, state: DeviceState::Activating });
+        drop(bus);
+        let url = format!("{}/hook", server.url());
+        SlackAlerter::new(url, "#farm".into(), AlertSeverity::Warning).run(events).await;
+
+        zone_1.assert_async().await;
+        zone_2.assert_async().await;
+        other_posts.assert_async().await;
+    }
+}
@@ -0,0 +1,124 @@
+//! Telegram alerts sent through the Bot API's `sendMessage` method.
+
+use serde_json::json;
+use std::mem::{self, Discriminant};
+use tokio::time::{Duration, Instant};
+
+use super::Cooldown;
+use crate::event_bus::Subscriber;
+use crate::events::{AgriEvent, AlertSeverity};
+
+/// Minimum time between two messages for the same event type
+const COOLDOWN: Duration = Duration::from_secs(5 * 60);
+
+/// Bot API server messages are sent through unless [`TelegramBot::with_api_url`] picks another
+pub const DEFAULT_API_URL: &str = "https://api.telegram.org";
+
+/// Sends critical events to a Telegram chat, suppressing duplicates for five minutes
+pub struct TelegramBot {
+    token: String,
+    chat_id: i64,
+    api_url: String,
+    client: reqwest::Client,
+    cooldown: Cooldown<Discriminant<AgriEvent>>,
+}
+
+impl TelegramBot {
+    /// Creates a bot posting to `chat_id` with the token issued by @BotFather
+    pub fn new(token: String, chat_id: i64) -> Self {
+        Self {
+            token,
+            chat_id,
+            api_url: DEFAULT_API_URL.to_string(),
+            client: reqwest::Client::new(),
+            cooldown: Cooldown::new(COOLDOWN),
+        }
+    }
+
+    /// Sends through a local Bot API server, or a mock one, at `api_url` instead of Telegram's
+    pub fn with_api_url(mut self, api_url: String) -> Self {
+        self.api_url = api_url;
+        self
+    }
+
+    /// Formats `event` as a Telegram Markdown message
+    fn format_message(event: &AgriEvent) -> String {
+        format!(
+            "*🚨 Zone {} {}*\nMoisture: {:.1}%\nReason: {}\nState: {:?}",
+            event.zone_id(),
+            event.title(),
+            event.moisture_level(),
+            event.reason(),
+            event.state()
+        )
+    }
+
+    /// Sends critical events from `events` until the simulation shuts down
+    pub async fn run(mut self, mut events: Subscriber) {
+        let url = format!("{}/bot{}/sendMessage", self.api_url, self.token);
+        while let Some(event) = events.recv().await {
+            if event.severity() < AlertSeverity::Critical
+                || !self.cooldown.try_acquire(mem::discriminant(&event), Instant::now())
+            {
+                continue;
+            }
+            let payload = json!({
+                "chat_id": self.chat_id,
+                "text": Self::format_message(&event),
+                "parse_mode": "Markdown",
+            });
+            // Delivery failures cannot be reported while the TUI owns the terminal
+            let _ = self.client.post(&url).json(&payload).send().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_bus::EventBus;
+    use crate::DeviceState;
+    use mockito::Matcher;
+
+    fn sensor_error(zone_id: u8) -> AgriEvent {
+        AgriEvent::SensorError { zone_id, moisture_level: 12.34, state: DeviceState::Error }
+    }
+
+    #[test]
+    fn message_is_telegram_markdown() {
+        assert_eq!(
+            TelegramBot::format_message(&sensor_error(2)),
+            "*🚨 Zone 2 Sensor error*\nMoisture: 12.3%\nReason: Sensor stopped giving valid readings\nState: Error"
+        );
+    }
+
+    #[tokio::test]
+    async fn send_message_is_called_once_per_event_type_within_the_cooldown() {
+        let mut server = mockito::Server::new_async().await;
+        let send_message = server
+            .mock("POST", "/bot123:ABC/sendMessage")
+            .match_header("content-type", "application/json")
+            .match_body(Matcher::Json(json!({
+                "chat_id": -1001,
+                "text": TelegramBot::format_message(&sensor_error(2)),
+                "parse_mode": "Markdown",
+            })))
+            .with_body(r#"{"ok":true}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let other_messages = server.mock("POST", Matcher::Any).expect(0).create_async().await;
+
+        let bus = EventBus::new(16);
+        let events = bus.subscribe();
+        // Only critical events are sent, and a repeat of the same type is a duplicate
+        bus.publish(AgriEvent::MoistureStillLow { zone_id: 1, moisture_level: 20.0, state: DeviceState::Monitoring });
+        bus.publish(sensor_error(2));
+        bus.publish(sensor_error(3));
+        drop(bus);
+        TelegramBot::new("123:ABC".into(), -1001).with_api_url(server.url()).run(events).await;
+
+        send_message.assert_async().await;
+        other_messages.assert_async().await;
+    }
+}
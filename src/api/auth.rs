@@ -0,0 +1,143 @@
+//! API key authentication for the HTTP API
+
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+
+use crate::config::AuthConfig;
+
+/// Accepted API keys, stored only as SHA-256 digests
+#[derive(Debug, Clone)]
+pub struct ApiKeys {
+    hashes: Vec<[u8; 32]>,
+}
+
+impl ApiKeys {
+    /// Whether `key` hashes to one of the accepted digests, compared in constant time so the
+    /// response time gives away nothing about how close a guess came
+    pub fn accepts(&self, key: &str) -> bool {
+        let digest = Sha256::digest(key.as_bytes());
+        self.hashes.iter().fold(false, |accepted, hash| accepted | bool::from(hash.ct_eq(digest.as_slice())))
+    }
+}
+
+impl From<&AuthConfig> for ApiKeys {
+    /// Decodes the configured hex digests; any that do not decode, which config validation
+    /// rejects, accept no key
+    fn from(config: &AuthConfig) -> Self {
+        Self { hashes: config.keys.iter().filter_map(|hash| decode_digest(hash)).collect() }
+    }
+}
+
+/// The 32 bytes of a hex SHA-256 digest, in either case
+fn decode_digest(hex: &str) -> Option<[u8; 32]> {
+    let mut digest = [0; 32];
+    if hex.len() != 64 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    for (byte, pair) in digest.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(digest)
+}
+
+/// Lowercase hex SHA-256 of `key`, as stored in `[api.auth] keys`
+pub fn hash_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes()).iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Middleware requiring `Authorization: Bearer <key>`
+///
+/// Answers `401 Unauthorized` when the header is missing and `403 Forbidden`
+/// when the key is not accepted.
+pub async fn require_key(State(keys): State<Arc<ApiKeys>>, request: Request, next: Next) -> Response {
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match bearer {
+        None => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            "missing API key",
+        )
+            .into_response(),
+        Some(key) if !keys.accepts(key.trim()) => (StatusCode::FORBIDDEN, "invalid API key").into_response(),
+        Some(_) => next.run(request).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use axum::{middleware, Router};
+    use tokio::net::TcpListener;
+
+    const KEY: &str = "field-station-7";
+
+    fn keys() -> ApiKeys {
+        ApiKeys::from(&AuthConfig { enabled: true, keys: vec![hash_key(KEY)] })
+    }
+
+    /// Serves a route behind [`require_key`] on a free local port and returns its URL
+    async fn serve() -> String {
+        let app = Router::new()
+            .route("/api/zones", get(|| async { "[]" }))
+            .route_layer(middleware::from_fn_with_state(Arc::new(keys()), require_key));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api/zones", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    #[test]
+    fn hash_key_is_lowercase_hex_sha256() {
+        assert_eq!(hash_key("abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    #[test]
+    fn accepts_only_the_configured_key() {
+        assert!(keys().accepts(KEY));
+        assert!(!keys().accepts("field-station-8"));
+        assert!(!keys().accepts(""));
+    }
+
+    #[test]
+    fn uppercase_digests_are_accepted() {
+        let keys = ApiKeys::from(&AuthConfig { enabled: true, keys: vec![hash_key(KEY).to_uppercase()] });
+        assert!(keys.accepts(KEY));
+    }
+
+    #[test]
+    fn malformed_digests_accept_nothing() {
+        let keys = ApiKeys::from(&AuthConfig { enabled: true, keys: vec![KEY.to_string(), "+f".repeat(32)] });
+        assert!(!keys.accepts(KEY));
+        assert!(keys.hashes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_key_is_unauthorized() {
+        let response = reqwest::get(serve().await).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+    }
+
+    #[tokio::test]
+    async fn invalid_key_is_forbidden() {
+        let response = reqwest::Client::new().get(serve().await).bearer_auth("guess").send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn valid_key_is_let_through() {
+        let response = reqwest::Client::new().get(serve().await).bearer_auth(KEY).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().await.unwrap(), "[]");
+    }
+}
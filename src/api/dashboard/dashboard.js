@@ -0,0 +1,49 @@
+// Polls the zone API and redraws one card per zone
+const REFRESH_MS = 2000;
+
+function zoneCard(zone) {
+  const card = document.createElement("section");
+  card.className = "zone";
+
+  const title = document.createElement("h2");
+  title.textContent = `Zone ${zone.zone_id}`;
+
+  const gauge = document.createElement("div");
+  gauge.className = "gauge";
+  const fill = document.createElement("div");
+  fill.style.width = `${Math.min(Math.max(zone.moisture_level, 0), 100)}%`;
+  gauge.appendChild(fill);
+
+  const moisture = document.createElement("p");
+  moisture.className = "moisture";
+  moisture.textContent = `${zone.moisture_level.toFixed(1)}%`;
+
+  const badge = document.createElement("span");
+  badge.className = `badge ${zone.state}`;
+  badge.textContent = zone.state;
+
+  const status = document.createElement("p");
+  status.className = "status";
+  status.textContent = zone.status_message || "—";
+
+  card.append(title, gauge, moisture, badge, status);
+  return card;
+}
+
+async function refresh() {
+  const updated = document.getElementById("updated");
+  try {
+    const response = await fetch("/api/zones");
+    if (!response.ok) {
+      throw new Error(`HTTP ${response.status}`);
+    }
+    const zones = await response.json();
+    document.getElementById("zones").replaceChildren(...zones.map(zoneCard));
+    updated.textContent = `Updated ${new Date().toLocaleTimeString()}`;
+  } catch (error) {
+    updated.textContent = `Disconnected (${error.message})`;
+  }
+}
+
+refresh();
+setInterval(refresh, REFRESH_MS);
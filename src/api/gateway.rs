@@ -0,0 +1,157 @@
+//! Routing of MQTT publishes across redundant brokers
+//!
+//! [`GatewayManager`] holds one [`MqttPublisher`] per broker. Only brokers whose
+//! connection is up are chosen; a publish that fails on one broker is retried on
+//! the next in the same call, so a failed broker is passed over within the tick.
+
+use rumqttc::ClientError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+use super::mqtt::MqttPublisher;
+use crate::config::BrokerConfig;
+use crate::events::AgriEvent;
+use crate::offline_cache::CachedReading;
+use crate::SoilMoistureSensor;
+
+/// How publishes are spread over the brokers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadBalancingStrategy {
+    /// Each message to the next broker in turn
+    RoundRobin,
+    /// Every message to the first broker that is up
+    #[default]
+    FailoverPrimary,
+    /// Every message to all brokers that are up
+    BroadcastAll,
+}
+
+impl LoadBalancingStrategy {
+    pub fn name(self) -> &'static str {
+        match self {
+            LoadBalancingStrategy::RoundRobin => "round robin",
+            LoadBalancingStrategy::FailoverPrimary => "failover",
+            LoadBalancingStrategy::BroadcastAll => "broadcast",
+        }
+    }
+}
+
+/// Publishes and failures on one broker
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BrokerStats {
+    pub address: String, // `host:port`
+    pub connected: bool,
+    pub messages_sent: u64,
+    pub errors: u64, // Publishes the client could not queue
+}
+
+impl BrokerStats {
+    /// Share of the publishes tried on this broker that failed
+    pub fn error_rate(&self) -> f64 {
+        let attempts = self.messages_sent + self.errors;
+        if attempts == 0 {
+            return 0.0;
+        }
+        self.errors as f64 / attempts as f64
+    }
+}
+
+/// What the TUI shows of the gateway
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GatewayStatus {
+    pub strategy: LoadBalancingStrategy,
+    pub active_broker: Option<String>, // Address the last message went to
+    pub brokers: Vec<BrokerStats>,     // In configured order
+}
+
+struct Broker {
+    publisher: MqttPublisher,
+    stats: BrokerStats,
+}
+
+/// One MQTT client per broker, with publishes routed by a [`LoadBalancingStrategy`]
+pub struct GatewayManager {
+    pub strategy: LoadBalancingStrategy,
+    brokers: Vec<Broker>,
+    next: usize,           // Where the round-robin rotation resumes
+    active: Option<usize>, // Broker the last message went to
+}
+
+impl GatewayManager {
+    /// Connects to every broker; they are listed primary first
+    pub fn connect(brokers: &[BrokerConfig], strategy: LoadBalancingStrategy) -> Result<Self, Box<dyn Error>> {
+        let brokers = brokers
+            .iter()
+            .map(|config| {
+                let address = format!("{}:{}", config.host, config.port);
+                let stats = BrokerStats { address, ..BrokerStats::default() };
+                Ok(Broker { publisher: MqttPublisher::connect(config)?, stats })
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+        Ok(Self { strategy, brokers, next: 0, active: None })
+    }
+
+    /// Every broker's counts and connection, and the active one
+    pub fn status(&self) -> GatewayStatus {
+        GatewayStatus {
+            strategy: self.strategy,
+            active_broker: self.active_broker().map(str::to_string),
+            brokers: self
+                .brokers
+                .iter()
+                .map(|broker| BrokerStats { connected: broker.publisher.is_connected(), ..broker.stats.clone() })
+                .collect(),
+        }
+    }
+
+    /// The broker the last message went to
+    pub fn active_broker(&self) -> Option<&str> {
+        self.active.map(|index| self.brokers[index].stats.address.as_str())
+    }
+
+    pub fn publish_reading(&mut self, zone_id: usize, sensor: &SoilMoistureSensor) {
+        self.route(|publisher| publisher.publish_reading(zone_id, sensor));
+    }
+
+    pub fn publish_cached(&mut self, reading: &CachedReading) {
+        self.route(|publisher| publisher.publish_cached(reading));
+    }
+
+    pub fn publish_event(&mut self, event: &AgriEvent) {
+        self.route(|publisher| publisher.publish_event(event));
+    }
+
+    /// Brokers that are up, in the order the strategy tries them. With none up, all of
+    /// them: the clients queue the message until their connection comes back.
+    fn candidates(&self) -> Vec<usize> {
+        let count = self.brokers.len();
+        let start = if self.strategy == LoadBalancingStrategy::RoundRobin { self.next } else { 0 };
+        let order: Vec<usize> = (0..count).map(|offset| (start + offset) % count).collect();
+        let is_up = |index: &usize| self.brokers[*index].publisher.is_connected();
+        let up: Vec<usize> = order.iter().copied().filter(is_up).collect();
+        if up.is_empty() {
+            order
+        } else {
+            up
+        }
+    }
+
+    fn route(&mut self, publish: impl Fn(&MqttPublisher) -> Result<(), ClientError>) {
+        let broadcast = self.strategy == LoadBalancingStrategy::BroadcastAll;
+        for index in self.candidates() {
+            let broker = &mut self.brokers[index];
+            if publish(&broker.publisher).is_err() {
+                broker.stats.errors += 1;
+                continue;
+            }
+            broker.stats.messages_sent += 1;
+            self.active = Some(index);
+            if !broadcast {
+                self.next = (index + 1) % self.brokers.len();
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! gRPC `SensorService` serving live zone readings (schema in `proto/agri.proto`).
+//!
+//! [`proto::sensor_service_client::SensorServiceClient`] is generated too, for programs that
+//! consume the readings.
+
+use std::error::Error;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio_stream::wrappers::{TcpListenerStream, WatchStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+use crate::SoilMoistureSensor;
+
+pub mod proto {
+    tonic::include_proto!("agri");
+}
+
+use proto::sensor_service_server::{SensorService, SensorServiceServer};
+use proto::{SensorUpdate, ZoneRequest, ZoneState};
+
+/// Zone readings captured at the end of one tick
+#[derive(Clone, Default)]
+struct TickSnapshot {
+    zones: Vec<ZoneState>,
+    timestamp_ms: u64, // Unix time the tick completed
+}
+
+/// Looks up one zone in a snapshot, mapping unknown ids to `NOT_FOUND`
+fn find_zone(snapshot: &TickSnapshot, zone_id: u32) -> Result<ZoneState, Status> {
+    snapshot
+        .zones
+        .get(zone_id as usize)
+        .cloned()
+        .ok_or_else(|| Status::not_found(format!("no zone {}", zone_id)))
+}
+
+/// Answers RPCs from the latest snapshot published by the simulation loop
+struct SensorServiceImpl {
+    snapshots: watch::Receiver<TickSnapshot>,
+}
+
+#[tonic::async_trait]
+impl SensorService for SensorServiceImpl {
+    async fn get_zone_state(&self, request: Request<ZoneRequest>) -> Result<Response<ZoneState>, Status> {
+        let zone_id = request.into_inner().zone_id;
+        find_zone(&self.snapshots.borrow(), zone_id).map(Response::new)
+    }
+
+    type StreamSensorDataStream = Pin<Box<dyn Stream<Item = Result<SensorUpdate, Status>> + Send>>;
+
+    async fn stream_sensor_data(
+        &self,
+        request: Request<ZoneRequest>,
+    ) -> Result<Response<Self::StreamSensorDataStream>, Status> {
+        let zone_id = request.into_inner().zone_id;
+        find_zone(&self.snapshots.borrow(), zone_id)?;
+        let updates = WatchStream::from_changes(self.snapshots.clone()).map(move |snapshot| {
+            find_zone(&snapshot, zone_id).map(|zone| SensorUpdate {
+                zone: Some(zone),
+                timestamp_ms: snapshot.timestamp_ms,
+            })
+        });
+        Ok(Response::new(Box::pin(updates)))
+    }
+}
+
+/// gRPC server streaming zone readings in the background
+pub struct GrpcServer {
+    snapshots: watch::Sender<TickSnapshot>,
+    local_addr: SocketAddr,
+}
+
+impl GrpcServer {
+    /// Binds to `port` on all interfaces, or any free port for 0, and starts serving `SensorService`
+    pub async fn start(port: u16) -> Result<Self, Box<dyn Error>> {
+        let listener = TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], port))).await?;
+        let local_addr = listener.local_addr()?;
+        let (snapshots, receiver) = watch::channel(TickSnapshot::default());
+        let service = SensorServiceServer::new(SensorServiceImpl { snapshots: receiver });
+
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        Ok(Self { snapshots, local_addr })
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Publishes the current readings to streaming clients, once per tick
+    pub fn update(&self, sensors: &[&SoilMoistureSensor]) {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let zones = sensors
+            .iter()
+            .enumerate()
+            .map(|(i, sensor)| ZoneState {
+                zone_id: i as u32,
+                moisture_level: sensor.moisture_level,
+                state: sensor.state.name().to_string(),
+                water_pump_running: sensor.water_running(),
+            })
+            .collect();
+        self.snapshots.send_replace(TickSnapshot { zones, timestamp_ms });
+    }
+}
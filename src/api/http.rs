@@ -0,0 +1,249 @@
+//! HTTP server hosting the web dashboard and its JSON zone API.
+//!
+//! | Route             | Response                                    |
+//! |-------------------|---------------------------------------------|
+//! | `GET /`           | Dashboard page (embedded in the binary)     |
+//! | `GET /api/zones`  | JSON array with one object per zone         |
+//! | `GET /api/sensors/{sensor_id}` | That sensor's zone object, or `404` |
+//! | `GET /health/live`  | `200` while the simulation loop runs, paused too, else `503` |
+//! | `GET /health/ready` | `200` once every zone has completed a tick, else `503` |
+//! | `GET /health/zones` | JSON array with each zone's health                     |
+//!
+//! Every route except `/health` shares one [`RateLimiter`] and answers
+//! `429 Too Many Requests` once it is exhausted. When `[api.auth]` is enabled
+//! those routes also need an `Authorization: Bearer <key>` header. With
+//! `[api.tls]` set, everything is served over HTTPS instead of plain HTTP.
+
+use axum::extract::{FromRef, Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{Html, IntoResponse};
+use axum::middleware;
+use axum::routing::get;
+use axum::{Json, Router};
+use axum_server::tls_rustls::RustlsConfig;
+use serde::Serialize;
+use serde_json::json;
+use std::future::IntoFuture;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+
+use super::auth::{self, ApiKeys};
+use super::rate_limit::{self, RateLimiter};
+use crate::config::ApiConfig;
+use crate::{DeviceState, SoilMoistureSensor};
+
+static INDEX_HTML: &str = include_str!("dashboard/index.html");
+static DASHBOARD_CSS: &str = include_str!("dashboard/dashboard.css");
+static DASHBOARD_JS: &str = include_str!("dashboard/dashboard.js");
+
+/// One zone as returned by `GET /api/zones`
+#[derive(Debug, Clone, Serialize)]
+struct ZoneStatus {
+    zone_id: u8,
+    sensor_id: String,
+    moisture_level: f32,
+    state: String,
+    water_pump_running: bool,
+    status_message: String,
+    #[serde(skip)]
+    tick: u64, // Ticks completed, for the health routes
+}
+
+/// One zone as returned by `GET /health/zones`
+#[derive(Debug, Clone, Serialize)]
+struct ZoneHealth {
+    zone_id: u8,
+    healthy: bool, // False while the sensor is in the Error state
+    state: String,
+    ticks_completed: u64,
+}
+
+type SharedZones = Arc<RwLock<Vec<ZoneStatus>>>;
+
+/// The simulation loop counts as dead once it has not come round for this long
+const LIVENESS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Liveness and readiness of the simulation loop
+#[derive(Debug)]
+struct Health {
+    started: Instant,
+    last_heartbeat: RwLock<Option<Instant>>,
+    is_ready: Arc<AtomicBool>, // Set once every zone has completed a tick
+}
+
+/// State shared by all routes
+#[derive(Clone)]
+struct AppState {
+    zones: SharedZones,
+    health: Arc<Health>,
+}
+
+impl FromRef<AppState> for SharedZones {
+    fn from_ref(state: &AppState) -> Self {
+        Arc::clone(&state.zones)
+    }
+}
+
+impl FromRef<AppState> for Arc<Health> {
+    fn from_ref(state: &AppState) -> Self {
+        Arc::clone(&state.health)
+    }
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+async fn dashboard_css() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/css; charset=utf-8")], DASHBOARD_CSS)
+}
+
+async fn dashboard_js() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/javascript; charset=utf-8")], DASHBOARD_JS)
+}
+
+async fn list_zones(State(zones): State<SharedZones>) -> Json<Vec<ZoneStatus>> {
+    Json(zones.read().expect("zone list lock poisoned").clone())
+}
+
+async fn get_sensor(State(zones): State<SharedZones>, Path(sensor_id): Path<String>) -> impl IntoResponse {
+    let zones = zones.read().expect("zone list lock poisoned");
+    match zones.iter().find(|zone| zone.sensor_id == sensor_id) {
+        Some(zone) => Json(zone.clone()).into_response(),
+        None => (StatusCode::NOT_FOUND, Json(json!({ "error": format!("no sensor {sensor_id:?}") }))).into_response(),
+    }
+}
+
+async fn health_live(State(health): State<Arc<Health>>) -> impl IntoResponse {
+    let last_activity = health.last_heartbeat.read().expect("health lock poisoned").unwrap_or(health.started);
+    let uptime_secs = health.started.elapsed().as_secs();
+    if last_activity.elapsed() < LIVENESS_TIMEOUT {
+        (StatusCode::OK, Json(json!({ "status": "ok", "uptime_secs": uptime_secs })))
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, Json(json!({ "status": "stalled", "uptime_secs": uptime_secs })))
+    }
+}
+
+async fn health_ready(State(health): State<Arc<Health>>) -> impl IntoResponse {
+    if health.is_ready.load(Ordering::Acquire) {
+        (StatusCode::OK, Json(json!({ "status": "ready" })))
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, Json(json!({ "status": "starting" })))
+    }
+}
+
+async fn health_zones(State(zones): State<SharedZones>) -> Json<Vec<ZoneHealth>> {
+    let zones = zones.read().expect("zone list lock poisoned");
+    Json(
+        zones
+            .iter()
+            .map(|zone| ZoneHealth {
+                zone_id: zone.zone_id,
+                healthy: zone.state != DeviceState::Error.name(),
+                state: zone.state.clone(),
+                ticks_completed: zone.tick,
+            })
+            .collect(),
+    )
+}
+
+/// Routes served by [`HttpServer`]
+fn router(state: AppState, config: &ApiConfig) -> Router {
+    let mut api = Router::new()
+        .route("/", get(index))
+        .route("/dashboard.css", get(dashboard_css))
+        .route("/dashboard.js", get(dashboard_js))
+        .route("/api/zones", get(list_zones))
+        .route("/api/sensors/{sensor_id}", get(get_sensor));
+    if config.auth.enabled {
+        let keys = Arc::new(ApiKeys::from(&config.auth));
+        api = api.route_layer(middleware::from_fn_with_state(keys, auth::require_key));
+    }
+    let limiter = Arc::new(RateLimiter::from(&config.rate_limit));
+    api.route_layer(middleware::from_fn_with_state(limiter, rate_limit::limit))
+        .route("/health/live", get(health_live))
+        .route("/health/ready", get(health_ready))
+        .route("/health/zones", get(health_zones))
+        .with_state(state)
+}
+
+/// HTTP server for the web dashboard, running in the background
+pub struct HttpServer {
+    zones: SharedZones,
+    health: Arc<Health>,
+    local_addr: SocketAddr,
+}
+
+impl HttpServer {
+    /// Binds to `port` on all interfaces, or any free port for 0, and starts serving requests, over
+    /// TLS when `config.tls` is set
+    pub async fn start(port: u16, config: &ApiConfig) -> io::Result<Self> {
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        let state = AppState {
+            zones: SharedZones::default(),
+            health: Arc::new(Health {
+                started: Instant::now(),
+                last_heartbeat: RwLock::new(None),
+                is_ready: Arc::new(AtomicBool::new(false)),
+            }),
+        };
+        let zones = Arc::clone(&state.zones);
+        let health = Arc::clone(&state.health);
+        let app = router(state, config);
+        let local_addr = match &config.tls {
+            Some(tls) => {
+                // Several crates link a rustls provider; make ring the process default
+                let _ = rustls::crypto::ring::default_provider().install_default();
+                let rustls_config = RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path).await?;
+                let listener = std::net::TcpListener::bind(addr)?;
+                let local_addr = listener.local_addr()?;
+                tokio::spawn(axum_server::from_tcp_rustls(listener, rustls_config).serve(app.into_make_service()));
+                local_addr
+            }
+            None => {
+                let listener = TcpListener::bind(addr).await?;
+                let local_addr = listener.local_addr()?;
+                tokio::spawn(axum::serve(listener, app).into_future());
+                local_addr
+            }
+        };
+        Ok(Self { zones, health, local_addr })
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Publishes each zone's reading and latest status message, once per tick
+    pub fn update(&self, zones: &[(&SoilMoistureSensor, &str)]) {
+        let statuses = zones
+            .iter()
+            .enumerate()
+            .map(|(i, (sensor, status_message))| ZoneStatus {
+                zone_id: i as u8,
+                sensor_id: sensor.sensor_id.to_string(),
+                moisture_level: sensor.moisture_level,
+                state: sensor.state.name().to_string(),
+                water_pump_running: sensor.water_running(),
+                status_message: status_message.to_string(),
+                tick: sensor.current_tick.0,
+            })
+            .collect::<Vec<_>>();
+        if !statuses.is_empty() && statuses.iter().all(|zone| zone.tick > 0) {
+            self.health.is_ready.store(true, Ordering::Release);
+        }
+        *self.zones.write().expect("zone list lock poisoned") = statuses;
+        self.heartbeat();
+    }
+
+    /// Marks the simulation loop alive. The loop calls this on every pass, ticking or not, so a
+    /// paused or single-stepped simulation stays live.
+    pub fn heartbeat(&self) {
+        *self.health.last_heartbeat.write().expect("health lock poisoned") = Some(Instant::now());
+    }
+}
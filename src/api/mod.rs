@@ -0,0 +1,10 @@
+//! Network interfaces that expose simulator state to external systems
+
+pub mod auth;
+pub mod gateway;
+pub mod grpc;
+pub mod http;
+pub mod modbus;
+pub mod mqtt;
+pub mod opcua;
+pub mod rate_limit;
@@ -0,0 +1,110 @@
+//! Modbus TCP server exposing zone readings to SCADA systems.
+//!
+//! Register map (zone N is the Nth sensor passed to [`ModbusTcpServer::update`]):
+//!
+//! | Table             | Address | Value                                            |
+//! |-------------------|---------|--------------------------------------------------|
+//! | Holding registers | N       | Zone N moisture, 0–10000 (percent × 100)         |
+//! | Coils             | N       | Zone N water running (`Activating`/`Adjusting`)  |
+//!
+//! The map is read-only: write requests are rejected with `IllegalFunction`,
+//! and reads past the last zone with `IllegalDataAddress`.
+
+use std::future;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use tokio::net::TcpListener;
+use tokio_modbus::server::tcp::{accept_tcp_connection, Server};
+use tokio_modbus::server::Service;
+use tokio_modbus::{ExceptionCode, Request, Response};
+
+use crate::SoilMoistureSensor;
+
+/// Register image shared between the simulation loop and client connections
+#[derive(Default)]
+struct RegisterMap {
+    holding: Vec<u16>,
+    coils: Vec<bool>,
+}
+
+/// Answers read requests from the shared register image
+#[derive(Clone)]
+struct RegisterService {
+    registers: Arc<RwLock<RegisterMap>>,
+}
+
+impl Service for RegisterService {
+    type Request = Request<'static>;
+    type Response = Response;
+    type Exception = ExceptionCode;
+    type Future = future::Ready<Result<Response, ExceptionCode>>;
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        let registers = self.registers.read().expect("register map lock poisoned");
+        let response = match req {
+            Request::ReadHoldingRegisters(addr, count) => {
+                read_range(&registers.holding, addr, count).map(Response::ReadHoldingRegisters)
+            }
+            Request::ReadCoils(addr, count) => {
+                read_range(&registers.coils, addr, count).map(Response::ReadCoils)
+            }
+            _ => Err(ExceptionCode::IllegalFunction),
+        };
+        future::ready(response)
+    }
+}
+
+/// Copies `count` entries starting at `addr`, failing if any fall outside the table
+fn read_range<T: Copy>(table: &[T], addr: u16, count: u16) -> Result<Vec<T>, ExceptionCode> {
+    let start = usize::from(addr);
+    let end = start + usize::from(count);
+    table
+        .get(start..end)
+        .map(<[T]>::to_vec)
+        .ok_or(ExceptionCode::IllegalDataAddress)
+}
+
+/// Scales a moisture percentage to the 0–10000 holding register range
+fn moisture_register(moisture_level: f32) -> u16 {
+    (moisture_level.clamp(0.0, 100.0) * 100.0).round() as u16
+}
+
+/// Modbus TCP server serving the latest zone readings in the background
+pub struct ModbusTcpServer {
+    registers: Arc<RwLock<RegisterMap>>,
+    local_addr: SocketAddr,
+}
+
+impl ModbusTcpServer {
+    /// Binds to `port` on all interfaces, or any free port for 0, and starts accepting clients
+    pub async fn start(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], port))).await?;
+        let local_addr = listener.local_addr()?;
+        let registers = Arc::new(RwLock::new(RegisterMap::default()));
+        let service = RegisterService { registers: Arc::clone(&registers) };
+
+        tokio::spawn(async move {
+            let on_connected = |stream, socket_addr| {
+                let service = service.clone();
+                async move { accept_tcp_connection(stream, socket_addr, |_| Ok(Some(service.clone()))) }
+            };
+            // A failed client connection only ends that connection; nothing to report in the TUI
+            let _ = Server::new(listener).serve(&on_connected, |_| {}).await;
+        });
+
+        Ok(Self { registers, local_addr })
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Refreshes the register image from the current readings, once per tick
+    pub fn update(&self, sensors: &[&SoilMoistureSensor]) {
+        let mut registers = self.registers.write().expect("register map lock poisoned");
+        registers.holding = sensors.iter().map(|s| moisture_register(s.moisture_level)).collect();
+        registers.coils = sensors.iter().map(|s| s.water_running()).collect();
+    }
+}
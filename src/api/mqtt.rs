@@ -0,0 +1,142 @@
+//! MQTT publisher for sensor readings, with optional TLS and broker authentication
+//!
+//! Each tick, every sensor's reading is published as JSON to
+//! `<topic_prefix>/sensors/<sensor_id>`
+//! with the retain flag set, so new subscribers get the latest value at once.
+//! Events go to `<topic_prefix>/events/<zone_id>` at QoS 1.
+//! Readings replayed from the offline cache carry the time they were taken in
+//! `timestamp_ms` and are not retained, so they do not displace the latest value.
+
+use rumqttc::{AsyncClient, ClientError, Event, MqttOptions, Packet, QoS, TlsConfiguration, Transport};
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::MqttConfig;
+use crate::events::AgriEvent;
+use crate::offline_cache::CachedReading;
+use crate::SoilMoistureSensor;
+
+/// Payload published for each zone
+#[derive(Debug, Serialize)]
+struct ZoneMessage<'a> {
+    zone_id: usize,
+    moisture_level: f32,
+    state: &'a str,
+    water_pump_running: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp_ms: Option<u64>, // Only on readings replayed from the offline cache
+}
+
+/// Payload published for each event
+#[derive(Debug, Serialize)]
+struct EventMessage {
+    zone_id: u8,
+    title: &'static str,
+    moisture_level: f32,
+}
+
+/// MQTT client publishing to a broker, reconnecting in the background
+pub struct MqttPublisher {
+    client: AsyncClient,
+    topic_prefix: String,
+    connected: Arc<AtomicBool>, // Set on CONNACK, cleared when the connection fails
+}
+
+impl MqttPublisher {
+    /// Connects to the broker in `config`; certificates are read from disk up front
+    ///
+    /// With `[mqtt.tls]` the connection uses rustls, verifying the broker against
+    /// `ca_cert`, and mutual TLS when `client_cert` and `client_key` are set.
+    pub fn connect(config: &MqttConfig) -> Result<Self, Box<dyn Error>> {
+        let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+        options.set_keep_alive(Duration::from_secs(30));
+        if let Some(auth) = &config.auth {
+            options.set_credentials(&auth.username, &auth.password);
+        }
+        if let Some(tls) = &config.tls {
+            // Several crates link a rustls provider; make ring the process default
+            let _ = rustls::crypto::ring::default_provider().install_default();
+            let client_auth = match (&tls.client_cert, &tls.client_key) {
+                (Some(cert), Some(key)) => Some((fs::read(cert)?, fs::read(key)?)),
+                _ => None,
+            };
+            options.set_transport(Transport::tls_with_config(TlsConfiguration::Simple {
+                ca: fs::read(&tls.ca_cert)?,
+                alpn: None,
+                client_auth,
+            }));
+        }
+
+        let (client, mut eventloop) = AsyncClient::new(options, 64);
+        let connected = Arc::new(AtomicBool::new(false));
+        let connection = Arc::clone(&connected);
+        tokio::spawn(async move {
+            loop {
+                // Polling drives the connection; after an error the next poll reconnects
+                match eventloop.poll().await {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => connection.store(true, Ordering::Relaxed),
+                    Ok(_) => {}
+                    Err(_) => {
+                        connection.store(false, Ordering::Relaxed);
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                    }
+                }
+            }
+        });
+        Ok(Self { client, topic_prefix: config.topic_prefix.clone(), connected })
+    }
+
+    /// Whether the broker has accepted the connection and it has not failed since
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// Publishes every zone's reading; call once per tick
+    pub fn update(&self, sensors: &[&SoilMoistureSensor]) {
+        for (zone_id, sensor) in sensors.iter().enumerate() {
+            // Drop the reading rather than stall the tick while the broker is unreachable
+            let _ = self.publish_reading(zone_id, sensor);
+        }
+    }
+
+    /// Publishes one zone's reading; fails when the client's queue is full
+    pub fn publish_reading(&self, zone_id: usize, sensor: &SoilMoistureSensor) -> Result<(), ClientError> {
+        let message = ZoneMessage {
+            zone_id,
+            moisture_level: sensor.moisture_level,
+            state: sensor.state.name(),
+            water_pump_running: sensor.water_running(),
+            timestamp_ms: None,
+        };
+        let payload = serde_json::to_vec(&message).expect("zone message serializes to JSON");
+        let topic = format!("{}/sensors/{}", self.topic_prefix, sensor.sensor_id);
+        self.client.try_publish(topic, QoS::AtMostOnce, true, payload)
+    }
+
+    /// Publishes a reading from the offline cache, stamped with when it was taken
+    pub fn publish_cached(&self, reading: &CachedReading) -> Result<(), ClientError> {
+        let message = ZoneMessage {
+            zone_id: reading.zone_id,
+            moisture_level: reading.moisture_level,
+            state: &reading.state,
+            water_pump_running: reading.water_pump_running,
+            timestamp_ms: Some(reading.timestamp_ms),
+        };
+        let payload = serde_json::to_vec(&message).expect("zone message serializes to JSON");
+        let topic = format!("{}/sensors/{}", self.topic_prefix, reading.sensor_id);
+        self.client.try_publish(topic, QoS::AtLeastOnce, false, payload)
+    }
+
+    /// Publishes an event for the zone it happened in
+    pub fn publish_event(&self, event: &AgriEvent) -> Result<(), ClientError> {
+        let message =
+            EventMessage { zone_id: event.zone_id(), title: event.title(), moisture_level: event.moisture_level() };
+        let payload = serde_json::to_vec(&message).expect("event message serializes to JSON");
+        let topic = format!("{}/events/{}", self.topic_prefix, message.zone_id);
+        self.client.try_publish(topic, QoS::AtLeastOnce, false, payload)
+    }
+}
@@ -0,0 +1,92 @@
+//! OPC-UA server publishing zone readings to industrial automation clients.
+//!
+//! Node tree (all nodes in the `urn:agri-iot-simulator` namespace):
+//!
+//! ```text
+//! Objects/AgriIoT/Zone[N]/Moisture          Float, percent
+//! Objects/AgriIoT/Zone[N]/State             String, DeviceState variant name
+//! Objects/AgriIoT/Zone[N]/WaterPumpRunning  Boolean
+//! ```
+//!
+//! Values are written into the address space once per tick, so clients can
+//! either read them or create a subscription and receive change notifications.
+
+use opcua::server::prelude::*;
+use opcua::sync::RwLock;
+use std::sync::Arc;
+
+use crate::SoilMoistureSensor;
+
+const NAMESPACE_URI: &str = "urn:agri-iot-simulator";
+
+/// Addresses of the variables published for one zone
+struct ZoneNodes {
+    moisture: NodeId,
+    state: NodeId,
+    water_pump_running: NodeId,
+}
+
+/// OPC-UA server running on the simulator's tokio runtime
+pub struct OpcUaServer {
+    address_space: Arc<RwLock<AddressSpace>>,
+    zones: Vec<ZoneNodes>,
+}
+
+impl OpcUaServer {
+    /// Builds the node tree for `zone_count` zones and starts listening on `port`
+    pub fn start(port: u16, zone_count: usize) -> Result<Self, String> {
+        let server = ServerBuilder::new_anonymous("Agri-IoT Simulator")
+            .application_uri(NAMESPACE_URI)
+            .product_uri(NAMESPACE_URI)
+            .host_and_port("0.0.0.0", port)
+            .pki_dir(std::env::temp_dir().join("agri_iot_simulator-pki"))
+            .server()
+            .ok_or("invalid OPC-UA server configuration")?;
+
+        let address_space = server.address_space();
+        let zones = {
+            let mut address_space = address_space.write();
+            let ns = address_space
+                .register_namespace(NAMESPACE_URI)
+                .map_err(|_| "cannot register OPC-UA namespace")?;
+            let root = NodeId::new(ns, "AgriIoT");
+            address_space.add_folder_with_id(&root, "AgriIoT", "AgriIoT", &NodeId::objects_folder_id());
+
+            (0..zone_count)
+                .map(|i| {
+                    let name = format!("Zone{}", i);
+                    let folder = NodeId::new(ns, name.clone());
+                    address_space.add_folder_with_id(&folder, name.as_str(), name.as_str(), &root);
+                    let nodes = ZoneNodes {
+                        moisture: NodeId::new(ns, format!("{}.Moisture", name)),
+                        state: NodeId::new(ns, format!("{}.State", name)),
+                        water_pump_running: NodeId::new(ns, format!("{}.WaterPumpRunning", name)),
+                    };
+                    address_space.add_variables(
+                        vec![
+                            Variable::new(&nodes.moisture, "Moisture", "Moisture", 0f32),
+                            Variable::new(&nodes.state, "State", "State", UAString::from("")),
+                            Variable::new(&nodes.water_pump_running, "WaterPumpRunning", "WaterPumpRunning", false),
+                        ],
+                        &folder,
+                    );
+                    nodes
+                })
+                .collect()
+        };
+
+        tokio::spawn(Server::new_server_task(Arc::new(RwLock::new(server))));
+        Ok(Self { address_space, zones })
+    }
+
+    /// Writes the current readings into the address space, once per tick
+    pub fn update(&self, sensors: &[&SoilMoistureSensor]) {
+        let now = DateTime::now();
+        let mut address_space = self.address_space.write();
+        for (nodes, sensor) in self.zones.iter().zip(sensors) {
+            address_space.set_variable_value(nodes.moisture.clone(), sensor.moisture_level, &now, &now);
+            address_space.set_variable_value(nodes.state.clone(), sensor.state.name(), &now, &now);
+            address_space.set_variable_value(nodes.water_pump_running.clone(), sensor.water_running(), &now, &now);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Token-bucket rate limiting for the HTTP API
+
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::config::RateLimitConfig;
+
+/// Longest `Retry-After` sent, in seconds; a bucket refilling slower than this still asks
+/// clients to come back within the hour rather than in an unrepresentable future
+pub const MAX_RETRY_AFTER_SECS: u64 = 60 * 60;
+
+/// Token bucket shared by every request; each request takes one token
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: u32,
+    tokens: AtomicU64, // f64 bits, since std has no AtomicF64
+    refill_rate_per_ms: f64,
+    last_refill: AtomicU64, // Milliseconds since `started`
+    started: Instant,
+}
+
+impl RateLimiter {
+    /// A full bucket holding `capacity` tokens, refilled at `refill_per_second`
+    pub fn new(capacity: u32, refill_per_second: f64) -> Self {
+        Self {
+            capacity,
+            tokens: AtomicU64::new(f64::from(capacity).to_bits()),
+            refill_rate_per_ms: refill_per_second / 1000.0,
+            last_refill: AtomicU64::new(0),
+            started: Instant::now(),
+        }
+    }
+
+    /// Takes a token, or returns how many seconds until one is available
+    pub fn try_acquire(&self) -> Result<(), u64> {
+        let now = self.started.elapsed().as_millis() as u64;
+        let elapsed = now.saturating_sub(self.last_refill.swap(now, Ordering::AcqRel));
+        let refill = elapsed as f64 * self.refill_rate_per_ms;
+        let mut current = self.tokens.load(Ordering::Acquire);
+        loop {
+            let available = (f64::from_bits(current) + refill).min(f64::from(self.capacity));
+            let (remaining, result) = if available >= 1.0 {
+                (available - 1.0, Ok(()))
+            } else {
+                (available, Err(self.retry_after_secs(available)))
+            };
+            match self.tokens.compare_exchange_weak(current, remaining.to_bits(), Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return result,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Seconds until the bucket holds a token again, 1 to [`MAX_RETRY_AFTER_SECS`]
+    fn retry_after_secs(&self, available: f64) -> u64 {
+        if self.refill_rate_per_ms <= 0.0 {
+            return MAX_RETRY_AFTER_SECS;
+        }
+        let wait_ms = (1.0 - available) / self.refill_rate_per_ms;
+        ((wait_ms / 1000.0).ceil() as u64).clamp(1, MAX_RETRY_AFTER_SECS)
+    }
+}
+
+impl From<&RateLimitConfig> for RateLimiter {
+    fn from(config: &RateLimitConfig) -> Self {
+        Self::new(config.capacity, config.refill_per_second)
+    }
+}
+
+/// Middleware answering `429 Too Many Requests` once the bucket is empty
+pub async fn limit(State(limiter): State<Arc<RateLimiter>>, request: Request, next: Next) -> Response {
+    match limiter.try_acquire() {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.to_string())],
+            "rate limit exceeded",
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use axum::{middleware, Router};
+    use tokio::net::TcpListener;
+
+    /// Serves a route behind [`limit`] on a free local port and returns its URL
+    async fn serve(limiter: RateLimiter) -> String {
+        let app = Router::new()
+            .route("/api/zones", get(|| async { "[]" }))
+            .route_layer(middleware::from_fn_with_state(Arc::new(limiter), limit));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api/zones", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    #[test]
+    fn a_full_bucket_allows_a_burst_of_its_capacity() {
+        let limiter = RateLimiter::new(3, 0.001);
+        assert!((0..3).all(|_| limiter.try_acquire().is_ok()));
+        assert!(limiter.try_acquire().is_err());
+    }
+
+    #[test]
+    fn retry_after_covers_the_wait_for_one_token() {
+        let limiter = RateLimiter::new(1, 0.5);
+        limiter.try_acquire().unwrap();
+        assert_eq!(limiter.try_acquire(), Err(2));
+    }
+
+    #[test]
+    fn retry_after_is_clamped() {
+        assert_eq!(RateLimiter::new(1, 0.0).retry_after_secs(0.0), MAX_RETRY_AFTER_SECS);
+        assert_eq!(RateLimiter::new(1, 1e-9).retry_after_secs(0.0), MAX_RETRY_AFTER_SECS);
+        assert_eq!(RateLimiter::new(1, 1e6).retry_after_secs(0.999), 1);
+    }
+
+    #[tokio::test]
+    async fn exceeding_the_rate_returns_429() {
+        let url = serve(RateLimiter::new(2, 0.01)).await;
+        let client = reqwest::Client::new();
+        for _ in 0..2 {
+            assert_eq!(client.get(&url).send().await.unwrap().status(), StatusCode::OK);
+        }
+        let response = client.get(&url).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "100");
+    }
+}
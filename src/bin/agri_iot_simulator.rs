@@ -0,0 +1,1957 @@
+use agri_iot_simulator::alerts::discord::DiscordAlerter;
+use agri_iot_simulator::alerts::email::{AlertConfig, EmailAlerter};
+use agri_iot_simulator::alerts::ntfy::{NtfyAlerter, NtfyPriority};
+use agri_iot_simulator::alerts::slack::SlackAlerter;
+use agri_iot_simulator::alerts::telegram::TelegramBot;
+use agri_iot_simulator::api::grpc::GrpcServer;
+use agri_iot_simulator::api::http::HttpServer;
+use agri_iot_simulator::api::gateway::{GatewayManager, LoadBalancingStrategy};
+use agri_iot_simulator::api::modbus::ModbusTcpServer;
+use agri_iot_simulator::api::opcua::OpcUaServer;
+use agri_iot_simulator::calibration::SensorCalibration;
+use agri_iot_simulator::climate::microclimate::MIN_VENTILATION_RATE;
+use agri_iot_simulator::climate::forecast::WeatherForecastStub;
+use agri_iot_simulator::climate::{DaylightModel, MicroclimateModel, WeatherStation};
+use agri_iot_simulator::clock::SimulationClock;
+use agri_iot_simulator::config::{
+    self, AlertHistoryConfig, CarbonConfig, EmitterConfig, EnergyConfig, MicroclimateConfig, OfflineCacheConfig,
+    SimulationConfig,
+};
+use agri_iot_simulator::cost_benefit::{self, CostBenefitAnalyzer};
+use agri_iot_simulator::diagnostics;
+use agri_iot_simulator::export::DataExportManager;
+use agri_iot_simulator::crop::calendar::CropCalendar;
+use agri_iot_simulator::crop::cover_crop::CoverCropModel;
+use agri_iot_simulator::crop::cwsi::{estimate_canopy_temp, vapour_pressure_deficit_kpa};
+use agri_iot_simulator::crop::disease::{diurnal_conditions, PestDiseaseRiskCalculator};
+use agri_iot_simulator::crop::fusion::SensorFusion;
+use agri_iot_simulator::crop::germination::GerminationModel;
+use agri_iot_simulator::crop::harvest::HarvestEvent;
+use agri_iot_simulator::crop::rotation::{self, CropRotation};
+use agri_iot_simulator::crop::stage::SeasonalThresholdAdjuster;
+use agri_iot_simulator::crop::wue::WueTracker;
+use agri_iot_simulator::crop::yield_estimator::YieldEstimator;
+use agri_iot_simulator::crop::yield_map::YieldMapGenerator;
+use agri_iot_simulator::crop::GrowthStage;
+use agri_iot_simulator::event_bus::{EventBus, Subscriber};
+use agri_iot_simulator::events::{AgriEvent, AlertSeverity};
+use agri_iot_simulator::crop::nitrogen::{NitrogenFixationModel, SOIL_MASS_KG_HA};
+use agri_iot_simulator::hydroponic::{AeroponicController, HydroponicState, NpkSensor, NutrientSolutionMonitor};
+use agri_iot_simulator::irrigation::{
+    BackflowPreventer, DroughtStressTracker, EnergyModel, FilterModel, FrostProtectionController, IrrigationOptimizer,
+    IrrigationScheduler, MaintenanceItem, MaintenanceScheduler, MaintenanceStatus, PipeNetwork, RainwaterHarvester,
+    SurgeIrrigationController, WaterBudget, WaterPump,
+};
+use agri_iot_simulator::network::{self, NetworkLink, QosBuffer};
+use agri_iot_simulator::offline_cache::{CachedReading, OfflineCache};
+use agri_iot_simulator::replay::ReplayController;
+use agri_iot_simulator::ghg::GhgTracker;
+use agri_iot_simulator::soil::biochar;
+use agri_iot_simulator::soil::van_genuchten::VanGenuchten;
+use agri_iot_simulator::soil::wetting_front::WettingFrontTracker;
+use agri_iot_simulator::soil::carbon::CarbonTracker;
+use agri_iot_simulator::soil::drainage::DrainageTile;
+use agri_iot_simulator::soil::nitrogen::NitrogenPool;
+use agri_iot_simulator::soil::nutrient_leaching::LeachingModel;
+use agri_iot_simulator::soil::organic_matter::SomTracker;
+use agri_iot_simulator::soil::respiration::SoilRespirationModel;
+use agri_iot_simulator::soil::sampling::{take_soil_sample, SoilChemistry};
+use agri_iot_simulator::soil::trace_elements::TraceElementMonitor;
+use agri_iot_simulator::systemd;
+use agri_iot_simulator::tui::art::{style_root_line, ROOTS_BASE};
+use agri_iot_simulator::tui::mist::MistCountdown;
+use agri_iot_simulator::tui::{
+    handle_input, handle_mouse, render, Charset, Theme, UiState, AIR_TEMP_C, COLD_SNAP_TEMP_C, ET0_MM_PER_DAY, VPD_KPA,
+};
+use agri_iot_simulator::wal::{WalEntry, WriteAheadLog};
+use agri_iot_simulator::water_volume::{self, WaterSource, WaterVolumeTracker};
+use agri_iot_simulator::agri_iot_core::moisture_profile::MoistureProfiler;
+use agri_iot_simulator::agri_iot_core::trend::TrendDetector;
+use agri_iot_simulator::simulation::TICK_INTERVAL;
+use agri_iot_simulator::{
+    DeviceState, IrrigationZone, PHSensor, Simulation, SoilMoistureSensor, SoilTemperatureSensor, TensiometerSensor,
+    Tick, WATERING_BOOST,
+};
+use clap::Parser;
+use rand::rngs::ThreadRng;
+use rand::Rng;
+use tokio::time::Duration;
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+use chrono::{Days, NaiveDate, NaiveTime, TimeDelta, Utc};
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::execute;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Ticks between WAL compactions, which keep the log from growing without bound
+const WAL_COMPACT_TICKS: u64 = 3600;
+
+/// Cached readings replayed per tick once the uplink is back, half the MQTT client's queue
+const CACHE_FLUSH_BATCH: usize = 32;
+
+/// Simulated hours irrigation stays cut back after a falling barometer forecasts rain
+const RAIN_WARNING_HOURS: i64 = 6;
+
+/// Share of the usual watering volume applied while rain is forecast
+const RAIN_WARNING_VOLUME_FACTOR: f32 = 0.5;
+
+/// Seconds in one simulated year, over which soil organic matter rates are given
+const SECONDS_PER_YEAR: f32 = 365.0 * 86_400.0;
+
+/// Ticks each strategy is run for by `--cost-report`: a simulated day of one-second ticks
+const COST_REPORT_TICKS: u64 = 86_400;
+
+/// Seed shared by every `--cost-report` run, so the strategies see the same evaporation
+const COST_REPORT_SEED: u64 = 42;
+
+/// Command-line options
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    /// TOML file with simulation settings
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Print the JSON Schema for the config file and exit
+    #[arg(long)]
+    export_schema: bool,
+
+    /// Print a systemd unit that runs the simulator with the other given options, and exit
+    #[arg(long)]
+    generate_systemd: bool,
+
+    /// Use only high-contrast colors and spell out states as text labels
+    #[arg(long)]
+    high_contrast: bool,
+
+    /// Draw with ASCII characters only, for terminals without Unicode support
+    #[arg(long)]
+    ascii_only: bool,
+
+    /// Simulate a recirculating nutrient solution instead of soil moisture
+    #[arg(long)]
+    hydroponic: bool,
+
+    /// Mist the roots in short cycles instead of recirculating; implies --hydroponic
+    #[arg(long)]
+    aeroponic: bool,
+
+    /// Log zone state to this write-ahead log each tick and resume from it on startup
+    #[arg(long)]
+    wal: Option<PathBuf>,
+
+    /// Append each zone's water totals by source to this CSV file daily and on exit
+    #[arg(long)]
+    water_csv: Option<PathBuf>,
+
+    /// Play back a JSON lines export (an `[[export]]` with format "json") instead of simulating
+    #[arg(long)]
+    replay: Option<PathBuf>,
+
+    /// Print the pump's energy use per simulated hour as CSV on exit
+    #[arg(long)]
+    energy_report: bool,
+
+    /// Print the annual soil carbon balance as CSV on exit
+    #[arg(long)]
+    carbon_report: bool,
+
+    /// Compare the costs and returns of full and deficit irrigation headlessly, print them as a
+    /// table and write them to this CSV file, then exit
+    #[arg(long)]
+    cost_report: Option<PathBuf>,
+
+    /// Withhold irrigation on days before rain forecast in this CSV file
+    #[arg(long)]
+    forecast_file: Option<PathBuf>,
+
+    /// Simulated seconds per wall-clock second; 3600 runs an hour of the field each tick
+    #[arg(long, default_value_t = 1.0)]
+    time_acceleration: f32,
+
+    /// Append a summary row to this CSV file at every harvest
+    #[arg(long)]
+    season_csv: Option<PathBuf>,
+
+    /// Serve zone moisture and pump state over Modbus TCP on this port
+    #[arg(long)]
+    modbus_port: Option<u16>,
+
+    /// Serve the zone node tree over OPC-UA on this port
+    #[arg(long)]
+    opcua_port: Option<u16>,
+
+    /// Serve the gRPC SensorService on this port
+    #[arg(long)]
+    grpc_port: Option<u16>,
+
+    /// Serve the web dashboard and JSON API on this port
+    #[arg(long)]
+    http_port: Option<u16>,
+
+    /// SMTP relay for critical event emails (enables email alerts)
+    #[arg(long, requires_all = ["smtp_from", "smtp_to"])]
+    smtp_host: Option<String>,
+
+    /// SMTP submission port
+    #[arg(long, default_value_t = 587)]
+    smtp_port: u16,
+
+    /// Sender address for alert emails
+    #[arg(long)]
+    smtp_from: Option<String>,
+
+    /// Recipient address for alert emails (repeatable)
+    #[arg(long)]
+    smtp_to: Vec<String>,
+
+    /// SMTP login user name (leave unset for an unauthenticated relay)
+    #[arg(long, env = "AGRI_SMTP_USERNAME")]
+    smtp_username: Option<String>,
+
+    /// SMTP login password
+    #[arg(long, env = "AGRI_SMTP_PASSWORD", hide_env_values = true)]
+    smtp_password: Option<String>,
+
+    /// Slack incoming webhook URL for alert messages (enables Slack alerts)
+    #[arg(long)]
+    slack_webhook: Option<String>,
+
+    /// Slack channel to post to instead of the webhook's default
+    #[arg(long)]
+    slack_channel: Option<String>,
+
+    /// Lowest event severity posted to Slack
+    #[arg(long, value_enum, default_value_t = AlertSeverity::Warning)]
+    slack_severity: AlertSeverity,
+
+    /// Telegram bot token for critical alerts (enables Telegram alerts)
+    #[arg(long, env = "AGRI_TELEGRAM_TOKEN", hide_env_values = true, requires = "telegram_chat_id")]
+    telegram_token: Option<String>,
+
+    /// Telegram chat that receives the alerts
+    #[arg(long, allow_negative_numbers = true)]
+    telegram_chat_id: Option<i64>,
+
+    /// Discord channel webhook URL for alert embeds (enables Discord alerts)
+    #[arg(long)]
+    discord_webhook: Option<String>,
+
+    /// ntfy topic for push notifications (enables ntfy alerts)
+    #[arg(long)]
+    ntfy_topic: Option<String>,
+
+    /// ntfy server hosting the topic
+    #[arg(long, default_value = "https://ntfy.sh")]
+    ntfy_server: String,
+
+    /// Lowest ntfy priority published (critical events are urgent, warnings default)
+    #[arg(long, value_enum, default_value_t = NtfyPriority::Default)]
+    ntfy_priority: NtfyPriority,
+}
+
+/// Runs the TUI loop for `--hydroponic` until the user quits
+async fn run_hydroponic(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    theme: Theme,
+    charset: Charset,
+    mut mister: Option<AeroponicController>,
+) -> io::Result<()> {
+    let mut monitor = NutrientSolutionMonitor::new();
+    let mut status_message = String::new();
+
+    loop {
+        if let Some(msg) = monitor.step() {
+            status_message = msg;
+        }
+        if let Some(mister) = &mut mister {
+            mister.step(&mut monitor);
+        }
+        tokio::time::sleep(TICK_INTERVAL).await;
+
+        terminal.draw(|f| {
+            let mister_height = if mister.is_some() { 4 } else { 0 };
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .margin(1)
+                .constraints([Constraint::Percentage(40), Constraint::Length(mister_height), Constraint::Min(0)])
+                .split(f.size());
+
+            let text = format!(
+                "State: {:?}\nEC: {:.2} dS/m  pH: {:.2}\nDO: {:.1} ppm  Temp: {:.1} {}  Flow: {:.1} L/min\n\
+                 N: {:.0} ppm  P: {:.0} ppm  K: {:.0} ppm\nStatus: {}",
+                monitor.state,
+                monitor.ec_ds_m,
+                monitor.ph,
+                monitor.dissolved_oxygen_ppm,
+                monitor.temp_c,
+                charset.celsius(),
+                monitor.flow_rate_lpm,
+                monitor.npk.nitrate_ppm,
+                monitor.npk.phosphate_ppm,
+                monitor.npk.potassium_ppm,
+                status_message
+            );
+            let mode = if mister.is_some() { "aeroponic" } else { "hydroponic" };
+            let status = Paragraph::new(text)
+                .block(charset.block(format!("Agri-IoT Simulator ({mode})")))
+                .style(Style::default().fg(Color::White));
+            f.render_widget(status, chunks[0]);
+            if let Some(mister) = &mister {
+                f.render_widget(MistCountdown::new(mister).theme(theme).charset(charset), chunks[1]);
+            }
+
+            let root_lines: Vec<Line> = ROOTS_BASE
+                .lines()
+                .map(|line| style_root_line(line, monitor.animation_frame, monitor.state, theme))
+                .collect();
+            let roots = Paragraph::new(Text::from(root_lines))
+                .block(charset.block("Root System"));
+            f.render_widget(roots, chunks[2]);
+        })?;
+
+        if event::poll(Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                if key.code == KeyCode::Char('q') {
+                    return Ok(());
+                }
+                if key.code == KeyCode::Char('e') {
+                    monitor.state = HydroponicState::Error;
+                }
+            }
+        }
+    }
+}
+
+/// Runs the backflow test if it is due or `manual`, raising a fault in every zone if it fails
+fn test_backflow_preventer(
+    ui: &mut UiState,
+    zones: &[IrrigationZone],
+    events: &EventBus,
+    rng: &mut impl Rng,
+    manual: bool,
+) {
+    let Some(backflow) = &mut ui.backflow else {
+        return;
+    };
+    let tick = zones.first().map_or(Tick::ZERO, |zone| zone.sensor.current_tick);
+    let passed = if manual { Some(backflow.run_test(tick, true, rng)) } else { backflow.step(tick, rng) };
+    let bfp_type = backflow.type_;
+    match passed {
+        Some(false) => {
+            ui.push_status(format!(
+                "Backflow preventer ({}) failed its test, irrigation blocked; press a to acknowledge",
+                bfp_type.name()
+            ));
+            for zone in zones {
+                events.publish(AgriEvent::BackflowFault {
+                    zone_id: zone.id,
+                    moisture_level: zone.sensor.moisture_level,
+                    state: zone.sensor.state,
+                    bfp_type,
+                });
+            }
+        }
+        Some(true) if manual => ui.push_status(format!("Backflow preventer ({}) passed its test", bfp_type.name())),
+        _ => {}
+    }
+}
+
+/// Canopy temperature the zone's infrared thermometer reads. The crop stresses once the soil
+/// is below a threshold that rises with evaporative demand, so on a hot, dry afternoon the
+/// canopy warms before the soil sensor reaches its own threshold.
+fn simulated_canopy_temp(sensor: &SoilMoistureSensor, air_temp_c: f32, vpd_kpa: f32) -> f32 {
+    let demand_threshold = sensor.threshold * vpd_kpa / VPD_KPA;
+    let stress_index = (demand_threshold - sensor.moisture_level) / demand_threshold.max(f32::EPSILON);
+    estimate_canopy_temp(air_temp_c, vpd_kpa, stress_index)
+}
+
+/// Tells the operator and every zone's subscribers that the season's water budget is used up
+fn report_budget_exceeded(ui: &mut UiState, zones: &[IrrigationZone], events: &EventBus) {
+    let Some(budget) = &ui.water_budget else {
+        return;
+    };
+    let (used_mm, limit_mm) = (budget.used_mm, budget.seasonal_limit_mm);
+    for zone in zones {
+        events.publish(AgriEvent::BudgetExceeded {
+            zone_id: zone.id,
+            moisture_level: zone.sensor.moisture_level,
+            state: zone.sensor.state,
+            used_mm,
+            limit_mm,
+        });
+    }
+    ui.push_status(format!(
+        "Water budget of {limit_mm:.0} mm used up, irrigation stopped until the next season; frost protection continues"
+    ));
+}
+
+/// Ends the season on `day`: summarizes it, records its yield and resets every seasonal accumulator
+fn harvest(
+    ui: &mut UiState,
+    zones: &mut [IrrigationZone],
+    accumulated_gdd: &mut f32,
+    yield_estimator: &mut YieldEstimator,
+    season: u32,
+    day: u16,
+) -> HarvestEvent {
+    let zone_count = zones.len().max(1) as f32;
+    let final_stress_index = zones.iter().map(|zone| zone.stress.stress_index()).sum::<f32>() / zone_count;
+    let estimated_yield_t_ha =
+        yield_estimator.estimate_yield(final_stress_index, *accumulated_gdd / ui.crop.season_gdd);
+    let event = HarvestEvent {
+        season,
+        day,
+        total_water_mm: zones.iter().map(|zone| zone.water.total_mm()).sum::<f32>() / zone_count,
+        seasonal_et_mm: ui.et.season_total_mm(),
+        gdd: *accumulated_gdd,
+        estimated_yield_t_ha,
+        final_stress_index,
+    };
+    yield_estimator.record_season(estimated_yield_t_ha);
+    // Everything but the harvested grain is worked back into the soil
+    let residue_t_ha = estimated_yield_t_ha * (1.0 - ui.crop.harvest_index) / ui.crop.harvest_index;
+    let som_gain_pct = ui.som.incorporate_residue(residue_t_ha);
+    if let Some(carbon) = &mut ui.carbon {
+        carbon.humify(som_gain_pct);
+    }
+    // The next season starts from a fresh pool holding only what this crop leaves behind
+    ui.nitrogen = NitrogenPool::default();
+    rotation::credit_previous_crop(&ui.crop, residue_t_ha, &mut ui.nitrogen);
+    for zone in zones {
+        zone.stress = DroughtStressTracker::default();
+        zone.wue = WueTracker::default();
+        zone.water = WaterVolumeTracker::new(zone.sensor.current_tick);
+    }
+    ui.et.reset_seasonal();
+    // Disease carries over into a following crop of the same family
+    if ui.rotation.as_ref().is_none_or(|rotation| rotation.upcoming().is_break_crop(&ui.crop)) {
+        ui.disease.reset();
+    }
+    if let Some(energy) = &mut ui.energy {
+        energy.reset_season();
+    }
+    if let Some(ghg) = &mut ui.ghg {
+        let report = format!("Season {season} emissions: {}", ghg.close_season().summary(ghg.target_kg_co2e_ha));
+        ui.push_status(report);
+    }
+    if let Some(harvester) = &mut ui.rainwater {
+        harvester.reset_season();
+    }
+    if let Some(budget) = &mut ui.water_budget {
+        budget.reset_season();
+    }
+    if let Some(legume) = &mut ui.nitrogen_fixation {
+        let report = format!(
+            "Season {season} nitrogen fixed by {}: {:.1} kg N/ha",
+            legume.legume_profile.name, legume.season_fixed_n_kg_ha
+        );
+        legume.reset_season();
+        ui.push_status(report);
+    }
+    *accumulated_gdd = 0.0;
+    ui.growth_stage = GrowthStage::default();
+    // The next crop is sown straight away and has to germinate again
+    if let Some(germination) = &mut ui.germination {
+        germination.reset();
+        ui.growth_stage = GrowthStage::Germination;
+    }
+    ui.estimated_yield_t_ha = yield_estimator.potential_yield_t_ha;
+    ui.push_status(event.summary());
+    event
+}
+
+/// Spreads the nitrogen the last soil sample called for, counting its emissions
+fn apply_recommended_nitrogen(ui: &mut UiState) {
+    let Some(sample) = &mut ui.last_soil_sample else {
+        ui.push_status("Take a soil sample (S) before fertilizing".to_string());
+        return;
+    };
+    let n_kg_ha = std::mem::take(&mut sample.recommendation.n_kg_ha);
+    if n_kg_ha <= 0.0 {
+        ui.push_status("The last soil sample called for no nitrogen".to_string());
+        return;
+    }
+    ui.nitrogen.mineral_n += n_kg_ha;
+    let emissions = ui.ghg.as_mut().map_or(0.0, |ghg| ghg.record_fertilizer(n_kg_ha));
+    ui.push_status(format!("Applied {n_kg_ha:.0} kg N/ha ({emissions:.0} kg CO2e/ha)"));
+}
+
+/// Restores zones from the log's last entries, then compacts it to just those
+fn recover_from_wal(path: &Path, zones: &mut [IrrigationZone], ui: &mut UiState) -> io::Result<WriteAheadLog> {
+    let mut wal = WriteAheadLog::open(path)?;
+    let entries = wal.recover()?;
+    for entry in &entries {
+        if let Some(zone) = zones.iter_mut().find(|zone| zone.id == entry.zone_id) {
+            zone.sensor.state = entry.state_snapshot.state;
+            zone.sensor.moisture_level = entry.state_snapshot.moisture_level;
+            zone.sensor.current_tick = entry.tick;
+        }
+    }
+    if let Some(last) = entries.iter().map(|entry| entry.tick).max() {
+        ui.push_status(format!("Recovered {} zone(s) from the WAL at {last}", entries.len()));
+    }
+    wal.compact(&entries)?;
+    Ok(wal)
+}
+
+/// Appends every zone's state and syncs, compacting the log once an hour
+fn log_tick(wal: &mut WriteAheadLog, zones: &[IrrigationZone]) -> io::Result<()> {
+    let entries: Vec<WalEntry> = zones
+        .iter()
+        .map(|zone| WalEntry { tick: zone.sensor.current_tick, zone_id: zone.id, state_snapshot: zone.snapshot() })
+        .collect();
+    if zones.first().is_some_and(|zone| zone.sensor.current_tick.0 % WAL_COMPACT_TICKS == 0) {
+        return wal.compact(&entries);
+    }
+    for entry in &entries {
+        wal.append(entry)?;
+    }
+    wal.commit()
+}
+
+/// Warms or cools the structure's air over one tick and moves the night vents. By day the vents
+/// are as configured; after dark they stay shut unless night ventilation opens them.
+fn update_inside_air(ui: &mut UiState, model: MicroclimateModel, hour_of_day: f32, step_s: f32) {
+    let ventilation_rate = match &ui.night_ventilation {
+        Some(_) if ui.daylight.is_daytime(hour_of_day) => model.ventilation_rate,
+        Some(night) => night.ventilation_rate().unwrap_or(MIN_VENTILATION_RATE),
+        None => model.ventilation_rate,
+    };
+    let solar_rad = ui.daylight.solar_radiation_w_m2(hour_of_day);
+    let inside_temp_c = model.step_inside_temp_c(
+        ui.air_temp.temperature_c,
+        ui.outside_temp_c,
+        solar_rad,
+        ventilation_rate,
+        step_s,
+    );
+    ui.air_temp.record(inside_temp_c);
+    let moved = ui.night_ventilation.as_mut().and_then(|night| night.update(hour_of_day, inside_temp_c));
+    let celsius = ui.charset.celsius();
+    match moved {
+        Some(true) => ui.push_status(format!("Night vents opened at {inside_temp_c:.1} {celsius}")),
+        Some(false) => ui.push_status(format!("Night vents closed at {inside_temp_c:.1} {celsius}")),
+        None => {}
+    }
+}
+
+/// Water used by a finished surge cycle compared with continuous flow
+fn surge_report(surge: &SurgeIrrigationController) -> String {
+    let advances: Vec<String> = surge.surge_advance_m.iter().map(|advance| format!("{advance:.0}")).collect();
+    if !surge.reached_end() {
+        return format!(
+            "Surge cycle ended {:.0} m short of the basin end (advances {} m)",
+            surge.basin.length_m - surge.front_m,
+            advances.join("/")
+        );
+    }
+    format!(
+        "Surge cycle wetted the basin with {:.0} L, {:.0}% less than continuous flow (advances {} m)",
+        surge.water_applied_l(),
+        surge.water_saving_fraction() * 100.0,
+        advances.join("/")
+    )
+}
+
+/// Runs the configured zone under each compared strategy, then prints and writes what each costs
+fn write_cost_report(path: &Path, config: &SimulationConfig) -> io::Result<()> {
+    let analyzer = config.cost_benefit.as_ref().map_or_else(CostBenefitAnalyzer::default, CostBenefitAnalyzer::from);
+    let rows: Vec<_> = cost_benefit::COMPARED_STRATEGIES
+        .into_iter()
+        .map(|strategy| {
+            let mut simulation = Simulation::new(1, config.threshold, COST_REPORT_SEED);
+            simulation.crop = config.crop.profile();
+            simulation.strategy = strategy;
+            (strategy, analyzer.analyze(&simulation.run_for(COST_REPORT_TICKS)))
+        })
+        .collect();
+    print!("{}", cost_benefit::comparison_table(&rows));
+    cost_benefit::write_comparison_csv(File::create(path)?, &rows)
+}
+
+/// Plays `--replay` back through the TUI until the user quits; nothing is simulated. The zones
+/// take the recording's sensors in the order they first appear, and any left over are ignored.
+fn run_replay(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    ui: &mut UiState,
+    zones: &mut [IrrigationZone],
+    clock: &SimulationClock,
+) -> io::Result<()> {
+    let sensor_ids: Vec<String> = match &ui.replay {
+        Some(replay) => replay.sensor_ids().into_iter().map(str::to_string).collect(),
+        None => return Ok(()),
+    };
+    let mut last_frame = Instant::now();
+    let mut recorded_tick = None;
+    while !ui.quit {
+        let Some(replay) = &mut ui.replay else {
+            break;
+        };
+        replay.advance(last_frame.elapsed());
+        last_frame = Instant::now();
+        for (zone, sensor_id) in zones.iter_mut().zip(&sensor_ids) {
+            if let Some(entry) = replay.latest(sensor_id) {
+                zone.sensor.moisture_level = entry.moisture_level;
+                zone.sensor.state = entry.state;
+                zone.sensor.current_tick = entry.tick;
+                zone.sensor.animation_frame = (entry.tick.0 % 2) as usize;
+            }
+        }
+        let tick = replay.current_tick();
+        ui.simulated_now = clock.simulated_at(tick);
+        if recorded_tick != Some(tick) {
+            recorded_tick = Some(tick);
+            ui.record_tick(zones);
+        }
+        render(ui, zones, terminal)?;
+        poll_input(ui, zones)?;
+    }
+    Ok(())
+}
+
+/// Applies the key press or mouse event that arrives within 100 ms, if any
+fn poll_input(ui: &mut UiState, zones: &mut [IrrigationZone]) -> io::Result<()> {
+    if event::poll(Duration::from_millis(100))? {
+        match event::read()? {
+            Event::Key(key) => handle_input(key, ui, zones),
+            Event::Mouse(mouse) => handle_mouse(mouse, ui),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Appends every zone's cumulative water totals and the day's ET, dated with the simulated `date`
+fn write_water_csv(path: &Path, zones: &[IrrigationZone], date: NaiveDate, daily_et_mm: f32) -> io::Result<()> {
+    let rows: Vec<_> = zones.iter().map(|zone| (zone.id, &zone.water)).collect();
+    water_volume::append_csv(path, &date.to_string(), daily_et_mm, &rows)
+}
+
+/// Raw mode and the alternate screen with mouse capture, left again when dropped so that every
+/// way out of `main`, an error or a panic included, hands the terminal back as it was
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let guard = Self;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // Nothing more can be done about a terminal that will not restore
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+    }
+}
+
+/// Everything the simulation loop carries from one tick to the next
+struct SimulationState {
+    config: SimulationConfig,
+    clock: SimulationClock,
+    events: EventBus,
+    zones: Vec<IrrigationZone>,
+    ui: UiState,
+    rng: ThreadRng,
+    pump: WaterPump,
+    adjuster: SeasonalThresholdAdjuster,
+    scheduler: Option<IrrigationScheduler>,
+    forecast: Option<WeatherForecastStub>,
+    calendar: Option<CropCalendar>,
+    yield_map: Option<YieldMapGenerator>,
+    yield_estimator: YieldEstimator,
+    retention: f32, // Biochar's and any incorporated cover crop's, before organic matter's
+    gdd_per_tick: f32,
+    accumulated_gdd: f32,
+    season: u32,
+    season_csv: Option<PathBuf>,
+    water_csv: Option<PathBuf>,
+    wal: Option<WriteAheadLog>,
+    offline_cache: Option<OfflineCache>,
+    exports: DataExportManager,
+    modbus: Option<ModbusTcpServer>,
+    opcua: Option<OpcUaServer>,
+    grpc: Option<GrpcServer>,
+    http: Option<HttpServer>,
+    mqtt: Option<GatewayManager>,
+    uplink: Option<Subscriber>, // Events bound for the broker, when the uplink is simulated
+    alert_feed: Subscriber,     // Every event, for the alert history
+}
+
+/// What the weather and the crop are like on one tick, for the steps after them
+#[derive(Debug, Clone, Copy)]
+struct TickConditions {
+    day: u16,
+    in_season: bool,
+    stage: GrowthStage,
+    outside_temp_c: f32, // The air the crop stands in outdoors
+    temp_c: f32,         // The air around the crop, inside any structure
+    humidity_pct: f32,
+}
+
+impl SimulationState {
+    /// Acts on the alert acknowledgement, irrigation scheduling and pause the last key presses
+    /// asked for; these work while paused too
+    fn handle_requests(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Self { clock, events, zones, ui, .. } = self;
+        if std::mem::take(&mut ui.alert_ack_requested) {
+            let tick = zones.first().map_or(Tick::ZERO, |zone| zone.sensor.current_tick);
+            match ui.alert_history.acknowledge_oldest(tick)? {
+                Some(alert) => {
+                    let zone = zones.iter().find(|zone| zone.id == alert.zone);
+                    let moisture_level = zone.map_or(0.0, |zone| zone.sensor.moisture_level);
+                    let state = zone.map_or(DeviceState::Monitoring, |zone| zone.sensor.state);
+                    events.publish(AgriEvent::AlertAcknowledged {
+                        zone_id: alert.zone,
+                        moisture_level,
+                        state,
+                        alert_id: alert.id,
+                        alert_title: alert.message.clone(),
+                    });
+                    let message = format!("Acknowledged alert {}: {} (zone {})", alert.id, alert.message, alert.zone);
+                    ui.push_status(message);
+                }
+                None => ui.push_status("No alerts to acknowledge".to_string()),
+            }
+        }
+        if std::mem::take(&mut ui.irrigation_accept_requested) {
+            let tick = zones.first().map_or(Tick::ZERO, |zone| zone.sensor.current_tick);
+            match (zones.first(), ui.optimizer.suggest_next_irrigation_tick(tick)) {
+                (Some(zone), Some(start_tick)) => {
+                    ui.scheduled_irrigation = Some(start_tick);
+                    events.publish(AgriEvent::IrrigationScheduled {
+                        zone_id: zone.id,
+                        moisture_level: zone.sensor.moisture_level,
+                        state: zone.sensor.state,
+                        start_tick: start_tick.0,
+                    });
+                    ui.push_status(format!("Irrigation of zone {} scheduled for tick {}", zone.id, start_tick.0));
+                }
+                _ => ui.push_status("No irrigation to schedule, the soil is not drying".to_string()),
+            }
+        }
+        if std::mem::take(&mut ui.pause_requested) {
+            if clock.is_paused() {
+                clock.resume();
+                ui.push_status("Simulation resumed".to_string());
+            } else {
+                clock.pause();
+                ui.push_status("Simulation paused, p to resume".to_string());
+            }
+        }
+        ui.paused = clock.is_paused();
+        Ok(())
+    }
+
+    /// Runs the next tick unless paused, or in step mode before `t` asks for it; returns whether it ran
+    fn advance_if_due(&mut self) -> Result<bool, Box<dyn std::error::Error>> {
+        let ui = &mut self.ui;
+        let due = !ui.paused && (!ui.step_mode || std::mem::take(&mut ui.step_requested));
+        if due {
+            self.advance_one_tick()?;
+        }
+        Ok(due)
+    }
+
+    /// Runs the field through one tick, then hands the readings to every integration
+    fn advance_one_tick(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let conditions = self.update_weather_and_growth();
+        let retention = self.update_crop_and_soil(conditions)?;
+        self.apply_water_inputs(conditions);
+        let budget_exceeded = self.irrigate_zones(conditions, retention);
+        self.sample_and_flush_soil(conditions, budget_exceeded)?;
+        self.run_equipment();
+        self.write_daily_reports(conditions)?;
+        self.publish_tick()
+    }
+
+    /// Moves the clock, weather, structure climate and crop stage on, and reports the
+    /// germination and disease risk they bring
+    fn update_weather_and_growth(&mut self) -> TickConditions {
+        let Self { clock, events, zones, ui, rng, calendar, gdd_per_tick, accumulated_gdd, .. } = self;
+        clock.tick_count = zones.first().map_or(Tick::ZERO, |zone| zone.sensor.current_tick);
+        ui.simulated_now = clock.simulated_now();
+        let day = clock.day();
+        let in_season = calendar.is_none_or(|calendar| calendar.in_season(day));
+        let germinated = ui.germination.is_none_or(|germination| germination.is_complete());
+        if in_season && germinated {
+            *accumulated_gdd += *gdd_per_tick;
+        }
+        let stage = if germinated { GrowthStage::at(&ui.crop, *accumulated_gdd) } else { GrowthStage::Germination };
+        if stage != ui.growth_stage {
+            ui.growth_stage = stage;
+            ui.push_status(format!("{} entered the {} stage", ui.crop.name, stage.name()));
+        }
+        // The coming tick is still on this day even when it is the day's last, which the CSV then logs
+        ui.et.record(day, ET0_MM_PER_DAY, ui.crop.crop_coefficient, clock.tick_days());
+        let hour_of_day = clock.hour_of_day();
+        let (mut outside_temp_c, mut humidity_pct) = diurnal_conditions(AIR_TEMP_C, hour_of_day);
+        if let Some(station) = &mut ui.weather_station {
+            let sky = station.engine.condition;
+            let clear_sky_w_m2 = ui.daylight.solar_radiation_w_m2(hour_of_day);
+            station.tick(outside_temp_c, humidity_pct, clear_sky_w_m2, clock.tick_hours(), rng);
+            outside_temp_c = station.temperature.temperature_c;
+            humidity_pct = station.humidity.relative_humidity_pct;
+            // Hand the rain over a millimetre at a time rather than as a drizzle on every tick
+            let rain_mm = station.rainfall.take_precipitation_mm();
+            if rain_mm >= 1.0 {
+                ui.rain_gauge.record(rain_mm);
+            } else {
+                station.rainfall.record(rain_mm);
+            }
+            let now_sky = station.engine.condition;
+            if now_sky != sky {
+                ui.push_status(format!("Weather: {} turned {}", sky.name(), now_sky.name().to_lowercase()));
+            }
+        }
+        let now = clock.simulated_now();
+        if ui.rain_warning_until.is_some_and(|until| now >= until) {
+            ui.rain_warning_until = None;
+        }
+        if ui.cold_snap_until.is_some_and(|until| now >= until) {
+            ui.cold_snap_until = None;
+            ui.push_status("Cold snap over".to_string());
+        }
+        if ui.cold_snap_until.is_some() {
+            outside_temp_c = outside_temp_c.min(COLD_SNAP_TEMP_C);
+        }
+        let pressure = ui.weather_station.as_ref().map(|station| &station.pressure);
+        let falling = pressure.filter(|pressure| pressure.rain_likely());
+        if let (Some(pressure), None) = (falling, ui.rain_warning_until) {
+            let trend_hpa_per_h = pressure.trend_hpa_per_h;
+            for zone in zones.iter() {
+                events.publish(AgriEvent::RainForecastWarning {
+                    zone_id: zone.id,
+                    moisture_level: zone.sensor.moisture_level,
+                    state: zone.sensor.state,
+                    trend_hpa_per_h,
+                });
+            }
+            ui.rain_warning_until = Some(now + TimeDelta::hours(RAIN_WARNING_HOURS));
+            ui.push_status(format!(
+                "Pressure falling {:.1} hPa/h: rain likely, irrigation halved for {RAIN_WARNING_HOURS} h",
+                -trend_hpa_per_h
+            ));
+        }
+        ui.outside_temp_c = outside_temp_c;
+        if let Some(model) = ui.microclimate {
+            update_inside_air(ui, model, hour_of_day, clock.tick_seconds());
+        } else {
+            ui.air_temp.record(outside_temp_c);
+        }
+        let temp_c = ui.air_temp.temperature_c;
+        if let (Some(germination), Some(seedbed)) = (&mut ui.germination, &mut ui.seedbed) {
+            let soil_temp_c = seedbed.update(outside_temp_c, clock.tick_hours());
+            if in_season && germination.record(soil_temp_c, clock.tick_days()) {
+                let heat_units = germination.accumulated_heat_units;
+                for zone in zones.iter() {
+                    events.publish(AgriEvent::GerminationComplete {
+                        zone_id: zone.id,
+                        moisture_level: zone.sensor.moisture_level,
+                        state: zone.sensor.state,
+                        heat_units,
+                    });
+                }
+                let message = format!("{} germinated after {heat_units:.0} {}", ui.crop.name, ui.charset.degree_days());
+                ui.push_status(message);
+            }
+        }
+        for (model, risk_level) in ui.disease.record(temp_c, humidity_pct, clock.tick_hours()) {
+            let message = format!("{} risk {} after humid nights", model.name, risk_level.name());
+            for zone in zones.iter() {
+                events.publish(AgriEvent::DiseaseRiskAlert {
+                    zone_id: zone.id,
+                    moisture_level: zone.sensor.moisture_level,
+                    state: zone.sensor.state,
+                    disease_name: model.name.clone(),
+                    risk_level,
+                });
+            }
+            ui.status_message = message;
+        }
+        TickConditions { day, in_season, stage, outside_temp_c, temp_c, humidity_pct }
+    }
+
+    /// Harvests when due, then grows the cover crop, companion legume and soil carbon; returns
+    /// how well the soil holds water this tick
+    fn update_crop_and_soil(&mut self, conditions: TickConditions) -> Result<f32, Box<dyn std::error::Error>> {
+        let Self {
+            clock,
+            zones,
+            ui,
+            adjuster,
+            calendar,
+            yield_map,
+            yield_estimator,
+            retention,
+            gdd_per_tick,
+            accumulated_gdd,
+            season,
+            season_csv,
+            ..
+        } = self;
+        let TickConditions { day, in_season, temp_c, .. } = conditions;
+        let harvest_due = calendar.is_some_and(|calendar| calendar.harvest_due(day));
+        if std::mem::take(&mut ui.harvest_requested) || harvest_due {
+            if let Some(generator) = yield_map {
+                // A layout drawn in the TUI replaces the configured one
+                generator.layout.clone_from(&ui.field_layout);
+                let yields = generator.zone_yields(zones, yield_estimator, *accumulated_gdd, ui.crop.season_gdd);
+                let path = generator.write(*season, &yields)?;
+                ui.push_status(format!("Yield map written to {}", path.display()));
+            }
+            let event = harvest(ui, zones, accumulated_gdd, yield_estimator, *season, day);
+            if let Some(path) = season_csv {
+                event.append_csv(path)?;
+            }
+            if let Some(calendar) = calendar {
+                if calendar.advance(day) {
+                    ui.push_status(format!("Season {} will be planted on day {}", *season + 1, calendar.planting_day));
+                }
+            }
+            *season += 1;
+            if let Some(crop) = ui.rotation.as_mut().map(|rotation| rotation.advance().clone()) {
+                *gdd_per_tick = crop.growing_degree_days(AIR_TEMP_C, clock.tick_days());
+                yield_estimator.potential_yield_t_ha = crop.potential_yield_t_ha;
+                yield_estimator.crop_response_factor_ky = crop.yield_response_factor;
+                ui.estimated_yield_t_ha = crop.potential_yield_t_ha;
+                ui.disease.crop = crop.clone();
+                ui.optimizer.profile = crop.clone();
+                *adjuster = SeasonalThresholdAdjuster::new(crop.clone());
+                let year = ui.rotation.as_ref().map_or(1, CropRotation::rotation_year);
+                ui.push_status(format!("Rotation year {year}: {} follows {}", crop.name, ui.crop.name));
+                ui.crop = crop;
+            }
+        }
+        let now = zones.first().map_or(Tick::ZERO, |zone| zone.sensor.current_tick);
+        if let Some(cover) = &mut ui.cover_crop {
+            if let Some(released) = cover.step(now, &mut ui.nitrogen) {
+                *retention *= cover.retention_factor();
+                let som_gain_pct = ui.som.incorporate_residue(cover.dry_matter_t_ha);
+                if let Some(carbon) = &mut ui.carbon {
+                    carbon.humify(som_gain_pct);
+                }
+                let message = format!("{} incorporated: {released:.0} kg N/ha into the organic pool", cover.species);
+                ui.push_status(message);
+            }
+        }
+        if let Some(legume) = ui.nitrogen_fixation.as_mut().filter(|_| in_season) {
+            // The crop takes up what its companion fixes through the shared mineral pool
+            ui.nitrogen.mineral_n += legume.step(temp_c, clock.tick_days());
+        }
+        for plugin in ui.plugins.plugins_mut() {
+            plugin.read();
+        }
+        let years = clock.tick_seconds() / SECONDS_PER_YEAR;
+        let som_loss_pct = ui.som.step(SomTracker::temperature_modifier(temp_c), years);
+        if let Some(carbon) = &mut ui.carbon {
+            carbon.respire(som_loss_pct);
+            let flux = |zone: &IrrigationZone| ui.respiration.flux_g_co2_m2_h(temp_c, zone.sensor.moisture_level);
+            let mean_flux_g_m2_h = zones.iter().map(flux).sum::<f32>() / zones.len().max(1) as f32;
+            carbon.record_co2_efflux(mean_flux_g_m2_h * clock.tick_hours());
+        }
+        Ok(*retention * ui.som.retention_factor())
+    }
+
+    /// Adds frost protection and rain to every zone and drains what the tiles carry away
+    fn apply_water_inputs(&mut self, conditions: TickConditions) {
+        let Self { clock, events, zones, ui, .. } = self;
+        let outside_temp_c = conditions.outside_temp_c;
+        if let Some(frost) = &mut ui.frost {
+            // Sprinkling follows the outside air the crop stands in, not the greenhouse air
+            let change = frost.update(outside_temp_c);
+            let frost_mm = frost.apply(clock.tick_hours());
+            for zone in zones.iter_mut() {
+                let points = frost_mm / zone.water_depth_mm(1.0);
+                zone.sensor.moisture_level = (zone.sensor.moisture_level + points).min(100.0);
+                zone.water.record(WaterSource::FrostProtection, frost_mm);
+            }
+            if let Some(budget) = &mut ui.water_budget {
+                budget.record(WaterSource::FrostProtection, frost_mm);
+            }
+            if change == Some(true) {
+                for zone in zones.iter() {
+                    events.publish(AgriEvent::FrostAlert {
+                        zone_id: zone.id,
+                        moisture_level: zone.sensor.moisture_level,
+                        state: zone.sensor.state,
+                        air_temp_c: outside_temp_c,
+                    });
+                }
+            }
+            let celsius = ui.charset.celsius();
+            let message = match change {
+                Some(true) => Some(format!("Frost protection on at {outside_temp_c:.1} {celsius} outside")),
+                Some(false) => Some(format!("Frost protection off, {:.1} mm applied", frost.total_applied_mm)),
+                None => None,
+            };
+            if let Some(message) = message {
+                ui.push_status(message);
+            }
+        }
+        let rainfall_mm = ui.rain_gauge.take_precipitation_mm();
+        if rainfall_mm > 0.0 {
+            for zone in zones.iter_mut() {
+                let points = rainfall_mm / zone.water_depth_mm(1.0);
+                zone.sensor.moisture_level = (zone.sensor.moisture_level + points).min(100.0);
+                zone.water.record(WaterSource::Rain, rainfall_mm);
+            }
+            let stored = ui.rainwater.as_mut().map_or(0.0, |harvester| harvester.capture(rainfall_mm));
+            ui.push_status(format!("{rainfall_mm:.0} mm of rain, {stored:.0} L into the tank"));
+        }
+        if let Some(tile) = ui.drainage {
+            let days = clock.tick_days();
+            let (mut drained_mm, mut soil_water_mm) = (0.0, 0.0);
+            for zone in zones.iter_mut() {
+                let mm_per_point = zone.water_depth_mm(1.0);
+                soil_water_mm += zone.water_depth_mm(zone.sensor.moisture_level);
+                let mm = tile.drain_mm(zone.sensor.moisture_level, mm_per_point, days);
+                zone.sensor.moisture_level -= mm / mm_per_point;
+                drained_mm += mm;
+            }
+            let zone_count = zones.len().max(1) as f32;
+            ui.drained.record(drained_mm / zone_count, days, soil_water_mm / zone_count, &mut ui.nitrogen);
+        }
+    }
+
+    /// Decides which zones may water, steps their sensors and counts the water each applied;
+    /// returns whether the season's water budget was passed
+    fn irrigate_zones(&mut self, conditions: TickConditions, retention: f32) -> bool {
+        let Self {
+            config,
+            clock,
+            events,
+            zones,
+            ui,
+            rng,
+            pump,
+            adjuster,
+            scheduler,
+            forecast,
+            gdd_per_tick,
+            ..
+        } = self;
+        let TickConditions { stage, temp_c, humidity_pct, .. } = conditions;
+        let manual = std::mem::take(&mut ui.backflow_test_requested);
+        test_backflow_preventer(ui, zones, events, rng, manual);
+        let blocked = ui.backflow.as_ref().is_some_and(BackflowPreventer::blocks_irrigation);
+        let tomorrow = clock.date() + Days::new(1);
+        let rain_forecast = forecast.as_ref().and_then(|forecast| forecast.for_date(tomorrow)).copied();
+        let rain_expected = forecast.as_ref().is_some_and(|forecast| forecast.skip_irrigation(tomorrow));
+        if rain_expected && ui.rain_forecast != rain_forecast {
+            let rain_mm = rain_forecast.map_or(0.0, |forecast| forecast.expected_rain_mm);
+            ui.push_status(format!("Irrigation withheld: {rain_mm:.0} mm of rain forecast for {tomorrow}"));
+        }
+        ui.rain_forecast = rain_forecast;
+        let germination_threshold_c = ui.germination.as_ref().map_or(ui.crop.base_temp_c, |model| model.base_temp_c);
+        let budget_spent = ui.water_budget.as_ref().is_some_and(WaterBudget::is_exhausted);
+        let mut budget_exceeded = false;
+        let zone_count = zones.len().max(1) as f32;
+        let n_risk_threshold_kg_ha =
+            config.nutrient_leaching.as_ref().map_or(0.0, |leaching| leaching.n_risk_threshold_kg_ha);
+        let mut running: Vec<u8> =
+            zones.iter().filter(|zone| zone.sensor.water_running()).map(|zone| zone.id).collect();
+        let watering = zones.iter().map(|zone| zone.sensor.state == DeviceState::Activating);
+        let flow_factors = ui.pipe_network.as_mut().map(|network| network.balance(watering));
+        for zone in zones.iter_mut() {
+            zone.sensor.threshold = adjuster.adjusted_threshold(stage, config.threshold);
+            let pipes_full = scheduler
+                .as_ref()
+                .is_some_and(|scheduler| !zone.sensor.water_running() && !scheduler.may_activate(&running, zone.id));
+            zone.sensor.irrigation_blocked =
+                blocked || pipes_full || rain_expected || budget_spent || ui.crop.is_fallow();
+            let flow_factor = flow_factors.as_ref().and_then(|factors| factors.get(zone.id as usize)).copied();
+            let flow_factor = flow_factor.unwrap_or(1.0);
+            let volume_factor = if ui.rain_warning_until.is_some() { RAIN_WARNING_VOLUME_FACTOR } else { 1.0 };
+            zone.sensor.watering_boost = pump.application_mm(WATERING_BOOST) * volume_factor * flow_factor;
+            let last_state = zone.sensor.state;
+            let was_running = zone.sensor.water_running();
+            let moisture_before = zone.sensor.moisture_level;
+            let drop = rng.gen_range(config.evaporation.min_drop..config.evaporation.max_drop) / retention;
+            let new_moisture = (zone.sensor.moisture_level - drop).max(0.0);
+            if let Some(msg) = zone.sensor.step(new_moisture) {
+                ui.push_status(msg.to_string());
+            }
+            // Only a zone that went on from Activating watered; a blocked one was cancelled instead
+            let watered = last_state == DeviceState::Activating && zone.sensor.state == DeviceState::Adjusting;
+            let applied = if watered { zone.sensor.watering_boost } else { 0.0 };
+            if watered {
+                let index = zone.id as usize;
+                if let Some(network) = ui.pipe_network.as_ref().filter(|network| network.low_pressure(index)) {
+                    let head_m = network.zone_heads_m[index];
+                    let flow_pct = flow_factor * 100.0;
+                    ui.push_status(format!("Zone {} short of pressure: {head_m:.1} m, {flow_pct:.0}% flow", zone.id));
+                }
+                if ui.surge.as_mut().is_some_and(SurgeIrrigationController::start) {
+                    ui.push_status(format!("Zone {} watering in surges", zone.id));
+                }
+                zone.stress.record(zone.water_depth_mm(WATERING_BOOST), zone.water_depth_mm(applied));
+                if let Some(tracker) = ui.wetting_front.as_mut().filter(|_| zone.id == 0) {
+                    tracker.start(zone.water_depth_mm(applied), moisture_before);
+                }
+                zone.filter.record_flow(zone.watering_volume_l());
+                let soil_temp_before = ui.seedbed.map_or(temp_c, |seedbed| seedbed.temperature_c);
+                let watering =
+                    config.irrigation_water.model(soil_temp_before, zone.soil_volume_l, zone.watering_volume_l());
+                if let Some(event) = watering.cold_water_stress(zone.id, &zone.sensor, germination_threshold_c) {
+                    let (soil_temp_c, celsius) = (watering.post_irrigation_soil_temp(), ui.charset.celsius());
+                    let message = format!("Zone {} soil chilled to {soil_temp_c:.1} {celsius} by cold water", zone.id);
+                    ui.push_status(message);
+                    events.publish(event);
+                }
+            }
+            zone.trend.record(zone.sensor.moisture_level);
+            if zone.id == 0 {
+                ui.optimizer.record(zone.sensor.moisture_level, zone.sensor.threshold);
+            }
+            if let Some(profiler) = ui.moisture_profile.as_mut().filter(|_| zone.id == 0) {
+                profiler.infiltrate(applied);
+                profiler.step(drop);
+            }
+            running.retain(|&id| id != zone.id);
+            if zone.sensor.water_running() {
+                running.push(zone.id);
+            }
+            let (et_mm, applied_mm) = (zone.water_depth_mm(drop), zone.water_depth_mm(applied));
+            zone.wue.record(&ui.crop, *gdd_per_tick, et_mm, applied_mm);
+            // Harvested rainwater is used up before the main supply
+            let volume_l = applied / 100.0 * zone.soil_volume_l;
+            let harvested_l = ui.rainwater.as_mut().map_or(0.0, |harvester| harvester.supply(volume_l));
+            let harvested_mm = if volume_l > 0.0 { applied_mm * harvested_l / volume_l } else { 0.0 };
+            zone.water.record(WaterSource::Harvested, harvested_mm);
+            zone.water.record(WaterSource::Irrigation, applied_mm - harvested_mm);
+            if let Some(budget) = &mut ui.water_budget {
+                budget_exceeded |= budget.record(WaterSource::Irrigation, (applied_mm - harvested_mm) / zone_count);
+            }
+            if let Some(leaching) = &mut ui.leaching {
+                leaching.record_irrigation(volume_l);
+            }
+            if let Some(ghg) = ui.ghg.as_mut().filter(|_| applied_mm > 0.0) {
+                ghg.record_water_pumped(applied_mm / zone_count);
+            }
+            if let Some(model) = ui.nutrient_leaching.filter(|_| applied_mm > 0.0) {
+                // The zone loses nutrients at the field's concentration; the field pool by its share
+                let mut npk = NpkSensor {
+                    nitrate_ppm: ui.nitrogen.mineral_n / SOIL_MASS_KG_HA * 1e6,
+                    phosphate_ppm: config.soil_sampling.p_ppm,
+                    potassium_ppm: ui.soil_k_ppm,
+                };
+                let loss = model.leach(&mut npk, applied_mm);
+                let zone_share = 1.0 / zone_count;
+                ui.nitrogen.mineral_n -= loss.n_kg_ha * zone_share;
+                ui.soil_k_ppm -= (ui.soil_k_ppm - npk.potassium_ppm) * zone_share;
+                let was_safe = zone.water.n_leached_kg_ha < n_risk_threshold_kg_ha;
+                zone.water.record_nutrient_loss(loss);
+                let n_leached_kg_ha = zone.water.n_leached_kg_ha;
+                if was_safe && n_leached_kg_ha >= n_risk_threshold_kg_ha {
+                    events.publish(AgriEvent::EnvironmentalRiskAlert {
+                        zone_id: zone.id,
+                        moisture_level: zone.sensor.moisture_level,
+                        state: zone.sensor.state,
+                        n_leached_kg_ha,
+                    });
+                    ui.push_status(format!("Zone {} has leached {n_leached_kg_ha:.0} kg N/ha of nitrate", zone.id));
+                }
+            }
+            if let Some(fusion) = &mut zone.fusion {
+                fusion.record_soil(&zone.sensor);
+                let vpd_kpa = vapour_pressure_deficit_kpa(temp_c, humidity_pct);
+                fusion.cwsi_calculator.record(temp_c, simulated_canopy_temp(&zone.sensor, temp_c, vpd_kpa), vpd_kpa);
+                let fused = fusion.fused_stress_index();
+                if fusion.is_stressed() && zone.sensor.start_watering() {
+                    let threshold = fusion.stress_threshold;
+                    ui.push_status(format!("Zone {} fused stress {fused:.2} above {threshold:.2}, watering", zone.id));
+                }
+            }
+            if let Some(horizon) = config.forecast_horizon_hours {
+                let hours = zone.trend.detect().hours_to_reach(zone.sensor.moisture_level, zone.sensor.threshold);
+                if hours.is_some_and(|hours| hours <= horizon) && zone.sensor.start_watering() {
+                    ui.push_status(format!("Moisture forecast below threshold within {horizon} h, watering early"));
+                }
+            }
+            let irrigation_due = ui.scheduled_irrigation.is_some_and(|tick| zone.sensor.current_tick >= tick);
+            if zone.id == 0 && irrigation_due {
+                ui.scheduled_irrigation = None;
+                if zone.sensor.start_watering() {
+                    ui.push_status(format!("Scheduled irrigation of zone {} started", zone.id));
+                } else {
+                    let id = zone.id;
+                    ui.push_status(format!("Scheduled irrigation of zone {id} skipped, already watering or blocked"));
+                }
+            }
+            let valve_closed = was_running && !zone.sensor.water_running();
+            if let Some(hammer) = config.water_hammer.as_ref().filter(|_| valve_closed) {
+                let surge_kpa = hammer.pipe.pressure_surge_kpa(hammer.closure_time_s());
+                if surge_kpa > hammer.max_allowable_kpa {
+                    ui.push_status(format!(
+                        "Water hammer: {surge_kpa:.0} kPa surge on valve closure (limit {:.0}); close over at least {:.1} s",
+                        hammer.max_allowable_kpa,
+                        hammer.pipe.safe_closure_time_s(hammer.max_allowable_kpa)
+                    ));
+                    events.publish(AgriEvent::WaterHammerAlert {
+                        zone_id: zone.id,
+                        moisture_level: zone.sensor.moisture_level,
+                        state: zone.sensor.state,
+                        surge_kpa,
+                    });
+                }
+            }
+            let event = AgriEvent::from_transition(zone.id, last_state, zone.sensor.state, zone.sensor.moisture_level);
+            if let Some(event) = event {
+                // Nobody may be subscribed; events are only for optional integrations
+                events.publish(event);
+            }
+        }
+        budget_exceeded
+    }
+
+    /// Takes the soil samples asked for or due, applies fertilizer and flushes salt, and
+    /// reports the water budget once it is passed
+    fn sample_and_flush_soil(
+        &mut self,
+        conditions: TickConditions,
+        mut budget_exceeded: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let Self { config, clock, events, zones, ui, .. } = self;
+        let (temp_c, zone_count) = (conditions.temp_c, zones.len().max(1) as f32);
+        let now = zones.first().map_or(Tick::ZERO, |zone| zone.sensor.current_tick);
+        let sampling = &config.soil_sampling;
+        let sample_due = sampling.soil_sample_interval_ticks.is_some_and(|interval| now.0 % interval == 0);
+        if std::mem::take(&mut ui.fertilize_requested) {
+            apply_recommended_nitrogen(ui);
+        }
+        if std::mem::take(&mut ui.soil_sample_requested) || sample_due {
+            let soil = SoilChemistry {
+                ec_water_ds_m: config.salinity.as_ref().map(|salinity| salinity.ec_water_ds_m),
+                ph: ui.soil_ph.ph_level,
+                nitrogen: ui.nitrogen,
+                p_ppm: sampling.p_ppm,
+                k_ppm: ui.soil_k_ppm,
+                som_pct: ui.som.som_pct,
+                soil_temp_c: temp_c,
+            };
+            let date = clock.date().to_string();
+            for zone in zones.iter() {
+                let report = take_soil_sample(zone, now, date.clone(), &soil, &ui.respiration, &ui.crop);
+                let path = report.write_json(&sampling.directory)?;
+                ui.push_status(format!("Soil sample of zone {} written to {}", zone.id, path.display()));
+                ui.last_soil_sample = Some(report);
+            }
+        }
+        if let Some(leaching) = ui.leaching.as_mut().filter(|leaching| leaching.leach_due(now)) {
+            let volume_l = leaching.leach(now);
+            if volume_l > 0.0 {
+                let zone_volume_l = volume_l / zones.len() as f32;
+                for zone in zones.iter_mut() {
+                    // Flushing water drains below the root zone, so only the totals see it
+                    let points = zone_volume_l / zone.soil_volume_l * 100.0;
+                    zone.water.record(WaterSource::Leaching, zone.water_depth_mm(points));
+                    if let Some(budget) = &mut ui.water_budget {
+                        let mm = zone.water_depth_mm(points) / zone_count;
+                        budget_exceeded |= budget.record(WaterSource::Leaching, mm);
+                    }
+                }
+                ui.push_status(format!("Leaching: {volume_l:.0} L flushed salt below the root zone"));
+            }
+        }
+        if budget_exceeded {
+            report_budget_exceeded(ui, zones, events);
+        }
+        Ok(())
+    }
+
+    /// Runs surges, maintenance counters, the tensiometer, trace elements and pump energy
+    fn run_equipment(&mut self) {
+        let Self { clock, events, zones, ui, .. } = self;
+        if let Some(surge) = &mut ui.surge {
+            if surge.step(clock.tick_seconds()) {
+                let report = surge_report(surge);
+                ui.push_status(report);
+            }
+        }
+        let pump_on = zones.iter().any(|zone| zone.sensor.water_running())
+            || ui.surge.as_ref().is_some_and(SurgeIrrigationController::pump_running)
+            || ui.frost.as_ref().is_some_and(|frost| frost.active);
+        if let Some(maintenance) = &mut ui.maintenance {
+            let running = |item: &MaintenanceItem| match item.zone_id() {
+                Some(zone_id) => zones.iter().any(|zone| zone.id == zone_id && zone.sensor.water_running()),
+                None => pump_on,
+            };
+            let mut messages = Vec::new();
+            for item in maintenance.record_runtime(clock.tick_hours(), running) {
+                let (component_id, interval_used_pct) = (item.component_id.clone(), item.interval_used_pct());
+                let affected = zones.iter().filter(|zone| item.zone_id().is_none_or(|zone_id| zone.id == zone_id));
+                for zone in affected {
+                    events.publish(AgriEvent::MaintenanceWarning {
+                        zone_id: zone.id,
+                        moisture_level: zone.sensor.moisture_level,
+                        state: zone.sensor.state,
+                        component_id: component_id.clone(),
+                        interval_used_pct,
+                    });
+                }
+                let verdict = if item.status() == MaintenanceStatus::Overdue { "overdue" } else { "due soon" };
+                let message = format!("Maintenance {verdict}: {component_id} at {interval_used_pct:.0}% of its interval");
+                messages.push(message);
+            }
+            for message in messages {
+                ui.push_status(message);
+            }
+        }
+        if let Some(tracker) = &mut ui.wetting_front {
+            tracker.advance(clock.tick_hours());
+        }
+        if let Some(tensiometer) = &mut ui.tensiometer {
+            // Read at the cup's depth when sensors are buried there, otherwise at zone 0's sensor
+            let profile = ui.moisture_profile.as_ref();
+            let moisture = profile.and_then(|profile| profile.moisture_near(tensiometer.depth_cm));
+            let before = tensiometer.state();
+            let moisture = moisture.or(zones.first().map(|zone| zone.sensor.moisture_level));
+            let kpa = moisture.map(|moisture| tensiometer.update(moisture, clock.tick_hours()));
+            let state = tensiometer.state();
+            if let Some(kpa) = kpa.filter(|_| state != before) {
+                ui.push_status(format!("Tensiometer at {kpa:.0} kPa: {}", state.name()));
+            }
+        }
+        let ph_level = ui.soil_ph.ph_level;
+        let deficient = ui.trace_elements.as_mut().map_or_else(Vec::new, |trace_elements| {
+            trace_elements.update(ph_level, clock.tick_days(), &ui.crop.micronutrient_min_ppm)
+        });
+        for element in deficient {
+            for zone in zones.iter() {
+                events.publish(AgriEvent::MicronutrientDeficiency {
+                    zone_id: zone.id,
+                    moisture_level: zone.sensor.moisture_level,
+                    state: zone.sensor.state,
+                    element,
+                });
+            }
+            ui.push_status(format!("Micronutrient deficiency: {} at pH {ph_level:.1}, T to spray", element.name()));
+        }
+        if let Some(energy) = ui.energy.as_mut().filter(|_| pump_on) {
+            // The tick just stepped is the one the clock is still on
+            let energy_kwh = energy.record_running(clock.hour_index(clock.tick_count), clock.tick_hours());
+            if let Some(ghg) = &mut ui.ghg {
+                ghg.record_electricity(energy_kwh);
+            }
+        }
+    }
+
+    /// Logs the tick and, at the end of a day, the day's reports and water totals
+    fn write_daily_reports(&mut self, conditions: TickConditions) -> Result<(), Box<dyn std::error::Error>> {
+        let Self { clock, zones, ui, yield_estimator, accumulated_gdd, water_csv, wal, .. } = self;
+        let day = conditions.day;
+        if let Some(wal) = wal {
+            log_tick(wal, zones)?;
+        }
+        if clock.is_last_tick_of_day() {
+            if (day + 1).is_multiple_of(365) {
+                ui.som.record_year_end();
+                if let Some(balance) = ui.carbon.as_mut().map(CarbonTracker::close_year) {
+                    ui.push_status(balance.summary());
+                }
+            }
+            if ui.nutrient_leaching.is_some() {
+                let n_kg_ha = zones.iter().map(|zone| zone.water.n_leached_kg_ha).sum::<f32>() / zones.len() as f32;
+                let k_kg_ha = zones.iter().map(|zone| zone.water.k_leached_kg_ha).sum::<f32>() / zones.len() as f32;
+                let report = format!("Day {day} nutrient leaching: {n_kg_ha:.1} kg N/ha, {k_kg_ha:.1} kg K/ha so far");
+                ui.push_status(report);
+            }
+            if let Some(ghg) = &ui.ghg {
+                let report = format!("Day {day} emissions so far: {}", ghg.season.summary(ghg.target_kg_co2e_ha));
+                ui.push_status(report);
+            }
+            if let Some(legume) = &ui.nitrogen_fixation {
+                let report = format!(
+                    "Day {day} nitrogen fixed by {}: {:.1} kg N/ha this season",
+                    legume.legume_profile.name, legume.season_fixed_n_kg_ha
+                );
+                ui.push_status(report);
+            }
+            if let Some(co2_g_m2) = ui.carbon.as_mut().map(CarbonTracker::close_day) {
+                ui.push_status(format!("Day {day} soil CO2 efflux: {co2_g_m2:.1} g{}", ui.charset.per_m2()));
+            }
+            if let Some(energy) = &ui.energy {
+                let day_kwh = energy.day_energy_kwh(day as usize);
+                let report = format!("Day {day} pump energy: {day_kwh:.2} kWh, cost {:.2}", energy.cost(day_kwh));
+                ui.push_status(report);
+            }
+            let stress_index = zones.iter().map(|zone| zone.stress.stress_index()).sum::<f32>() / zones.len() as f32;
+            let gdd_fraction_complete = *accumulated_gdd / ui.crop.season_gdd;
+            ui.estimated_yield_t_ha = yield_estimator.estimate_yield(stress_index, gdd_fraction_complete);
+        }
+        if let Some(path) = water_csv {
+            if clock.is_last_tick_of_day() {
+                write_water_csv(path, zones, clock.date(), ui.et.daily_et_mm)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Hands the readings and alerts to the broker, servers, exporters and alert history
+    fn publish_tick(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Self {
+            config,
+            clock,
+            events,
+            zones,
+            ui,
+            rng,
+            offline_cache,
+            exports,
+            modbus,
+            opcua,
+            grpc,
+            http,
+            mqtt,
+            uplink,
+            alert_feed,
+            ..
+        } = self;
+        for letter in events.dead_letters().drain() {
+            ui.push_status(format!(
+                "Dropped for slow subscriber #{}: {} (zone {})",
+                letter.subscriber_id,
+                letter.event.title(),
+                letter.event.zone_id()
+            ));
+        }
+        ui.record_tick(zones);
+        let now = zones.first().map_or(Tick::ZERO, |zone| zone.sensor.current_tick);
+        diagnostics::update_all(&mut ui.diagnostics, zones.iter().map(|zone| zone.sensor.reading()), now);
+
+        let sensors: Vec<&SoilMoistureSensor> = zones.iter().map(|zone| &zone.sensor).collect();
+        if let Some(link) = ui.network.as_mut() {
+            link.advance(TICK_INTERVAL.as_millis() as u64);
+        }
+        if let Some(cache) = offline_cache.as_mut() {
+            if ui.network.as_ref().is_some_and(|link| !link.is_connected()) {
+                for (zone_id, &sensor) in sensors.iter().enumerate() {
+                    cache.store(&CachedReading::now(zone_id, sensor))?;
+                }
+            } else {
+                // Older readings first, a batch per tick so the broker client's queue is not overrun
+                let readings = cache.flush(CACHE_FLUSH_BATCH)?;
+                if let Some(mqtt) = mqtt.as_mut() {
+                    readings.iter().for_each(|reading| mqtt.publish_cached(reading));
+                }
+                if !readings.is_empty() && cache.is_empty()? {
+                    ui.push_status(format!("Offline cache replayed, {} readings flushed", cache.stats.entries_flushed));
+                }
+            }
+            ui.cache_stats = Some(cache.stats);
+        }
+        if let Some(mqtt) = mqtt.as_mut() {
+            for (zone_id, &sensor) in sensors.iter().enumerate() {
+                // Readings are sent at most once; the next tick brings a fresh one
+                let sent = ui.network.as_mut().is_none_or(|link| {
+                    link.transmit(network::READING_PAYLOAD_BYTES, rng).is_some()
+                });
+                if sent {
+                    mqtt.publish_reading(zone_id, sensor);
+                }
+            }
+        }
+        while let Some(event) = alert_feed.try_recv() {
+            ui.alert_history.record(&event, clock.tick_count)?;
+        }
+        let uplink_parts = (uplink.as_mut(), ui.qos_buffer.as_mut(), ui.network.as_mut());
+        if let (Some(uplink), Some(buffer), Some(link)) = uplink_parts {
+            while let Some(event) = uplink.try_recv() {
+                buffer.push(event);
+            }
+            let delivered = buffer.flush(link, rng);
+            if let Some(mqtt) = mqtt.as_mut() {
+                delivered.iter().for_each(|event| mqtt.publish_event(event));
+            }
+            // More than one at once means the events were held through an outage or a loss
+            if delivered.len() > 1 {
+                ui.push_status(format!("Uplink delivered {} buffered events", delivered.len()));
+            }
+        }
+        if let Some(mqtt) = mqtt.as_ref().filter(|_| config.gateway.is_some()) {
+            ui.gateway = Some(mqtt.status());
+        }
+        if let Some(modbus) = &modbus {
+            modbus.update(&sensors);
+        }
+        if let Some(opcua) = &opcua {
+            opcua.update(&sensors);
+        }
+        if let Some(grpc) = &grpc {
+            grpc.update(&sensors);
+        }
+        for &sensor in &sensors {
+            exports.write(&sensor.reading())?;
+        }
+        if let Some(http) = &http {
+            let statuses: Vec<_> = sensors.iter().map(|&sensor| (sensor, ui.status_message.as_str())).collect();
+            http.update(&statuses);
+        }
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    if cli.export_schema {
+        println!("{}", SimulationConfig::json_schema());
+        return Ok(());
+    }
+    if cli.generate_systemd {
+        let args: Vec<String> = std::env::args().skip(1).filter(|arg| arg != "--generate-systemd").collect();
+        print!("{}", systemd::unit_file(&std::env::current_exe()?, &std::env::current_dir()?, &args));
+        return Ok(());
+    }
+    let config = match &cli.config {
+        Some(path) => SimulationConfig::load(path)?,
+        None => SimulationConfig::default(),
+    };
+    if let Some(path) = &cli.cost_report {
+        write_cost_report(path, &config)?;
+        return Ok(());
+    }
+    let forecast = cli.forecast_file.as_deref().map(WeatherForecastStub::load_from_csv).transpose()?;
+    let replay = cli.replay.as_deref().map(ReplayController::load).transpose()?;
+    if cli.time_acceleration <= 0.0 {
+        return Err("--time-acceleration must be above 0".into());
+    }
+    // Simulated day 0 is the day the run starts, from midnight
+    let start_date = Utc::now().date_naive();
+    let mut clock = SimulationClock::new(start_date.and_time(NaiveTime::MIN), cli.time_acceleration);
+    let events = EventBus::new(config.event_bus.capacity);
+    let modbus = match cli.modbus_port {
+        Some(port) => Some(ModbusTcpServer::start(port).await?),
+        None => None,
+    };
+    let opcua = cli.opcua_port.map(|port| OpcUaServer::start(port, 1)).transpose()?;
+    let grpc = match cli.grpc_port {
+        Some(port) => Some(GrpcServer::start(port).await?),
+        None => None,
+    };
+    let http = match cli.http_port {
+        Some(port) => Some(HttpServer::start(port, &config.api).await?),
+        None => None,
+    };
+    // A lone `[mqtt]` broker is a gateway of one
+    let mqtt = match (&config.gateway, &config.mqtt) {
+        (Some(gateway), _) => Some(GatewayManager::connect(&gateway.brokers, gateway.strategy)?),
+        (None, Some(broker)) => {
+            Some(GatewayManager::connect(std::slice::from_ref(broker), LoadBalancingStrategy::FailoverPrimary)?)
+        }
+        (None, None) => None,
+    };
+    if let Some(smtp_host) = cli.smtp_host {
+        let alerter = EmailAlerter::new(AlertConfig {
+            smtp_host,
+            smtp_port: cli.smtp_port,
+            from: cli.smtp_from.unwrap_or_default(),
+            to: cli.smtp_to,
+            username: cli.smtp_username.unwrap_or_default(),
+            password: cli.smtp_password.unwrap_or_default(),
+        })?;
+        tokio::spawn(alerter.run(events.subscribe()));
+    }
+    if let Some(webhook_url) = cli.slack_webhook {
+        let alerter = SlackAlerter::new(webhook_url, cli.slack_channel.unwrap_or_default(), cli.slack_severity);
+        tokio::spawn(alerter.run(events.subscribe()));
+    }
+    if let (Some(token), Some(chat_id)) = (cli.telegram_token, cli.telegram_chat_id) {
+        tokio::spawn(TelegramBot::new(token, chat_id).run(events.subscribe()));
+    }
+    if let Some(webhook_url) = cli.discord_webhook {
+        tokio::spawn(DiscordAlerter::new(webhook_url).run(events.subscribe()));
+    }
+    if let Some(topic) = cli.ntfy_topic {
+        let alerter = NtfyAlerter::new(cli.ntfy_server, topic, cli.ntfy_priority);
+        tokio::spawn(alerter.run(events.subscribe()));
+    }
+    // Events bound for the broker, which cross the simulated uplink when one is configured
+    let uplink = config.network.as_ref().map(|_| events.subscribe());
+    // Every event, for the alert history
+    let alert_feed = events.subscribe();
+
+    let terminal_guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = Terminal::new(backend)?;
+
+    let theme = if cli.high_contrast { Theme::HighContrast } else { Theme::Neon };
+    let charset = if cli.ascii_only { Charset::Ascii } else { Charset::Unicode };
+    if cli.hydroponic || cli.aeroponic {
+        let mister = cli
+            .aeroponic
+            .then(|| config.aeroponic.as_ref().map_or_else(AeroponicController::default, AeroponicController::from));
+        return Ok(run_hydroponic(&mut terminal, theme, charset, mister).await?);
+    }
+
+    let pump = WaterPump::new(config.irrigation_strategy);
+    let mut sensor =
+        SoilMoistureSensor::new(config.threshold, config.initial_moisture).with_sensor_id(config.sensor_id.clone());
+    sensor.watering_boost = pump.application_mm(WATERING_BOOST);
+    let mut zones = vec![IrrigationZone {
+        id: 0,
+        sensor,
+        soil_volume_l: 1000.0,
+        trend: TrendDetector::new(clock.tick_duration()),
+        wue: WueTracker::default(),
+        water: WaterVolumeTracker::default(),
+        stress: DroughtStressTracker::default(),
+        filter: FilterModel::default(),
+        fusion: config.sensor_fusion.as_ref().map(SensorFusion::from),
+    }];
+    let mut rng = rand::thread_rng();
+    let adjuster = SeasonalThresholdAdjuster::new(config.crop.profile());
+    let scheduler = config.topology.as_ref().map(IrrigationScheduler::from);
+    let yield_map = config.yield_map.as_ref().map(YieldMapGenerator::from);
+    // The energy and carbon reports need a model, so they fall back to the defaults, as do emissions
+    let needs_energy = cli.energy_report || config.ghg.is_some();
+    let energy = config.energy.clone().or_else(|| needs_energy.then(EnergyConfig::default));
+    let carbon = config.carbon.clone().or_else(|| cli.carbon_report.then(CarbonConfig::default));
+    let mut ui = UiState {
+        crop: adjuster.profile.clone(),
+        rotation: config.crop.rotation(),
+        disease: PestDiseaseRiskCalculator::builtin(adjuster.profile.clone()),
+        strategy: pump.strategy,
+        emitters: config.emitters.as_ref().map(EmitterConfig::array),
+        backflow: config.backflow.as_ref().map(BackflowPreventer::from),
+        pipe_network: config.pipe_network.as_ref().map(PipeNetwork::from),
+        maintenance: config.maintenance.as_ref().map(MaintenanceScheduler::from),
+        moisture_profile: config.moisture_profile.as_ref().map(|profile| {
+            MoistureProfiler::new(&profile.layers(), config.initial_moisture, clock.tick_duration())
+        }),
+        wetting_front: config.wetting_front.as_ref().map(WettingFrontTracker::from),
+        germination: config.germination.as_ref().map(GerminationModel::from),
+        seedbed: config
+            .germination
+            .as_ref()
+            .map(|germination| SoilTemperatureSensor::new(germination.seeding_depth_cm, AIR_TEMP_C)),
+        soil_ph: PHSensor::default(),
+        trace_elements: config.trace_elements.as_ref().map(TraceElementMonitor::from),
+        tensiometer: config.tensiometer.as_ref().map(|tensiometer| {
+            let retention = biochar::amend(VanGenuchten::loam(), &config.biochar);
+            let depth_cm = tensiometer.depth_cm;
+            TensiometerSensor::new(depth_cm, tensiometer.ceramic_cup_conductance, retention, config.initial_moisture)
+        }),
+        root_distribution: config
+            .moisture_profile
+            .as_ref()
+            .map_or_else(Vec::new, |profile| profile.root_distribution.clone()),
+        surge: config.surge.as_ref().map(SurgeIrrigationController::from),
+        frost: config.frost_protection.as_ref().map(FrostProtectionController::from),
+        water_budget: config.water_budget.as_ref().map(WaterBudget::from),
+        cold_snap_until: None,
+        energy: energy.as_ref().map(EnergyModel::from),
+        rainwater: config.rainwater.as_ref().map(RainwaterHarvester::from),
+        leaching: config.salinity.as_ref().map(|salinity| salinity.manager(&adjuster.profile)),
+        nutrient_leaching: config.nutrient_leaching.as_ref().map(LeachingModel::from),
+        ghg: config.ghg.as_ref().map(GhgTracker::from),
+        soil_k_ppm: config.soil_sampling.k_ppm,
+        microclimate: config.microclimate.as_ref().map(MicroclimateModel::from),
+        weather_station: config.weather_station.as_ref().map(WeatherStation::from),
+        rain_warning_until: None,
+        daylight: config.microclimate.as_ref().map_or_else(DaylightModel::default, MicroclimateConfig::daylight),
+        night_ventilation: config.microclimate.as_ref().and_then(MicroclimateConfig::night_ventilation),
+        cover_crop: config.cover_crop.as_ref().map(CoverCropModel::from),
+        nitrogen_fixation: config.nitrogen_fixation.as_ref().map(NitrogenFixationModel::from),
+        som: SomTracker::from(&config.organic_matter),
+        respiration: SoilRespirationModel::from(&config.soil_respiration),
+        carbon: carbon.map(|carbon| carbon.tracker(&config.organic_matter)),
+        drainage: config.drainage.as_ref().map(DrainageTile::from),
+        network: config.network.as_ref().map(NetworkLink::from),
+        qos_buffer: config.network.as_ref().map(QosBuffer::from),
+        carbon_stock_t_ha: biochar::carbon_stock_t_ha(&config.biochar),
+        field_layout: yield_map.as_ref().and_then(|generator| generator.layout.clone()),
+        probes: zones.iter().map(|_| SensorCalibration::random_probe(&mut rng)).collect(),
+        replay,
+        optimizer: IrrigationOptimizer::new(adjuster.profile.clone(), ET0_MM_PER_DAY, clock.tick_days()),
+        plugins: config::plugin_loader(&config.plugins)?,
+        alert_history: config.alert_history.as_ref().map(AlertHistoryConfig::open).transpose()?.unwrap_or_default(),
+        theme,
+        charset,
+        ..UiState::default()
+    };
+    let loaded: Vec<String> = ui
+        .plugins
+        .plugins()
+        .iter()
+        .map(|plugin| format!("Loaded plugin {}: {}", plugin.label(), plugin.sensor.name()))
+        .collect();
+    for message in loaded {
+        ui.push_status(message);
+    }
+    if http.is_some() && config.api.tls.is_none() {
+        ui.push_status("Warning: HTTP API is unencrypted; set [api.tls] to serve HTTPS".to_string());
+    }
+    let wal = match &cli.wal {
+        Some(path) => Some(recover_from_wal(path, &mut zones, &mut ui)?),
+        None => None,
+    };
+    let offline_cache = config.offline_cache.as_ref().map(OfflineCacheConfig::open).transpose()?;
+    let exports = config::export_manager(&config.export)?;
+    let retention = biochar::retention_factor(&config.biochar);
+
+    let gdd_per_tick = ui.crop.growing_degree_days(AIR_TEMP_C, clock.tick_days());
+    let yield_estimator = YieldEstimator::from(&ui.crop);
+    let calendar = config.crop.calendar.as_ref().map(CropCalendar::from);
+    ui.estimated_yield_t_ha = yield_estimator.potential_yield_t_ha;
+    // Any structure starts out at the outside temperature
+    clock.tick_count = zones.first().map_or(Tick::ZERO, |zone| zone.sensor.current_tick);
+    ui.air_temp.record(diurnal_conditions(AIR_TEMP_C, clock.hour_of_day()).0);
+    // Biochar goes in at the start of the run, as the first year's carbon input
+    if let Some(carbon) = &mut ui.carbon {
+        carbon.add_biochar(ui.carbon_stock_t_ha);
+    }
+    // Planting is the start of the run
+    ui.et.reset_seasonal();
+    ui.soil_ph.record(config.soil_sampling.ph);
+    if ui.germination.is_some() {
+        ui.growth_stage = GrowthStage::Germination;
+    }
+
+    if ui.replay.is_some() {
+        return Ok(run_replay(&mut terminal, &mut ui, &mut zones, &clock)?);
+    }
+
+    let mut state = SimulationState {
+        config,
+        clock,
+        events,
+        zones,
+        ui,
+        rng,
+        pump,
+        adjuster,
+        scheduler,
+        forecast,
+        calendar,
+        yield_map,
+        yield_estimator,
+        retention,
+        gdd_per_tick,
+        accumulated_gdd: 0.0,
+        season: 1,
+        season_csv: cli.season_csv,
+        water_csv: cli.water_csv,
+        wal,
+        offline_cache,
+        exports,
+        modbus,
+        opcua,
+        grpc,
+        http,
+        mqtt,
+        uplink,
+        alert_feed,
+    };
+    while !state.ui.quit {
+        state.handle_requests()?;
+        // Nothing ticks and nothing is published while paused, or in step mode until `t` asks
+        // for the next tick, but the TUI keeps up
+        // In step mode `t` paces the ticks
+        if state.advance_if_due()? && !state.ui.step_mode {
+            tokio::time::sleep(TICK_INTERVAL).await;
+        }
+        if let Some(http) = &state.http {
+            http.heartbeat();
+        }
+        render(&state.ui, &state.zones, &mut terminal)?;
+        poll_input(&mut state.ui, &mut state.zones)?;
+    }
+
+    let SimulationState { exports, water_csv, zones, clock, ui, .. } = &mut state;
+    exports.flush()?;
+    if let Some(path) = water_csv {
+        write_water_csv(path, zones, clock.date(), ui.et.daily_et_mm)?;
+    }
+    // The reports go to the normal screen
+    drop(terminal_guard);
+    if let Some(energy) = ui.energy.as_ref().filter(|_| cli.energy_report) {
+        energy.write_hourly_csv(io::stdout().lock())?;
+    }
+    if let Some(carbon) = ui.carbon.as_ref().filter(|_| cli.carbon_report) {
+        carbon.write_annual_csv(io::stdout().lock())?;
+    }
+    Ok(())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agri_iot_simulator::config::MqttConfig;
+    use agri_iot_simulator::irrigation::IrrigationStrategy;
+
+    /// A run of `zones` with the default config and no servers, files or alerters
+    fn headless(zones: Vec<IrrigationZone>, mqtt: Option<GatewayManager>) -> SimulationState {
+        let config = SimulationConfig::default();
+        let start = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_time(NaiveTime::MIN);
+        let clock = SimulationClock::new(start, 60.0);
+        let events = EventBus::new(config.event_bus.capacity);
+        let pump = WaterPump::new(config.irrigation_strategy);
+        let adjuster = SeasonalThresholdAdjuster::new(config.crop.profile());
+        let ui = UiState { crop: adjuster.profile.clone(), strategy: pump.strategy, ..UiState::default() };
+        let gdd_per_tick = ui.crop.growing_degree_days(AIR_TEMP_C, clock.tick_days());
+        let yield_estimator = YieldEstimator::from(&ui.crop);
+        let alert_feed = events.subscribe();
+        SimulationState {
+            config,
+            clock,
+            events,
+            zones,
+            ui,
+            rng: rand::thread_rng(),
+            pump,
+            adjuster,
+            scheduler: None,
+            forecast: None,
+            calendar: None,
+            yield_map: None,
+            yield_estimator,
+            retention: 1.0,
+            gdd_per_tick,
+            accumulated_gdd: 0.0,
+            season: 1,
+            season_csv: None,
+            water_csv: None,
+            wal: None,
+            offline_cache: None,
+            exports: DataExportManager::new(),
+            modbus: None,
+            opcua: None,
+            grpc: None,
+            http: None,
+            mqtt,
+            uplink: None,
+            alert_feed,
+        }
+    }
+
+    #[tokio::test]
+    async fn a_100_ms_pause_runs_no_ticks_and_publishes_nothing() {
+        // Nothing listens on port 1, so every publish stays queued in the client and is counted
+        let broker = MqttConfig { host: "127.0.0.1".to_string(), port: 1, ..MqttConfig::default() };
+        let mqtt = GatewayManager::connect(&[broker], LoadBalancingStrategy::FailoverPrimary).unwrap();
+        let mut state = headless(Simulation::new(2, 30.0, 5).zones, Some(mqtt));
+        let published = |state: &SimulationState| state.mqtt.as_ref().unwrap().status().brokers[0].messages_sent;
+        let tick = |state: &SimulationState| state.zones[0].sensor.current_tick;
+        assert!(state.advance_if_due().unwrap());
+        let (ticked, sent) = (tick(&state), published(&state));
+        assert_eq!((ticked, sent), (Tick(1), 2));
+
+        state.ui.pause_requested = true;
+        let paused_at = Instant::now();
+        while paused_at.elapsed() < Duration::from_millis(100) {
+            state.handle_requests().unwrap();
+            assert!(!state.advance_if_due().unwrap());
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        state.ui.pause_requested = true;
+        state.handle_requests().unwrap();
+        assert!(!state.ui.paused);
+        assert_eq!((tick(&state), published(&state)), (ticked, sent));
+
+        assert!(state.advance_if_due().unwrap());
+        assert_eq!((tick(&state), published(&state)), (Tick(2), 4));
+    }
+
+    #[test]
+    fn a_blocked_zone_counts_no_water() {
+        // Both zones are about to water when the exhausted budget blocks irrigation
+        let mut state = headless(Simulation::new(2, 30.0, 5).zones, None);
+        let mut budget = WaterBudget::new(100.0);
+        budget.record(WaterSource::Irrigation, 100.0);
+        state.ui.water_budget = Some(budget.clone());
+        state.zones.iter_mut().for_each(|zone| assert!(zone.sensor.start_watering()));
+        let conditions = state.update_weather_and_growth();
+        assert!(!state.irrigate_zones(conditions, 1.0));
+        for zone in &state.zones {
+            assert_eq!(zone.sensor.state, DeviceState::Monitoring);
+            assert_eq!(zone.water.total_mm(), 0.0);
+            assert_eq!(zone.stress, DroughtStressTracker::default());
+            assert_eq!(zone.filter, FilterModel::default());
+        }
+        assert_eq!(state.ui.water_budget, Some(budget));
+
+        // Unblocked, the same zones water and the water is counted
+        let mut state = headless(Simulation::new(2, 30.0, 5).zones, None);
+        state.zones.iter_mut().for_each(|zone| assert!(zone.sensor.start_watering()));
+        state.advance_one_tick().unwrap();
+        for zone in &state.zones {
+            assert_eq!(zone.sensor.state, DeviceState::Adjusting);
+            assert!(zone.water.total_mm() > 0.0);
+            assert_ne!(zone.filter, FilterModel::default());
+        }
+    }
+
+    #[test]
+    fn step_mode_runs_one_tick_per_request() {
+        let mut state = headless(Simulation::new(1, 30.0, 5).zones, None);
+        state.ui.step_mode = true;
+        assert!(!state.advance_if_due().unwrap());
+        state.ui.step_requested = true;
+        assert!(state.advance_if_due().unwrap());
+        assert!(!state.advance_if_due().unwrap());
+        assert_eq!(state.zones[0].sensor.current_tick, Tick(1));
+    }
+
+    #[test]
+    fn harvest_resets_every_seasonal_accumulator() {
+        // A 120-day season of deficit irrigation with every optional tracker running
+        let mut simulation = Simulation::new(2, 30.0, 5);
+        simulation.strategy = IrrigationStrategy::Deficit { fraction: 0.6 };
+        simulation.run_for(5_000);
+        let mut ui = UiState {
+            water_budget: Some(WaterBudget::new(300.0)),
+            nitrogen_fixation: Some(NitrogenFixationModel::beans()),
+            energy: Some(EnergyModel::new(1.5, 0.7, 0.2)),
+            ..UiState::default()
+        };
+        for day in 0..120 {
+            ui.et.record(day, 5.0, 1.2, 1.0);
+        }
+        ui.water_budget.as_mut().unwrap().record(WaterSource::Irrigation, 120.0);
+        ui.nitrogen_fixation.as_mut().unwrap().step(25.0, 100.0);
+        ui.energy.as_mut().unwrap().record_running(6, 2.0);
+        ui.growth_stage = GrowthStage::Maturity;
+        ui.nitrogen = NitrogenPool { organic_n: 1_500.0, mineral_n: 80.0 };
+        let mut accumulated_gdd = ui.crop.season_gdd;
+        let mut yield_estimator = YieldEstimator::from(&ui.crop);
+        let stress_index = simulation.zones[0].stress.stress_index();
+        assert!(stress_index > 0.0);
+
+        let event = harvest(&mut ui, &mut simulation.zones, &mut accumulated_gdd, &mut yield_estimator, 1, 120);
+        assert_eq!((event.season, event.day, event.gdd), (1, 120, 1500.0));
+        assert!((event.seasonal_et_mm - 720.0).abs() < 1e-2);
+        assert!(event.total_water_mm > 0.0);
+        assert!(event.final_stress_index > 0.0 && event.estimated_yield_t_ha < yield_estimator.potential_yield_t_ha);
+        assert_eq!(yield_estimator.season_yields_t_ha, [event.estimated_yield_t_ha]);
+        assert!(ui.event_log.iter().any(|message| message.starts_with("Harvested season 1:")));
+
+        for zone in &simulation.zones {
+            assert_eq!(zone.stress, DroughtStressTracker::default());
+            assert_eq!(zone.wue, WueTracker::default());
+            assert_eq!(zone.water.total_mm(), 0.0);
+            assert_eq!(zone.water.start_tick, zone.sensor.current_tick);
+        }
+        assert_eq!((ui.et.daily_et_mm, ui.et.seasonal_et_mm), (0.0, 0.0));
+        assert_eq!(accumulated_gdd, 0.0);
+        assert_eq!(ui.growth_stage, GrowthStage::default());
+        assert_eq!(ui.water_budget.as_ref().unwrap().used_mm, 0.0);
+        assert_eq!(ui.nitrogen_fixation.as_ref().unwrap().season_fixed_n_kg_ha, 0.0);
+        assert_eq!(ui.energy.as_ref().unwrap().season_energy_kwh, 0.0);
+        assert_eq!(ui.estimated_yield_t_ha, yield_estimator.potential_yield_t_ha);
+        // Only the residue's nitrogen carries over into the next season
+        let residue_t_ha = event.estimated_yield_t_ha * (1.0 - ui.crop.harvest_index) / ui.crop.harvest_index;
+        let mut season_start = NitrogenPool::default();
+        rotation::credit_previous_crop(&ui.crop, residue_t_ha, &mut season_start);
+        assert_eq!(ui.nitrogen, season_start);
+        assert!(ui.nitrogen.organic_n > 0.0 && ui.nitrogen.organic_n < 1_500.0);
+    }
+}
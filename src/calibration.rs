@@ -0,0 +1,125 @@
+//! Two-point calibration of every zone's moisture probe at once
+//!
+//! Each probe is placed in a dry reference medium and then a wet one. A probe's error is taken
+//! to be linear, so the two raw readings fix the `offset` and `gain` mapping it back onto the
+//! references.
+
+use rand::Rng;
+
+/// Moisture of the oven-dried reference medium
+pub const DRY_REFERENCE_PCT: f32 = 0.0;
+/// Moisture of the saturated reference medium
+pub const WET_REFERENCE_PCT: f32 = 100.0;
+
+/// Linear correction of a probe's raw reading, `raw × gain + offset`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensorCalibration {
+    pub offset: f32,
+    pub gain: f32,
+}
+
+impl Default for SensorCalibration {
+    /// Leaves readings as they are
+    fn default() -> Self {
+        Self { offset: 0.0, gain: 1.0 }
+    }
+}
+
+impl SensorCalibration {
+    /// The correction taking `dry_raw` to [`DRY_REFERENCE_PCT`] and `wet_raw` to
+    /// [`WET_REFERENCE_PCT`]; `None` when the two readings are too close to tell apart
+    pub fn from_references(dry_raw: f32, wet_raw: f32) -> Option<Self> {
+        let span = wet_raw - dry_raw;
+        if span.abs() < f32::EPSILON {
+            return None;
+        }
+        let gain = (WET_REFERENCE_PCT - DRY_REFERENCE_PCT) / span;
+        Some(Self { offset: DRY_REFERENCE_PCT - dry_raw * gain, gain })
+    }
+
+    /// An uncalibrated probe's response to the true moisture, within ±15% gain and ±8 points offset
+    pub fn random_probe(rng: &mut impl Rng) -> Self {
+        Self { offset: rng.gen_range(-8.0..8.0), gain: rng.gen_range(0.85..1.15) }
+    }
+
+    pub fn apply(&self, raw: f32) -> f32 {
+        raw * self.gain + self.offset
+    }
+}
+
+/// Where the wizard is; every zone is at the same step
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WizardStep {
+    #[default]
+    DryMedium,
+    WetMedium,
+    Done,
+}
+
+impl WizardStep {
+    /// What to do before pressing Enter
+    pub fn instructions(self) -> &'static str {
+        match self {
+            WizardStep::DryMedium => "Place every probe in the dry medium",
+            WizardStep::WetMedium => "Place every probe in the wet medium",
+            WizardStep::Done => "Calibrated; put the probes back in the soil",
+        }
+    }
+
+    /// Moisture of the medium the probes are in, until they are done
+    pub fn reference_pct(self) -> Option<f32> {
+        match self {
+            WizardStep::DryMedium => Some(DRY_REFERENCE_PCT),
+            WizardStep::WetMedium => Some(WET_REFERENCE_PCT),
+            WizardStep::Done => None,
+        }
+    }
+}
+
+/// Reference readings and the resulting calibration of one zone's probe
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ZoneCalibration {
+    pub dry_raw: Option<f32>,
+    pub wet_raw: Option<f32>,
+    pub calibration: Option<SensorCalibration>, // Once both references are read
+}
+
+/// Calibrates the probes of `zones` together, one reference medium at a time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MultiSensorCalibrationWizard {
+    pub zones: Vec<u8>,
+    pub step: WizardStep,
+    pub results: Vec<ZoneCalibration>, // Indexed like `zones`
+}
+
+impl MultiSensorCalibrationWizard {
+    pub fn new(zones: Vec<u8>) -> Self {
+        let results = vec![ZoneCalibration::default(); zones.len()];
+        Self { zones, step: WizardStep::DryMedium, results }
+    }
+
+    /// Takes every zone's `raw_reading` for the current medium and moves all of them to the next
+    /// step, computing each probe's calibration once the wet medium is read
+    pub fn advance(&mut self, mut raw_reading: impl FnMut(u8) -> f32) {
+        for (&zone_id, result) in self.zones.iter().zip(&mut self.results) {
+            match self.step {
+                WizardStep::DryMedium => result.dry_raw = Some(raw_reading(zone_id)),
+                WizardStep::WetMedium => {
+                    let wet_raw = raw_reading(zone_id);
+                    result.wet_raw = Some(wet_raw);
+                    result.calibration =
+                        result.dry_raw.and_then(|dry_raw| SensorCalibration::from_references(dry_raw, wet_raw));
+                }
+                WizardStep::Done => {}
+            }
+        }
+        self.step = match self.step {
+            WizardStep::DryMedium => WizardStep::WetMedium,
+            WizardStep::WetMedium | WizardStep::Done => WizardStep::Done,
+        };
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.step == WizardStep::Done
+    }
+}
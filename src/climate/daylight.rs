@@ -0,0 +1,35 @@
+//! Day length and the clear-sky solar input over the day
+
+use std::f32::consts::PI;
+
+/// Clear-sky global radiation at solar noon, in W/m²
+pub const PEAK_SOLAR_W_M2: f32 = 800.0;
+
+/// Sunrise and sunset, in hours after local midnight
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DaylightModel {
+    pub sunrise_hour: f32,
+    pub sunset_hour: f32,
+}
+
+impl Default for DaylightModel {
+    fn default() -> Self {
+        Self { sunrise_hour: 6.0, sunset_hour: 18.0 }
+    }
+}
+
+impl DaylightModel {
+    /// Whether the sun is up at `hour_of_day`
+    pub fn is_daytime(&self, hour_of_day: f32) -> bool {
+        (self.sunrise_hour..self.sunset_hour).contains(&hour_of_day)
+    }
+
+    /// Clear-sky global radiation at `hour_of_day`: a half sine from sunrise to sunset
+    pub fn solar_radiation_w_m2(&self, hour_of_day: f32) -> f32 {
+        if !self.is_daytime(hour_of_day) {
+            return 0.0;
+        }
+        let day_fraction = (hour_of_day - self.sunrise_hour) / (self.sunset_hour - self.sunrise_hour);
+        PEAK_SOLAR_W_M2 * (day_fraction * PI).sin()
+    }
+}
@@ -0,0 +1,178 @@
+//! Multi-day weather forecasts read from a file, standing in for a forecast service
+
+use chrono::NaiveDate;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Header line a forecast CSV starts with
+pub const CSV_HEADER: &str = "date,expected_rain_mm,max_temp_c,min_temp_c,solar_rad";
+
+/// Rain expected tomorrow above which irrigation is withheld, in mm
+pub const RAIN_SKIP_THRESHOLD_MM: f32 = 5.0;
+
+/// Forecast for one calendar day
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DailyForecast {
+    pub date: NaiveDate,
+    pub expected_rain_mm: f32,
+    pub max_temp_c: f32,
+    pub min_temp_c: f32,
+    pub solar_rad: f32, // Daily total, in MJ/m²
+}
+
+/// Why a forecast file could not be loaded
+#[derive(Debug)]
+pub enum ForecastError {
+    Io(io::Error),
+    /// A malformed record, on 1-based `line` of the file
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for ForecastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ForecastError::Io(err) => write!(f, "cannot read forecast file: {}", err),
+            ForecastError::Parse { line, message } => write!(f, "forecast file line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for ForecastError {}
+
+impl From<io::Error> for ForecastError {
+    fn from(err: io::Error) -> Self {
+        ForecastError::Io(err)
+    }
+}
+
+/// Forecasts loaded up front in place of fetching them from a weather API
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeatherForecastStub {
+    pub forecasts: Vec<DailyForecast>, // Ordered by date
+}
+
+impl WeatherForecastStub {
+    /// Reads a CSV with a [`CSV_HEADER`] line and one `YYYY-MM-DD` row per day.
+    /// Blank lines are skipped; rows may come in any order but a date may appear only once.
+    pub fn load_from_csv(path: &Path) -> Result<WeatherForecastStub, ForecastError> {
+        Self::parse_csv(&fs::read_to_string(path)?)
+    }
+
+    /// Parses the contents of a forecast CSV, as [`load_from_csv`](Self::load_from_csv) does
+    pub fn parse_csv(contents: &str) -> Result<WeatherForecastStub, ForecastError> {
+        let mut lines = contents.lines().enumerate().map(|(index, line)| (index + 1, line.trim()));
+        match lines.next() {
+            Some((_, header)) if header.split(',').map(str::trim).eq(CSV_HEADER.split(',')) => {}
+            _ => return Err(ForecastError::Parse { line: 1, message: format!("expected header `{CSV_HEADER}`") }),
+        }
+        let mut forecasts: Vec<DailyForecast> = Vec::new();
+        for (line, record) in lines.filter(|(_, record)| !record.is_empty()) {
+            let forecast = parse_record(record).map_err(|message| ForecastError::Parse { line, message })?;
+            if forecasts.iter().any(|other| other.date == forecast.date) {
+                return Err(ForecastError::Parse { line, message: format!("{} is forecast twice", forecast.date) });
+            }
+            forecasts.push(forecast);
+        }
+        forecasts.sort_by_key(|forecast| forecast.date);
+        Ok(WeatherForecastStub { forecasts })
+    }
+
+    /// The forecast for `date`, if the file covers it
+    pub fn for_date(&self, date: NaiveDate) -> Option<&DailyForecast> {
+        self.forecasts.iter().find(|forecast| forecast.date == date)
+    }
+
+    /// Whether the rain forecast for `tomorrow` makes watering today unnecessary
+    pub fn skip_irrigation(&self, tomorrow: NaiveDate) -> bool {
+        self.for_date(tomorrow).is_some_and(|forecast| forecast.expected_rain_mm > RAIN_SKIP_THRESHOLD_MM)
+    }
+}
+
+fn parse_record(record: &str) -> Result<DailyForecast, String> {
+    let fields: Vec<&str> = record.split(',').map(str::trim).collect();
+    let [date, rain, max_temp, min_temp, solar] = fields[..] else {
+        return Err(format!("expected 5 fields, found {}", fields.len()));
+    };
+    let number = |name: &str, value: &str| value.parse::<f32>().map_err(|_| format!("{name} `{value}` is not a number"));
+    let forecast = DailyForecast {
+        date: date.parse().map_err(|_| format!("date `{date}` is not YYYY-MM-DD"))?,
+        expected_rain_mm: number("expected_rain_mm", rain)?,
+        max_temp_c: number("max_temp_c", max_temp)?,
+        min_temp_c: number("min_temp_c", min_temp)?,
+        solar_rad: number("solar_rad", solar)?,
+    };
+    if forecast.expected_rain_mm < 0.0 || forecast.solar_rad < 0.0 {
+        return Err("expected_rain_mm and solar_rad must not be negative".to_string());
+    }
+    if forecast.min_temp_c > forecast.max_temp_c {
+        return Err("min_temp_c is above max_temp_c".to_string());
+    }
+    Ok(forecast)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, day).unwrap()
+    }
+
+    const FORECAST: &str = "\
+date,expected_rain_mm,max_temp_c,min_temp_c,solar_rad
+2024-06-03,12.5,19.0,11.0,9.5
+2024-06-01,0.0,27.0,14.0,24.0
+
+2024-06-02,5.0,24.0,13.0,18.0
+";
+
+    #[test]
+    fn irrigation_is_withheld_before_heavy_rain() {
+        let stub = WeatherForecastStub::parse_csv(FORECAST).unwrap();
+        assert!(stub.skip_irrigation(date(3)));
+        // Exactly 5 mm is not more than the threshold, and a dry day never skips
+        assert!(!stub.skip_irrigation(date(2)));
+        assert!(!stub.skip_irrigation(date(1)));
+        // Days the file does not cover irrigate as usual
+        assert!(!stub.skip_irrigation(date(4)));
+        assert!(!WeatherForecastStub::default().skip_irrigation(date(3)));
+    }
+
+    #[test]
+    fn rows_are_sorted_by_date() {
+        let stub = WeatherForecastStub::parse_csv(FORECAST).unwrap();
+        let dates: Vec<NaiveDate> = stub.forecasts.iter().map(|forecast| forecast.date).collect();
+        assert_eq!(dates, [date(1), date(2), date(3)]);
+        let wet = stub.for_date(date(3)).unwrap();
+        assert_eq!((wet.expected_rain_mm, wet.max_temp_c, wet.min_temp_c, wet.solar_rad), (12.5, 19.0, 11.0, 9.5));
+    }
+
+    #[test]
+    fn malformed_files_name_the_line() {
+        let parse_error = |contents: &str| match WeatherForecastStub::parse_csv(contents) {
+            Err(ForecastError::Parse { line, message }) => (line, message),
+            other => panic!("expected a parse error, got {other:?}"),
+        };
+        assert_eq!(parse_error("date,rain\n").0, 1);
+        let (line, message) = parse_error(&format!("{CSV_HEADER}\n2024-06-01,1,20,10\n"));
+        assert_eq!((line, message.as_str()), (2, "expected 5 fields, found 4"));
+        let (line, message) = parse_error(&format!("{CSV_HEADER}\n2024-06-01,1,20,10,5\n2024-06-01,2,21,11,6\n"));
+        assert_eq!((line, message.as_str()), (3, "2024-06-01 is forecast twice"));
+        assert_eq!(parse_error(&format!("{CSV_HEADER}\n2024-06-01,-1,20,10,5\n")).0, 2);
+        assert_eq!(parse_error(&format!("{CSV_HEADER}\n2024-06-01,1,10,20,5\n")).0, 2);
+        assert_eq!(parse_error(&format!("{CSV_HEADER}\n6/1/2024,1,20,10,5\n")).1, "date `6/1/2024` is not YYYY-MM-DD");
+    }
+
+    #[test]
+    fn loads_from_a_file() {
+        let path = std::env::temp_dir().join(format!("agri-forecast-{}.csv", std::process::id()));
+        fs::write(&path, FORECAST).unwrap();
+        let stub = WeatherForecastStub::load_from_csv(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(stub, WeatherForecastStub::parse_csv(FORECAST).unwrap());
+        assert!(matches!(WeatherForecastStub::load_from_csv(&path), Err(ForecastError::Io(_))));
+    }
+}
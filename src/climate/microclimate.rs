@@ -0,0 +1,147 @@
+//! Greenhouse and polytunnel air temperature from a steady-state energy balance
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Density of air at about 20 °C, in kg/m³
+pub const AIR_DENSITY_KG_M3: f32 = 1.2;
+
+/// Specific heat capacity of air, in J/(kg·K)
+pub const AIR_CP_J_KG_K: f32 = 1006.0;
+
+/// Heat stored per m² of floor by soil, crop and structure, in J/(m²·K)
+pub const THERMAL_MASS_J_M2_K: f32 = 1.5e6;
+
+/// Leakage and heat loss through the cover of a fully closed structure, as the
+/// equivalent ventilation in m³ of air per m² of floor per second
+pub const MIN_VENTILATION_RATE: f32 = 0.015;
+
+/// What the crop grows under
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StructureType {
+    #[default]
+    OpenField,
+    Polytunnel,
+    Glasshouse,
+}
+
+impl StructureType {
+    pub fn name(self) -> &'static str {
+        match self {
+            StructureType::OpenField => "open field",
+            StructureType::Polytunnel => "polytunnel",
+            StructureType::Glasshouse => "glasshouse",
+        }
+    }
+}
+
+/// Solar input and ventilation of one structure
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MicroclimateModel {
+    pub structure_type: StructureType,
+    pub glazing_transmissivity: f32, // Share of the solar radiation the cover lets through
+    pub ventilation_rate: f32,       // Air exchanged, in m³ per m² of floor per second
+}
+
+impl Default for MicroclimateModel {
+    fn default() -> Self {
+        Self::for_structure(StructureType::default())
+    }
+}
+
+impl MicroclimateModel {
+    /// Typical cover and vent opening for `structure_type`
+    pub fn for_structure(structure_type: StructureType) -> Self {
+        let (glazing_transmissivity, ventilation_rate) = match structure_type {
+            StructureType::OpenField => (1.0, f32::INFINITY),
+            StructureType::Polytunnel => (0.85, 0.04), // Polythene, roll-up sides
+            StructureType::Glasshouse => (0.9, 0.08),  // Glass, ridge vents
+        };
+        Self { structure_type, glazing_transmissivity, ventilation_rate }
+    }
+
+    /// Air temperature inside: `T_out + S·τ / (V·ρ·cp)`, the solar gain carried away by
+    /// ventilation alone. Open fields are always at the outside temperature.
+    pub fn inside_temp_c(&self, outside_temp_c: f32, solar_rad_w_m2: f32) -> f32 {
+        if self.structure_type == StructureType::OpenField {
+            return outside_temp_c;
+        }
+        let ventilation_rate = self.ventilation_rate.max(MIN_VENTILATION_RATE);
+        outside_temp_c
+            + solar_rad_w_m2 * self.glazing_transmissivity / (ventilation_rate * AIR_DENSITY_KG_M3 * AIR_CP_J_KG_K)
+    }
+
+    /// Heat lost by ventilating at `ventilation_rate`, as the fall in inside temperature
+    /// per second: proportional to `(T_inside − T_outside) · ventilation_rate`
+    pub fn cooling_rate_k_per_s(inside_temp_c: f32, outside_temp_c: f32, ventilation_rate: f32) -> f32 {
+        let ventilation_rate = ventilation_rate.max(MIN_VENTILATION_RATE);
+        (inside_temp_c - outside_temp_c) * ventilation_rate * AIR_DENSITY_KG_M3 * AIR_CP_J_KG_K / THERMAL_MASS_J_M2_K
+    }
+
+    /// Inside temperature `dt_s` later, warmed by the sun and cooled by ventilating at
+    /// `ventilation_rate`; it settles at [`inside_temp_c`](Self::inside_temp_c) in steady conditions
+    pub fn step_inside_temp_c(
+        &self,
+        inside_temp_c: f32,
+        outside_temp_c: f32,
+        solar_rad_w_m2: f32,
+        ventilation_rate: f32,
+        dt_s: f32,
+    ) -> f32 {
+        if self.structure_type == StructureType::OpenField {
+            return outside_temp_c;
+        }
+        let heating_rate = solar_rad_w_m2 * self.glazing_transmissivity / THERMAL_MASS_J_M2_K;
+        let cooling_rate = Self::cooling_rate_k_per_s(inside_temp_c, outside_temp_c, ventilation_rate);
+        inside_temp_c + (heating_rate - cooling_rate) * dt_s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    fn closed_polytunnel() -> MicroclimateModel {
+        MicroclimateModel { ventilation_rate: 0.0, ..MicroclimateModel::for_structure(StructureType::Polytunnel) }
+    }
+
+    #[test]
+    fn closed_polytunnel_heats_more_than_a_ventilated_glasshouse() {
+        let glasshouse = MicroclimateModel::for_structure(StructureType::Glasshouse);
+        // 500 W/m² at 20 °C: 425 W/m² through the polythene leaves only by leakage, 0.015 × 1.2 × 1006,
+        // while the glasshouse vents 450 W/m² at 0.08 × 1.2 × 1006
+        let polytunnel_c = closed_polytunnel().inside_temp_c(20.0, 500.0);
+        let glasshouse_c = glasshouse.inside_temp_c(20.0, 500.0);
+        assert_close(polytunnel_c, 20.0 + 425.0 / 18.108, 0.01);
+        assert_close(glasshouse_c, 20.0 + 450.0 / 96.576, 0.01);
+        assert!(polytunnel_c > glasshouse_c);
+        // Without sun both sit at the outside temperature
+        assert_close(closed_polytunnel().inside_temp_c(20.0, 0.0), 20.0, 1e-6);
+        assert_close(glasshouse.inside_temp_c(20.0, 0.0), 20.0, 1e-6);
+    }
+
+    #[test]
+    fn open_field_is_always_at_the_outside_temperature() {
+        let field = MicroclimateModel::default();
+        assert_eq!(field.structure_type, StructureType::OpenField);
+        assert_eq!(field.inside_temp_c(18.0, 900.0), 18.0);
+        assert_eq!(field.step_inside_temp_c(30.0, 18.0, 900.0, 0.0, 60.0), 18.0);
+    }
+
+    #[test]
+    fn stepping_settles_at_the_steady_state() {
+        let glasshouse = MicroclimateModel::for_structure(StructureType::Glasshouse);
+        // The time constant is 1.5e6 / 96.576 ≈ 4.3 h, so two days settle it
+        let mut inside_c = 20.0;
+        for _ in 0..2_880 {
+            inside_c = glasshouse.step_inside_temp_c(inside_c, 20.0, 500.0, glasshouse.ventilation_rate, 60.0);
+        }
+        assert_close(inside_c, glasshouse.inside_temp_c(20.0, 500.0), 0.01);
+        // Venting wider cools a warm house faster
+        let slow = MicroclimateModel::cooling_rate_k_per_s(30.0, 20.0, 0.02);
+        let fast = MicroclimateModel::cooling_rate_k_per_s(30.0, 20.0, 0.08);
+        assert_close(fast, 4.0 * slow, 1e-9);
+    }
+}
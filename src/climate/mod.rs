@@ -0,0 +1,12 @@
+//! Outdoor weather, air temperature inside growing structures, the solar input that drives it and the forecast
+
+pub mod daylight;
+pub mod forecast;
+pub mod microclimate;
+pub mod ventilation;
+pub mod weather;
+
+pub use daylight::DaylightModel;
+pub use microclimate::{MicroclimateModel, StructureType};
+pub use ventilation::{NightVentilationController, VentilationFan};
+pub use weather::{AtmosphericData, WeatherCondition, WeatherEngine, WeatherStation};
@@ -0,0 +1,106 @@
+//! Night-time venting to cool a structure down to a target temperature
+
+use super::daylight::DaylightModel;
+
+/// Degrees below the target at which open vents close again
+pub const CLOSE_HYSTERESIS_C: f32 = 2.0;
+
+/// Roof vents or an extractor fan
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VentilationFan {
+    pub ventilation_rate: f32, // Air exchanged while open, in m³ per m² of floor per second
+    pub open: bool,
+}
+
+impl VentilationFan {
+    /// A closed vent exchanging `ventilation_rate` once opened
+    pub fn new(ventilation_rate: f32) -> Self {
+        Self { ventilation_rate, open: false }
+    }
+}
+
+/// Opens the vents after sunset while the inside air is above `target_night_temp_c`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NightVentilationController {
+    pub target_night_temp_c: f32,
+    pub vent_actuator: VentilationFan,
+    pub daylight: DaylightModel,
+}
+
+impl NightVentilationController {
+    pub fn new(target_night_temp_c: f32, vent_actuator: VentilationFan, daylight: DaylightModel) -> Self {
+        Self { target_night_temp_c, vent_actuator, daylight }
+    }
+
+    /// Checks the inside temperature at `hour_of_day` and moves the vents. Vents open at night
+    /// above the target and close at sunrise or once the air is [`CLOSE_HYSTERESIS_C`] below it.
+    /// Returns the new vent position when it changed.
+    pub fn update(&mut self, hour_of_day: f32, inside_temp_c: f32) -> Option<bool> {
+        let open = if self.daylight.is_daytime(hour_of_day) {
+            false
+        } else if self.vent_actuator.open {
+            inside_temp_c > self.target_night_temp_c - CLOSE_HYSTERESIS_C
+        } else {
+            inside_temp_c > self.target_night_temp_c
+        };
+        if open == self.vent_actuator.open {
+            return None;
+        }
+        self.vent_actuator.open = open;
+        Some(open)
+    }
+
+    /// Ventilation while the vents are open, or `None` while they are shut
+    pub fn ventilation_rate(&self) -> Option<f32> {
+        self.vent_actuator.open.then_some(self.vent_actuator.ventilation_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller() -> NightVentilationController {
+        NightVentilationController::new(18.0, VentilationFan::new(0.05), DaylightModel::default())
+    }
+
+    #[test]
+    fn vents_open_at_night_once_above_the_target() {
+        let mut controller = controller();
+        assert_eq!(controller.update(20.0, 18.0), None);
+        assert_eq!(controller.ventilation_rate(), None);
+        assert_eq!(controller.update(20.0, 18.1), Some(true));
+        assert_eq!(controller.ventilation_rate(), Some(0.05));
+        assert_eq!(controller.update(20.5, 19.0), None);
+    }
+
+    #[test]
+    fn vents_stay_shut_during_the_day() {
+        let mut controller = controller();
+        assert_eq!(controller.update(12.0, 30.0), None);
+        assert_eq!(controller.update(17.9, 30.0), None);
+        assert_eq!(controller.update(18.0, 30.0), Some(true));
+    }
+
+    #[test]
+    fn vents_close_two_degrees_below_the_target() {
+        let mut controller = controller();
+        controller.update(22.0, 20.0);
+        // Inside the hysteresis band the vents stay open
+        assert_eq!(controller.update(23.0, 17.0), None);
+        assert_eq!(controller.update(0.0, 16.1), None);
+        assert_eq!(controller.update(1.0, 16.0), Some(false));
+        // And they do not reopen until the air is back above the target
+        assert_eq!(controller.update(2.0, 17.5), None);
+        assert_eq!(controller.update(3.0, 18.5), Some(true));
+    }
+
+    #[test]
+    fn vents_close_at_sunrise() {
+        let mut controller = controller();
+        controller.update(4.0, 25.0);
+        assert_eq!(controller.update(5.9, 25.0), None);
+        assert_eq!(controller.update(6.0, 25.0), Some(false));
+        assert!(!controller.vent_actuator.open);
+    }
+}
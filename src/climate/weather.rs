@@ -0,0 +1,302 @@
+//! Outdoor weather as a Markov chain of sky conditions, read by one station's sensors
+
+use rand::Rng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BarometricPressureSensor, CO2Sensor, HumiditySensor, LightSensor, RainfallSensor, TemperatureSensor, WindSensor,
+};
+
+/// Outdoor CO2 concentration of well-mixed daytime air, in ppm
+pub const AMBIENT_CO2_PPM: f32 = 420.0;
+
+/// Rate at which the pressure moves towards that of the current condition, in hPa per hour
+const PRESSURE_RATE_HPA_PER_H: f32 = 1.2;
+
+/// Sky over the field, one state of the [`WeatherEngine`] chain
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WeatherCondition {
+    #[default]
+    Clear,
+    Cloudy,
+    Overcast,
+    Rain,
+}
+
+impl WeatherCondition {
+    pub const ALL: [WeatherCondition; 4] =
+        [WeatherCondition::Clear, WeatherCondition::Cloudy, WeatherCondition::Overcast, WeatherCondition::Rain];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WeatherCondition::Clear => "Clear",
+            WeatherCondition::Cloudy => "Cloudy",
+            WeatherCondition::Overcast => "Overcast",
+            WeatherCondition::Rain => "Rain",
+        }
+    }
+
+    /// Share of the clear-sky solar radiation that gets through the cloud
+    pub fn transmission(self) -> f32 {
+        match self {
+            WeatherCondition::Clear => 1.0,
+            WeatherCondition::Cloudy => 0.7,
+            WeatherCondition::Overcast => 0.35,
+            WeatherCondition::Rain => 0.2,
+        }
+    }
+
+    /// Air temperature against the day's diurnal curve, in °C
+    fn temperature_offset_c(self) -> f32 {
+        match self {
+            WeatherCondition::Clear => 1.0,
+            WeatherCondition::Cloudy => 0.0,
+            WeatherCondition::Overcast => -1.5,
+            WeatherCondition::Rain => -3.0,
+        }
+    }
+
+    /// Relative humidity against the day's diurnal curve, in percentage points
+    fn humidity_offset_pct(self) -> f32 {
+        match self {
+            WeatherCondition::Clear => -5.0,
+            WeatherCondition::Cloudy => 0.0,
+            WeatherCondition::Overcast => 10.0,
+            WeatherCondition::Rain => 25.0,
+        }
+    }
+
+    /// Mean wind speed, in m/s
+    fn wind_speed_m_s(self) -> f32 {
+        match self {
+            WeatherCondition::Clear => 2.0,
+            WeatherCondition::Cloudy => 3.0,
+            WeatherCondition::Overcast => 4.0,
+            WeatherCondition::Rain => 6.0,
+        }
+    }
+
+    /// Pressure the condition settles at, in hPa: high under an anticyclone, low in a depression
+    fn pressure_hpa(self) -> f32 {
+        match self {
+            WeatherCondition::Clear => 1022.0,
+            WeatherCondition::Cloudy => 1014.0,
+            WeatherCondition::Overcast => 1006.0,
+            WeatherCondition::Rain => 998.0,
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|&condition| condition == self).expect("every condition is in ALL")
+    }
+}
+
+/// Markov chain stepping the sky condition once per simulated hour
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherEngine {
+    pub condition: WeatherCondition,
+    /// Chance of moving from each condition (row) to each other (column) within an hour,
+    /// indexed like [`WeatherCondition::ALL`]; each row sums to 1
+    pub transitions: [[f32; 4]; 4],
+    hours_to_next_step: f32,
+}
+
+impl Default for WeatherEngine {
+    fn default() -> Self {
+        Self::new(WeatherCondition::Clear)
+    }
+}
+
+impl WeatherEngine {
+    /// Starts at `condition` with spells that last a few hours to a day
+    pub fn new(condition: WeatherCondition) -> Self {
+        Self {
+            condition,
+            transitions: [
+                [0.90, 0.08, 0.02, 0.00],
+                [0.10, 0.80, 0.08, 0.02],
+                [0.02, 0.10, 0.78, 0.10],
+                [0.00, 0.05, 0.20, 0.75],
+            ],
+            hours_to_next_step: 1.0,
+        }
+    }
+
+    /// Lets `hours` pass, taking one step of the chain for every whole hour
+    pub fn advance(&mut self, hours: f32, rng: &mut impl Rng) -> WeatherCondition {
+        self.hours_to_next_step -= hours;
+        while self.hours_to_next_step <= 0.0 {
+            self.hours_to_next_step += 1.0;
+            let row = self.transitions[self.condition.index()];
+            let mut draw = rng.gen::<f32>();
+            self.condition = *WeatherCondition::ALL
+                .iter()
+                .zip(row)
+                .find(|&(_, chance)| {
+                    draw -= chance;
+                    draw < 0.0
+                })
+                .map_or(&self.condition, |(condition, _)| condition);
+        }
+        self.condition
+    }
+}
+
+/// Every reading of a [`WeatherStation`] at one moment
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AtmosphericData {
+    pub condition: WeatherCondition,
+    pub temperature_c: f32,
+    pub relative_humidity_pct: f32,
+    pub wind_speed_m_s: f32,
+    pub wind_direction_deg: f32,
+    pub rain_rate_mm_h: f32,
+    pub solar_radiation_w_m2: f32,
+    pub co2_ppm: f32,
+    pub pressure_hpa: f32,
+    pub pressure_trend_hpa_per_h: f32,
+}
+
+/// The field's outdoor sensors on one mast, all driven by one [`WeatherEngine`]
+#[derive(Debug, Clone)]
+pub struct WeatherStation {
+    pub engine: WeatherEngine,
+    pub temperature: TemperatureSensor,
+    pub humidity: HumiditySensor,
+    pub wind: WindSensor,
+    pub rainfall: RainfallSensor, // Holds the rain until the simulation takes it
+    pub light: LightSensor,
+    pub co2: CO2Sensor,
+    pub pressure: BarometricPressureSensor,
+    pub rain_rate_mm_h: f32, // Over the last tick
+}
+
+impl WeatherStation {
+    pub fn new(engine: WeatherEngine) -> Self {
+        let mut pressure = BarometricPressureSensor::default();
+        pressure.record(engine.condition.pressure_hpa(), 0.0);
+        Self {
+            engine,
+            temperature: TemperatureSensor::default(),
+            humidity: HumiditySensor::default(),
+            wind: WindSensor::default(),
+            rainfall: RainfallSensor::default(),
+            light: LightSensor::default(),
+            co2: CO2Sensor { co2_ppm: AMBIENT_CO2_PPM },
+            pressure,
+            rain_rate_mm_h: 0.0,
+        }
+    }
+
+    /// Steps the weather over `step_hours` and samples every sensor. `diurnal_temp_c` and
+    /// `diurnal_humidity_pct` are the day's curve under average skies, and
+    /// `clear_sky_solar_w_m2` the radiation that would arrive without cloud.
+    pub fn tick(
+        &mut self,
+        diurnal_temp_c: f32,
+        diurnal_humidity_pct: f32,
+        clear_sky_solar_w_m2: f32,
+        step_hours: f32,
+        rng: &mut impl Rng,
+    ) {
+        let condition = self.engine.advance(step_hours, rng);
+        self.temperature.record(diurnal_temp_c + condition.temperature_offset_c());
+        self.humidity.record(diurnal_humidity_pct + condition.humidity_offset_pct());
+        let gust = rng.gen_range(0.7..1.3);
+        let veer = rng.gen_range(-10.0..10.0);
+        self.wind.record(condition.wind_speed_m_s() * gust, self.wind.direction_deg + veer);
+        self.rain_rate_mm_h = match condition {
+            WeatherCondition::Rain => rng.gen_range(0.5..4.0),
+            _ => 0.0,
+        };
+        self.rainfall.record(self.rain_rate_mm_h * step_hours);
+        let solar_w_m2 = clear_sky_solar_w_m2 * condition.transmission();
+        self.light.record(solar_w_m2);
+        // Photosynthesis draws CO2 down by day; respiration builds it up under the night's still air
+        let daylight_fraction = (clear_sky_solar_w_m2 / super::daylight::PEAK_SOLAR_W_M2).min(1.0);
+        self.co2.record(AMBIENT_CO2_PPM + 30.0 * (1.0 - daylight_fraction) - 10.0 * daylight_fraction);
+        let target_hpa = condition.pressure_hpa();
+        let max_change = PRESSURE_RATE_HPA_PER_H * step_hours;
+        let change = (target_hpa - self.pressure.pressure_hpa).clamp(-max_change, max_change);
+        self.pressure.record(self.pressure.pressure_hpa + change + rng.gen_range(-0.05..0.05), step_hours);
+    }
+
+    /// Snapshot of every sensor's last reading
+    pub fn atmospheric_data(&self) -> AtmosphericData {
+        AtmosphericData {
+            condition: self.engine.condition,
+            temperature_c: self.temperature.temperature_c,
+            relative_humidity_pct: self.humidity.relative_humidity_pct,
+            wind_speed_m_s: self.wind.speed_m_s,
+            wind_direction_deg: self.wind.direction_deg,
+            rain_rate_mm_h: self.rain_rate_mm_h,
+            solar_radiation_w_m2: self.light.solar_radiation_w_m2,
+            co2_ppm: self.co2.co2_ppm,
+            pressure_hpa: self.pressure.pressure_hpa,
+            pressure_trend_hpa_per_h: self.pressure.trend_hpa_per_h,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// A station under clear skies whose chain always turns to rain
+    fn station_turning_to_rain() -> WeatherStation {
+        let mut engine = WeatherEngine::new(WeatherCondition::Clear);
+        engine.transitions = [[0.0, 0.0, 0.0, 1.0]; 4];
+        WeatherStation::new(engine)
+    }
+
+    #[test]
+    fn one_tick_updates_every_sensor() {
+        let mut station = station_turning_to_rain();
+        let before = station.atmospheric_data();
+        assert_eq!((before.condition, before.pressure_hpa, before.co2_ppm), (WeatherCondition::Clear, 1022.0, 420.0));
+        station.tick(20.0, 70.0, 400.0, 1.0, &mut StdRng::seed_from_u64(1));
+
+        let after = station.atmospheric_data();
+        assert_eq!(after.condition, WeatherCondition::Rain);
+        assert_eq!(after.temperature_c, 17.0);
+        assert_eq!(after.relative_humidity_pct, 95.0);
+        assert!((4.2..7.8).contains(&after.wind_speed_m_s));
+        assert_ne!(after.wind_direction_deg, before.wind_direction_deg);
+        assert!((0.5..4.0).contains(&after.rain_rate_mm_h));
+        assert_eq!(station.rainfall.take_precipitation_mm(), after.rain_rate_mm_h);
+        // Rain lets a fifth of the 400 W/m² through; half daylight means 420 + 15 − 5 ppm
+        assert!((after.solar_radiation_w_m2 - 80.0).abs() < 1e-3);
+        assert!((after.co2_ppm - 430.0).abs() < 1e-3);
+        // The pressure heads for the low at 1.2 hPa an hour
+        assert!((after.pressure_hpa - 1020.8).abs() < 0.06);
+        assert!((after.pressure_trend_hpa_per_h + 1.2).abs() < 0.06);
+    }
+
+    #[test]
+    fn sensors_update_between_steps_of_the_chain() {
+        let mut station = station_turning_to_rain();
+        station.tick(25.0, 60.0, 800.0, 0.25, &mut StdRng::seed_from_u64(2));
+        let data = station.atmospheric_data();
+        // A quarter of an hour is too soon for the chain to move
+        assert_eq!(data.condition, WeatherCondition::Clear);
+        assert_eq!((data.temperature_c, data.relative_humidity_pct), (26.0, 55.0));
+        assert_eq!((data.solar_radiation_w_m2, data.rain_rate_mm_h), (800.0, 0.0));
+        assert!((1.4..2.6).contains(&data.wind_speed_m_s));
+        assert!((data.co2_ppm - 410.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn engine_steps_once_per_whole_hour() {
+        let mut engine = WeatherEngine::new(WeatherCondition::Clear);
+        // Clear → Cloudy → Overcast → Rain → Clear, one step at a time
+        engine.transitions = [[0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0], [1.0, 0.0, 0.0, 0.0]];
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_eq!(engine.advance(0.5, &mut rng), WeatherCondition::Clear);
+        assert_eq!(engine.advance(0.5, &mut rng), WeatherCondition::Cloudy);
+        assert_eq!(engine.advance(3.0, &mut rng), WeatherCondition::Clear);
+    }
+}
@@ -0,0 +1,181 @@
+//! Simulated calendar time, kept apart from the wall clock the ticks run on
+//!
+//! Each tick takes [`TICK_INTERVAL`] of wall time and covers that interval
+//! times the acceleration of simulated time, so a run can get through a
+//! season faster than it grows. While paused no ticks run, so simulated time
+//! stands still.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Timelike};
+use std::time::{Duration, Instant};
+
+use crate::simulation::TICK_INTERVAL;
+use crate::Tick;
+
+/// Maps ticks to simulated date and time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationClock {
+    pub start_wall: Instant,
+    pub tick_count: Tick,             // Ticks run so far
+    pub time_acceleration: f32,       // Simulated seconds per wall-clock second
+    pub simulated_start: NaiveDateTime, // Simulated time at tick 0
+    pub paused_since: Option<Instant>,
+    pub paused_for: Duration, // Wall time spent paused before `paused_since`
+}
+
+impl SimulationClock {
+    pub fn new(simulated_start: NaiveDateTime, time_acceleration: f32) -> Self {
+        Self {
+            start_wall: Instant::now(),
+            tick_count: Tick::ZERO,
+            time_acceleration,
+            simulated_start,
+            paused_since: None,
+            paused_for: Duration::ZERO,
+        }
+    }
+
+    /// Stops ticks from running until [`resume`](Self::resume); pausing twice does nothing
+    pub fn pause(&mut self) {
+        self.paused_since.get_or_insert_with(Instant::now);
+    }
+
+    /// Lets ticks run again after a [`pause`](Self::pause), leaving the simulated time where it stood
+    pub fn resume(&mut self) {
+        if let Some(since) = self.paused_since.take() {
+            self.paused_for += since.elapsed();
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_since.is_some()
+    }
+
+    /// Simulated time one tick covers
+    pub fn tick_duration(&self) -> Duration {
+        TICK_INTERVAL.mul_f32(self.time_acceleration)
+    }
+
+    pub fn tick_seconds(&self) -> f32 {
+        self.tick_duration().as_secs_f32()
+    }
+
+    pub fn tick_hours(&self) -> f32 {
+        self.tick_seconds() / 3600.0
+    }
+
+    pub fn tick_days(&self) -> f32 {
+        self.tick_seconds() / 86_400.0
+    }
+
+    /// Simulated time at the start of `tick`: `simulated_start + tick · TICK_INTERVAL · time_acceleration`
+    pub fn simulated_at(&self, tick: Tick) -> NaiveDateTime {
+        let elapsed_ms = tick.0 as f64 * TICK_INTERVAL.as_secs_f64() * f64::from(self.time_acceleration) * 1000.0;
+        self.simulated_start + TimeDelta::milliseconds(elapsed_ms as i64)
+    }
+
+    /// Simulated time at the current tick
+    pub fn simulated_now(&self) -> NaiveDateTime {
+        self.simulated_at(self.tick_count)
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.simulated_now().date()
+    }
+
+    /// Whole days since the simulated start date
+    pub fn day(&self) -> u16 {
+        (self.date() - self.simulated_start.date()).num_days() as u16
+    }
+
+    /// Simulated hours since midnight
+    pub fn hour_of_day(&self) -> f32 {
+        self.simulated_now().num_seconds_from_midnight() as f32 / 3600.0
+    }
+
+    /// Whole simulated hours from the start of the start date to the start of `tick`
+    pub fn hour_index(&self, tick: Tick) -> usize {
+        (self.simulated_at(tick) - self.simulated_start.date().and_time(NaiveTime::MIN)).num_hours() as usize
+    }
+
+    /// Whether the next tick falls on a new simulated day
+    pub fn is_last_tick_of_day(&self) -> bool {
+        self.simulated_at(Tick(self.tick_count.0 + 1)).date() != self.date()
+    }
+
+    /// Wall-clock time the clock has been running since it was started, not counting pauses
+    pub fn wall_elapsed(&self) -> Duration {
+        let paused = self.paused_for + self.paused_since.map_or(Duration::ZERO, |since| since.elapsed());
+        self.start_wall.elapsed().saturating_sub(paused)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 4, 1).unwrap().and_hms_opt(6, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn an_hour_per_tick_covers_a_day_in_24_ticks() {
+        let mut clock = SimulationClock::new(start(), 3600.0);
+        assert_eq!(clock.tick_duration(), Duration::from_secs(3600));
+        assert_eq!((clock.tick_hours(), clock.tick_days()), (1.0, 1.0 / 24.0));
+        assert_eq!(clock.simulated_now(), start());
+        clock.tick_count = Tick(1);
+        assert_eq!(clock.simulated_now(), start() + TimeDelta::hours(1));
+        assert_eq!(clock.hour_of_day(), 7.0);
+        // 18 hours after 6:00 is midnight, the first tick of the second day
+        clock.tick_count = Tick(17);
+        assert!(clock.is_last_tick_of_day());
+        clock.tick_count = Tick(18);
+        assert_eq!((clock.day(), clock.hour_of_day()), (1, 0.0));
+        assert_eq!(clock.date(), NaiveDate::from_ymd_opt(2024, 4, 2).unwrap());
+        assert!(!clock.is_last_tick_of_day());
+    }
+
+    #[test]
+    fn real_time_and_fractional_acceleration() {
+        let clock = SimulationClock::new(start(), 1.0);
+        assert_eq!(clock.simulated_at(Tick(90)), start() + TimeDelta::seconds(90));
+        let slow = SimulationClock::new(start(), 0.5);
+        assert_eq!(slow.simulated_at(Tick(3)), start() + TimeDelta::milliseconds(1_500));
+        // A season of 120 days at one day per tick
+        let fast = SimulationClock::new(start(), 86_400.0);
+        assert_eq!(fast.simulated_at(Tick(120)).date(), NaiveDate::from_ymd_opt(2024, 7, 30).unwrap());
+    }
+
+    #[test]
+    fn hour_index_counts_from_midnight_of_the_start_date() {
+        let clock = SimulationClock::new(start(), 1800.0);
+        assert_eq!(clock.hour_index(Tick(0)), 6);
+        assert_eq!(clock.hour_index(Tick(1)), 6);
+        assert_eq!(clock.hour_index(Tick(2)), 7);
+        assert_eq!(clock.hour_index(Tick(48)), 30);
+    }
+
+    #[test]
+    fn pausing_leaves_the_simulated_time_where_it_stood() {
+        let mut clock = SimulationClock::new(start(), 60.0);
+        clock.tick_count = Tick(10);
+        let before = clock.simulated_now();
+        clock.pause();
+        clock.pause();
+        assert!(clock.is_paused());
+        assert_eq!(clock.simulated_now(), before);
+        clock.resume();
+        assert!(!clock.is_paused());
+        assert_eq!(clock.simulated_now(), before);
+    }
+
+    #[test]
+    fn a_pause_is_kept_off_the_wall_time() {
+        let mut clock = SimulationClock::new(start(), 60.0);
+        clock.pause();
+        std::thread::sleep(Duration::from_millis(100));
+        clock.resume();
+        assert!(clock.paused_for >= Duration::from_millis(100));
+        assert!(clock.wall_elapsed() < Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,1954 @@
+//! Simulation settings loaded from a TOML file
+
+use crate::agri_iot_core::root_zone::RootZoneModel;
+use crate::alerts::history::{AlertHistory, DEFAULT_CAPACITY};
+use crate::api::gateway::LoadBalancingStrategy;
+use crate::climate::{
+    DaylightModel, MicroclimateModel, NightVentilationController, StructureType, VentilationFan, WeatherCondition,
+    WeatherEngine, WeatherStation,
+};
+use crate::cost_benefit::CostBenefitAnalyzer;
+use crate::export::{self, DataExportManager, ExportError, ExportFormat};
+use crate::crop::calendar::CropCalendar;
+use crate::crop::cover_crop::CoverCropModel;
+use crate::crop::germination::GerminationModel;
+use crate::crop::nitrogen::NitrogenFixationModel;
+use crate::crop::profile::CropKind;
+use crate::crop::rotation::CropRotation;
+use crate::crop::yield_map::{FieldLayout, GeoPoint, YieldMapGenerator};
+use crate::crop::{GrowthStage, PlantProfile};
+use crate::ghg::GhgTracker;
+use crate::hydroponic::AeroponicController;
+use crate::irrigation::emitter::{EmitterArray, EmitterSpec};
+use crate::irrigation::water_hammer::VALVE_CLOSE_TIME_S;
+use crate::irrigation::{
+    BackflowPreventer, BasinModel, BfpType, EnergyModel, FrostProtectionController, IrrigationScheduler,
+    IrrigationStrategy, MaintenanceItem, MaintenanceScheduler, Pipe, PipeNetwork, RainwaterHarvester,
+    SurgeIrrigationController, WaterBudget, WaterHammerModel, ZoneTopology,
+};
+use crate::network::{NetworkLink, QosBuffer};
+use crate::offline_cache::OfflineCache;
+use crate::plugin::{PluginError, PluginLoader};
+use crate::soil::biochar::BiocharAmendment;
+use crate::crop::cwsi::CwsiCalculator;
+use crate::crop::fusion::SensorFusion;
+use crate::soil::carbon::CarbonTracker;
+use crate::soil::drainage::DrainageTile;
+use crate::soil::nutrient_leaching::{LeachingModel, DEFAULT_N_RISK_THRESHOLD_KG_HA};
+use crate::soil::organic_matter::SomTracker;
+use crate::soil::respiration::SoilRespirationModel;
+use crate::soil::salinity::{leaching_requirement, LeachingManager};
+use crate::soil::trace_elements::TraceElementMonitor;
+use crate::soil::water_temperature::WaterTemperatureModel;
+use crate::soil::wetting_front::{SoilType, WettingFrontTracker};
+use crate::{SensorId, Tick, WaterLevelSensor};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Top-level settings; every field is optional and falls back to the built-in default
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulationConfig {
+    /// Moisture percentage below which watering starts
+    pub threshold: f32,
+    /// Moisture percentage when the simulation starts
+    pub initial_moisture: f32,
+    /// Label of the zone's sensor in MQTT topics and the HTTP API
+    #[schemars(with = "String")]
+    pub sensor_id: SensorId,
+    /// How much moisture the soil loses each tick
+    pub evaporation: EvaporationConfig,
+    /// Full or deficit irrigation
+    pub irrigation_strategy: IrrigationStrategy,
+    /// Drip lateral on a slope; its emitter uniformity is shown in the stats panel
+    pub emitters: Option<EmitterConfig>,
+    /// Backflow preventer on the mainline; a failed test blocks irrigation
+    pub backflow: Option<BackflowConfig>,
+    /// Check valve closures for water hammer; disabled when absent
+    pub water_hammer: Option<WaterHammerConfig>,
+    /// Pipes shared between zones; zones start one at a time when they cannot all be fed
+    pub topology: Option<TopologyConfig>,
+    /// Pump and pipes whose friction losses cut the flow of far zones; full flow everywhere when absent
+    pub pipe_network: Option<PipeNetworkConfig>,
+    /// Flood the basin in pulses each time watering starts; disabled when absent
+    pub surge: Option<SurgeConfig>,
+    /// Overhead sprinklers run on frosty nights; no frost protection when absent
+    pub frost_protection: Option<FrostProtectionConfig>,
+    /// Water each season may draw for irrigation, frost protection exempt; unlimited when absent
+    pub water_budget: Option<WaterBudgetConfig>,
+    /// Misting cycle of `--aeroponic` mode; the defaults apply when absent
+    pub aeroponic: Option<AeroponicConfig>,
+    /// Service intervals of the pump, valves and filters; not tracked when absent
+    pub maintenance: Option<MaintenanceConfig>,
+    /// Moisture sensors at several depths under zone 0, drawn as a soil profile; none when absent
+    pub moisture_profile: Option<MoistureProfileConfig>,
+    /// Matric potential sensor in zone 0, shown in the status panel; none when absent
+    pub tensiometer: Option<TensiometerConfig>,
+    /// How deep each of zone 0's waterings soaks, shown in the soil profile; not tracked when absent
+    pub wetting_front: Option<WettingFrontConfig>,
+    /// Heat the seed needs in the seedbed before the crop emerges; it emerges at once when absent
+    pub germination: Option<GerminationConfig>,
+    /// Temperature of the irrigation water, which can chill the soil below the germination threshold
+    pub irrigation_water: IrrigationWaterConfig,
+    /// Iron, zinc, manganese and boron in the topsoil, locked up by alkaline soil; not tracked when absent
+    pub trace_elements: Option<TraceElementsConfig>,
+    /// Electric pump rating and tariff for the energy cost display; disabled when absent
+    pub energy: Option<EnergyConfig>,
+    /// Rainwater tank that irrigation draws on before the main supply; disabled when absent
+    pub rainwater: Option<RainwaterConfig>,
+    /// Salinity of the irrigation water and how often salt is flushed out; disabled when absent
+    pub salinity: Option<SalinityConfig>,
+    /// Nitrate and potassium washed out by irrigation; nutrients stay put when absent
+    pub nutrient_leaching: Option<NutrientLeachingConfig>,
+    /// Greenhouse or polytunnel the crop grows in; open field when absent
+    pub microclimate: Option<MicroclimateConfig>,
+    /// Weather station whose changing skies drive the outdoor air and rain; a fixed daily cycle when absent
+    pub weather_station: Option<WeatherStationConfig>,
+    /// Off-season cover crop worked in ahead of the main crop; bare fallow when absent
+    pub cover_crop: Option<CoverCropConfig>,
+    /// Legume intercropped with the crop, whose fixed nitrogen the crop takes up; none when absent
+    pub nitrogen_fixation: Option<NitrogenFixationConfig>,
+    /// Soil organic matter and how fast it turns over
+    pub organic_matter: OrganicMatterConfig,
+    /// Microbial respiration, whose CO2 efflux is reported with the soil samples and carbon balance
+    pub soil_respiration: SoilRespirationConfig,
+    /// Soil carbon accounting for the annual carbon balance; disabled when absent
+    pub carbon: Option<CarbonConfig>,
+    /// Emission factors for the season's greenhouse gas footprint; not tracked when absent
+    pub ghg: Option<GhgConfig>,
+    /// Prices for `--cost-report`; the defaults when absent
+    pub cost_benefit: Option<CostBenefitConfig>,
+    /// Subsurface tile drains beneath every zone; undrained when absent
+    pub drainage: Option<DrainageConfig>,
+    /// Canopy thermometer whose CWSI is combined with soil moisture to trigger watering; disabled when absent
+    pub sensor_fusion: Option<SensorFusionConfig>,
+    /// Soil lab baseline and how often zones are sampled
+    pub soil_sampling: SoilSamplingConfig,
+    /// Write a yield map of the zones at each harvest; none when absent
+    pub yield_map: Option<YieldMapConfig>,
+    /// Start watering early when the moisture trend reaches the threshold within this many hours
+    pub forecast_horizon_hours: Option<f32>,
+    /// Biochar applications; each raises water retention and so slows moisture loss
+    pub biochar: Vec<BiocharAmendment>,
+    /// Files every tick's sensor readings are written to, each in its own format
+    pub export: Vec<ExportConfig>,
+    /// Dynamic libraries that each add a sensor, read every tick
+    pub plugins: Vec<PluginConfig>,
+    /// Crop-specific adjustments
+    pub crop: CropConfig,
+    /// HTTP API settings
+    pub api: ApiConfig,
+    /// Publish readings to an MQTT broker; disabled when absent
+    pub mqtt: Option<MqttConfig>,
+    /// Publish to several brokers instead of `[mqtt]`'s one
+    pub gateway: Option<MultiGatewayConfig>,
+    /// Lossy, limited-bandwidth gateway uplink the readings and events cross; a perfect link when absent
+    pub network: Option<NetworkConfig>,
+    /// SQLite store for readings taken while the uplink is down; they are dropped when absent
+    pub offline_cache: Option<OfflineCacheConfig>,
+    /// Keep the alert history and its acknowledgments in SQLite across runs; in memory only when absent
+    pub alert_history: Option<AlertHistoryConfig>,
+    /// In-process event delivery to alerters
+    pub event_bus: EventBusConfig,
+}
+
+/// Sizing of the in-process event bus
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct EventBusConfig {
+    /// Events buffered per subscriber before the slowest starts losing them
+    pub capacity: usize,
+}
+
+impl Default for EventBusConfig {
+    fn default() -> Self {
+        Self { capacity: 64 }
+    }
+}
+
+/// MQTT broker connection
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct MqttConfig {
+    /// Broker host name or address
+    pub host: String,
+    /// Broker port, usually 1883 for plain MQTT and 8883 for TLS
+    pub port: u16,
+    /// Client identifier sent to the broker
+    pub client_id: String,
+    /// Readings go to `<topic_prefix>/zones/<zone id>`
+    pub topic_prefix: String,
+    /// Connect over TLS
+    pub tls: Option<MqttTlsConfig>,
+    /// Username and password sent in the CONNECT packet
+    pub auth: Option<MqttAuthConfig>,
+}
+
+/// One broker of a `[gateway]`, set up like `[mqtt]`
+pub type BrokerConfig = MqttConfig;
+
+/// Redundant MQTT brokers and how publishes are spread over them
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct MultiGatewayConfig {
+    /// In priority order: the first is the primary for `failover_primary`
+    pub brokers: Vec<BrokerConfig>,
+    /// `round_robin`, `failover_primary` or `broadcast_all`
+    pub strategy: LoadBalancingStrategy,
+}
+
+/// PEM files for an MQTT TLS connection
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MqttTlsConfig {
+    /// CA certificate the broker's certificate must chain to
+    pub ca_cert: PathBuf,
+    /// Client certificate for mutual TLS; requires `client_key`
+    pub client_cert: Option<PathBuf>,
+    /// Private key for `client_cert`
+    pub client_key: Option<PathBuf>,
+}
+
+/// MQTT broker credentials
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MqttAuthConfig {
+    pub username: String,
+    pub password: String,
+}
+
+/// A drip lateral laid up a slope
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmitterConfig {
+    /// Emitters on the lateral
+    pub count: usize,
+    /// Rise from the inlet to the last emitter, in m
+    pub elevation_diff_m: f32,
+    /// Pressure head at the inlet, in m
+    pub inlet_head_m: f32,
+    /// Emitter hydraulics
+    pub spec: EmitterSpec,
+}
+
+impl Default for EmitterConfig {
+    fn default() -> Self {
+        Self { count: 20, elevation_diff_m: 10.0, inlet_head_m: 20.0, spec: EmitterSpec::default() }
+    }
+}
+
+impl EmitterConfig {
+    /// The lateral, pressurized at the inlet head
+    pub fn array(&self) -> EmitterArray {
+        let mut array = EmitterArray::on_slope(self.count, self.elevation_diff_m, self.spec);
+        array.pressurize(self.inlet_head_m);
+        array
+    }
+}
+
+/// A backflow preventer and its test schedule
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackflowConfig {
+    /// Assembly type
+    #[serde(rename = "type")]
+    pub bfp_type: BfpType,
+    /// Ticks between scheduled tests
+    pub test_frequency_ticks: u32,
+}
+
+impl Default for BackflowConfig {
+    fn default() -> Self {
+        Self { bfp_type: BfpType::default(), test_frequency_ticks: 3600 }
+    }
+}
+
+impl From<&BackflowConfig> for BackflowPreventer {
+    fn from(config: &BackflowConfig) -> Self {
+        BackflowPreventer::new(config.bfp_type, config.test_frequency_ticks)
+    }
+}
+
+/// The mainline checked for surges each time a zone valve closes
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct WaterHammerConfig {
+    /// Pipe and flow the valve closes against
+    pub pipe: WaterHammerModel,
+    /// Surge the pipe tolerates above operating pressure, in kPa
+    pub max_allowable_kpa: f32,
+    /// Close valves gradually over this many seconds instead of snapping shut
+    pub slow_close_time_s: Option<f32>,
+}
+
+impl Default for WaterHammerConfig {
+    fn default() -> Self {
+        Self { pipe: WaterHammerModel::default(), max_allowable_kpa: 400.0, slow_close_time_s: None }
+    }
+}
+
+impl WaterHammerConfig {
+    /// How long each valve takes to close, in s
+    pub fn closure_time_s(&self) -> f32 {
+        self.slow_close_time_s.unwrap_or(VALVE_CLOSE_TIME_S)
+    }
+}
+
+/// When the frost protection sprinklers start and how much they apply
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct FrostProtectionConfig {
+    /// Air temperature below which sprinkling starts, in °C; it stops 0.5 °C above
+    pub activation_temp_c: f32,
+    /// Sprinkling rate, in mm/h
+    pub required_application_rate_mm_h: f32,
+}
+
+impl Default for FrostProtectionConfig {
+    fn default() -> Self {
+        let controller = FrostProtectionController::default();
+        Self {
+            activation_temp_c: controller.activation_temp_c,
+            required_application_rate_mm_h: controller.required_application_rate_mm_h,
+        }
+    }
+}
+
+impl From<&FrostProtectionConfig> for FrostProtectionController {
+    fn from(config: &FrostProtectionConfig) -> Self {
+        FrostProtectionController::new(config.activation_temp_c, config.required_application_rate_mm_h)
+    }
+}
+
+/// Seasonal allowance of supply water
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct WaterBudgetConfig {
+    /// Irrigation, fertigation and leaching water allowed per season, in mm over the field
+    pub seasonal_limit_mm: f32,
+}
+
+impl Default for WaterBudgetConfig {
+    fn default() -> Self {
+        Self { seasonal_limit_mm: 500.0 }
+    }
+}
+
+impl From<&WaterBudgetConfig> for WaterBudget {
+    fn from(config: &WaterBudgetConfig) -> Self {
+        WaterBudget::new(config.seasonal_limit_mm)
+    }
+}
+
+/// Components on a service interval
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct MaintenanceConfig {
+    /// One entry per component; the pump, `valve-0` and `filter-0` by default
+    pub items: Vec<MaintenanceItemConfig>,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        let items = MaintenanceScheduler::default()
+            .items
+            .into_iter()
+            .map(|item| MaintenanceItemConfig {
+                component_id: item.component_id,
+                interval_operating_hours: item.interval_operating_hours,
+                warning_threshold_pct: item.warning_threshold_pct,
+                hours_since_service: item.operating_hours - item.last_service_hours,
+            })
+            .collect();
+        Self { items }
+    }
+}
+
+/// Service interval of one component
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct MaintenanceItemConfig {
+    /// `pump` runs whenever the pump does; `valve-<zone>` and `filter-<zone>` while that zone waters
+    pub component_id: String,
+    /// Operating hours between services
+    pub interval_operating_hours: f32,
+    /// Percentage of the interval after which a warning is raised
+    pub warning_threshold_pct: f32,
+    /// Operating hours already run since the last service when the simulation starts
+    pub hours_since_service: f32,
+}
+
+impl Default for MaintenanceItemConfig {
+    fn default() -> Self {
+        Self {
+            component_id: "pump".to_string(),
+            interval_operating_hours: 500.0,
+            warning_threshold_pct: 80.0,
+            hours_since_service: 0.0,
+        }
+    }
+}
+
+/// Sensors buried at several depths under zone 0 and how the crop's roots spread between them
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct MoistureProfileConfig {
+    /// One entry per sensor, shallowest first; 10, 30 and 60 cm by default
+    pub layers: Vec<SoilLayerConfig>,
+    /// Share of the roots in each layer, in the same order, for the root-weighted moisture
+    pub root_distribution: Vec<f32>,
+}
+
+impl Default for MoistureProfileConfig {
+    fn default() -> Self {
+        let layer = |depth_cm, wetting_front_velocity_cm_per_h| SoilLayerConfig {
+            depth_cm,
+            wetting_front_velocity_cm_per_h,
+        };
+        Self {
+            layers: vec![layer(10, 5.0), layer(30, 3.0), layer(60, 2.0)],
+            root_distribution: vec![0.5, 0.3, 0.2],
+        }
+    }
+}
+
+impl MoistureProfileConfig {
+    /// Wetting front model of every layer, in order
+    pub fn layers(&self) -> Vec<RootZoneModel> {
+        self.layers
+            .iter()
+            .map(|layer| RootZoneModel {
+                depth_cm: layer.depth_cm as f32,
+                wetting_front_velocity_cm_per_h: layer.wetting_front_velocity_cm_per_h,
+            })
+            .collect()
+    }
+}
+
+/// Depth of one buried sensor and how fast infiltrating water reaches it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct SoilLayerConfig {
+    /// Depth of the sensor below the surface, in cm
+    pub depth_cm: u8,
+    /// Speed of the wetting front down to the sensor, in cm/h
+    pub wetting_front_velocity_cm_per_h: f32,
+}
+
+impl Default for SoilLayerConfig {
+    fn default() -> Self {
+        Self { depth_cm: 10, wetting_front_velocity_cm_per_h: 5.0 }
+    }
+}
+
+impl From<&MaintenanceConfig> for MaintenanceScheduler {
+    fn from(config: &MaintenanceConfig) -> Self {
+        let item = |config: &MaintenanceItemConfig| {
+            let mut item = MaintenanceItem::new(
+                config.component_id.clone(),
+                config.interval_operating_hours,
+                config.warning_threshold_pct,
+            );
+            item.operating_hours = config.hours_since_service;
+            item
+        };
+        MaintenanceScheduler::new(config.items.iter().map(item).collect())
+    }
+}
+
+/// Tensiometer buried in zone 0, read against the soil's retention curve
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct TensiometerConfig {
+    /// Depth of the ceramic cup, in cm; the nearest `[moisture_profile]` sensor's moisture is read there
+    pub depth_cm: f32,
+    /// Rate at which the reading closes on the soil's matric potential, per hour
+    pub ceramic_cup_conductance: f32,
+}
+
+impl Default for TensiometerConfig {
+    fn default() -> Self {
+        Self { depth_cm: 30.0, ceramic_cup_conductance: 2.0 }
+    }
+}
+
+/// Soil the wetting fronts of zone 0's waterings soak into
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct WettingFrontConfig {
+    /// Texture of the soil: `sand`, `loam` or `clay`
+    pub soil_type: SoilType,
+}
+
+impl From<&WettingFrontConfig> for WettingFrontTracker {
+    fn from(config: &WettingFrontConfig) -> Self {
+        WettingFrontTracker::new(config.soil_type)
+    }
+}
+
+/// Seedbed the crop germinates in before its growth stages start counting degree days
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct GerminationConfig {
+    /// Soil temperature below which the seed accrues no heat, in °C
+    pub base_temp_c: f32,
+    /// Heat the seed needs to germinate, in °C·days above `base_temp_c`
+    pub germination_threshold_hu: f32,
+    /// Depth of the seed and the soil temperature probe beside it, in cm
+    pub seeding_depth_cm: f32,
+}
+
+impl Default for GerminationConfig {
+    /// Maize sown 2 cm deep
+    fn default() -> Self {
+        Self { base_temp_c: 10.0, germination_threshold_hu: 60.0, seeding_depth_cm: 2.0 }
+    }
+}
+
+impl From<&GerminationConfig> for GerminationModel {
+    fn from(config: &GerminationConfig) -> Self {
+        GerminationModel::new(config.base_temp_c, config.germination_threshold_hu)
+    }
+}
+
+/// Irrigation water and the soil it mixes its heat into
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct IrrigationWaterConfig {
+    /// Temperature of the water as it leaves the supply, in °C
+    pub water_temp_c: f32,
+    /// Volumetric heat capacity of the root-zone soil, in kJ/(L·K)
+    pub soil_specific_heat: f32,
+}
+
+impl Default for IrrigationWaterConfig {
+    /// Water from a deep well into moist loam
+    fn default() -> Self {
+        Self { water_temp_c: 8.0, soil_specific_heat: 2.5 }
+    }
+}
+
+impl IrrigationWaterConfig {
+    /// One watering of `water_volume_l` into `soil_volume_l` of soil at `soil_temp_before`
+    pub fn model(&self, soil_temp_before: f32, soil_volume_l: f32, water_volume_l: f32) -> WaterTemperatureModel {
+        WaterTemperatureModel {
+            water_temp_c: self.water_temp_c,
+            soil_temp_before,
+            soil_volume_l,
+            water_volume_l,
+            soil_specific_heat: self.soil_specific_heat,
+        }
+    }
+}
+
+/// Micronutrients at the start of the run and how fast alkaline soil locks up iron
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct TraceElementsConfig {
+    /// DTPA-extractable iron, in ppm
+    pub fe_ppm: f32,
+    /// DTPA-extractable zinc, in ppm
+    pub zn_ppm: f32,
+    /// DTPA-extractable manganese, in ppm
+    pub mn_ppm: f32,
+    /// Hot-water-extractable boron, in ppm
+    pub b_ppm: f32,
+    /// Iron lost per day while `soil_sampling.ph` is above 7.5, and gained below 5.5; manganese moves at half this
+    pub fe_rate_ppm_per_day: f32,
+}
+
+impl Default for TraceElementsConfig {
+    fn default() -> Self {
+        Self { fe_ppm: 6.0, zn_ppm: 1.5, mn_ppm: 3.0, b_ppm: 0.8, fe_rate_ppm_per_day: 0.05 }
+    }
+}
+
+impl From<&TraceElementsConfig> for TraceElementMonitor {
+    fn from(config: &TraceElementsConfig) -> Self {
+        TraceElementMonitor {
+            fe_ppm: config.fe_ppm,
+            zn_ppm: config.zn_ppm,
+            mn_ppm: config.mn_ppm,
+            b_ppm: config.b_ppm,
+            fe_rate_ppm_per_day: config.fe_rate_ppm_per_day,
+        }
+    }
+}
+
+/// How long the aeroponic nozzles mist and rest, and what each mist sprays
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct AeroponicConfig {
+    /// Ticks each mist lasts
+    pub on_duration_ticks: u32,
+    /// Ticks the roots hang in the air between mists
+    pub off_duration_ticks: u32,
+    /// Solution sprayed per mist, in mL
+    pub mist_volume_ml_per_cycle: f32,
+}
+
+impl Default for AeroponicConfig {
+    fn default() -> Self {
+        let controller = AeroponicController::default();
+        Self {
+            on_duration_ticks: controller.on_duration_ticks,
+            off_duration_ticks: controller.off_duration_ticks,
+            mist_volume_ml_per_cycle: controller.mist_volume_ml_per_cycle,
+        }
+    }
+}
+
+impl From<&AeroponicConfig> for AeroponicController {
+    fn from(config: &AeroponicConfig) -> Self {
+        AeroponicController::new(config.on_duration_ticks, config.off_duration_ticks, config.mist_volume_ml_per_cycle)
+    }
+}
+
+/// Pulse timing of surge irrigation and the basin it floods
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct SurgeConfig {
+    /// Seconds the pump runs per surge
+    pub surge_duration_s: u32,
+    /// Seconds the pump rests between surges
+    pub pause_s: u32,
+    /// Surges per watering at most; the cycle ends early once the basin is wetted
+    pub n_surges: u8,
+    /// Basin size, dry-soil advance speed and inflow
+    pub basin: BasinModel,
+}
+
+impl Default for SurgeConfig {
+    fn default() -> Self {
+        Self { surge_duration_s: 600, pause_s: 600, n_surges: 4, basin: BasinModel::default() }
+    }
+}
+
+impl From<&SurgeConfig> for SurgeIrrigationController {
+    fn from(config: &SurgeConfig) -> Self {
+        SurgeIrrigationController::new(config.surge_duration_s, config.pause_s, config.n_surges, config.basin)
+    }
+}
+
+/// Electric pump and the tariff its energy is billed at
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnergyConfig {
+    /// Hydraulic power the pump delivers, in kW
+    pub pump_power_kw: f32,
+    /// Wire-to-water efficiency, above 0 and at most 1
+    pub pump_efficiency: f32,
+    /// Electricity price per kWh, in the local currency
+    pub electricity_rate_per_kwh: f32,
+}
+
+impl Default for EnergyConfig {
+    fn default() -> Self {
+        Self { pump_power_kw: 1.5, pump_efficiency: 0.7, electricity_rate_per_kwh: 0.15 }
+    }
+}
+
+impl From<&EnergyConfig> for EnergyModel {
+    fn from(config: &EnergyConfig) -> Self {
+        EnergyModel::new(config.pump_power_kw, config.pump_efficiency, config.electricity_rate_per_kwh)
+    }
+}
+
+/// Catchment and tank for rainwater harvesting
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct RainwaterConfig {
+    /// Roof or field area draining into the tank, in m²
+    pub catchment_area_m2: f32,
+    /// Share of the rain that runs off into the tank, 0 to 1
+    pub runoff_coefficient: f32,
+    /// Tank size, in litres
+    pub tank_capacity_l: f32,
+}
+
+impl Default for RainwaterConfig {
+    fn default() -> Self {
+        Self { catchment_area_m2: 200.0, runoff_coefficient: 0.8, tank_capacity_l: 5000.0 }
+    }
+}
+
+impl From<&RainwaterConfig> for RainwaterHarvester {
+    fn from(config: &RainwaterConfig) -> Self {
+        let tank = WaterLevelSensor::new(config.tank_capacity_l);
+        RainwaterHarvester::new(config.catchment_area_m2, config.runoff_coefficient, tank)
+    }
+}
+
+/// Irrigation water quality and the flushing schedule that manages it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct SalinityConfig {
+    /// Electrical conductivity of the irrigation water, in dS/m
+    pub ec_water_ds_m: f32,
+    /// Soil ECe the crop tolerates without yield loss, in dS/m; the crop's own value when unset
+    pub ec_threshold_ds_m: Option<f32>,
+    /// Ticks between flushing irrigations
+    pub leach_interval_ticks: u32,
+}
+
+impl Default for SalinityConfig {
+    fn default() -> Self {
+        Self { ec_water_ds_m: 1.2, ec_threshold_ds_m: None, leach_interval_ticks: 86_400 }
+    }
+}
+
+impl SalinityConfig {
+    /// Leaching manager for water of this quality on `crop`
+    pub fn manager(&self, crop: &PlantProfile) -> LeachingManager {
+        let threshold = self.ec_threshold_ds_m.unwrap_or(crop.salinity_threshold_ds_m);
+        LeachingManager::new(leaching_requirement(self.ec_water_ds_m, threshold), self.leach_interval_ticks)
+    }
+}
+
+/// How readily irrigation washes nutrients out and how much nitrate loss is tolerated
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct NutrientLeachingConfig {
+    /// Share of the soil nitrate lost per mm of irrigation
+    pub leach_coeff_n: f32,
+    /// Share of the soil potassium lost per mm of irrigation
+    pub leach_coeff_k: f32,
+    /// Cumulative nitrate leached from a zone, in kg N/ha, that raises an environmental risk alert
+    pub n_risk_threshold_kg_ha: f32,
+}
+
+impl Default for NutrientLeachingConfig {
+    fn default() -> Self {
+        let model = LeachingModel::default();
+        Self {
+            leach_coeff_n: model.leach_coeff_n,
+            leach_coeff_k: model.leach_coeff_k,
+            n_risk_threshold_kg_ha: DEFAULT_N_RISK_THRESHOLD_KG_HA,
+        }
+    }
+}
+
+impl From<&NutrientLeachingConfig> for LeachingModel {
+    fn from(config: &NutrientLeachingConfig) -> Self {
+        LeachingModel { leach_coeff_n: config.leach_coeff_n, leach_coeff_k: config.leach_coeff_k }
+    }
+}
+
+/// Growing structure, with its typical cover and vents unless overridden
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct MicroclimateConfig {
+    /// `open_field`, `polytunnel` or `glasshouse`
+    pub structure_type: StructureType,
+    /// Share of the sunlight the cover lets through, 0 to 1
+    pub glazing_transmissivity: Option<f32>,
+    /// Air exchanged by the vents, in m³ per m² of floor per second
+    pub ventilation_rate: Option<f32>,
+    /// Hour of sunrise, local time
+    pub sunrise_hour: f32,
+    /// Hour of sunset, local time
+    pub sunset_hour: f32,
+    /// Open the vents at night to cool the structure; vents stay shut after dark when absent
+    pub night_ventilation: Option<NightVentilationConfig>,
+}
+
+impl Default for MicroclimateConfig {
+    fn default() -> Self {
+        let daylight = DaylightModel::default();
+        Self {
+            structure_type: StructureType::default(),
+            glazing_transmissivity: None,
+            ventilation_rate: None,
+            sunrise_hour: daylight.sunrise_hour,
+            sunset_hour: daylight.sunset_hour,
+            night_ventilation: None,
+        }
+    }
+}
+
+impl MicroclimateConfig {
+    pub fn daylight(&self) -> DaylightModel {
+        DaylightModel { sunrise_hour: self.sunrise_hour, sunset_hour: self.sunset_hour }
+    }
+
+    /// Night vent controller, if configured
+    pub fn night_ventilation(&self) -> Option<NightVentilationController> {
+        self.night_ventilation.as_ref().map(|night| {
+            let fan = VentilationFan::new(night.vent_ventilation_rate);
+            NightVentilationController::new(night.target_night_temp_c, fan, self.daylight())
+        })
+    }
+}
+
+/// Vents opened after sunset while the structure is too warm
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct NightVentilationConfig {
+    /// Inside temperature above which vents open at night, in °C; they close 2 °C below it
+    pub target_night_temp_c: f32,
+    /// Air the open vents exchange, in m³ per m² of floor per second
+    pub vent_ventilation_rate: f32,
+}
+
+impl Default for NightVentilationConfig {
+    fn default() -> Self {
+        Self { target_night_temp_c: 24.0, vent_ventilation_rate: 0.1 }
+    }
+}
+
+impl From<&MicroclimateConfig> for MicroclimateModel {
+    fn from(config: &MicroclimateConfig) -> Self {
+        let defaults = MicroclimateModel::for_structure(config.structure_type);
+        MicroclimateModel {
+            glazing_transmissivity: config.glazing_transmissivity.unwrap_or(defaults.glazing_transmissivity),
+            ventilation_rate: config.ventilation_rate.unwrap_or(defaults.ventilation_rate),
+            ..defaults
+        }
+    }
+}
+
+/// Cover crop residue incorporated into the soil
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct CoverCropConfig {
+    /// Species name shown in the TUI
+    pub species: String,
+    /// Dry matter at termination, in t/ha
+    pub dry_matter_t_ha: f32,
+    /// Nitrogen content of the dry matter, in percent
+    pub n_content_pct: f32,
+    /// Tick the cover crop is terminated and incorporated; at the start of the run by default
+    pub termination_tick: u64,
+}
+
+impl Default for CoverCropConfig {
+    fn default() -> Self {
+        let rye = CoverCropModel::cereal_rye(Tick::ZERO);
+        Self {
+            species: rye.species,
+            dry_matter_t_ha: rye.dry_matter_t_ha,
+            n_content_pct: rye.n_content_pct,
+            termination_tick: rye.termination_tick.0,
+        }
+    }
+}
+
+impl From<&CoverCropConfig> for CoverCropModel {
+    fn from(config: &CoverCropConfig) -> Self {
+        CoverCropModel::new(
+            config.species.clone(),
+            config.dry_matter_t_ha,
+            config.n_content_pct,
+            Tick(config.termination_tick),
+        )
+    }
+}
+
+/// A legume intercropped with the crop that fixes atmospheric nitrogen
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct NitrogenFixationConfig {
+    /// The companion legume, whose base temperature sets when fixation starts
+    pub legume: CropKind,
+    /// Nitrogen fixed per growing degree day, in kg/ha
+    pub fixation_rate_kg_ha_per_gdd: f32,
+}
+
+impl Default for NitrogenFixationConfig {
+    fn default() -> Self {
+        let beans = NitrogenFixationModel::beans();
+        Self { legume: CropKind::Beans, fixation_rate_kg_ha_per_gdd: beans.fixation_rate_kg_ha_per_gdd }
+    }
+}
+
+impl From<&NitrogenFixationConfig> for NitrogenFixationModel {
+    fn from(config: &NitrogenFixationConfig) -> Self {
+        NitrogenFixationModel::new(config.legume.profile(), config.fixation_rate_kg_ha_per_gdd)
+    }
+}
+
+/// Starting soil organic matter and its turnover rates
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrganicMatterConfig {
+    /// Organic matter share of the topsoil at the start, in percent
+    pub som_pct: f32,
+    /// Share of the organic matter decomposed per year at 25 °C
+    pub mineralization_rate: f32,
+    /// Percentage points of organic matter each t/ha of incorporated residue adds
+    pub humification_rate: f32,
+}
+
+impl Default for OrganicMatterConfig {
+    fn default() -> Self {
+        let som = SomTracker::default();
+        Self {
+            som_pct: som.som_pct,
+            mineralization_rate: som.mineralization_rate,
+            humification_rate: som.humification_rate,
+        }
+    }
+}
+
+impl From<&OrganicMatterConfig> for SomTracker {
+    fn from(config: &OrganicMatterConfig) -> Self {
+        SomTracker::new(config.som_pct, config.mineralization_rate, config.humification_rate)
+    }
+}
+
+/// Q10 respiration model of the soil's microbes
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct SoilRespirationConfig {
+    /// CO2 flux at 10 °C and the optimum moisture, in g CO2/m²/h
+    pub basal_rate_g_co2_m2_h: f32,
+    /// Factor respiration rises by for every 10 °C
+    pub q10: f32,
+    /// Moisture level of the soil at field capacity; respiration peaks at 60% of it
+    pub field_capacity_pct: f32,
+}
+
+impl Default for SoilRespirationConfig {
+    fn default() -> Self {
+        let model = SoilRespirationModel::default();
+        Self {
+            basal_rate_g_co2_m2_h: model.basal_rate_g_co2_m2_h,
+            q10: model.q10,
+            field_capacity_pct: model.field_capacity_pct,
+        }
+    }
+}
+
+impl From<&SoilRespirationConfig> for SoilRespirationModel {
+    fn from(config: &SoilRespirationConfig) -> Self {
+        SoilRespirationModel::new(config.basal_rate_g_co2_m2_h, config.q10, config.field_capacity_pct)
+    }
+}
+
+/// Soil layer the carbon stock is reported over and how much biochar carbon lasts
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct CarbonConfig {
+    /// Dry bulk density of the sampled layer, in g/cm³
+    pub bulk_density_g_cm3: f32,
+    /// Sampling depth, in cm
+    pub depth_cm: f32,
+    /// Share of applied biochar carbon counted as sequestered, 0 to 1
+    pub biochar_stability_factor: f32,
+}
+
+impl Default for CarbonConfig {
+    fn default() -> Self {
+        Self { bulk_density_g_cm3: 1.3, depth_cm: 30.0, biochar_stability_factor: 0.8 }
+    }
+}
+
+impl CarbonConfig {
+    /// Tracker starting from the carbon in `organic_matter`'s starting SOM
+    pub fn tracker(&self, organic_matter: &OrganicMatterConfig) -> CarbonTracker {
+        let Self { bulk_density_g_cm3, depth_cm, biochar_stability_factor } = *self;
+        CarbonTracker::new(organic_matter.som_pct, bulk_density_g_cm3, depth_cm, biochar_stability_factor)
+    }
+}
+
+/// Emission factors of the field's inputs and the footprint aimed for
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct GhgConfig {
+    /// Grid emissions per kWh the pump draws, in kg CO2e
+    pub electricity_emission_factor_kg_co2_kwh: f32,
+    /// Emissions of supplying and conveying each m³ of water pumped, in kg CO2e
+    pub water_transport_factor: f32,
+    /// Emissions per kg of nitrogen fertilizer applied, manufacture and field N2O, in kg CO2e
+    pub fertilizer_factor_kg_co2_per_kg_n: f32,
+    /// Area the pump irrigates, in ha
+    pub field_area_ha: f32,
+    /// Season footprint to stay within, in kg CO2e/ha
+    pub target_kg_co2e_ha: Option<f32>,
+}
+
+impl Default for GhgConfig {
+    fn default() -> Self {
+        Self {
+            electricity_emission_factor_kg_co2_kwh: 0.4,
+            water_transport_factor: 0.1,
+            fertilizer_factor_kg_co2_per_kg_n: 5.5,
+            field_area_ha: 1.0,
+            target_kg_co2e_ha: None,
+        }
+    }
+}
+
+impl From<&GhgConfig> for GhgTracker {
+    fn from(config: &GhgConfig) -> Self {
+        GhgTracker::new(
+            config.electricity_emission_factor_kg_co2_kwh,
+            config.water_transport_factor,
+            config.fertilizer_factor_kg_co2_per_kg_n,
+            config.field_area_ha,
+            config.target_kg_co2e_ha,
+        )
+    }
+}
+
+/// Prices and inputs the batch cost report values each strategy's season at
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct CostBenefitConfig {
+    /// Price of irrigation water per m³
+    pub water_cost_per_m3: f32,
+    /// Electricity tariff per kWh
+    pub electricity_cost_per_kwh: f32,
+    /// Farm-gate price of the harvested crop per tonne
+    pub crop_price_per_tonne: f32,
+    /// Energy the pump draws per m³ it delivers, in kWh
+    pub pumping_energy_kwh_per_m3: f32,
+    /// Price of nitrogen fertilizer per kg N
+    pub fertilizer_cost_per_kg_n: f32,
+    /// Nitrogen applied per season, in kg/ha
+    pub fertilizer_n_kg_ha: f32,
+    /// Irrigation water a season of full irrigation applies, in mm
+    pub season_irrigation_need_mm: f32,
+}
+
+impl Default for CostBenefitConfig {
+    fn default() -> Self {
+        let analyzer = CostBenefitAnalyzer::default();
+        Self {
+            water_cost_per_m3: analyzer.water_cost_per_m3,
+            electricity_cost_per_kwh: analyzer.electricity_cost_per_kwh,
+            crop_price_per_tonne: analyzer.crop_price_per_tonne,
+            pumping_energy_kwh_per_m3: analyzer.pumping_energy_kwh_per_m3,
+            fertilizer_cost_per_kg_n: analyzer.fertilizer_cost_per_kg_n,
+            fertilizer_n_kg_ha: analyzer.fertilizer_n_kg_ha,
+            season_irrigation_need_mm: analyzer.season_irrigation_need_mm,
+        }
+    }
+}
+
+impl From<&CostBenefitConfig> for CostBenefitAnalyzer {
+    fn from(config: &CostBenefitConfig) -> Self {
+        CostBenefitAnalyzer {
+            water_cost_per_m3: config.water_cost_per_m3,
+            electricity_cost_per_kwh: config.electricity_cost_per_kwh,
+            crop_price_per_tonne: config.crop_price_per_tonne,
+            pumping_energy_kwh_per_m3: config.pumping_energy_kwh_per_m3,
+            fertilizer_cost_per_kg_n: config.fertilizer_cost_per_kg_n,
+            fertilizer_n_kg_ha: config.fertilizer_n_kg_ha,
+            season_irrigation_need_mm: config.season_irrigation_need_mm,
+        }
+    }
+}
+
+/// Tile drain layout and the soil it drains
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct DrainageConfig {
+    /// Depth of the drains below the surface, in cm
+    pub depth_cm: f32,
+    /// Spacing between laterals, in m
+    pub spacing_m: f32,
+    /// Saturated hydraulic conductivity of the soil, in m/day
+    pub flow_coefficient: f32,
+    /// Moisture level the soil drains down to, in percent
+    pub field_capacity_pct: f32,
+}
+
+impl Default for DrainageConfig {
+    fn default() -> Self {
+        Self { depth_cm: 100.0, spacing_m: 20.0, flow_coefficient: 0.5, field_capacity_pct: 60.0 }
+    }
+}
+
+impl From<&DrainageConfig> for DrainageTile {
+    fn from(config: &DrainageConfig) -> Self {
+        DrainageTile::new(config.depth_cm, config.spacing_m, config.flow_coefficient, config.field_capacity_pct)
+    }
+}
+
+/// Weights of the soil water deficit and canopy CWSI in the fused stress index
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct SensorFusionConfig {
+    /// Weight of the soil water deficit
+    pub fusion_weight_soil: f32,
+    /// Weight of the crop water stress index
+    pub fusion_weight_cwsi: f32,
+    /// Fused index, 0 to 1, above which a zone is watered even with moisture above the threshold
+    pub stress_threshold: f32,
+}
+
+impl Default for SensorFusionConfig {
+    fn default() -> Self {
+        Self { fusion_weight_soil: 0.5, fusion_weight_cwsi: 0.5, stress_threshold: 0.6 }
+    }
+}
+
+impl From<&SensorFusionConfig> for SensorFusion {
+    fn from(config: &SensorFusionConfig) -> Self {
+        SensorFusion::new(
+            CwsiCalculator::default(),
+            config.fusion_weight_soil,
+            config.fusion_weight_cwsi,
+            config.stress_threshold,
+        )
+    }
+}
+
+/// Soil test values the models do not simulate, and where and how often samples are taken
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct SoilSamplingConfig {
+    /// Sample every zone this often; only on demand with `S` when unset
+    pub soil_sample_interval_ticks: Option<u64>,
+    /// Directory the JSON reports are written to
+    pub directory: PathBuf,
+    /// Soil pH
+    pub ph: f32,
+    /// Plant-available (Olsen) phosphorus, in ppm
+    pub p_ppm: f32,
+    /// Exchangeable potassium, in ppm
+    pub k_ppm: f32,
+}
+
+/// Where the zones lie and where their yield maps go
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct YieldMapConfig {
+    /// Directory the `season_<n>.csv` and `.geojson` maps are written to
+    pub directory: PathBuf,
+    /// Latitude of zone 0 when `zones` does not place it, in decimal degrees
+    pub origin_latitude: f64,
+    /// Longitude of zone 0; further unplaced zones follow 0.001° apart to the east
+    pub origin_longitude: f64,
+    /// Position of each zone by zone id
+    pub zones: Vec<PositionConfig>,
+    /// Grid over the field to interpolate the yield onto; zone points only when absent
+    pub field: Option<FieldLayoutConfig>,
+}
+
+impl Default for YieldMapConfig {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("yield_maps"),
+            origin_latitude: 52.0,
+            origin_longitude: 5.0,
+            zones: Vec::new(),
+            field: None,
+        }
+    }
+}
+
+/// A position in decimal degrees
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PositionConfig {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl From<PositionConfig> for GeoPoint {
+    fn from(config: PositionConfig) -> Self {
+        GeoPoint { latitude: config.latitude, longitude: config.longitude }
+    }
+}
+
+/// Bounding box of the field and the grid the yield is interpolated onto
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FieldLayoutConfig {
+    pub south_west: PositionConfig,
+    pub north_east: PositionConfig,
+    /// Grid cells from south to north
+    pub rows: u32,
+    /// Grid cells from west to east
+    pub columns: u32,
+}
+
+impl From<&YieldMapConfig> for YieldMapGenerator {
+    fn from(config: &YieldMapConfig) -> Self {
+        YieldMapGenerator {
+            directory: config.directory.clone(),
+            origin: GeoPoint { latitude: config.origin_latitude, longitude: config.origin_longitude },
+            zone_positions: config.zones.iter().copied().map(GeoPoint::from).collect(),
+            layout: config.field.as_ref().map(|field| FieldLayout {
+                south_west: field.south_west.into(),
+                north_east: field.north_east.into(),
+                rows: field.rows,
+                columns: field.columns,
+                cell_zones: Vec::new(),
+            }),
+        }
+    }
+}
+
+impl Default for SoilSamplingConfig {
+    fn default() -> Self {
+        Self {
+            soil_sample_interval_ticks: None,
+            directory: PathBuf::from("soil_samples"),
+            ph: 6.5,
+            p_ppm: 25.0,
+            k_ppm: 150.0,
+        }
+    }
+}
+
+/// Outdoor weather station
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct WeatherStationConfig {
+    /// Sky at the start of the run: `clear`, `cloudy`, `overcast` or `rain`
+    pub initial_condition: WeatherCondition,
+}
+
+impl From<&WeatherStationConfig> for WeatherStation {
+    fn from(config: &WeatherStationConfig) -> Self {
+        WeatherStation::new(WeatherEngine::new(config.initial_condition))
+    }
+}
+
+/// Gateway uplink quality and how many events are held while it is down
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// Chance that a packet is lost, 0 to 1
+    pub packet_loss_rate: f64,
+    /// One-way delay of an idle link, in ms
+    pub latency_ms: u64,
+    /// Link rate; traffic above it waits in a queue
+    pub bandwidth_bps: u64,
+    /// Events held for delivery during an outage; the oldest are dropped beyond this
+    pub buffer_size: usize,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self { packet_loss_rate: 0.01, latency_ms: 150, bandwidth_bps: 50_000, buffer_size: 256 }
+    }
+}
+
+impl From<&NetworkConfig> for NetworkLink {
+    fn from(config: &NetworkConfig) -> Self {
+        NetworkLink::new(config.packet_loss_rate, config.latency_ms, config.bandwidth_bps)
+    }
+}
+
+impl From<&NetworkConfig> for QosBuffer {
+    fn from(config: &NetworkConfig) -> Self {
+        QosBuffer::new(config.buffer_size)
+    }
+}
+
+/// One file the sensor readings are exported to
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExportConfig {
+    /// `csv`, `json` (one object per line), `sqlite` or `null`
+    pub format: ExportFormat,
+    /// File written, truncated first unless it is an SQLite database; unused by `null`
+    #[serde(default)]
+    pub path: PathBuf,
+}
+
+/// Opens an exporter for every `[[export]]` table
+pub fn export_manager(exports: &[ExportConfig]) -> Result<DataExportManager, ExportError> {
+    let mut manager = DataExportManager::new();
+    for config in exports {
+        manager.register(export::open(config.format, &config.path)?);
+    }
+    Ok(manager)
+}
+
+/// One dynamic library adding a sensor
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PluginConfig {
+    /// Library built as a `cdylib`, e.g. `target/release/libexample_plugin.so`
+    pub path: PathBuf,
+    /// Handed to the plugin to build its sensor; each plugin documents its own keys
+    #[serde(default)]
+    #[schemars(with = "serde_json::Map<String, serde_json::Value>")]
+    pub config: toml::Table,
+}
+
+/// Loads every `[[plugins]]` table's library and builds its sensor
+pub fn plugin_loader(plugins: &[PluginConfig]) -> Result<PluginLoader, PluginError> {
+    let mut loader = PluginLoader::new();
+    for plugin in plugins {
+        loader.load(&plugin.path, &toml::Value::Table(plugin.config.clone()))?;
+    }
+    Ok(loader)
+}
+
+/// Where readings are kept during an outage and how many
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct OfflineCacheConfig {
+    /// SQLite database file, created if missing
+    pub db_path: PathBuf,
+    /// Readings kept; the oldest expire beyond this
+    pub max_entries: usize,
+}
+
+impl Default for OfflineCacheConfig {
+    fn default() -> Self {
+        Self { db_path: PathBuf::from("offline_cache.db"), max_entries: 10_000 }
+    }
+}
+
+impl OfflineCacheConfig {
+    /// Opens the cache database
+    pub fn open(&self) -> rusqlite::Result<OfflineCache> {
+        OfflineCache::open(&self.db_path, self.max_entries)
+    }
+}
+
+/// Where the alert history is stored and how much of it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertHistoryConfig {
+    /// SQLite database file, created if missing
+    pub db_path: PathBuf,
+    /// Alerts kept; acknowledged ones are dropped first beyond this
+    pub capacity: usize,
+}
+
+impl Default for AlertHistoryConfig {
+    fn default() -> Self {
+        Self { db_path: PathBuf::from("alerts.db"), capacity: DEFAULT_CAPACITY }
+    }
+}
+
+impl AlertHistoryConfig {
+    /// Opens the history database with the alerts of earlier runs
+    pub fn open(&self) -> rusqlite::Result<AlertHistory> {
+        AlertHistory::open(&self.db_path, self.capacity)
+    }
+}
+
+/// Zone pipe network as an adjacency list keyed by the upstream zone
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct TopologyConfig {
+    /// Flow one watering zone draws, in L/s
+    pub zone_demand_lps: f32,
+    /// Pipes leaving each zone
+    pub pipes: BTreeMap<u8, Vec<PipeConfig>>,
+}
+
+impl Default for TopologyConfig {
+    fn default() -> Self {
+        Self { zone_demand_lps: 1.0, pipes: BTreeMap::new() }
+    }
+}
+
+/// One pipe segment to a downstream zone
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PipeConfig {
+    /// Downstream zone
+    pub to: u8,
+    /// Most flow the segment carries, in L/s
+    pub capacity_lps: f32,
+}
+
+impl From<&TopologyConfig> for IrrigationScheduler {
+    fn from(config: &TopologyConfig) -> Self {
+        let edges = config
+            .pipes
+            .iter()
+            .flat_map(|(&from, pipes)| pipes.iter().map(move |pipe| (from, pipe.to, pipe.capacity_lps)))
+            .collect();
+        IrrigationScheduler { topology: ZoneTopology { edges }, zone_demand_lps: config.zone_demand_lps }
+    }
+}
+
+/// Pump curve, inlet pressure the zones need and the pipe from the pump to each zone
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct PipeNetworkConfig {
+    /// Pump head with no flow, in m (10 m is about 1 bar)
+    pub pump_shutoff_head_m: f32,
+    /// Flow at which the pump's head falls to nothing, in L/s
+    pub pump_max_flow_lps: f32,
+    /// Head a zone needs at its inlet for its full flow, in m
+    pub required_head_m: f32,
+    /// Flow one watering zone draws at the required head, in L/s
+    pub zone_demand_lps: f32,
+    /// Pipe to each zone in zone order; zones past the last pipe always get full flow
+    pub pipes: Vec<NetworkPipeConfig>,
+}
+
+impl Default for PipeNetworkConfig {
+    fn default() -> Self {
+        Self {
+            pump_shutoff_head_m: 40.0,
+            pump_max_flow_lps: 8.0,
+            required_head_m: 20.0,
+            zone_demand_lps: 1.5,
+            pipes: Vec::new(),
+        }
+    }
+}
+
+/// One pipe from the pump's manifold to a zone
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkPipeConfig {
+    /// Length, in m
+    pub length_m: f32,
+    /// Inside diameter, in mm
+    pub diameter_mm: f32,
+    /// Hazen-Williams C: 150 for PVC, 140 for PE, 100 for old steel
+    pub roughness: f32,
+}
+
+impl Default for NetworkPipeConfig {
+    /// 100 m of 50 mm PVC
+    fn default() -> Self {
+        Self { length_m: 100.0, diameter_mm: 50.0, roughness: 150.0 }
+    }
+}
+
+impl From<&PipeNetworkConfig> for PipeNetwork {
+    fn from(config: &PipeNetworkConfig) -> Self {
+        let pipe = |pipe: &NetworkPipeConfig| Pipe::new(pipe.length_m, pipe.diameter_mm, pipe.roughness);
+        let pipes = config.pipes.iter().map(pipe).collect();
+        let (head_m, max_flow_lps) = (config.pump_shutoff_head_m, config.pump_max_flow_lps);
+        PipeNetwork::new(pipes, head_m, max_flow_lps, config.required_head_m, config.zone_demand_lps)
+    }
+}
+
+/// Overrides for the simulated crop (maize unless a rotation is set)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct CropConfig {
+    /// Threshold multiplier per growth stage, replacing the crop's built-in value for that stage
+    pub stage_threshold_multipliers: HashMap<GrowthStage, f32>,
+    /// Planting and harvest dates; without it the crop is only harvested with `H`
+    pub calendar: Option<CalendarConfig>,
+    /// Crops grown in turn, one per season, starting over after the last; maize every season when empty
+    pub rotation: Vec<CropKind>,
+}
+
+/// Season dates, in simulated days since the start of the run
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalendarConfig {
+    /// Day the first season is planted
+    pub planting_day: u16,
+    /// Day the first season is harvested
+    pub harvest_day: u16,
+    /// Fallow days between a harvest and the next planting; a single season when unset
+    pub replanting_interval_days: Option<u16>,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self { planting_day: 0, harvest_day: 120, replanting_interval_days: None }
+    }
+}
+
+impl From<&CalendarConfig> for CropCalendar {
+    fn from(config: &CalendarConfig) -> Self {
+        CropCalendar::new(config.planting_day, config.harvest_day, config.replanting_interval_days)
+    }
+}
+
+impl CropConfig {
+    /// The first season's crop, the default unless a rotation is set, with these overrides applied
+    pub fn profile(&self) -> PlantProfile {
+        let crop = self.rotation.first().map_or_else(PlantProfile::default, |kind| kind.profile());
+        self.with_overrides(crop)
+    }
+
+    /// The rotation with these overrides applied to each of its crops, if one is set
+    pub fn rotation(&self) -> Option<CropRotation> {
+        let sequence: Vec<PlantProfile> = self.rotation.iter().map(|kind| self.with_overrides(kind.profile())).collect();
+        (!sequence.is_empty()).then(|| CropRotation::new(sequence))
+    }
+
+    fn with_overrides(&self, mut profile: PlantProfile) -> PlantProfile {
+        profile.stage_threshold_multipliers.extend(&self.stage_threshold_multipliers);
+        profile
+    }
+}
+
+/// Settings for the HTTP API started with `--http-port`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiConfig {
+    /// Request rate limit shared by all clients
+    pub rate_limit: RateLimitConfig,
+    /// API key authentication
+    pub auth: AuthConfig,
+    /// Serve over HTTPS; plain HTTP when absent
+    pub tls: Option<TlsConfig>,
+}
+
+/// PEM certificate chain and private key for HTTPS; self-signed certificates work
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// Certificate chain, leaf first
+    pub cert_path: PathBuf,
+    /// PKCS#8, PKCS#1 or SEC1 private key
+    pub key_path: PathBuf,
+}
+
+/// Bearer-token authentication for every route except `/health`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    /// Require an `Authorization: Bearer <key>` header
+    pub enabled: bool,
+    /// Hex SHA-256 digests of the accepted keys, never the keys themselves
+    pub keys: Vec<String>,
+}
+
+/// Token bucket limiting API requests; `/health` routes are exempt
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Largest burst of requests accepted at once
+    pub capacity: u32,
+    /// Tokens added back per second
+    pub refill_per_second: f64,
+}
+
+/// Random moisture loss per tick, drawn uniformly from `min_drop..max_drop`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct EvaporationConfig {
+    /// Smallest drop in percentage points
+    pub min_drop: f32,
+    /// Largest drop in percentage points (exclusive)
+    pub max_drop: f32,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 30.0,
+            initial_moisture: 50.0,
+            sensor_id: SensorId::default(),
+            evaporation: EvaporationConfig::default(),
+            forecast_horizon_hours: None,
+            irrigation_strategy: IrrigationStrategy::default(),
+            emitters: None,
+            backflow: None,
+            water_hammer: None,
+            surge: None,
+            frost_protection: None,
+            water_budget: None,
+            aeroponic: None,
+            maintenance: None,
+            moisture_profile: None,
+            tensiometer: None,
+            wetting_front: None,
+            germination: None,
+            irrigation_water: IrrigationWaterConfig::default(),
+            trace_elements: None,
+            energy: None,
+            rainwater: None,
+            salinity: None,
+            nutrient_leaching: None,
+            microclimate: None,
+            weather_station: None,
+            cover_crop: None,
+            nitrogen_fixation: None,
+            organic_matter: OrganicMatterConfig::default(),
+            soil_respiration: SoilRespirationConfig::default(),
+            carbon: None,
+            ghg: None,
+            cost_benefit: None,
+            drainage: None,
+            sensor_fusion: None,
+            soil_sampling: SoilSamplingConfig::default(),
+            yield_map: None,
+            topology: None,
+            pipe_network: None,
+            biochar: Vec::new(),
+            export: Vec::new(),
+            plugins: Vec::new(),
+            crop: CropConfig::default(),
+            api: ApiConfig::default(),
+            mqtt: None,
+            gateway: None,
+            network: None,
+            offline_cache: None,
+            alert_history: None,
+            event_bus: EventBusConfig::default(),
+        }
+    }
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 1883,
+            client_id: "agri-iot-simulator".to_string(),
+            topic_prefix: "agri".to_string(),
+            tls: None,
+            auth: None,
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self { capacity: 20, refill_per_second: 10.0 }
+    }
+}
+
+impl Default for EvaporationConfig {
+    fn default() -> Self {
+        Self { min_drop: 0.5, max_drop: 2.0 }
+    }
+}
+
+impl SimulationConfig {
+    /// Reads and validates a config file
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let config: Self = toml::from_str(&fs::read_to_string(path)?)?;
+        if config.evaporation.min_drop >= config.evaporation.max_drop {
+            return Err("evaporation.min_drop must be less than evaporation.max_drop".into());
+        }
+        if config.biochar.iter().any(|amendment| amendment.application_rate_t_ha < 0.0) {
+            return Err("biochar.application_rate_t_ha must not be negative".into());
+        }
+        let rate_limit = &config.api.rate_limit;
+        let refill_per_second = rate_limit.refill_per_second;
+        if rate_limit.capacity == 0 || !refill_per_second.is_finite() || refill_per_second <= 0.0 {
+            return Err("api.rate_limit.capacity and refill_per_second must be positive".into());
+        }
+        if let IrrigationStrategy::Deficit { fraction } = config.irrigation_strategy {
+            if !(0.5..=1.0).contains(&fraction) {
+                return Err("irrigation_strategy.fraction must be between 0.5 and 1.0".into());
+            }
+        }
+        if config.forecast_horizon_hours.is_some_and(|hours| hours < 0.0) {
+            return Err("forecast_horizon_hours must not be negative".into());
+        }
+        if config.crop.stage_threshold_multipliers.values().any(|&multiplier| multiplier <= 0.0) {
+            return Err("crop.stage_threshold_multipliers must be positive".into());
+        }
+        if let Some(emitters) = &config.emitters {
+            let spec = &emitters.spec;
+            if emitters.count == 0 || spec.nominal_flow_lph <= 0.0 || spec.nominal_head_m <= 0.0 {
+                return Err("emitters.count, spec.nominal_flow_lph and spec.nominal_head_m must be positive".into());
+            }
+            if spec.compensation_head_m.is_some_and(|head| head <= 0.0) {
+                return Err("emitters.spec.compensation_head_m must be positive".into());
+            }
+        }
+        if config.backflow.as_ref().is_some_and(|backflow| backflow.test_frequency_ticks == 0) {
+            return Err("backflow.test_frequency_ticks must be positive".into());
+        }
+        if let Some(hammer) = &config.water_hammer {
+            let pipe = &hammer.pipe;
+            if pipe.pipe_length_m <= 0.0 || pipe.wave_speed_ms <= 0.0 || pipe.flow_velocity_ms < 0.0 {
+                return Err("water_hammer.pipe lengths and speeds must be positive".into());
+            }
+            if hammer.max_allowable_kpa <= 0.0 || hammer.slow_close_time_s.is_some_and(|time| time <= 0.0) {
+                return Err("water_hammer.max_allowable_kpa and slow_close_time_s must be positive".into());
+            }
+        }
+        if let Some(surge) = &config.surge {
+            if surge.surge_duration_s == 0 || surge.n_surges == 0 {
+                return Err("surge.surge_duration_s and n_surges must be positive".into());
+            }
+            let basin = &surge.basin;
+            if basin.length_m <= 0.0 || basin.advance_velocity_m_per_min <= 0.0 || basin.inflow_lps <= 0.0 {
+                return Err("surge.basin length, advance velocity and inflow must be positive".into());
+            }
+        }
+        if let Some(field) = config.yield_map.as_ref().and_then(|map| map.field.as_ref()) {
+            if field.rows == 0 || field.columns == 0 {
+                return Err("yield_map.field.rows and columns must be positive".into());
+            }
+        }
+        if let Some(profile) = &config.moisture_profile {
+            if profile.layers.iter().any(|layer| layer.wetting_front_velocity_cm_per_h <= 0.0) {
+                return Err("moisture_profile.layers wetting_front_velocity_cm_per_h must be positive".into());
+            }
+            if profile.layers.windows(2).any(|pair| pair[0].depth_cm >= pair[1].depth_cm) {
+                return Err("moisture_profile.layers must be listed shallowest first, each at its own depth".into());
+            }
+            let weights = &profile.root_distribution;
+            if weights.len() != profile.layers.len() || weights.iter().any(|&weight| weight < 0.0) {
+                return Err("moisture_profile.root_distribution needs one non-negative share per layer".into());
+            }
+        }
+        if let Some(tensiometer) = &config.tensiometer {
+            if tensiometer.depth_cm < 0.0 || tensiometer.ceramic_cup_conductance <= 0.0 {
+                return Err("tensiometer.depth_cm must not be negative, ceramic_cup_conductance positive".into());
+            }
+        }
+        if let Some(germination) = &config.germination {
+            if germination.germination_threshold_hu <= 0.0 || germination.seeding_depth_cm < 0.0 {
+                return Err("germination threshold must be positive and seeding_depth_cm not negative".into());
+            }
+        }
+        if config.irrigation_water.soil_specific_heat <= 0.0 {
+            return Err("irrigation_water.soil_specific_heat must be positive".into());
+        }
+        if let Some(trace) = &config.trace_elements {
+            let levels = [trace.fe_ppm, trace.zn_ppm, trace.mn_ppm, trace.b_ppm, trace.fe_rate_ppm_per_day];
+            if levels.iter().any(|&level| level < 0.0) {
+                return Err("trace_elements levels and fe_rate_ppm_per_day must not be negative".into());
+            }
+        }
+        if let Some(maintenance) = &config.maintenance {
+            if maintenance.items.iter().any(|item| item.interval_operating_hours <= 0.0) {
+                return Err("maintenance.items interval_operating_hours must be positive".into());
+            }
+        }
+        if let Some(aeroponic) = &config.aeroponic {
+            if aeroponic.on_duration_ticks == 0 || aeroponic.off_duration_ticks == 0 {
+                return Err("aeroponic.on_duration_ticks and off_duration_ticks must be positive".into());
+            }
+        }
+        if config.water_budget.as_ref().is_some_and(|budget| budget.seasonal_limit_mm <= 0.0) {
+            return Err("water_budget.seasonal_limit_mm must be positive".into());
+        }
+        if let Some(frost) = &config.frost_protection {
+            if frost.required_application_rate_mm_h <= 0.0 {
+                return Err("frost_protection.required_application_rate_mm_h must be positive".into());
+            }
+        }
+        if let Some(energy) = &config.energy {
+            if energy.pump_power_kw < 0.0 || energy.electricity_rate_per_kwh < 0.0 {
+                return Err("energy.pump_power_kw and electricity_rate_per_kwh must not be negative".into());
+            }
+            if !(energy.pump_efficiency > 0.0 && energy.pump_efficiency <= 1.0) {
+                return Err("energy.pump_efficiency must be above 0 and at most 1".into());
+            }
+        }
+        if let Some(rainwater) = &config.rainwater {
+            if rainwater.catchment_area_m2 < 0.0 || rainwater.tank_capacity_l < 0.0 {
+                return Err("rainwater.catchment_area_m2 and tank_capacity_l must not be negative".into());
+            }
+            if !(0.0..=1.0).contains(&rainwater.runoff_coefficient) {
+                return Err("rainwater.runoff_coefficient must be between 0 and 1".into());
+            }
+        }
+        if let Some(salinity) = &config.salinity {
+            let threshold = salinity.ec_threshold_ds_m.unwrap_or(config.crop.profile().salinity_threshold_ds_m);
+            if salinity.ec_water_ds_m < 0.0 || threshold <= 0.0 || salinity.leach_interval_ticks == 0 {
+                return Err("salinity EC values must be positive and leach_interval_ticks above 0".into());
+            }
+            if leaching_requirement(salinity.ec_water_ds_m, threshold) >= 1.0 {
+                return Err("salinity.ec_water_ds_m is too saline to manage by leaching for this crop".into());
+            }
+        }
+        if let Some(leaching) = &config.nutrient_leaching {
+            if !(0.0..=1.0).contains(&leaching.leach_coeff_n) || !(0.0..=1.0).contains(&leaching.leach_coeff_k) {
+                return Err("nutrient_leaching coefficients must be between 0 and 1".into());
+            }
+            if leaching.n_risk_threshold_kg_ha <= 0.0 {
+                return Err("nutrient_leaching.n_risk_threshold_kg_ha must be positive".into());
+            }
+        }
+        if let Some(microclimate) = &config.microclimate {
+            let transmissivity = microclimate.glazing_transmissivity;
+            if transmissivity.is_some_and(|transmissivity| !(0.0..=1.0).contains(&transmissivity)) {
+                return Err("microclimate.glazing_transmissivity must be between 0 and 1".into());
+            }
+            if microclimate.ventilation_rate.is_some_and(|rate| rate < 0.0) {
+                return Err("microclimate.ventilation_rate must not be negative".into());
+            }
+            if !(0.0 <= microclimate.sunrise_hour && microclimate.sunrise_hour < microclimate.sunset_hour)
+                || microclimate.sunset_hour > 24.0
+            {
+                return Err("microclimate.sunrise_hour must come before sunset_hour, within 0 to 24".into());
+            }
+            if microclimate.night_ventilation.as_ref().is_some_and(|night| night.vent_ventilation_rate < 0.0) {
+                return Err("microclimate.night_ventilation.vent_ventilation_rate must not be negative".into());
+            }
+        }
+        if let Some(cover) = &config.cover_crop {
+            if cover.dry_matter_t_ha < 0.0 || !(0.0..=100.0).contains(&cover.n_content_pct) {
+                return Err("cover_crop.dry_matter_t_ha must not be negative and n_content_pct be 0 to 100".into());
+            }
+        }
+        if config.nitrogen_fixation.as_ref().is_some_and(|fixation| fixation.fixation_rate_kg_ha_per_gdd < 0.0) {
+            return Err("nitrogen_fixation.fixation_rate_kg_ha_per_gdd must not be negative".into());
+        }
+        let som = &config.organic_matter;
+        if !(0.0..=100.0).contains(&som.som_pct) || som.mineralization_rate < 0.0 || som.humification_rate < 0.0 {
+            return Err("organic_matter.som_pct must be 0 to 100 and its rates not negative".into());
+        }
+        let respiration = &config.soil_respiration;
+        if respiration.basal_rate_g_co2_m2_h < 0.0 || respiration.q10 <= 0.0 {
+            return Err("soil_respiration.basal_rate_g_co2_m2_h must not be negative and q10 must be positive".into());
+        }
+        if !(respiration.field_capacity_pct > 0.0 && respiration.field_capacity_pct <= 100.0) {
+            return Err("soil_respiration.field_capacity_pct must be above 0 and at most 100".into());
+        }
+        if let Some(carbon) = &config.carbon {
+            if carbon.bulk_density_g_cm3 <= 0.0 || carbon.depth_cm <= 0.0 {
+                return Err("carbon.bulk_density_g_cm3 and carbon.depth_cm must be positive".into());
+            }
+            if !(0.0..=1.0).contains(&carbon.biochar_stability_factor) {
+                return Err("carbon.biochar_stability_factor must be between 0 and 1".into());
+            }
+        }
+        if let Some(costs) = &config.cost_benefit {
+            let prices = [
+                costs.water_cost_per_m3,
+                costs.electricity_cost_per_kwh,
+                costs.crop_price_per_tonne,
+                costs.pumping_energy_kwh_per_m3,
+                costs.fertilizer_cost_per_kg_n,
+                costs.fertilizer_n_kg_ha,
+                costs.season_irrigation_need_mm,
+            ];
+            if prices.iter().any(|&price| price < 0.0) {
+                return Err("cost_benefit prices and inputs must not be negative".into());
+            }
+        }
+        if let Some(ghg) = &config.ghg {
+            let factors = [
+                ghg.electricity_emission_factor_kg_co2_kwh,
+                ghg.water_transport_factor,
+                ghg.fertilizer_factor_kg_co2_per_kg_n,
+            ];
+            if factors.iter().any(|&factor| factor < 0.0) {
+                return Err("ghg emission factors must not be negative".into());
+            }
+            if ghg.field_area_ha <= 0.0 || ghg.target_kg_co2e_ha.is_some_and(|target| target <= 0.0) {
+                return Err("ghg.field_area_ha and target_kg_co2e_ha must be positive".into());
+            }
+        }
+        if let Some(drainage) = &config.drainage {
+            if drainage.depth_cm <= 0.0 || drainage.spacing_m <= 0.0 || drainage.flow_coefficient < 0.0 {
+                return Err("drainage.depth_cm and spacing_m must be positive and flow_coefficient not negative".into());
+            }
+            if !(0.0..100.0).contains(&drainage.field_capacity_pct) {
+                return Err("drainage.field_capacity_pct must be at least 0 and below 100".into());
+            }
+        }
+        if let Some(fusion) = &config.sensor_fusion {
+            if fusion.fusion_weight_soil < 0.0 || fusion.fusion_weight_cwsi < 0.0 {
+                return Err("sensor_fusion weights must not be negative".into());
+            }
+            if fusion.fusion_weight_soil + fusion.fusion_weight_cwsi <= 0.0 {
+                return Err("sensor_fusion needs a positive weight on soil or CWSI".into());
+            }
+            if !(0.0..=1.0).contains(&fusion.stress_threshold) {
+                return Err("sensor_fusion.stress_threshold must be between 0 and 1".into());
+            }
+        }
+        let sampling = &config.soil_sampling;
+        if sampling.soil_sample_interval_ticks == Some(0) {
+            return Err("soil_sampling.soil_sample_interval_ticks must be above 0".into());
+        }
+        if !(0.0..=14.0).contains(&sampling.ph) || sampling.p_ppm < 0.0 || sampling.k_ppm < 0.0 {
+            return Err("soil_sampling.ph must be 0 to 14 and p_ppm and k_ppm not negative".into());
+        }
+        if let Some(gateway) = &config.gateway {
+            if gateway.brokers.is_empty() {
+                return Err("gateway needs at least one [[gateway.brokers]] table".into());
+            }
+            if config.mqtt.is_some() {
+                return Err("set either [mqtt] or [gateway], not both".into());
+            }
+        }
+        if let Some(network) = &config.network {
+            if !(0.0..1.0).contains(&network.packet_loss_rate) {
+                return Err("network.packet_loss_rate must be at least 0 and below 1".into());
+            }
+            if network.bandwidth_bps == 0 || network.buffer_size == 0 {
+                return Err("network.bandwidth_bps and buffer_size must be above 0".into());
+            }
+        }
+        let needs_path = |export: &ExportConfig| export.format != ExportFormat::Null;
+        if config.export.iter().any(|export| needs_path(export) && export.path.as_os_str().is_empty()) {
+            return Err("every [[export]] but a null one needs a path".into());
+        }
+        if config.plugins.iter().any(|plugin| plugin.path.as_os_str().is_empty()) {
+            return Err("every [[plugins]] table needs a path".into());
+        }
+        if config.alert_history.as_ref().is_some_and(|history| history.capacity == 0) {
+            return Err("alert_history.capacity must be above 0".into());
+        }
+        if let Some(cache) = &config.offline_cache {
+            if cache.max_entries == 0 {
+                return Err("offline_cache.max_entries must be above 0".into());
+            }
+            if config.network.is_none() {
+                return Err("offline_cache needs a [network] table, whose outages it covers".into());
+            }
+        }
+        if let Some(network) = &config.pipe_network {
+            let pump = [network.pump_shutoff_head_m, network.pump_max_flow_lps, network.required_head_m];
+            let pipes = network.pipes.iter().flat_map(|pipe| [pipe.length_m, pipe.diameter_mm, pipe.roughness]);
+            if pump.into_iter().chain(pipes).any(|value| value <= 0.0) || network.zone_demand_lps < 0.0 {
+                return Err("pipe_network pump, head and pipe dimensions must be positive".into());
+            }
+        }
+        if let Some(topology) = &config.topology {
+            let mut pipes = topology.pipes.values().flatten();
+            if topology.zone_demand_lps <= 0.0 || pipes.any(|pipe| pipe.capacity_lps < 0.0) {
+                return Err("topology.zone_demand_lps must be positive and pipe capacities not negative".into());
+            }
+        }
+        if config.crop.calendar.as_ref().is_some_and(|calendar| calendar.harvest_day <= calendar.planting_day) {
+            return Err("crop.calendar.harvest_day must be after planting_day".into());
+        }
+        if config.event_bus.capacity == 0 {
+            return Err("event_bus.capacity must be positive".into());
+        }
+        let auth = &config.api.auth;
+        if auth.enabled && auth.keys.is_empty() {
+            return Err("api.auth.keys must not be empty when auth is enabled".into());
+        }
+        if auth.keys.iter().any(|hash| hash.len() != 64 || !hash.bytes().all(|byte| byte.is_ascii_hexdigit())) {
+            return Err("api.auth.keys must be hex SHA-256 digests, not plaintext keys".into());
+        }
+        if let Some(tls) = config.mqtt.as_ref().and_then(|mqtt| mqtt.tls.as_ref()) {
+            if tls.client_cert.is_some() != tls.client_key.is_some() {
+                return Err("mqtt.tls.client_cert and client_key must be set together".into());
+            }
+        }
+        Ok(config)
+    }
+
+    /// JSON Schema describing the config file, for editor validation
+    pub fn json_schema() -> String {
+        serde_json::to_string_pretty(&schemars::schema_for!(SimulationConfig))
+            .expect("schema serializes to JSON")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_schema_describes_the_threshold() {
+        let schema: serde_json::Value = serde_json::from_str(&SimulationConfig::json_schema()).unwrap();
+        assert_eq!(schema["title"], "SimulationConfig");
+        let threshold = &schema["properties"]["threshold"];
+        assert_eq!(threshold["type"], "number");
+        assert!(threshold["description"].as_str().is_some_and(|description| !description.is_empty()));
+        // Tables are `deny_unknown_fields`, so editors flag misspelt keys
+        assert_eq!(schema["additionalProperties"], false);
+    }
+
+    #[test]
+    fn stage_multipliers_from_toml_replace_the_crop_defaults() {
+        let crop: CropConfig = toml::from_str("[stage_threshold_multipliers]\nflowering = 1.5\n").unwrap();
+        let profile = crop.profile();
+        assert_eq!(profile.stage_threshold_multipliers[&GrowthStage::Flowering], 1.5);
+        assert_eq!(profile.stage_threshold_multipliers[&GrowthStage::Maturity], 0.75);
+        assert!(toml::from_str::<CropConfig>("[stage_threshold_multipliers]\nblooming = 1.5\n").is_err());
+    }
+}
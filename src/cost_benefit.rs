@@ -0,0 +1,233 @@
+//! Whether irrigating pays: crop revenue against the water, energy and fertilizer it costs
+//!
+//! Everything is per hectare, for a season at the stress a [`SimulationReport`] ended on, so
+//! irrigation strategies can be compared on the same field. A run's ticks are far shorter than a
+//! season, so the season's water is the full irrigation need cut by the share withheld.
+
+use std::io::{self, Write};
+
+use crate::ghg::M3_PER_MM_HA;
+use crate::irrigation::IrrigationStrategy;
+use crate::simulation::SimulationReport;
+
+/// Strategies compared by the batch cost report: full irrigation and two deficit levels
+pub const COMPARED_STRATEGIES: [IrrigationStrategy; 3] = [
+    IrrigationStrategy::Full,
+    IrrigationStrategy::Deficit { fraction: 0.75 },
+    IrrigationStrategy::Deficit { fraction: 0.5 },
+];
+
+/// Header line written by [`write_comparison_csv`]
+pub const CSV_HEADER: &str =
+    "strategy,yield_t_ha,water_applied_mm,gross_revenue,water_cost,energy_cost,fertilizer_cost,net_profit,roi_pct";
+
+/// Prices and inputs a season is costed at, in the local currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostBenefitAnalyzer {
+    pub water_cost_per_m3: f32,
+    pub electricity_cost_per_kwh: f32,
+    pub crop_price_per_tonne: f32,
+    pub pumping_energy_kwh_per_m3: f32, // Drawn by the pump per m³ it delivers
+    pub fertilizer_cost_per_kg_n: f32,
+    pub fertilizer_n_kg_ha: f32, // Nitrogen applied per season
+    pub season_irrigation_need_mm: f32, // Applied over a season of full irrigation
+}
+
+impl Default for CostBenefitAnalyzer {
+    /// Drip-irrigated field crop at typical European prices
+    fn default() -> Self {
+        Self {
+            water_cost_per_m3: 0.2,
+            electricity_cost_per_kwh: 0.15,
+            crop_price_per_tonne: 200.0,
+            pumping_energy_kwh_per_m3: 0.4,
+            fertilizer_cost_per_kg_n: 1.2,
+            fertilizer_n_kg_ha: 150.0,
+            season_irrigation_need_mm: 500.0,
+        }
+    }
+}
+
+/// One season's money per hectare
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CostBenefitReport {
+    pub yield_t_ha: f32,
+    pub water_applied_mm: f32,
+    pub gross_revenue: f32,
+    pub water_cost: f32,
+    pub energy_cost: f32,
+    pub fertilizer_cost: f32,
+    pub net_profit: f32,
+    pub roi_pct: f32, // Net profit over the costs; 0 without costs
+}
+
+impl CostBenefitAnalyzer {
+    /// Costs a season at the mean yield and drought stress of the report's zones
+    pub fn analyze(&self, report: &SimulationReport) -> CostBenefitReport {
+        let yield_t_ha = mean(report.yield_t_ha.iter().copied());
+        let applied_share = 1.0 - mean(report.stress_index.iter().copied());
+        let water_applied_mm = self.season_irrigation_need_mm * applied_share;
+        let water_m3 = water_applied_mm * M3_PER_MM_HA;
+        let gross_revenue = yield_t_ha * self.crop_price_per_tonne;
+        let water_cost = water_m3 * self.water_cost_per_m3;
+        let energy_cost = water_m3 * self.pumping_energy_kwh_per_m3 * self.electricity_cost_per_kwh;
+        let fertilizer_cost = self.fertilizer_n_kg_ha * self.fertilizer_cost_per_kg_n;
+        let total_cost = water_cost + energy_cost + fertilizer_cost;
+        let net_profit = gross_revenue - total_cost;
+        CostBenefitReport {
+            yield_t_ha,
+            water_applied_mm,
+            gross_revenue,
+            water_cost,
+            energy_cost,
+            fertilizer_cost,
+            net_profit,
+            roi_pct: if total_cost > 0.0 { net_profit / total_cost * 100.0 } else { 0.0 },
+        }
+    }
+}
+
+/// Mean of `values`, 0 when there are none
+fn mean(values: impl Iterator<Item = f32>) -> f32 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f32
+    }
+}
+
+/// Short name of a strategy for tables: `full`, or `deficit-75` for 75% of the demand
+pub fn strategy_label(strategy: IrrigationStrategy) -> String {
+    match strategy {
+        IrrigationStrategy::Full => "full".to_string(),
+        IrrigationStrategy::Deficit { .. } => format!("deficit-{:.0}", strategy.fraction() * 100.0),
+    }
+}
+
+/// Aligned text table with one line per strategy, for the terminal
+pub fn comparison_table(rows: &[(IrrigationStrategy, CostBenefitReport)]) -> String {
+    let mut table = format!(
+        "{:<12} {:>8} {:>9} {:>10} {:>9} {:>9} {:>11} {:>10} {:>7}\n",
+        "strategy", "t/ha", "water mm", "revenue", "water", "energy", "fertilizer", "net", "ROI %"
+    );
+    for (strategy, report) in rows {
+        table += &format!(
+            "{:<12} {:>8.2} {:>9.0} {:>10.0} {:>9.0} {:>9.0} {:>11.0} {:>10.0} {:>7.1}\n",
+            strategy_label(*strategy),
+            report.yield_t_ha,
+            report.water_applied_mm,
+            report.gross_revenue,
+            report.water_cost,
+            report.energy_cost,
+            report.fertilizer_cost,
+            report.net_profit,
+            report.roi_pct
+        );
+    }
+    table
+}
+
+/// Writes the header and one row per strategy
+pub fn write_comparison_csv(mut out: impl Write, rows: &[(IrrigationStrategy, CostBenefitReport)]) -> io::Result<()> {
+    writeln!(out, "{CSV_HEADER}")?;
+    for (strategy, report) in rows {
+        writeln!(
+            out,
+            "{},{:.3},{:.1},{:.2},{:.2},{:.2},{:.2},{:.2},{:.1}",
+            strategy_label(*strategy),
+            report.yield_t_ha,
+            report.water_applied_mm,
+            report.gross_revenue,
+            report.water_cost,
+            report.energy_cost,
+            report.fertilizer_cost,
+            report.net_profit,
+            report.roi_pct
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+    use std::collections::HashMap;
+
+    use crate::events::EventLog;
+
+    /// Two zones: 8 t/ha with a fifth of the demand withheld, and 10 t/ha fully irrigated
+    fn two_zones() -> SimulationReport {
+        SimulationReport {
+            final_states: Vec::new(),
+            total_water_used_l: 0.0,
+            transition_counts: HashMap::new(),
+            events: EventLog::default(),
+            water_use_efficiency: Vec::new(),
+            water_by_source: Vec::new(),
+            stress_index: vec![0.2, 0.0],
+            yield_t_ha: vec![8.0, 10.0],
+            fertilizer_n_kg_ha: 0.0,
+        }
+    }
+
+    #[test]
+    fn revenue_is_the_mean_yield_at_the_crop_price() {
+        let report = CostBenefitAnalyzer::default().analyze(&two_zones());
+        assert_close(report.yield_t_ha, 9.0, 1e-3);
+        assert_close(report.gross_revenue, 9.0 * 200.0, 1e-3);
+    }
+
+    #[test]
+    fn water_cost_follows_the_share_of_the_need_applied() {
+        let report = CostBenefitAnalyzer::default().analyze(&two_zones());
+        // 10% withheld on average: 450 mm of the 500 mm need, 4500 m³ at 0.2 a m³
+        assert_close(report.water_applied_mm, 450.0, 1e-3);
+        assert_close(report.water_cost, 900.0, 1e-3);
+    }
+
+    #[test]
+    fn energy_cost_is_the_pumping_energy_at_the_electricity_price() {
+        let report = CostBenefitAnalyzer::default().analyze(&two_zones());
+        // 4500 m³ × 0.4 kWh/m³ = 1800 kWh at 0.15 a kWh
+        assert_close(report.energy_cost, 270.0, 1e-3);
+        let free_power = CostBenefitAnalyzer { electricity_cost_per_kwh: 0.0, ..CostBenefitAnalyzer::default() };
+        assert_eq!(free_power.analyze(&two_zones()).energy_cost, 0.0);
+    }
+
+    #[test]
+    fn fertilizer_cost_is_the_nitrogen_applied_at_its_price() {
+        let report = CostBenefitAnalyzer::default().analyze(&two_zones());
+        assert_close(report.fertilizer_cost, 150.0 * 1.2, 1e-3);
+    }
+
+    #[test]
+    fn net_profit_and_roi_take_every_cost() {
+        let report = CostBenefitAnalyzer::default().analyze(&two_zones());
+        // 1800 revenue against 900 + 270 + 180 = 1350 of costs
+        assert_close(report.net_profit, 450.0, 1e-3);
+        assert_close(report.roi_pct, 450.0 / 1350.0 * 100.0, 1e-3);
+        let free = CostBenefitAnalyzer {
+            water_cost_per_m3: 0.0,
+            electricity_cost_per_kwh: 0.0,
+            fertilizer_cost_per_kg_n: 0.0,
+            ..CostBenefitAnalyzer::default()
+        };
+        assert_eq!(free.analyze(&two_zones()).roi_pct, 0.0);
+    }
+
+    #[test]
+    fn comparison_csv_has_a_row_per_strategy() {
+        let report = CostBenefitAnalyzer::default().analyze(&two_zones());
+        let rows = [(IrrigationStrategy::Full, report), (IrrigationStrategy::Deficit { fraction: 0.75 }, report)];
+        let mut csv = Vec::new();
+        write_comparison_csv(&mut csv, &rows).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "full,9.000,450.0,1800.00,900.00,270.00,180.00,450.00,33.3");
+        assert!(lines[2].starts_with("deficit-75,"));
+        assert_eq!(comparison_table(&rows).lines().count(), 3);
+    }
+}
@@ -0,0 +1,58 @@
+//! Planting and harvest dates, in simulated days since the start of the run
+
+/// When the current season was planted and is due for harvest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CropCalendar {
+    pub planting_day: u16,
+    pub harvest_day: u16,
+    pub replanting_interval_days: Option<u16>, // Fallow days before the next season; one season only when unset
+    finished: bool, // Harvested with no replanting
+}
+
+impl CropCalendar {
+    pub fn new(planting_day: u16, harvest_day: u16, replanting_interval_days: Option<u16>) -> Self {
+        Self { planting_day, harvest_day, replanting_interval_days, finished: false }
+    }
+
+    /// Whether the crop is in the ground on `day`
+    pub fn in_season(&self, day: u16) -> bool {
+        !self.finished && (self.planting_day..self.harvest_day).contains(&day)
+    }
+
+    /// Whether the crop should be harvested on `day`
+    pub fn harvest_due(&self, day: u16) -> bool {
+        !self.finished && day >= self.harvest_day
+    }
+
+    /// Schedules the next season of the same length after a harvest on
+    /// `harvested_day`; returns false, ending the calendar, without a
+    /// replanting interval
+    pub fn advance(&mut self, harvested_day: u16) -> bool {
+        let Some(interval) = self.replanting_interval_days else {
+            self.finished = true;
+            return false;
+        };
+        let season_days = self.harvest_day - self.planting_day;
+        self.planting_day = harvested_day.saturating_add(interval);
+        self.harvest_day = self.planting_day.saturating_add(season_days);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calendar_advances_to_the_next_season_when_replanting() {
+        let mut calendar = CropCalendar::new(10, 130, Some(30));
+        assert!(calendar.harvest_due(130));
+        assert!(calendar.advance(130));
+        assert_eq!((calendar.planting_day, calendar.harvest_day), (160, 280));
+        assert!(!calendar.in_season(150) && calendar.in_season(160));
+
+        let mut single = CropCalendar::new(0, 120, None);
+        assert!(!single.advance(120));
+        assert!(!single.harvest_due(200) && !single.in_season(0));
+    }
+}
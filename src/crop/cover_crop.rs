@@ -0,0 +1,84 @@
+//! Off-season cover crops and what their incorporation leaves for the next crop
+
+use crate::soil::nitrogen::NitrogenPool;
+use crate::Tick;
+
+/// Field capacity gained per t/ha of cover crop dry matter incorporated
+pub const RETENTION_GAIN_PER_T_HA: f32 = 0.02;
+
+/// A cover crop terminated and worked into the soil ahead of the main crop
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverCropModel {
+    pub species: String,
+    pub dry_matter_t_ha: f32, // Above-ground biomass at termination
+    pub n_content_pct: f32,   // Nitrogen share of the dry matter
+    pub termination_tick: Tick,
+    pub incorporated: bool,
+}
+
+impl CoverCropModel {
+    pub fn new(species: String, dry_matter_t_ha: f32, n_content_pct: f32, termination_tick: Tick) -> Self {
+        Self { species, dry_matter_t_ha, n_content_pct, termination_tick, incorporated: false }
+    }
+
+    /// Cereal rye, a common winter cover: about 5 t/ha at 2.5% N
+    pub fn cereal_rye(termination_tick: Tick) -> Self {
+        Self::new("cereal rye".to_string(), 5.0, 2.5, termination_tick)
+    }
+
+    /// Nitrogen in the residue, `dry_matter × n_content / 100` with dry matter in kg/ha
+    pub fn n_release_kg_ha(&self) -> f32 {
+        self.dry_matter_t_ha * 1000.0 * self.n_content_pct / 100.0
+    }
+
+    /// Field capacity multiplier once incorporated: 1.02 per t/ha of dry matter
+    pub fn retention_factor(&self) -> f32 {
+        if !self.incorporated {
+            return 1.0;
+        }
+        1.0 + RETENTION_GAIN_PER_T_HA * self.dry_matter_t_ha
+    }
+
+    /// Incorporates the residue once `now` reaches the termination tick, adding its nitrogen
+    /// to `pool`'s organic N. Returns the kg/ha released on that tick only.
+    pub fn step(&mut self, now: Tick, pool: &mut NitrogenPool) -> Option<f32> {
+        if self.incorporated || now < self.termination_tick {
+            return None;
+        }
+        self.incorporated = true;
+        let released = self.n_release_kg_ha();
+        pool.organic_n += released;
+        Some(released)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn incorporation_releases_the_residue_nitrogen_once() {
+        let mut rye = CoverCropModel::cereal_rye(Tick(100));
+        let mut pool = NitrogenPool { organic_n: 40.0, mineral_n: 10.0 };
+        assert_eq!(rye.step(Tick(99), &mut pool), None);
+        assert_close(pool.organic_n, 40.0, 1e-3);
+        // 5 t/ha at 2.5% N is 125 kg N/ha, all of it organic until it mineralizes
+        assert_eq!(rye.step(Tick(100), &mut pool), Some(125.0));
+        assert_close(pool.organic_n, 165.0, 1e-3);
+        assert_close(pool.mineral_n, 10.0, 1e-3);
+        assert_eq!(rye.step(Tick(101), &mut pool), None);
+        assert_close(pool.total_n(), 175.0, 1e-3);
+    }
+
+    #[test]
+    fn incorporation_raises_field_capacity_by_two_percent_per_tonne() {
+        let mut vetch = CoverCropModel::new("hairy vetch".to_string(), 3.5, 3.8, Tick(10));
+        assert_close(vetch.n_release_kg_ha(), 133.0, 1e-3);
+        assert_close(vetch.retention_factor(), 1.0, 1e-3);
+        vetch.step(Tick(10), &mut NitrogenPool::default());
+        assert_close(vetch.retention_factor(), 1.07, 1e-3);
+        // A 30% field capacity holds 32.1% after the vetch
+        assert_close(30.0 * vetch.retention_factor(), 32.1, 1e-3);
+    }
+}
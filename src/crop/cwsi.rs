@@ -0,0 +1,137 @@
+//! Crop Water Stress Index (Jackson et al., 1981) from canopy infrared temperature
+//!
+//! CWSI places the measured canopy-air temperature difference between a lower
+//! baseline (well-watered, fully transpiring) and an upper baseline
+//! (non-transpiring): `CWSI = (dT - dT_lower) / (dT_upper - dT_lower)`.
+
+/// CWSI above which the crop counts as water-stressed
+pub const STRESS_THRESHOLD: f32 = 0.5;
+
+/// Canopy-air temperature baselines for one crop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CwsiBaselines {
+    pub intercept_c: f32,     // Lower baseline dT at zero vapour pressure deficit
+    pub slope_c_per_kpa: f32, // Lower baseline change per kPa of VPD
+    pub upper_limit_c: f32,   // dT of a canopy that has stopped transpiring
+}
+
+impl Default for CwsiBaselines {
+    /// Alfalfa lower baseline from Idso (1982) with a 5 °C upper limit
+    fn default() -> Self {
+        Self {
+            intercept_c: 0.51,
+            slope_c_per_kpa: -1.92,
+            upper_limit_c: 5.0,
+        }
+    }
+}
+
+impl CwsiBaselines {
+    /// Canopy-air temperature difference of a well-watered crop at `vpd_kpa`
+    pub fn lower_baseline(&self, vpd_kpa: f32) -> f32 {
+        self.intercept_c + self.slope_c_per_kpa * vpd_kpa
+    }
+
+    /// Canopy-air temperature difference of a fully stressed crop
+    pub fn upper_baseline(&self) -> f32 {
+        self.upper_limit_c
+    }
+
+    /// CWSI from a measured canopy temperature, clamped to 0.0 (no stress) ..= 1.0 (full stress)
+    pub fn crop_water_stress_index(&self, air_temp_c: f32, canopy_temp_c: f32, vpd_kpa: f32) -> f32 {
+        let lower = self.lower_baseline(vpd_kpa);
+        ((canopy_temp_c - air_temp_c - lower) / (self.upper_baseline() - lower)).clamp(0.0, 1.0)
+    }
+
+    /// Canopy temperature an infrared thermometer would read at the given stress index
+    pub fn estimate_canopy_temp(&self, air_temp_c: f32, vpd_kpa: f32, stress_index: f32) -> f32 {
+        let lower = self.lower_baseline(vpd_kpa);
+        air_temp_c + lower + stress_index.clamp(0.0, 1.0) * (self.upper_baseline() - lower)
+    }
+}
+
+/// Latest CWSI from an infrared canopy thermometer
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CwsiCalculator {
+    pub baselines: CwsiBaselines,
+    pub canopy_temp_c: f32, // Last reading
+    pub cwsi: f32,          // Of the last reading
+}
+
+impl CwsiCalculator {
+    pub fn new(baselines: CwsiBaselines) -> Self {
+        Self { baselines, canopy_temp_c: 0.0, cwsi: 0.0 }
+    }
+
+    /// Takes a canopy reading in `air_temp_c` air at `vpd_kpa` and returns its CWSI
+    pub fn record(&mut self, air_temp_c: f32, canopy_temp_c: f32, vpd_kpa: f32) -> f32 {
+        self.canopy_temp_c = canopy_temp_c;
+        self.cwsi = self.baselines.crop_water_stress_index(air_temp_c, canopy_temp_c, vpd_kpa);
+        self.cwsi
+    }
+}
+
+/// Vapour pressure deficit of air at `air_temp_c` and `relative_humidity_pct`, with the
+/// saturation vapour pressure from the Tetens equation
+pub fn vapour_pressure_deficit_kpa(air_temp_c: f32, relative_humidity_pct: f32) -> f32 {
+    let saturation_kpa = 0.6108 * (17.27 * air_temp_c / (air_temp_c + 237.3)).exp();
+    saturation_kpa * (1.0 - relative_humidity_pct.clamp(0.0, 100.0) / 100.0)
+}
+
+/// Canopy temperature at the given stress index, using the default baselines
+pub fn estimate_canopy_temp(air_temp_c: f32, vpd_kpa: f32, stress_index: f32) -> f32 {
+    CwsiBaselines::default().estimate_canopy_temp(air_temp_c, vpd_kpa, stress_index)
+}
+
+/// CWSI of a canopy measured at `canopy_temp_c`, using the default baselines
+pub fn crop_water_stress_index(air_temp_c: f32, canopy_temp_c: f32, vpd_kpa: f32) -> f32 {
+    CwsiBaselines::default().crop_water_stress_index(air_temp_c, canopy_temp_c, vpd_kpa)
+}
+
+/// Whether the canopy shows water stress, regardless of what the soil sensor reads
+pub fn is_water_stressed(cwsi: f32) -> bool {
+    cwsi > STRESS_THRESHOLD
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn well_watered_canopy_on_the_lower_baseline_is_unstressed() {
+        // Idso's alfalfa baseline at 2 kPa: a transpiring canopy runs 3.33 °C below the air
+        assert_close(CwsiBaselines::default().lower_baseline(2.0), -3.33, 1e-3);
+        assert_close(crop_water_stress_index(30.0, 26.67, 2.0), 0.0, 1e-3);
+        assert_close(estimate_canopy_temp(30.0, 2.0, 0.0), 26.67, 1e-3);
+        // Cooler still is clamped, not negative stress
+        assert_eq!(crop_water_stress_index(30.0, 24.0, 2.0), 0.0);
+    }
+
+    #[test]
+    fn non_transpiring_canopy_on_the_upper_baseline_is_fully_stressed() {
+        assert_eq!(crop_water_stress_index(30.0, 35.0, 2.0), 1.0);
+        assert_close(estimate_canopy_temp(30.0, 2.0, 1.0), 35.0, 1e-3);
+        assert_eq!(crop_water_stress_index(30.0, 38.0, 2.0), 1.0);
+    }
+
+    #[test]
+    fn stress_is_flagged_above_half_way_between_the_baselines() {
+        let half_way = estimate_canopy_temp(30.0, 2.0, 0.5);
+        assert_close(half_way, 30.835, 1e-3);
+        assert_close(crop_water_stress_index(30.0, half_way, 2.0), 0.5, 1e-3);
+        assert!(!is_water_stressed(0.5));
+        assert!(is_water_stressed(crop_water_stress_index(30.0, half_way + 0.5, 2.0)));
+        let mut calculator = CwsiCalculator::default();
+        assert_close(calculator.record(30.0, half_way, 2.0), 0.5, 1e-3);
+        assert_eq!(calculator.canopy_temp_c, half_way);
+    }
+
+    #[test]
+    fn vapour_pressure_deficit_from_tetens() {
+        // Saturation vapour pressure is 3.168 kPa at 25 °C
+        assert_close(vapour_pressure_deficit_kpa(25.0, 0.0), 3.168, 1e-3);
+        assert_close(vapour_pressure_deficit_kpa(25.0, 50.0), 1.584, 1e-3);
+        assert_eq!(vapour_pressure_deficit_kpa(25.0, 100.0), 0.0);
+    }
+}
@@ -0,0 +1,150 @@
+//! Pest and disease risk from temperature, humidity and leaf wetness
+
+use std::f32::consts::TAU;
+
+use super::profile::PlantProfile;
+
+/// Weighted risk hours at which a model raises an alert
+pub const RISK_ALERT_THRESHOLD: f32 = 6.0;
+
+/// Relative humidity at and above which leaves stay wet with dew
+pub const LEAF_WET_HUMIDITY_PCT: f32 = 90.0;
+
+/// How close conditions have come to an outbreak
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RiskLevel {
+    #[default]
+    Low,
+    Moderate, // Half the alert threshold
+    High,     // At or above the alert threshold
+}
+
+impl RiskLevel {
+    /// Level for `cumulative_risk` weighted risk hours
+    pub fn from_risk(cumulative_risk: f32) -> Self {
+        match cumulative_risk {
+            risk if risk >= RISK_ALERT_THRESHOLD => RiskLevel::High,
+            risk if risk >= RISK_ALERT_THRESHOLD / 2.0 => RiskLevel::Moderate,
+            _ => RiskLevel::Low,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RiskLevel::Low => "Low",
+            RiskLevel::Moderate => "Moderate",
+            RiskLevel::High => "High",
+        }
+    }
+}
+
+/// Weather a pest or pathogen needs to spread
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskModel {
+    pub name: String,
+    pub min_temp: f32,          // °C
+    pub max_temp: f32,          // °C
+    pub min_humidity: f32,      // Relative humidity, %
+    pub min_wet_hours: f32,     // Continuous leaf wetness needed before each hour counts
+    pub risk_score_weight: f32, // Risk added per hour of favourable weather
+}
+
+impl RiskModel {
+    /// Late blight (*Phytophthora infestans*) of potato and tomato, after the Hutton criteria
+    pub fn late_blight() -> Self {
+        Self {
+            name: "late blight".to_string(),
+            min_temp: 10.0,
+            max_temp: 25.0,
+            min_humidity: 90.0,
+            min_wet_hours: 2.0,
+            risk_score_weight: 1.0,
+        }
+    }
+
+    /// Grey mould (*Botrytis cinerea*) of strawberry
+    pub fn botrytis() -> Self {
+        Self {
+            name: "botrytis".to_string(),
+            min_temp: 15.0,
+            max_temp: 25.0,
+            min_humidity: 92.0,
+            min_wet_hours: 1.0,
+            risk_score_weight: 0.8,
+        }
+    }
+
+    /// Whether an hour at `temp_c` and `humidity_pct`, with leaves wet for `wet_hours`, favours the disease
+    pub fn favourable(&self, temp_c: f32, humidity_pct: f32, wet_hours: f32) -> bool {
+        (self.min_temp..=self.max_temp).contains(&temp_c)
+            && humidity_pct >= self.min_humidity
+            && wet_hours >= self.min_wet_hours
+    }
+}
+
+/// Air temperature and relative humidity at `hour_of_day` around a daily mean
+/// temperature, coolest and most humid at 3:00 and warmest and driest at 15:00
+pub fn diurnal_conditions(mean_temp_c: f32, hour_of_day: f32) -> (f32, f32) {
+    let phase = ((hour_of_day - 9.0) / 24.0 * TAU).sin();
+    (mean_temp_c + 6.0 * phase, 70.0 - 25.0 * phase)
+}
+
+/// Accumulates each model's risk over the season
+#[derive(Debug, Clone, PartialEq)]
+pub struct PestDiseaseRiskCalculator {
+    pub crop: PlantProfile,
+    pub risk_models: Vec<RiskModel>,
+    pub cumulative_risk: Vec<f32>, // Weighted risk hours, indexed like risk_models
+    pub wet_hours: f32,            // Current spell of leaf wetness
+}
+
+impl PestDiseaseRiskCalculator {
+    pub fn new(crop: PlantProfile, risk_models: Vec<RiskModel>) -> Self {
+        let cumulative_risk = vec![0.0; risk_models.len()];
+        Self { crop, risk_models, cumulative_risk, wet_hours: 0.0 }
+    }
+
+    /// The built-in late blight and botrytis models
+    pub fn builtin(crop: PlantProfile) -> Self {
+        Self::new(crop, vec![RiskModel::late_blight(), RiskModel::botrytis()])
+    }
+
+    /// Adds `hours` of weather and returns the models whose risk just reached
+    /// [`RiskLevel::High`]
+    pub fn record(&mut self, temp_c: f32, humidity_pct: f32, hours: f32) -> Vec<(&RiskModel, RiskLevel)> {
+        self.wet_hours = if humidity_pct >= LEAF_WET_HUMIDITY_PCT { self.wet_hours + hours } else { 0.0 };
+        let mut alerts = Vec::new();
+        for (model, risk) in self.risk_models.iter().zip(&mut self.cumulative_risk) {
+            if model.favourable(temp_c, humidity_pct, self.wet_hours) {
+                let before = RiskLevel::from_risk(*risk);
+                *risk += model.risk_score_weight * hours;
+                let after = RiskLevel::from_risk(*risk);
+                if after == RiskLevel::High && before != after {
+                    alerts.push((model, after));
+                }
+            }
+        }
+        alerts
+    }
+
+    /// The model closest to an outbreak and its level, if there are any models
+    pub fn highest_risk(&self) -> Option<(&RiskModel, RiskLevel)> {
+        self.risk_models
+            .iter()
+            .zip(&self.cumulative_risk)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(model, &risk)| (model, RiskLevel::from_risk(risk)))
+    }
+
+    /// Clears the season's risk, e.g. at harvest
+    pub fn reset(&mut self) {
+        self.cumulative_risk.iter_mut().for_each(|risk| *risk = 0.0);
+        self.wet_hours = 0.0;
+    }
+}
+
+impl Default for PestDiseaseRiskCalculator {
+    fn default() -> Self {
+        Self::builtin(PlantProfile::default())
+    }
+}
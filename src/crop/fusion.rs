@@ -0,0 +1,102 @@
+//! Water stress from the soil moisture sensor and the canopy thermometer combined
+//!
+//! Soil moisture shows the water left in the root zone, but the crop can be
+//! stressed before the soil reaches the threshold when evaporative demand is
+//! high. The canopy's CWSI catches that; the fused index weights the two.
+
+use super::cwsi::CwsiCalculator;
+use crate::SoilMoistureSensor;
+
+/// Moisture level of a soil with no water deficit, in percent
+const FULL_MOISTURE_PCT: f32 = 100.0;
+
+/// Weighted combination of the soil water deficit and the canopy CWSI for one zone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensorFusion {
+    pub cwsi_calculator: CwsiCalculator,
+    pub fusion_weight_soil: f32,
+    pub fusion_weight_cwsi: f32,
+    pub stress_threshold: f32,   // Fused index above which the zone is watered
+    pub soil_water_deficit: f32, // From the last soil reading
+}
+
+impl SensorFusion {
+    pub fn new(
+        cwsi_calculator: CwsiCalculator,
+        fusion_weight_soil: f32,
+        fusion_weight_cwsi: f32,
+        stress_threshold: f32,
+    ) -> Self {
+        Self { cwsi_calculator, fusion_weight_soil, fusion_weight_cwsi, stress_threshold, soil_water_deficit: 0.0 }
+    }
+
+    /// Soil water deficit of a `sensor` reading: 0.0 at full moisture rising to 1.0 at its threshold
+    pub fn deficit_of(sensor: &SoilMoistureSensor) -> f32 {
+        let range = (FULL_MOISTURE_PCT - sensor.threshold).max(f32::EPSILON);
+        ((FULL_MOISTURE_PCT - sensor.moisture_level) / range).clamp(0.0, 1.0)
+    }
+
+    /// Takes the latest soil moisture reading
+    pub fn record_soil(&mut self, sensor: &SoilMoistureSensor) {
+        self.soil_water_deficit = Self::deficit_of(sensor);
+    }
+
+    /// Weighted mean of the soil water deficit and the CWSI, from 0.0 (no stress) to 1.0
+    pub fn fused_stress_index(&self) -> f32 {
+        let total_weight = self.fusion_weight_soil + self.fusion_weight_cwsi;
+        if total_weight <= 0.0 {
+            return 0.0;
+        }
+        (self.fusion_weight_soil * self.soil_water_deficit + self.fusion_weight_cwsi * self.cwsi_calculator.cwsi)
+            / total_weight
+    }
+
+    /// Whether the combined readings call for watering, whatever the soil sensor says alone
+    pub fn is_stressed(&self) -> bool {
+        self.fused_stress_index() > self.stress_threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    fn fusion(weight_soil: f32, weight_cwsi: f32, moisture_level: f32, cwsi: f32) -> SensorFusion {
+        let calculator = CwsiCalculator { cwsi, ..CwsiCalculator::default() };
+        let mut fusion = SensorFusion::new(calculator, weight_soil, weight_cwsi, 0.5);
+        fusion.record_soil(&SoilMoistureSensor::new(30.0, moisture_level));
+        fusion
+    }
+
+    #[test]
+    fn soil_deficit_runs_from_full_moisture_to_the_threshold() {
+        assert_close(fusion(1.0, 0.0, 100.0, 0.0).soil_water_deficit, 0.0, 1e-5);
+        // 35 points dry of full against a 70 point range
+        assert_close(fusion(1.0, 0.0, 65.0, 0.0).soil_water_deficit, 0.5, 1e-5);
+        assert_close(fusion(1.0, 0.0, 30.0, 0.0).soil_water_deficit, 1.0, 1e-5);
+        assert_close(fusion(1.0, 0.0, 10.0, 0.0).soil_water_deficit, 1.0, 1e-5);
+    }
+
+    #[test]
+    fn fused_index_is_the_weighted_mean_of_both_components() {
+        // 0.6 × 0.5 + 0.4 × 0.8
+        assert_close(fusion(0.6, 0.4, 65.0, 0.8).fused_stress_index(), 0.62, 1e-5);
+        // Only the weights' ratio matters
+        assert_close(fusion(3.0, 2.0, 65.0, 0.8).fused_stress_index(), 0.62, 1e-5);
+        assert_close(fusion(1.0, 0.0, 65.0, 0.8).fused_stress_index(), 0.5, 1e-5);
+        assert_close(fusion(0.0, 1.0, 65.0, 0.8).fused_stress_index(), 0.8, 1e-5);
+        assert_close(fusion(0.0, 0.0, 65.0, 0.8).fused_stress_index(), 0.0, 1e-5);
+    }
+
+    #[test]
+    fn a_hot_canopy_calls_for_water_before_the_soil_does() {
+        // The soil at 86% is far above its 30% threshold, but the canopy is at CWSI 0.9:
+        // 0.4 × 0.2 + 0.6 × 0.9 = 0.62
+        let hot = fusion(0.4, 0.6, 86.0, 0.9);
+        assert_close(hot.fused_stress_index(), 0.62, 1e-5);
+        assert!(hot.is_stressed());
+        assert!(!fusion(1.0, 0.0, 86.0, 0.9).is_stressed());
+        assert!(!fusion(0.4, 0.6, 86.0, 0.3).is_stressed());
+    }
+}
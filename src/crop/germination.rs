@@ -0,0 +1,90 @@
+//! Germination of the seed, which has to come before any growth stage counts its degree days
+
+/// Heat the seed needs above its base temperature before seedlings emerge, in °C·days
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GerminationModel {
+    pub base_temp_c: f32, // No heat accrues with the seedbed at or below this
+    pub accumulated_heat_units: f32,
+    pub germination_threshold_hu: f32,
+}
+
+impl GerminationModel {
+    pub fn new(base_temp_c: f32, germination_threshold_hu: f32) -> Self {
+        Self { base_temp_c, accumulated_heat_units: 0.0, germination_threshold_hu }
+    }
+
+    /// Adds the heat of `tick_days` of seedbed at `soil_temp_c` and returns whether the seed has
+    /// just germinated
+    pub fn record(&mut self, soil_temp_c: f32, tick_days: f32) -> bool {
+        let was_complete = self.is_complete();
+        if soil_temp_c > self.base_temp_c {
+            self.accumulated_heat_units += (soil_temp_c - self.base_temp_c) * tick_days;
+        }
+        !was_complete && self.is_complete()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.accumulated_heat_units >= self.germination_threshold_hu
+    }
+
+    /// Share of the threshold accrued so far, up to 1
+    pub fn progress(&self) -> f32 {
+        if self.germination_threshold_hu <= 0.0 {
+            return 1.0;
+        }
+        (self.accumulated_heat_units / self.germination_threshold_hu).min(1.0)
+    }
+
+    /// Starts over for the next sowing
+    pub fn reset(&mut self) {
+        self.accumulated_heat_units = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cold_seedbed_never_germinates() {
+        let mut seed = GerminationModel::new(10.0, 5.0);
+        for soil_temp_c in [4.0, 9.5, 10.0, -2.0] {
+            for _ in 0..1_000 {
+                assert!(!seed.record(soil_temp_c, 0.25));
+            }
+        }
+        assert_eq!((seed.accumulated_heat_units, seed.progress()), (0.0, 0.0));
+        assert!(!seed.is_complete());
+    }
+
+    #[test]
+    fn germinates_once_the_heat_above_base_is_met() {
+        let mut seed = GerminationModel::new(10.0, 5.0);
+        // 4 °C above base for a quarter day is one heat unit
+        for _ in 0..4 {
+            assert!(!seed.record(14.0, 0.25));
+        }
+        assert_eq!((seed.accumulated_heat_units, seed.progress()), (4.0, 0.8));
+        assert!(seed.record(14.0, 0.25));
+        assert!(seed.is_complete());
+        assert!(!seed.record(14.0, 0.25));
+        assert_eq!(seed.progress(), 1.0);
+    }
+
+    #[test]
+    fn cold_spells_pause_but_do_not_undo_the_heat() {
+        let mut seed = GerminationModel::new(10.0, 5.0);
+        let mut germinated_at = None;
+        // Warm quarter days alternating with frosty ones
+        for tick in 0..20 {
+            let soil_temp_c = if tick % 2 == 0 { 14.0 } else { 2.0 };
+            if seed.record(soil_temp_c, 0.25) {
+                germinated_at = Some(tick);
+            }
+        }
+        assert_eq!(germinated_at, Some(8));
+        seed.reset();
+        assert_eq!(seed.accumulated_heat_units, 0.0);
+        assert!(!seed.is_complete());
+    }
+}
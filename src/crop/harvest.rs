@@ -0,0 +1,55 @@
+//! End-of-season summaries written when a crop is harvested
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Header line written by [`HarvestEvent::append_csv`]
+pub const CSV_HEADER: &str = "season,day,total_water_mm,seasonal_et_mm,gdd,estimated_yield_t_ha,final_stress_index";
+
+/// Summary of a season, taken just before its accumulators are reset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HarvestEvent {
+    pub season: u32, // Counted from 1
+    pub day: u16,    // Simulated day of the harvest
+    pub total_water_mm: f32, // Mean over zones
+    pub seasonal_et_mm: f32,
+    pub gdd: f32,
+    pub estimated_yield_t_ha: f32,
+    pub final_stress_index: f32, // Mean over zones
+}
+
+impl HarvestEvent {
+    /// One-line summary for the event log
+    pub fn summary(&self) -> String {
+        format!(
+            "Harvested season {}: {:.1} t/ha, {:.0} mm water, {:.0} mm ET, {:.0} GDD, stress {:.2}",
+            self.season,
+            self.estimated_yield_t_ha,
+            self.total_water_mm,
+            self.seasonal_et_mm,
+            self.gdd,
+            self.final_stress_index
+        )
+    }
+
+    /// Appends the summary as a row of the CSV file at `path`, writing the header if the file is new
+    pub fn append_csv(&self, path: &Path) -> io::Result<()> {
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if is_new {
+            writeln!(file, "{CSV_HEADER}")?;
+        }
+        writeln!(
+            file,
+            "{},{},{:.1},{:.1},{:.0},{:.2},{:.3}",
+            self.season,
+            self.day,
+            self.total_water_mm,
+            self.seasonal_et_mm,
+            self.gdd,
+            self.estimated_yield_t_ha,
+            self.final_stress_index
+        )
+    }
+}
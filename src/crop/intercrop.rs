@@ -0,0 +1,122 @@
+//! Two crops sharing one irrigation zone, such as maize and beans
+
+use super::profile::PlantProfile;
+
+/// Nutrients taken up from a zone, in kg/ha
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NpkUptake {
+    pub n_kg_ha: f32,
+    pub p_kg_ha: f32,
+    pub k_kg_ha: f32,
+}
+
+/// Water-stress yield loss of each crop in an intercropped zone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntercropYieldLoss {
+    pub primary: f32,
+    pub secondary: f32,
+}
+
+/// A zone planted with two crops, each on its own share of the land
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntercroppedZone {
+    pub primary: PlantProfile,
+    pub secondary: PlantProfile,
+    pub primary_fraction: f32,   // Land share of the primary crop, 0.0 ..= 1.0
+    pub companion_benefit: f32,  // Share of a crop's N demand covered by a nitrogen-fixing neighbour
+}
+
+impl IntercroppedZone {
+    /// Zone with no companion effect; `primary_fraction` is clamped to 0.0 ..= 1.0
+    pub fn new(primary: PlantProfile, secondary: PlantProfile, primary_fraction: f32) -> Self {
+        Self {
+            primary,
+            secondary,
+            primary_fraction: primary_fraction.clamp(0.0, 1.0),
+            companion_benefit: 0.0,
+        }
+    }
+
+    /// Sets the share of N demand a nitrogen-fixing crop covers for the other one
+    pub fn with_companion_benefit(mut self, companion_benefit: f32) -> Self {
+        self.companion_benefit = companion_benefit.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Zone ETc as the land-weighted average of both crops' ETc
+    pub fn etc_mm(&self, et0_mm: f32) -> f32 {
+        self.primary_fraction * self.primary.etc_mm(et0_mm)
+            + (1.0 - self.primary_fraction) * self.secondary.etc_mm(et0_mm)
+    }
+
+    /// Sum of both crops' uptake on their share of the land, after any companion N benefit
+    pub fn npk_uptake(&self) -> NpkUptake {
+        let primary_n = self.n_demand(&self.primary, &self.secondary);
+        let secondary_n = self.n_demand(&self.secondary, &self.primary);
+        let primary_share = self.primary_fraction;
+        let secondary_share = 1.0 - self.primary_fraction;
+        NpkUptake {
+            n_kg_ha: primary_share * primary_n + secondary_share * secondary_n,
+            p_kg_ha: primary_share * self.primary.p_demand_kg_ha + secondary_share * self.secondary.p_demand_kg_ha,
+            k_kg_ha: primary_share * self.primary.k_demand_kg_ha + secondary_share * self.secondary.k_demand_kg_ha,
+        }
+    }
+
+    /// Each crop's yield loss when the zone delivers `eta_over_etc` of the water demand
+    pub fn yield_loss(&self, eta_over_etc: f32) -> IntercropYieldLoss {
+        IntercropYieldLoss {
+            primary: self.primary.yield_loss(eta_over_etc),
+            secondary: self.secondary.yield_loss(eta_over_etc),
+        }
+    }
+
+    fn n_demand(&self, crop: &PlantProfile, neighbour: &PlantProfile) -> f32 {
+        if neighbour.fixes_nitrogen && !crop.fixes_nitrogen {
+            crop.n_demand_kg_ha * (1.0 - self.companion_benefit)
+        } else {
+            crop.n_demand_kg_ha
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    fn maize_beans() -> IntercroppedZone {
+        IntercroppedZone::new(PlantProfile::maize(), PlantProfile::beans(), 0.6)
+    }
+
+    #[test]
+    fn etc_is_the_land_weighted_average() {
+        // 0.6 × 1.2 × 5 mm + 0.4 × 1.15 × 5 mm
+        assert_close(maize_beans().etc_mm(5.0), 5.9, 1e-3);
+        assert_close(IntercroppedZone::new(PlantProfile::maize(), PlantProfile::beans(), 1.0).etc_mm(5.0), 6.0, 1e-3);
+        assert_eq!(IntercroppedZone::new(PlantProfile::maize(), PlantProfile::beans(), 1.5).primary_fraction, 1.0);
+    }
+
+    #[test]
+    fn npk_uptake_sums_both_crops() {
+        let uptake = maize_beans().npk_uptake();
+        assert_close(uptake.n_kg_ha, 0.6 * 180.0 + 0.4 * 100.0, 1e-3);
+        assert_close(uptake.p_kg_ha, 0.6 * 35.0 + 0.4 * 15.0, 1e-3);
+        assert_close(uptake.k_kg_ha, 0.6 * 150.0 + 0.4 * 80.0, 1e-3);
+    }
+
+    #[test]
+    fn beans_cut_the_maize_nitrogen_demand_only() {
+        let uptake = maize_beans().with_companion_benefit(0.3).npk_uptake();
+        // The legume fixes its own nitrogen, so only the maize share shrinks
+        assert_close(uptake.n_kg_ha, 0.6 * 180.0 * 0.7 + 0.4 * 100.0, 1e-3);
+        assert_close(uptake.p_kg_ha, maize_beans().npk_uptake().p_kg_ha, 1e-3);
+    }
+
+    #[test]
+    fn yield_loss_is_reported_per_crop() {
+        let loss = maize_beans().yield_loss(0.8);
+        assert_close(loss.primary, 1.25 * 0.2, 1e-3);
+        assert_close(loss.secondary, 1.15 * 0.2, 1e-3);
+        assert_eq!(maize_beans().yield_loss(1.0), IntercropYieldLoss { primary: 0.0, secondary: 0.0 });
+    }
+}
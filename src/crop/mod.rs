@@ -0,0 +1,21 @@
+//! Crop physiology models driven by the simulated soil and weather
+
+pub mod calendar;
+pub mod cover_crop;
+pub mod cwsi;
+pub mod disease;
+pub mod et;
+pub mod fusion;
+pub mod germination;
+pub mod harvest;
+pub mod intercrop;
+pub mod nitrogen;
+pub mod profile;
+pub mod rotation;
+pub mod stage;
+pub mod wue;
+pub mod yield_estimator;
+pub mod yield_map;
+
+pub use profile::PlantProfile;
+pub use stage::GrowthStage;
@@ -0,0 +1,107 @@
+//! Biological nitrogen fixation by legumes, shared with companion crops
+
+use super::profile::PlantProfile;
+use crate::hydroponic::NpkSensor;
+
+/// Soil mass of one hectare to 30 cm at a bulk density of 1.3 g/cm³, in kg
+pub const SOIL_MASS_KG_HA: f32 = 3.9e6;
+
+/// Air temperature at which fixation runs fastest, in °C
+pub const OPTIMUM_TEMP_C: f32 = 25.0;
+
+/// Air temperature above which heat starts inactivating nitrogenase, in °C
+pub const HEAT_STRESS_TEMP_C: f32 = 35.0;
+
+/// Air temperature at which fixation stops altogether, in °C
+pub const MAX_FIXATION_TEMP_C: f32 = 45.0;
+
+/// Nitrogen a legume fixes as growing degree days accumulate
+#[derive(Debug, Clone, PartialEq)]
+pub struct NitrogenFixationModel {
+    pub legume_profile: PlantProfile,
+    pub fixation_rate_kg_ha_per_gdd: f32, // At the 25 °C optimum
+    pub season_fixed_n_kg_ha: f32,        // Cumulative since sowing
+}
+
+impl NitrogenFixationModel {
+    /// Dry beans fix roughly 55 kg N/ha over a 1100 GDD season at the optimum
+    pub fn beans() -> Self {
+        Self::new(PlantProfile::beans(), 0.05)
+    }
+
+    pub fn new(legume_profile: PlantProfile, fixation_rate_kg_ha_per_gdd: f32) -> Self {
+        Self { legume_profile, fixation_rate_kg_ha_per_gdd, season_fixed_n_kg_ha: 0.0 }
+    }
+
+    /// Growing degree days that drive fixation over `days` at `air_temp_c`: none below the
+    /// legume's base temperature, accrued no faster than at the optimum, and falling back to none
+    /// between [`HEAT_STRESS_TEMP_C`] and [`MAX_FIXATION_TEMP_C`]
+    pub fn fixing_degree_days(&self, air_temp_c: f32, days: f32) -> f32 {
+        let optimum_gdd = self.legume_profile.growing_degree_days(OPTIMUM_TEMP_C, days);
+        if air_temp_c <= HEAT_STRESS_TEMP_C {
+            self.legume_profile.growing_degree_days(air_temp_c, days).min(optimum_gdd)
+        } else {
+            let heat_left = (MAX_FIXATION_TEMP_C - air_temp_c) / (MAX_FIXATION_TEMP_C - HEAT_STRESS_TEMP_C);
+            optimum_gdd * heat_left.max(0.0)
+        }
+    }
+
+    /// Fixes nitrogen for `days` at `air_temp_c`, adds it to the season total and returns it in kg/ha
+    pub fn step(&mut self, air_temp_c: f32, days: f32) -> f32 {
+        let fixed = self.fixing_degree_days(air_temp_c, days) * self.fixation_rate_kg_ha_per_gdd;
+        self.season_fixed_n_kg_ha += fixed;
+        fixed
+    }
+
+    /// Starts the next season's total
+    pub fn reset_season(&mut self) {
+        self.season_fixed_n_kg_ha = 0.0;
+    }
+
+    /// Credits `fixed_n_kg_ha` to a companion crop's N reading, converted to ppm of topsoil
+    pub fn apply_to(sensor: &mut NpkSensor, fixed_n_kg_ha: f32) {
+        sensor.nitrate_ppm += fixed_n_kg_ha / SOIL_MASS_KG_HA * 1e6;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn no_fixation_below_the_base_temperature() {
+        let mut beans = NitrogenFixationModel::beans();
+        assert_eq!(beans.step(5.0, 1.0), 0.0);
+        assert_eq!(beans.season_fixed_n_kg_ha, 0.0);
+    }
+
+    #[test]
+    fn fixation_peaks_at_the_optimum() {
+        let mut beans = NitrogenFixationModel::beans();
+        // 15 GDD a day above the 10 °C base at 0.05 kg N/ha each
+        assert_close(beans.step(25.0, 1.0), 0.75, 1e-4);
+        // Warmer days up to the heat-stress point accrue no faster than the optimum
+        assert_close(beans.step(30.0, 1.0), 0.75, 1e-4);
+        assert_close(beans.season_fixed_n_kg_ha, 1.5, 1e-4);
+    }
+
+    #[test]
+    fn heat_halves_fixation_at_40_degrees() {
+        let mut beans = NitrogenFixationModel::beans();
+        // Halfway from the 35 °C heat-stress point to the 45 °C cutoff
+        assert_close(beans.step(40.0, 1.0), 0.375, 1e-4);
+        assert_eq!(beans.step(MAX_FIXATION_TEMP_C, 1.0), 0.0);
+        assert_close(beans.season_fixed_n_kg_ha, 0.375, 1e-4);
+        beans.reset_season();
+        assert_eq!(beans.season_fixed_n_kg_ha, 0.0);
+    }
+
+    #[test]
+    fn fixed_nitrogen_is_credited_to_the_companion_reading() {
+        let mut sensor = NpkSensor { nitrate_ppm: 10.0, phosphate_ppm: 5.0, potassium_ppm: 20.0 };
+        NitrogenFixationModel::apply_to(&mut sensor, 39.0);
+        assert_close(sensor.nitrate_ppm, 20.0, 1e-4);
+        assert_eq!(sensor.phosphate_ppm, 5.0);
+    }
+}
@@ -0,0 +1,210 @@
+//! Per-crop parameters shared by the water and nutrient models
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::stage::GrowthStage;
+use crate::soil::trace_elements::TraceElement;
+
+/// Built-in crop profiles, as named in a config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CropKind {
+    Maize,
+    Beans,
+    Wheat,
+    Fallow,
+}
+
+impl CropKind {
+    pub fn profile(self) -> PlantProfile {
+        match self {
+            CropKind::Maize => PlantProfile::maize(),
+            CropKind::Beans => PlantProfile::beans(),
+            CropKind::Wheat => PlantProfile::wheat(),
+            CropKind::Fallow => PlantProfile::fallow(),
+        }
+    }
+}
+
+/// Water use, nutrient demand, growth and drought sensitivity of one crop at mid-season
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlantProfile {
+    pub name: String,
+    pub family: String,             // Botanical family; crops of one family share soil-borne diseases
+    pub crop_coefficient: f32,      // Kc; ETc = Kc * ET0
+    pub n_demand_kg_ha: f32,
+    pub p_demand_kg_ha: f32,
+    pub k_demand_kg_ha: f32,
+    pub yield_response_factor: f32, // Ky from FAO Irrigation and Drainage Paper 33
+    pub fixes_nitrogen: bool,       // Legumes supply nitrogen to neighbouring crops
+    pub base_temp_c: f32,           // No growing degree days accrue below this
+    pub harvest_index: f32,
+    pub radiation_use_efficiency: f32, // Biomass in kg/ha per growing degree day
+    pub target_wue_g_per_l: f32,    // Water use efficiency below this calls for a new strategy
+    pub season_gdd: f32,            // Growing degree days from sowing to harvest
+    pub potential_yield_t_ha: f32,  // Harvest without water stress
+    pub salinity_threshold_ds_m: f32, // Soil ECe above which yield declines (FAO-29)
+    pub stage_threshold_multipliers: HashMap<GrowthStage, f32>, // Scale the moisture threshold per stage
+    pub micronutrient_min_ppm: HashMap<TraceElement, f32>, // Critical soil levels; deficient below
+}
+
+impl PlantProfile {
+    /// Grain maize (FAO-56 mid-season Kc, FAO-33 Ky)
+    pub fn maize() -> Self {
+        Self {
+            name: "Maize".to_string(),
+            family: "Poaceae".to_string(),
+            crop_coefficient: 1.2,
+            n_demand_kg_ha: 180.0,
+            p_demand_kg_ha: 35.0,
+            k_demand_kg_ha: 150.0,
+            yield_response_factor: 1.25,
+            fixes_nitrogen: false,
+            base_temp_c: 10.0,
+            harvest_index: 0.5,
+            radiation_use_efficiency: 20.0,
+            target_wue_g_per_l: 1.5,
+            season_gdd: 1500.0,
+            potential_yield_t_ha: 11.0,
+            salinity_threshold_ds_m: 1.7,
+            stage_threshold_multipliers: HashMap::from([
+                (GrowthStage::Initial, 0.9),
+                (GrowthStage::Vegetative, 1.0),
+                (GrowthStage::Flowering, 1.25),
+                (GrowthStage::GrainFill, 1.15),
+                (GrowthStage::Maturity, 0.75),
+            ]),
+            micronutrient_min_ppm: HashMap::from([
+                (TraceElement::Iron, 4.5),
+                (TraceElement::Zinc, 1.0),
+                (TraceElement::Manganese, 1.0),
+                (TraceElement::Boron, 0.5),
+            ]),
+        }
+    }
+
+    /// Dry beans (FAO-56 mid-season Kc, FAO-33 Ky)
+    pub fn beans() -> Self {
+        Self {
+            name: "Beans".to_string(),
+            family: "Fabaceae".to_string(),
+            crop_coefficient: 1.15,
+            n_demand_kg_ha: 100.0,
+            p_demand_kg_ha: 15.0,
+            k_demand_kg_ha: 80.0,
+            yield_response_factor: 1.15,
+            fixes_nitrogen: true,
+            base_temp_c: 10.0,
+            harvest_index: 0.45,
+            radiation_use_efficiency: 12.0,
+            target_wue_g_per_l: 0.6,
+            season_gdd: 1100.0,
+            potential_yield_t_ha: 2.5,
+            salinity_threshold_ds_m: 1.0,
+            stage_threshold_multipliers: HashMap::from([
+                (GrowthStage::Initial, 0.9),
+                (GrowthStage::Vegetative, 1.0),
+                (GrowthStage::Flowering, 1.2),
+                (GrowthStage::GrainFill, 1.1),
+                (GrowthStage::Maturity, 0.8),
+            ]),
+            micronutrient_min_ppm: HashMap::from([
+                (TraceElement::Iron, 4.5),
+                (TraceElement::Zinc, 0.8),
+                (TraceElement::Manganese, 1.0),
+                (TraceElement::Boron, 0.5),
+            ]),
+        }
+    }
+
+    /// Spring wheat (FAO-56 mid-season Kc, FAO-33 Ky)
+    pub fn wheat() -> Self {
+        Self {
+            name: "Wheat".to_string(),
+            family: "Poaceae".to_string(),
+            crop_coefficient: 1.15,
+            n_demand_kg_ha: 150.0,
+            p_demand_kg_ha: 25.0,
+            k_demand_kg_ha: 100.0,
+            yield_response_factor: 1.15,
+            fixes_nitrogen: false,
+            base_temp_c: 0.0,
+            harvest_index: 0.45,
+            radiation_use_efficiency: 8.0,
+            target_wue_g_per_l: 1.2,
+            season_gdd: 1800.0,
+            potential_yield_t_ha: 6.0,
+            salinity_threshold_ds_m: 6.0,
+            stage_threshold_multipliers: HashMap::from([
+                (GrowthStage::Initial, 0.9),
+                (GrowthStage::Vegetative, 1.0),
+                (GrowthStage::Flowering, 1.2),
+                (GrowthStage::GrainFill, 1.1),
+                (GrowthStage::Maturity, 0.7),
+            ]),
+            micronutrient_min_ppm: HashMap::from([
+                (TraceElement::Iron, 4.5),
+                (TraceElement::Zinc, 0.6),
+                (TraceElement::Manganese, 1.0),
+                (TraceElement::Boron, 0.3),
+            ]),
+        }
+    }
+
+    /// A season of bare fallow: soil evaporation only (FAO-56 Kc ini), no yield and no residue
+    pub fn fallow() -> Self {
+        Self {
+            name: "Fallow".to_string(),
+            family: String::new(),
+            crop_coefficient: 0.3,
+            n_demand_kg_ha: 0.0,
+            p_demand_kg_ha: 0.0,
+            k_demand_kg_ha: 0.0,
+            yield_response_factor: 0.0,
+            fixes_nitrogen: false,
+            base_temp_c: 0.0,
+            harvest_index: 1.0,
+            radiation_use_efficiency: 0.0,
+            target_wue_g_per_l: 0.0,
+            season_gdd: 1500.0,
+            potential_yield_t_ha: 0.0,
+            salinity_threshold_ds_m: f32::INFINITY,
+            stage_threshold_multipliers: HashMap::new(),
+            micronutrient_min_ppm: HashMap::new(),
+        }
+    }
+
+    /// Whether this is a bare fallow rather than a crop
+    pub fn is_fallow(&self) -> bool {
+        self.family.is_empty()
+    }
+
+    /// Whether growing this crop after `previous` breaks the disease cycle, i.e. they are
+    /// not of the same family. A fallow hosts nothing, so it always breaks it.
+    pub fn is_break_crop(&self, previous: &PlantProfile) -> bool {
+        self.is_fallow() || self.family != previous.family
+    }
+
+    /// Growing degree days accrued over `days` at a mean air temperature of `mean_temp_c`
+    pub fn growing_degree_days(&self, mean_temp_c: f32, days: f32) -> f32 {
+        (mean_temp_c - self.base_temp_c).max(0.0) * days
+    }
+
+    /// Crop evapotranspiration for a reference evapotranspiration of `et0_mm`
+    pub fn etc_mm(&self, et0_mm: f32) -> f32 {
+        self.crop_coefficient * et0_mm
+    }
+
+    /// Relative yield loss `Ky * (1 - ETa/ETc)`, clamped to 0.0 ..= 1.0
+    pub fn yield_loss(&self, eta_over_etc: f32) -> f32 {
+        (self.yield_response_factor * (1.0 - eta_over_etc.clamp(0.0, 1.0))).clamp(0.0, 1.0)
+    }
+}
+
+impl Default for PlantProfile {
+    fn default() -> Self {
+        Self::maize()
+    }
+}
@@ -0,0 +1,111 @@
+//! Crops grown one after another, season by season, over a multi-year run
+
+use super::profile::PlantProfile;
+use crate::soil::nitrogen::NitrogenPool;
+
+/// Nitrogen content of crop residue dry matter, in percent
+pub const RESIDUE_N_PCT: f32 = 0.8;
+
+/// Mineral nitrogen a legume leaves for the next crop, in kg/ha
+pub const LEGUME_N_CREDIT_KG_HA: f32 = 30.0;
+
+/// A repeating sequence of crops, one per season
+#[derive(Debug, Clone, PartialEq)]
+pub struct CropRotation {
+    pub sequence: Vec<PlantProfile>,
+    pub current_index: usize, // Into `sequence`, of the crop in the ground
+}
+
+impl CropRotation {
+    /// Starts the rotation with its first crop; `sequence` must not be empty
+    pub fn new(sequence: Vec<PlantProfile>) -> Self {
+        assert!(!sequence.is_empty(), "a crop rotation needs at least one crop");
+        Self { sequence, current_index: 0 }
+    }
+
+    /// The crop in the ground
+    pub fn current(&self) -> &PlantProfile {
+        &self.sequence[self.current_index]
+    }
+
+    /// The crop that follows the current one, starting the sequence over after the last
+    pub fn upcoming(&self) -> &PlantProfile {
+        &self.sequence[(self.current_index + 1) % self.sequence.len()]
+    }
+
+    /// Moves on to the next season's crop and returns it
+    pub fn advance(&mut self) -> &PlantProfile {
+        self.current_index = (self.current_index + 1) % self.sequence.len();
+        self.current()
+    }
+
+    /// 1-based year of the rotation the current crop is in
+    pub fn rotation_year(&self) -> usize {
+        self.current_index + 1
+    }
+}
+
+/// Adds what `previous` left behind to `pool`: the nitrogen in its `residue_t_ha` of
+/// residue to the organic pool and, for a legume, its nitrogen credit to the mineral pool
+pub fn credit_previous_crop(previous: &PlantProfile, residue_t_ha: f32, pool: &mut NitrogenPool) {
+    pool.organic_n += residue_t_ha * 1000.0 * RESIDUE_N_PCT / 100.0;
+    if previous.fixes_nitrogen {
+        pool.mineral_n += LEGUME_N_CREDIT_KG_HA;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+    use crate::crop::disease::PestDiseaseRiskCalculator;
+
+    /// Residue left by a full harvest: everything but the grain
+    fn residue_t_ha(crop: &PlantProfile) -> f32 {
+        crop.potential_yield_t_ha * (1.0 - crop.harvest_index) / crop.harvest_index
+    }
+
+    #[test]
+    fn wheat_maize_fallow_over_three_seasons() {
+        let sequence = vec![PlantProfile::wheat(), PlantProfile::maize(), PlantProfile::fallow()];
+        let mut rotation = CropRotation::new(sequence);
+        let mut pool = NitrogenPool::default();
+        let mut disease = PestDiseaseRiskCalculator::builtin(rotation.current().clone());
+        // Wheat leaves 6 t/ha × 0.55 / 0.45 of straw and maize 11 t/ha of stover, both at 0.8% N;
+        // the fallow leaves nothing. Wheat to maize stays in the grass family, so blight carries over.
+        let seasons = [("Wheat", 1, 58.667, false), ("Maize", 2, 146.667, true), ("Fallow", 3, 146.667, true)];
+        for (name, year, organic_n, breaks) in seasons {
+            assert_eq!((rotation.current().name.as_str(), rotation.rotation_year()), (name, year));
+            let risk_before = disease.cumulative_risk[0];
+            for _ in 0..10 {
+                disease.record(20.0, 95.0, 1.0);
+            }
+            assert!(disease.cumulative_risk[0] > risk_before);
+            credit_previous_crop(rotation.current(), residue_t_ha(rotation.current()), &mut pool);
+            assert_close(pool.organic_n, organic_n, 1e-3);
+            assert_eq!(rotation.upcoming().is_break_crop(rotation.current()), breaks);
+            if breaks {
+                disease.reset();
+                assert_eq!(disease.cumulative_risk[0], 0.0);
+            }
+            rotation.advance();
+        }
+        // The fourth season starts the rotation over
+        assert_eq!((rotation.current().name.as_str(), rotation.rotation_year()), ("Wheat", 1));
+        assert_eq!(pool.mineral_n, 0.0);
+    }
+
+    #[test]
+    fn a_legume_credits_mineral_nitrogen_to_the_next_crop() {
+        let mut pool = NitrogenPool::default();
+        credit_previous_crop(&PlantProfile::beans(), 2.0, &mut pool);
+        assert_close(pool.organic_n, 16.0, 1e-3);
+        assert_close(pool.mineral_n, LEGUME_N_CREDIT_KG_HA, 1e-3);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one crop")]
+    fn an_empty_rotation_is_rejected() {
+        CropRotation::new(Vec::new());
+    }
+}
@@ -0,0 +1,129 @@
+//! Crop growth stages and the moisture thresholds that go with them
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::profile::PlantProfile;
+
+/// Phenological stage, reached as growing degree days accumulate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GrowthStage {
+    Germination, // Sown but not yet emerged; left once a germination model's heat is met
+    #[default]
+    Initial, // Seedlings establishing
+    Vegetative,
+    Flowering,
+    GrainFill,
+    Maturity,
+}
+
+impl GrowthStage {
+    /// Every stage in the order a crop passes through them
+    pub const ALL: [GrowthStage; 6] = [
+        GrowthStage::Germination,
+        GrowthStage::Initial,
+        GrowthStage::Vegetative,
+        GrowthStage::Flowering,
+        GrowthStage::GrainFill,
+        GrowthStage::Maturity,
+    ];
+
+    /// Fraction of the season's growing degree days at which this stage begins; germination
+    /// accrues none, so a crop is only in it while a [`GerminationModel`] holds it there
+    ///
+    /// [`GerminationModel`]: super::germination::GerminationModel
+    pub fn start_fraction(self) -> f32 {
+        match self {
+            GrowthStage::Germination | GrowthStage::Initial => 0.0,
+            GrowthStage::Vegetative => 0.1,
+            GrowthStage::Flowering => 0.4,
+            GrowthStage::GrainFill => 0.6,
+            GrowthStage::Maturity => 0.9,
+        }
+    }
+
+    /// The stage a crop with `profile` is in after `accumulated_gdd` growing degree days
+    pub fn at(profile: &PlantProfile, accumulated_gdd: f32) -> Self {
+        let progress = accumulated_gdd / profile.season_gdd;
+        Self::ALL
+            .into_iter()
+            .rev()
+            .find(|stage| progress >= stage.start_fraction())
+            .unwrap_or(GrowthStage::Initial)
+    }
+
+    /// Lowercase name for status messages
+    pub fn name(self) -> &'static str {
+        match self {
+            GrowthStage::Germination => "germination",
+            GrowthStage::Initial => "initial",
+            GrowthStage::Vegetative => "vegetative",
+            GrowthStage::Flowering => "flowering",
+            GrowthStage::GrainFill => "grain fill",
+            GrowthStage::Maturity => "maturity",
+        }
+    }
+}
+
+/// Scales the moisture threshold to what the crop needs at each stage
+#[derive(Debug, Clone)]
+pub struct SeasonalThresholdAdjuster {
+    pub profile: PlantProfile,
+}
+
+impl SeasonalThresholdAdjuster {
+    pub fn new(profile: PlantProfile) -> Self {
+        Self { profile }
+    }
+
+    /// `base_threshold` times the profile's multiplier for `stage`, or unchanged if it has none
+    pub fn adjusted_threshold(&self, stage: GrowthStage, base_threshold: f32) -> f32 {
+        let multiplier = self.profile.stage_threshold_multipliers.get(&stage).copied().unwrap_or(1.0);
+        (base_threshold * multiplier).clamp(0.0, 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_advance_with_growing_degree_days() {
+        let maize = PlantProfile::maize();
+        let stages = [0.0, 150.0, 600.0, 900.0, 1350.0, 2000.0].map(|gdd| GrowthStage::at(&maize, gdd));
+        assert_eq!(
+            stages,
+            [
+                GrowthStage::Initial,
+                GrowthStage::Vegetative,
+                GrowthStage::Flowering,
+                GrowthStage::GrainFill,
+                GrowthStage::Maturity,
+                GrowthStage::Maturity,
+            ]
+        );
+    }
+
+    #[test]
+    fn threshold_follows_the_stage_as_the_season_advances() {
+        let adjuster = SeasonalThresholdAdjuster::new(PlantProfile::maize());
+        let thresholds: Vec<f32> = [0.0, 150.0, 600.0, 900.0, 1350.0]
+            .into_iter()
+            .map(|gdd| adjuster.adjusted_threshold(GrowthStage::at(&adjuster.profile, gdd), 30.0))
+            .collect();
+        let expected = [27.0, 30.0, 37.5, 34.5, 22.5];
+        assert!(thresholds.iter().zip(expected).all(|(threshold, expected)| (threshold - expected).abs() < 1e-4));
+        // Flowering is maize's most drought-sensitive stage
+        assert_eq!(thresholds.iter().copied().fold(f32::MIN, f32::max), thresholds[2]);
+    }
+
+    #[test]
+    fn stages_without_a_multiplier_keep_the_base_threshold() {
+        let adjuster = SeasonalThresholdAdjuster::new(PlantProfile::maize());
+        assert_eq!(adjuster.adjusted_threshold(GrowthStage::Germination, 30.0), 30.0);
+        let mut profile = PlantProfile::maize();
+        profile.stage_threshold_multipliers.insert(GrowthStage::Flowering, 4.0);
+        assert_eq!(SeasonalThresholdAdjuster::new(profile).adjusted_threshold(GrowthStage::Flowering, 30.0), 100.0);
+    }
+}
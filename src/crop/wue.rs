@@ -0,0 +1,88 @@
+//! Water use efficiency: biomass produced per litre of water consumed
+
+use super::profile::PlantProfile;
+
+/// 1 kg/ha is 0.1 g/m² and 1 mm of water is 1 L/m², so kg/ha per mm is 0.1 g/L
+const G_PER_L_FROM_KG_HA_PER_MM: f32 = 0.1;
+
+/// Running totals of one zone's water use and biomass
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WueTracker {
+    pub total_et_mm: f32,            // Crop evapotranspiration, whatever the water source
+    pub total_water_applied_mm: f32, // Irrigation only
+    pub estimated_biomass_kg_ha: f32,
+}
+
+impl WueTracker {
+    /// Adds one tick: biomass grows by `gdd_delta * harvest_index * radiation_use_efficiency`
+    pub fn record(&mut self, profile: &PlantProfile, gdd_delta: f32, et_mm: f32, applied_mm: f32) {
+        self.estimated_biomass_kg_ha += gdd_delta * profile.harvest_index * profile.radiation_use_efficiency;
+        self.total_et_mm += et_mm;
+        self.total_water_applied_mm += applied_mm;
+    }
+
+    /// Evapotranspiration not covered by irrigation, i.e. supplied by rain and stored soil water
+    pub fn rainfed_et_mm(&self) -> f32 {
+        (self.total_et_mm - self.total_water_applied_mm).max(0.0)
+    }
+
+    /// Biomass per litre evapotranspired, in g/L; `None` before any water is used
+    pub fn wue_g_per_l(&self) -> Option<f32> {
+        per_litre(self.estimated_biomass_kg_ha, self.total_et_mm)
+    }
+
+    /// Biomass per litre of irrigation applied, in g/L; `None` before any watering
+    pub fn irrigation_wue_g_per_l(&self) -> Option<f32> {
+        per_litre(self.estimated_biomass_kg_ha, self.total_water_applied_mm)
+    }
+
+    /// Whether WUE has fallen below the crop's target, suggesting a change of irrigation strategy
+    pub fn below_target(&self, profile: &PlantProfile) -> bool {
+        self.wue_g_per_l().is_some_and(|wue| wue < profile.target_wue_g_per_l)
+    }
+}
+
+fn per_litre(biomass_kg_ha: f32, water_mm: f32) -> Option<f32> {
+    (water_mm > 0.0).then(|| biomass_kg_ha / water_mm * G_PER_L_FROM_KG_HA_PER_MM)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wue_from_known_biomass_and_water() {
+        let maize = PlantProfile::maize();
+        let mut tracker = WueTracker::default();
+        // 10 days of 15 GDD, 10 mm ET and 6 mm irrigation; maize grows 0.5 × 20 = 10 kg/ha per GDD
+        for _ in 0..10 {
+            tracker.record(&maize, 15.0, 10.0, 6.0);
+        }
+        assert!((tracker.estimated_biomass_kg_ha - 1500.0).abs() < 1e-2);
+        assert!((tracker.rainfed_et_mm() - 40.0).abs() < 1e-3);
+        // 1500 kg/ha over 100 mm is 150 g/m² over 100 L/m²
+        assert!((tracker.wue_g_per_l().unwrap() - 1.5).abs() < 1e-4);
+        assert!((tracker.irrigation_wue_g_per_l().unwrap() - 2.5).abs() < 1e-4);
+        assert!(!tracker.below_target(&maize));
+    }
+
+    #[test]
+    fn no_wue_before_any_water_is_used() {
+        let mut tracker = WueTracker::default();
+        assert_eq!(tracker.wue_g_per_l(), None);
+        tracker.record(&PlantProfile::beans(), 10.0, 5.0, 0.0);
+        assert!(tracker.wue_g_per_l().is_some());
+        assert_eq!(tracker.irrigation_wue_g_per_l(), None);
+        assert_eq!(tracker.rainfed_et_mm(), 5.0);
+    }
+
+    #[test]
+    fn low_wue_is_below_the_crop_target() {
+        let maize = PlantProfile::maize();
+        let mut tracker = WueTracker::default();
+        // 1000 kg/ha over 100 mm is 1.0 g/L, short of maize's 1.5 g/L
+        tracker.record(&maize, 100.0, 100.0, 100.0);
+        assert!(tracker.below_target(&maize));
+        assert!(!tracker.below_target(&PlantProfile::beans()));
+    }
+}
@@ -0,0 +1,96 @@
+//! Harvest yield forecast from drought stress, after the FAO-33 water production function
+
+use super::profile::PlantProfile;
+
+/// Predicts the yield a crop is on track for: `Y = Yp * (1 - Ky * (1 - ETa/ETc))`
+#[derive(Debug, Clone, PartialEq)]
+pub struct YieldEstimator {
+    pub potential_yield_t_ha: f32,   // Yield without water stress
+    pub crop_response_factor_ky: f32,
+    pub season_yields_t_ha: Vec<f32>, // Harvested seasons, oldest first
+}
+
+impl YieldEstimator {
+    pub fn new(potential_yield_t_ha: f32, crop_response_factor_ky: f32) -> Self {
+        Self { potential_yield_t_ha, crop_response_factor_ky, season_yields_t_ha: Vec::new() }
+    }
+
+    /// Spring wheat (FAO-33 Ky 1.15)
+    pub fn wheat() -> Self {
+        Self::new(6.0, 1.15)
+    }
+
+    /// Grain maize (FAO-33 Ky 1.25)
+    pub fn maize() -> Self {
+        Self::new(PlantProfile::maize().potential_yield_t_ha, 1.25)
+    }
+
+    /// Fresh-market tomato (FAO-33 Ky 1.05)
+    pub fn tomato() -> Self {
+        Self::new(80.0, 1.05)
+    }
+
+    /// Expected yield in t/ha given the relative ET deficit `stress_index`
+    /// (`1 - ETa/ETc`) so far and the share of the season's GDD already
+    /// accrued, assuming the rest of the season is unstressed
+    pub fn estimate_yield(&self, stress_index: f32, gdd_fraction_complete: f32) -> f32 {
+        let season_deficit = stress_index.clamp(0.0, 1.0) * gdd_fraction_complete.clamp(0.0, 1.0);
+        self.potential_yield_t_ha * (1.0 - self.crop_response_factor_ky * season_deficit).clamp(0.0, 1.0)
+    }
+
+    /// Adds a harvested season's yield to the history
+    pub fn record_season(&mut self, yield_t_ha: f32) {
+        self.season_yields_t_ha.push(yield_t_ha);
+    }
+}
+
+impl From<&PlantProfile> for YieldEstimator {
+    fn from(profile: &PlantProfile) -> Self {
+        Self::new(profile.potential_yield_t_ha, profile.yield_response_factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn tabulated_ky_values_at_a_20_percent_deficit() {
+        // FAO-33: Y/Yp = 1 - Ky × 0.2
+        for (estimator, ky, relative_yield) in [
+            (YieldEstimator::wheat(), 1.15, 0.77),
+            (YieldEstimator::maize(), 1.25, 0.75),
+            (YieldEstimator::tomato(), 1.05, 0.79),
+        ] {
+            assert_eq!(estimator.crop_response_factor_ky, ky);
+            assert_close(estimator.estimate_yield(0.2, 1.0), estimator.potential_yield_t_ha * relative_yield, 1e-3);
+        }
+        assert_close(YieldEstimator::maize().estimate_yield(0.2, 1.0), 8.25, 1e-3);
+    }
+
+    #[test]
+    fn no_stress_gives_the_potential_yield() {
+        let tomato = YieldEstimator::tomato();
+        assert_eq!(tomato.estimate_yield(0.0, 1.0), 80.0);
+        assert_eq!(tomato.estimate_yield(0.5, 0.0), 80.0);
+    }
+
+    #[test]
+    fn stress_counts_for_the_share_of_the_season_accrued() {
+        let wheat = YieldEstimator::wheat();
+        // Half a season at a 20% deficit is a 10% season deficit
+        assert_close(wheat.estimate_yield(0.2, 0.5), 6.0 * (1.0 - 1.15 * 0.1), 1e-3);
+        // Yield never goes negative, even when Ky × deficit exceeds 1
+        assert_eq!(wheat.estimate_yield(1.0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn season_history_and_profiles() {
+        let mut maize = YieldEstimator::from(&PlantProfile::maize());
+        assert_eq!(maize, YieldEstimator::maize());
+        maize.record_season(9.5);
+        maize.record_season(10.2);
+        assert_eq!(maize.season_yields_t_ha, [9.5, 10.2]);
+    }
+}
@@ -0,0 +1,356 @@
+//! Per-zone yield at harvest with each zone's position, for precision-agriculture yield maps
+//!
+//! Zone yields come from [`YieldEstimator::estimate_yield`] with the zone's own drought stress.
+//! With a [`FieldLayout`] the map also covers a grid over the field, filled in between the
+//! zones by inverse distance weighting.
+
+use serde_json::{json, Value};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use super::yield_estimator::YieldEstimator;
+use crate::simulation::IrrigationZone;
+
+/// Header line of the CSV written by [`YieldMapGenerator::write`]
+pub const CSV_HEADER: &str = "zone_id,latitude,longitude,yield_t_ha,water_applied_mm,stress_index,gdd_accumulated";
+
+/// Exponent of the inverse distance weights; 2 is the usual choice for yield maps
+pub const IDW_POWER: f64 = 2.0;
+
+/// East-west spacing of zones placed automatically, in degrees of longitude (about 70 m)
+pub const ZONE_SPACING_DEG: f64 = 0.001;
+
+/// A WGS 84 position, in decimal degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl GeoPoint {
+    /// Distance to `other` in metres, on a plane tangent to the field
+    pub fn distance_m(&self, other: GeoPoint) -> f64 {
+        const METRES_PER_DEG: f64 = 111_320.0;
+        let north_m = (other.latitude - self.latitude) * METRES_PER_DEG;
+        let east_m = (other.longitude - self.longitude) * METRES_PER_DEG * self.latitude.to_radians().cos();
+        north_m.hypot(east_m)
+    }
+}
+
+/// Grid of `rows` × `columns` cell centres spanning the field's bounding box
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldLayout {
+    pub south_west: GeoPoint,
+    pub north_east: GeoPoint,
+    pub rows: u32,
+    pub columns: u32,
+    pub cell_zones: Vec<Option<u8>>, // Zone of each cell, ordered like `grid`; empty unless drawn
+}
+
+impl FieldLayout {
+    /// Cell size in degrees of latitude and longitude
+    fn cell_deg(&self) -> (f64, f64) {
+        let (rows, columns) = (self.rows.max(1), self.columns.max(1));
+        (
+            (self.north_east.latitude - self.south_west.latitude) / rows as f64,
+            (self.north_east.longitude - self.south_west.longitude) / columns as f64,
+        )
+    }
+
+    /// Centre of every grid cell, row by row from the south-west corner
+    pub fn grid(&self) -> Vec<GeoPoint> {
+        let (rows, columns) = (self.rows.max(1), self.columns.max(1));
+        let (cell_lat, cell_lon) = self.cell_deg();
+        (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| GeoPoint {
+                    latitude: self.south_west.latitude + (row as f64 + 0.5) * cell_lat,
+                    longitude: self.south_west.longitude + (column as f64 + 0.5) * cell_lon,
+                })
+            })
+            .collect()
+    }
+
+    /// A GeoJSON FeatureCollection with a square Polygon per cell assigned to a zone, its
+    /// `zone_id` in the feature's `properties`
+    pub fn geojson(&self) -> Value {
+        json!({ "type": "FeatureCollection", "features": self.zone_cells() })
+    }
+
+    /// The Polygon features of [`geojson`](Self::geojson)
+    fn zone_cells(&self) -> Vec<Value> {
+        let (cell_lat, cell_lon) = self.cell_deg();
+        let columns = self.columns.max(1) as usize;
+        self.cell_zones
+            .iter()
+            .enumerate()
+            .filter_map(|(index, zone_id)| {
+                let zone_id = (*zone_id)?;
+                let south = self.south_west.latitude + (index / columns) as f64 * cell_lat;
+                let west = self.south_west.longitude + (index % columns) as f64 * cell_lon;
+                let (north, east) = (south + cell_lat, west + cell_lon);
+                // An outer ring runs counterclockwise and ends where it starts
+                let ring = [[west, south], [east, south], [east, north], [west, north], [west, south]];
+                Some(json!({
+                    "type": "Feature",
+                    "geometry": { "type": "Polygon", "coordinates": [ring] },
+                    "properties": { "zone_id": zone_id },
+                }))
+            })
+            .collect()
+    }
+}
+
+/// One zone's season at harvest
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZoneYield {
+    pub zone_id: u8,
+    pub position: GeoPoint,
+    pub yield_t_ha: f32,
+    pub water_applied_mm: f32,
+    pub stress_index: f32,
+    pub gdd_accumulated: f32,
+}
+
+/// Places the zones on the field and writes a yield map at each harvest
+#[derive(Debug, Clone, PartialEq)]
+pub struct YieldMapGenerator {
+    pub directory: PathBuf,
+    pub origin: GeoPoint, // Position of zone 0 unless `zone_positions` has one
+    pub zone_positions: Vec<GeoPoint>, // By zone id; zones past the end continue east of the origin
+    pub layout: Option<FieldLayout>,
+}
+
+impl YieldMapGenerator {
+    /// Where zone `zone_id` lies
+    pub fn position(&self, zone_id: u8) -> GeoPoint {
+        self.zone_positions.get(zone_id as usize).copied().unwrap_or(GeoPoint {
+            latitude: self.origin.latitude,
+            longitude: self.origin.longitude + zone_id as f64 * ZONE_SPACING_DEG,
+        })
+    }
+
+    /// Each zone's yield for its own stress index, after `gdd_accumulated` of the crop's
+    /// `season_gdd`, taken before the zones' season accumulators are reset
+    pub fn zone_yields(
+        &self,
+        zones: &[IrrigationZone],
+        estimator: &YieldEstimator,
+        gdd_accumulated: f32,
+        season_gdd: f32,
+    ) -> Vec<ZoneYield> {
+        zones
+            .iter()
+            .map(|zone| {
+                let stress_index = zone.stress.stress_index();
+                ZoneYield {
+                    zone_id: zone.id,
+                    position: self.position(zone.id),
+                    yield_t_ha: estimator.estimate_yield(stress_index, gdd_accumulated / season_gdd),
+                    water_applied_mm: zone.water.total_mm(),
+                    stress_index,
+                    gdd_accumulated,
+                }
+            })
+            .collect()
+    }
+
+    /// Yield at `at` as the inverse-distance-weighted mean of the zone yields; a zone's own
+    /// yield at its position, and nothing without zones
+    pub fn interpolate(yields: &[ZoneYield], at: GeoPoint) -> Option<f32> {
+        let mut weighted = 0.0;
+        let mut total_weight = 0.0;
+        for zone in yields {
+            let distance_m = zone.position.distance_m(at);
+            if distance_m < 1e-3 {
+                return Some(zone.yield_t_ha);
+            }
+            let weight = 1.0 / distance_m.powf(IDW_POWER);
+            weighted += weight * zone.yield_t_ha as f64;
+            total_weight += weight;
+        }
+        (total_weight > 0.0).then(|| (weighted / total_weight) as f32)
+    }
+
+    /// Yield at every cell of the layout, empty without one
+    pub fn interpolated_grid(&self, yields: &[ZoneYield]) -> Vec<(GeoPoint, f32)> {
+        let grid = self.layout.as_ref().map(FieldLayout::grid).unwrap_or_default();
+        grid.into_iter().filter_map(|point| Some((point, Self::interpolate(yields, point)?))).collect()
+    }
+
+    /// The zone rows, then one row per interpolated cell with only its position and yield
+    pub fn csv(&self, yields: &[ZoneYield]) -> String {
+        let mut csv = format!("{CSV_HEADER}\n");
+        for zone in yields {
+            csv += &format!(
+                "{},{:.6},{:.6},{:.2},{:.1},{:.3},{:.0}\n",
+                zone.zone_id,
+                zone.position.latitude,
+                zone.position.longitude,
+                zone.yield_t_ha,
+                zone.water_applied_mm,
+                zone.stress_index,
+                zone.gdd_accumulated
+            );
+        }
+        for (point, yield_t_ha) in self.interpolated_grid(yields) {
+            csv += &format!(",{:.6},{:.6},{yield_t_ha:.2},,,\n", point.latitude, point.longitude);
+        }
+        csv
+    }
+
+    /// A GeoJSON FeatureCollection with a Point per zone and per interpolated cell, the yield
+    /// in each feature's `properties`, and the zone cells of a drawn layout
+    pub fn geojson(&self, yields: &[ZoneYield]) -> Value {
+        let point = |at: GeoPoint| json!({ "type": "Point", "coordinates": [at.longitude, at.latitude] });
+        let zones = yields.iter().map(|zone| {
+            json!({
+                "type": "Feature",
+                "geometry": point(zone.position),
+                "properties": {
+                    "zone_id": zone.zone_id,
+                    "yield_t_ha": zone.yield_t_ha,
+                    "water_applied_mm": zone.water_applied_mm,
+                    "stress_index": zone.stress_index,
+                    "gdd_accumulated": zone.gdd_accumulated,
+                    "interpolated": false,
+                },
+            })
+        });
+        let cells = self.interpolated_grid(yields).into_iter().map(|(at, yield_t_ha)| {
+            json!({
+                "type": "Feature",
+                "geometry": point(at),
+                "properties": { "yield_t_ha": yield_t_ha, "interpolated": true },
+            })
+        });
+        let boundaries = self.layout.iter().flat_map(FieldLayout::zone_cells);
+        json!({ "type": "FeatureCollection", "features": zones.chain(cells).chain(boundaries).collect::<Vec<_>>() })
+    }
+
+    /// Writes `season_<n>.csv` and `season_<n>.geojson` under the directory, creating it if
+    /// needed, and returns the CSV's path
+    pub fn write(&self, season: u32, yields: &[ZoneYield]) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.directory)?;
+        let path = |extension: &str| self.directory.join(format!("season_{season}.{extension}"));
+        let geojson = serde_json::to_string_pretty(&self.geojson(yields)).map_err(io::Error::other)?;
+        fs::write(path("geojson"), geojson)?;
+        fs::write(path("csv"), self.csv(yields))?;
+        Ok(path("csv"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generator(layout: Option<FieldLayout>) -> YieldMapGenerator {
+        YieldMapGenerator {
+            directory: std::env::temp_dir().join(format!("agri-yield-map-{}", std::process::id())),
+            origin: GeoPoint { latitude: 52.0, longitude: 5.0 },
+            zone_positions: Vec::new(),
+            layout,
+        }
+    }
+
+    fn zone(generator: &YieldMapGenerator, zone_id: u8, yield_t_ha: f32) -> ZoneYield {
+        ZoneYield {
+            zone_id,
+            position: generator.position(zone_id),
+            yield_t_ha,
+            water_applied_mm: 250.0,
+            stress_index: 0.125,
+            gdd_accumulated: 1500.0,
+        }
+    }
+
+    /// Two rows of two cells around zones 0 and 1, zone 0 drawn on the west column
+    fn layout() -> FieldLayout {
+        FieldLayout {
+            south_west: GeoPoint { latitude: 51.9995, longitude: 4.9995 },
+            north_east: GeoPoint { latitude: 52.0005, longitude: 5.0015 },
+            rows: 2,
+            columns: 2,
+            cell_zones: vec![Some(0), Some(1), Some(0), Some(1)],
+        }
+    }
+
+    #[test]
+    fn zones_without_positions_continue_east_of_the_origin() {
+        let mut generator = generator(None);
+        assert_eq!(generator.position(3), GeoPoint { latitude: 52.0, longitude: 5.003 });
+        generator.zone_positions = vec![GeoPoint { latitude: 51.0, longitude: 4.0 }];
+        assert_eq!(generator.position(0), GeoPoint { latitude: 51.0, longitude: 4.0 });
+        assert_eq!(generator.position(1), GeoPoint { latitude: 52.0, longitude: 5.001 });
+    }
+
+    #[test]
+    fn idw_weights_the_nearer_zone_more() {
+        let generator = generator(None);
+        let yields = [zone(&generator, 0, 8.0), zone(&generator, 1, 4.0)];
+        assert_eq!(YieldMapGenerator::interpolate(&yields, generator.position(1)), Some(4.0));
+        let midway = GeoPoint { latitude: 52.0, longitude: 5.0005 };
+        assert!((YieldMapGenerator::interpolate(&yields, midway).unwrap() - 6.0).abs() < 1e-4);
+        // A quarter of the way from zone 0 its weight is (3/1)² = 9 times zone 1's: (9×8 + 4)/10
+        let quarter = GeoPoint { latitude: 52.0, longitude: 5.00025 };
+        assert!((YieldMapGenerator::interpolate(&yields, quarter).unwrap() - 7.6).abs() < 1e-3);
+        assert_eq!(YieldMapGenerator::interpolate(&[], midway), None);
+    }
+
+    #[test]
+    fn csv_has_a_zone_row_per_zone_and_a_position_row_per_cell() {
+        let generator = generator(Some(layout()));
+        let yields = [zone(&generator, 0, 8.0), zone(&generator, 1, 4.0)];
+        let csv = generator.csv(&yields);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(CSV_HEADER));
+        let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+        assert_eq!(rows.len(), 2 + 4);
+        assert!(rows.iter().all(|row| row.len() == CSV_HEADER.split(',').count()));
+        assert_eq!(rows[0], ["0", "52.000000", "5.000000", "8.00", "250.0", "0.125", "1500"]);
+        assert_eq!(rows[1][..3], ["1", "52.000000", "5.001000"]);
+        for cell in &rows[2..] {
+            assert!(cell[0].is_empty() && cell[4..].iter().all(|column| column.is_empty()));
+            let yield_t_ha: f32 = cell[3].parse().unwrap();
+            assert!((4.0..=8.0).contains(&yield_t_ha));
+        }
+        // Cells on the west column lie nearer zone 0
+        assert!(rows[2][3].parse::<f32>().unwrap() > rows[3][3].parse::<f32>().unwrap());
+        assert_eq!(generator.interpolated_grid(&yields).len(), 4);
+    }
+
+    #[test]
+    fn geojson_is_a_feature_collection_of_points_and_cells() {
+        let generator = generator(Some(layout()));
+        let yields = [zone(&generator, 0, 8.0), zone(&generator, 1, 4.0)];
+        let path = generator.write(2, &yields).unwrap();
+        assert_eq!(path, generator.directory.join("season_2.csv"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), generator.csv(&yields));
+        let geojson = fs::read_to_string(generator.directory.join("season_2.geojson")).unwrap();
+        fs::remove_dir_all(&generator.directory).unwrap();
+
+        let geojson: Value = serde_json::from_str(&geojson).unwrap();
+        assert_eq!(geojson["type"], "FeatureCollection");
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2 + 4 + 4);
+        assert!(features.iter().all(|feature| feature["type"] == "Feature" && feature["properties"].is_object()));
+        let (points, cells): (Vec<&Value>, Vec<&Value>) =
+            features.iter().partition(|feature| feature["geometry"]["type"] == "Point");
+        assert_eq!(points.len(), 6);
+        // GeoJSON positions are longitude first
+        assert_eq!(points[1]["geometry"]["coordinates"], json!([5.001, 52.0]));
+        assert_eq!(points[0]["properties"]["yield_t_ha"], 8.0);
+        assert_eq!(points[0]["properties"]["interpolated"], false);
+        assert!(points[2..].iter().all(|point| {
+            point["properties"]["interpolated"] == true && point["properties"]["yield_t_ha"].is_f64()
+        }));
+        for cell in cells {
+            assert_eq!(cell["geometry"]["type"], "Polygon");
+            let ring = cell["geometry"]["coordinates"][0].as_array().unwrap();
+            assert_eq!(ring.len(), 5);
+            assert_eq!(ring[0], ring[4]);
+            assert!(cell["properties"]["zone_id"].is_u64());
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Per-sensor health statistics, for spotting a misbehaving sensor at a glance
+
+use std::time::Instant;
+
+use crate::{DeviceState, SensorId, SensorReading, Tick};
+
+/// Ticks without a good reading after which a sensor is stale
+pub const STALE_AFTER_TICKS: u64 = 10;
+/// Share of updates in error above which a sensor is flagged
+pub const MAX_ERROR_RATE: f32 = 0.01;
+
+/// What one sensor has reported since the run started
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorDiagnostics {
+    pub sensor_id: SensorId,
+    pub update_count: u64,
+    pub error_count: u64,            // Updates in the Error state
+    pub last_update_tick: Tick,      // Of the last reading that was not an error
+    pub min_reading: f32,            // Over readings that were not errors; infinite before the first
+    pub max_reading: f32,
+    pub reading_rate_hz: f32,        // Updates per wall-clock second since the first
+    first_update: Option<Instant>,
+}
+
+impl SensorDiagnostics {
+    pub fn new(sensor_id: SensorId) -> Self {
+        Self {
+            sensor_id,
+            update_count: 0,
+            error_count: 0,
+            last_update_tick: Tick::ZERO,
+            min_reading: f32::INFINITY,
+            max_reading: f32::NEG_INFINITY,
+            reading_rate_hz: 0.0,
+            first_update: None,
+        }
+    }
+
+    /// Counts `reading`, taken at `tick`
+    pub fn update(&mut self, reading: &SensorReading, tick: Tick) {
+        let first_update = *self.first_update.get_or_insert_with(Instant::now);
+        self.update_count += 1;
+        if reading.state == DeviceState::Error {
+            self.error_count += 1;
+        } else {
+            self.last_update_tick = tick;
+            self.min_reading = self.min_reading.min(reading.moisture_level);
+            self.max_reading = self.max_reading.max(reading.moisture_level);
+        }
+        let elapsed_s = first_update.elapsed().as_secs_f32();
+        self.reading_rate_hz = if elapsed_s > 0.0 { (self.update_count - 1) as f32 / elapsed_s } else { 0.0 };
+    }
+
+    /// Share of updates in error; 0 before the first
+    pub fn error_rate(&self) -> f32 {
+        if self.update_count == 0 {
+            return 0.0;
+        }
+        self.error_count as f32 / self.update_count as f32
+    }
+
+    /// Whether errors are above [`MAX_ERROR_RATE`]
+    pub fn is_faulty(&self) -> bool {
+        self.error_rate() > MAX_ERROR_RATE
+    }
+
+    /// Whether `current_tick` is more than [`STALE_AFTER_TICKS`] past the last good reading
+    pub fn is_stale(&self, current_tick: Tick) -> bool {
+        current_tick.0.saturating_sub(self.last_update_tick.0) > STALE_AFTER_TICKS
+    }
+}
+
+/// Counts each of `readings` at `tick` against its sensor, adding sensors not seen before
+pub fn update_all(
+    diagnostics: &mut Vec<SensorDiagnostics>,
+    readings: impl IntoIterator<Item = SensorReading>,
+    tick: Tick,
+) {
+    for reading in readings {
+        let index = match diagnostics.iter().position(|sensor| sensor.sensor_id == reading.sensor_id) {
+            Some(index) => index,
+            None => {
+                diagnostics.push(SensorDiagnostics::new(reading.sensor_id.clone()));
+                diagnostics.len() - 1
+            }
+        };
+        diagnostics[index].update(&reading, tick);
+    }
+}
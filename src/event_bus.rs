@@ -0,0 +1,256 @@
+//! In-process publish/subscribe for [`AgriEvent`]s
+//!
+//! Sensors publish to the [`EventBus`]; alerters, loggers and any other
+//! consumer call [`EventBus::subscribe`] and receive every event
+//! independently. Events a slow subscriber misses land in the bus's
+//! [`DeadLetterQueue`] instead of vanishing.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::{self, error::RecvError, error::TryRecvError};
+
+use crate::events::AgriEvent;
+
+/// Dead letters kept before the oldest are discarded
+const DEAD_LETTER_CAPACITY: usize = 256;
+
+/// An event with its position in the publish order
+#[derive(Debug, Clone)]
+struct Envelope {
+    seq: u64,
+    event: AgriEvent,
+}
+
+/// An event one subscriber was too slow to receive
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub subscriber_id: usize,
+    pub event: AgriEvent,
+}
+
+/// Events dropped for slow subscribers, newest last
+#[derive(Debug, Default)]
+pub struct DeadLetterQueue {
+    letters: Mutex<VecDeque<DeadLetter>>,
+    unrecoverable: AtomicU64, // Dropped events already gone from the bus's history
+}
+
+impl DeadLetterQueue {
+    fn push(&self, letter: DeadLetter) {
+        let mut letters = self.letters.lock().expect("dead letter lock poisoned");
+        if letters.len() == DEAD_LETTER_CAPACITY {
+            letters.pop_front();
+        }
+        letters.push_back(letter);
+    }
+
+    /// Removes and returns every dead letter collected so far
+    pub fn drain(&self) -> Vec<DeadLetter> {
+        self.letters.lock().expect("dead letter lock poisoned").drain(..).collect()
+    }
+
+    /// Dropped events that were too old to recover, so only their count is known
+    pub fn unrecoverable(&self) -> u64 {
+        self.unrecoverable.load(Ordering::Relaxed)
+    }
+}
+
+/// State shared between the bus and its subscribers
+#[derive(Debug)]
+struct Shared {
+    history: Mutex<VecDeque<Envelope>>, // Recently published events, for recovering dropped ones
+    history_capacity: usize,
+    dead_letters: DeadLetterQueue,
+    next_subscriber_id: AtomicUsize,
+}
+
+/// Broadcasts every published event to all current subscribers
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<Envelope>,
+    shared: Arc<Shared>,
+}
+
+impl EventBus {
+    /// A bus buffering up to `capacity` events for each subscriber
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            sender,
+            shared: Arc::new(Shared {
+                // Dropped events are always older than the `capacity` still buffered
+                history: Mutex::new(VecDeque::with_capacity(2 * capacity)),
+                history_capacity: 2 * capacity,
+                dead_letters: DeadLetterQueue::default(),
+                next_subscriber_id: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Sends `event` to every subscriber and returns how many there are
+    pub fn publish(&self, event: AgriEvent) -> usize {
+        let mut history = self.shared.history.lock().expect("event history lock poisoned");
+        let seq = history.back().map_or(0, |last| last.seq + 1);
+        let envelope = Envelope { seq, event };
+        if history.len() == self.shared.history_capacity {
+            history.pop_front();
+        }
+        history.push_back(envelope.clone());
+        // Sending under the lock keeps sequence numbers in publish order
+        self.sender.send(envelope).unwrap_or(0)
+    }
+
+    /// A new subscriber that receives every event published from now on
+    pub fn subscribe(&self) -> Subscriber {
+        let history = self.shared.history.lock().expect("event history lock poisoned");
+        Subscriber {
+            id: self.shared.next_subscriber_id.fetch_add(1, Ordering::Relaxed),
+            next_seq: history.back().map_or(0, |last| last.seq + 1),
+            receiver: self.sender.subscribe(),
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    /// Events subscribers were too slow to receive
+    pub fn dead_letters(&self) -> &DeadLetterQueue {
+        &self.shared.dead_letters
+    }
+}
+
+/// One consumer's view of the [`EventBus`]
+#[derive(Debug)]
+pub struct Subscriber {
+    id: usize,
+    next_seq: u64, // Sequence number of the next event this subscriber expects
+    receiver: broadcast::Receiver<Envelope>,
+    shared: Arc<Shared>,
+}
+
+impl Subscriber {
+    /// Identifies this subscriber in dead letters
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Waits for the next event; returns `None` once the bus has shut down
+    pub async fn recv(&mut self) -> Option<AgriEvent> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(envelope) => return Some(self.accept(envelope)),
+                Err(RecvError::Lagged(missed)) => self.dead_letter(missed),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// The next event if one is already queued
+    pub fn try_recv(&mut self) -> Option<AgriEvent> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(envelope) => return Some(self.accept(envelope)),
+                Err(TryRecvError::Lagged(missed)) => self.dead_letter(missed),
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return None,
+            }
+        }
+    }
+
+    fn accept(&mut self, envelope: Envelope) -> AgriEvent {
+        self.next_seq = envelope.seq + 1;
+        envelope.event
+    }
+
+    /// Moves the `missed` events after the last one received to the dead letter queue
+    fn dead_letter(&mut self, missed: u64) {
+        let missed_seqs = self.next_seq..self.next_seq + missed;
+        self.next_seq = missed_seqs.end;
+        let history = self.shared.history.lock().expect("event history lock poisoned");
+        let mut recovered = 0;
+        for envelope in history.iter().filter(|envelope| missed_seqs.contains(&envelope.seq)) {
+            self.shared.dead_letters.push(DeadLetter { subscriber_id: self.id, event: envelope.event.clone() });
+            recovered += 1;
+        }
+        self.shared.dead_letters.unrecoverable.fetch_add(missed - recovered, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DeviceState;
+
+    fn watering(zone_id: u8) -> AgriEvent {
+        AgriEvent::WateringStarted { zone_id, moisture_level: 25.0, state: DeviceState::Activating }
+    }
+
+    #[tokio::test]
+    async fn every_event_reaches_three_subscribers() {
+        let bus = EventBus::new(16);
+        let subscribers: Vec<Subscriber> = (0..3).map(|_| bus.subscribe()).collect();
+        assert_eq!(subscribers.iter().map(Subscriber::id).collect::<Vec<_>>(), [0, 1, 2]);
+        let consumers: Vec<_> = subscribers
+            .into_iter()
+            .map(|mut subscriber| {
+                tokio::spawn(async move {
+                    let mut received = Vec::new();
+                    while let Some(event) = subscriber.recv().await {
+                        received.push(event);
+                    }
+                    received
+                })
+            })
+            .collect();
+
+        let published: Vec<AgriEvent> = (0..10).map(watering).collect();
+        for event in &published {
+            assert_eq!(bus.publish(event.clone()), 3);
+        }
+        drop(bus);
+        for consumer in consumers {
+            assert_eq!(consumer.await.unwrap(), published);
+        }
+    }
+
+    #[test]
+    fn subscribers_only_see_events_published_after_subscribing() {
+        let bus = EventBus::new(4);
+        assert_eq!(bus.publish(watering(0)), 0);
+        let mut late = bus.subscribe();
+        bus.publish(watering(1));
+        assert_eq!(late.try_recv(), Some(watering(1)));
+        assert_eq!(late.try_recv(), None);
+    }
+
+    #[test]
+    fn events_a_slow_subscriber_misses_become_dead_letters() {
+        let bus = EventBus::new(2);
+        let mut fast = bus.subscribe();
+        let mut slow = bus.subscribe();
+        for zone_id in 0..4 {
+            bus.publish(watering(zone_id));
+            assert_eq!(fast.try_recv(), Some(watering(zone_id)));
+        }
+        // The channel kept the newest two; the two before them were dropped for `slow`
+        assert_eq!(slow.try_recv(), Some(watering(2)));
+        assert_eq!(slow.try_recv(), Some(watering(3)));
+        let letters = bus.dead_letters().drain();
+        assert!(letters.iter().all(|letter| letter.subscriber_id == slow.id()));
+        assert_eq!(letters.into_iter().map(|letter| letter.event).collect::<Vec<_>>(), [0, 1].map(watering));
+        assert_eq!(bus.dead_letters().unrecoverable(), 0);
+        assert!(bus.dead_letters().drain().is_empty());
+    }
+
+    #[test]
+    fn events_older_than_the_history_are_only_counted() {
+        let bus = EventBus::new(2);
+        let mut slow = bus.subscribe();
+        for zone_id in 0..6 {
+            bus.publish(watering(zone_id));
+        }
+        // Four events were dropped, but the history of twice the capacity only still holds two of them
+        assert_eq!(slow.try_recv(), Some(watering(4)));
+        let letters = bus.dead_letters().drain();
+        assert_eq!(letters.into_iter().map(|letter| letter.event).collect::<Vec<_>>(), [2, 3].map(watering));
+        assert_eq!(bus.dead_letters().unrecoverable(), 2);
+    }
+}
@@ -0,0 +1,283 @@
+//! Events published by the simulation loop for independent subscribers
+
+use clap::ValueEnum;
+use uuid::Uuid;
+
+use crate::crop::disease::RiskLevel;
+use crate::irrigation::BfpType;
+use crate::soil::trace_elements::TraceElement;
+use crate::{DeviceState, Tick};
+
+/// How urgently an event needs an operator's attention
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, ValueEnum)]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Something noteworthy that happened during a tick
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgriEvent {
+    /// Moisture fell below the threshold and watering is starting
+    WateringStarted { zone_id: u8, moisture_level: f32, state: DeviceState },
+    /// A watering cycle finished without lifting moisture above the threshold
+    MoistureStillLow { zone_id: u8, moisture_level: f32, state: DeviceState },
+    /// A zone's sensor entered the `Error` state
+    SensorError { zone_id: u8, moisture_level: f32, state: DeviceState },
+    /// Irrigation water chilled the soil below the crop's germination threshold
+    ColdWaterStress { zone_id: u8, moisture_level: f32, state: DeviceState, soil_temp_c: f32 },
+    /// A backflow preventer failed its test; the zone cannot be irrigated until acknowledged
+    BackflowFault { zone_id: u8, moisture_level: f32, state: DeviceState, bfp_type: BfpType },
+    /// Closing the zone's valve sent a pressure surge above the pipe's allowable limit
+    WaterHammerAlert { zone_id: u8, moisture_level: f32, state: DeviceState, surge_kpa: f32 },
+    /// Frost protection started sprinkling because the outside air fell to `air_temp_c`
+    FrostAlert { zone_id: u8, moisture_level: f32, state: DeviceState, air_temp_c: f32 },
+    /// Weather has favoured a pest or disease long enough to risk an outbreak
+    DiseaseRiskAlert {
+        zone_id: u8,
+        moisture_level: f32,
+        state: DeviceState,
+        disease_name: String,
+        risk_level: RiskLevel,
+    },
+    /// Pressure is falling fast enough to expect rain; irrigation is cut back meanwhile
+    RainForecastWarning { zone_id: u8, moisture_level: f32, state: DeviceState, trend_hpa_per_h: f32 },
+    /// Nitrate leached from the zone has passed the environmental threshold
+    EnvironmentalRiskAlert { zone_id: u8, moisture_level: f32, state: DeviceState, n_leached_kg_ha: f32 },
+    /// A component has run most of its service interval, or all of it
+    MaintenanceWarning {
+        zone_id: u8,
+        moisture_level: f32,
+        state: DeviceState,
+        component_id: String,
+        interval_used_pct: f32,
+    },
+    /// The seedbed has been warm long enough for the seed to germinate
+    GerminationComplete { zone_id: u8, moisture_level: f32, state: DeviceState, heat_units: f32 },
+    /// A micronutrient has fallen below the crop's critical soil level
+    MicronutrientDeficiency { zone_id: u8, moisture_level: f32, state: DeviceState, element: TraceElement },
+    /// An operator acknowledged the alert with `alert_id`, titled `alert_title`
+    AlertAcknowledged { zone_id: u8, moisture_level: f32, state: DeviceState, alert_id: Uuid, alert_title: String },
+    /// Counted water passed the season's budget; irrigation is refused until the next season
+    BudgetExceeded { zone_id: u8, moisture_level: f32, state: DeviceState, used_mm: f32, limit_mm: f32 },
+    /// An operator accepted the optimizer's suggestion to water at `start_tick`
+    IrrigationScheduled { zone_id: u8, moisture_level: f32, state: DeviceState, start_tick: u64 },
+}
+
+impl AgriEvent {
+    /// The event, if any, signalled by a zone moving from `from` to `to`
+    pub fn from_transition(zone_id: u8, from: DeviceState, to: DeviceState, moisture_level: f32) -> Option<Self> {
+        match (from, to) {
+            (_, DeviceState::Error) if from != to => {
+                Some(AgriEvent::SensorError { zone_id, moisture_level, state: to })
+            }
+            (DeviceState::Monitoring, DeviceState::Activating) => {
+                Some(AgriEvent::WateringStarted { zone_id, moisture_level, state: to })
+            }
+            (DeviceState::Adjusting, DeviceState::Monitoring) => {
+                Some(AgriEvent::MoistureStillLow { zone_id, moisture_level, state: to })
+            }
+            _ => None,
+        }
+    }
+
+    /// Short human-readable name of the event type
+    pub fn title(&self) -> &'static str {
+        match self {
+            AgriEvent::WateringStarted { .. } => "Watering started",
+            AgriEvent::MoistureStillLow { .. } => "Moisture still low",
+            AgriEvent::SensorError { .. } => "Sensor error",
+            AgriEvent::ColdWaterStress { .. } => "Cold water stress",
+            AgriEvent::BackflowFault { .. } => "Backflow preventer fault",
+            AgriEvent::WaterHammerAlert { .. } => "Water hammer",
+            AgriEvent::FrostAlert { .. } => "Frost alert",
+            AgriEvent::DiseaseRiskAlert { .. } => "Disease risk",
+            AgriEvent::RainForecastWarning { .. } => "Rain forecast",
+            AgriEvent::EnvironmentalRiskAlert { .. } => "Nitrate leaching risk",
+            AgriEvent::MaintenanceWarning { .. } => "Maintenance due",
+            AgriEvent::GerminationComplete { .. } => "Germination complete",
+            AgriEvent::MicronutrientDeficiency { .. } => "Micronutrient deficiency",
+            AgriEvent::AlertAcknowledged { .. } => "Alert acknowledged",
+            AgriEvent::BudgetExceeded { .. } => "Water budget exceeded",
+            AgriEvent::IrrigationScheduled { .. } => "Irrigation scheduled",
+        }
+    }
+
+    /// How urgently the event needs attention
+    pub fn severity(&self) -> AlertSeverity {
+        match self {
+            AgriEvent::WateringStarted { .. } => AlertSeverity::Info,
+            AgriEvent::MoistureStillLow { .. } => AlertSeverity::Warning,
+            AgriEvent::SensorError { .. } => AlertSeverity::Critical,
+            AgriEvent::ColdWaterStress { .. } => AlertSeverity::Warning,
+            AgriEvent::BackflowFault { .. } => AlertSeverity::Critical,
+            AgriEvent::WaterHammerAlert { .. } => AlertSeverity::Warning,
+            AgriEvent::FrostAlert { .. } => AlertSeverity::Warning,
+            AgriEvent::DiseaseRiskAlert { .. } => AlertSeverity::Warning,
+            AgriEvent::RainForecastWarning { .. } => AlertSeverity::Info,
+            AgriEvent::EnvironmentalRiskAlert { .. } => AlertSeverity::Warning,
+            AgriEvent::MaintenanceWarning { .. } => AlertSeverity::Warning,
+            AgriEvent::GerminationComplete { .. } => AlertSeverity::Info,
+            AgriEvent::MicronutrientDeficiency { .. } => AlertSeverity::Warning,
+            AgriEvent::AlertAcknowledged { .. } => AlertSeverity::Info,
+            AgriEvent::BudgetExceeded { .. } => AlertSeverity::Warning,
+            AgriEvent::IrrigationScheduled { .. } => AlertSeverity::Info,
+        }
+    }
+
+    /// Zone the event happened in
+    pub fn zone_id(&self) -> u8 {
+        match *self {
+            AgriEvent::WateringStarted { zone_id, .. }
+            | AgriEvent::MoistureStillLow { zone_id, .. }
+            | AgriEvent::SensorError { zone_id, .. }
+            | AgriEvent::ColdWaterStress { zone_id, .. }
+            | AgriEvent::BackflowFault { zone_id, .. }
+            | AgriEvent::WaterHammerAlert { zone_id, .. }
+            | AgriEvent::FrostAlert { zone_id, .. }
+            | AgriEvent::DiseaseRiskAlert { zone_id, .. }
+            | AgriEvent::RainForecastWarning { zone_id, .. }
+            | AgriEvent::EnvironmentalRiskAlert { zone_id, .. }
+            | AgriEvent::MaintenanceWarning { zone_id, .. }
+            | AgriEvent::GerminationComplete { zone_id, .. }
+            | AgriEvent::MicronutrientDeficiency { zone_id, .. }
+            | AgriEvent::AlertAcknowledged { zone_id, .. }
+            | AgriEvent::BudgetExceeded { zone_id, .. }
+            | AgriEvent::IrrigationScheduled { zone_id, .. } => zone_id,
+        }
+    }
+
+    /// Zone moisture when the event fired
+    pub fn moisture_level(&self) -> f32 {
+        match *self {
+            AgriEvent::WateringStarted { moisture_level, .. }
+            | AgriEvent::MoistureStillLow { moisture_level, .. }
+            | AgriEvent::SensorError { moisture_level, .. }
+            | AgriEvent::ColdWaterStress { moisture_level, .. }
+            | AgriEvent::BackflowFault { moisture_level, .. }
+            | AgriEvent::WaterHammerAlert { moisture_level, .. }
+            | AgriEvent::FrostAlert { moisture_level, .. }
+            | AgriEvent::DiseaseRiskAlert { moisture_level, .. }
+            | AgriEvent::RainForecastWarning { moisture_level, .. }
+            | AgriEvent::EnvironmentalRiskAlert { moisture_level, .. }
+            | AgriEvent::MaintenanceWarning { moisture_level, .. }
+            | AgriEvent::GerminationComplete { moisture_level, .. }
+            | AgriEvent::MicronutrientDeficiency { moisture_level, .. }
+            | AgriEvent::AlertAcknowledged { moisture_level, .. }
+            | AgriEvent::BudgetExceeded { moisture_level, .. }
+            | AgriEvent::IrrigationScheduled { moisture_level, .. } => moisture_level,
+        }
+    }
+
+    /// State the zone was in when the event fired, the state it entered for a transition
+    pub fn state(&self) -> DeviceState {
+        match *self {
+            AgriEvent::WateringStarted { state, .. }
+            | AgriEvent::MoistureStillLow { state, .. }
+            | AgriEvent::SensorError { state, .. }
+            | AgriEvent::ColdWaterStress { state, .. }
+            | AgriEvent::BackflowFault { state, .. }
+            | AgriEvent::WaterHammerAlert { state, .. }
+            | AgriEvent::FrostAlert { state, .. }
+            | AgriEvent::DiseaseRiskAlert { state, .. }
+            | AgriEvent::RainForecastWarning { state, .. }
+            | AgriEvent::EnvironmentalRiskAlert { state, .. }
+            | AgriEvent::MaintenanceWarning { state, .. }
+            | AgriEvent::GerminationComplete { state, .. }
+            | AgriEvent::MicronutrientDeficiency { state, .. }
+            | AgriEvent::AlertAcknowledged { state, .. }
+            | AgriEvent::BudgetExceeded { state, .. }
+            | AgriEvent::IrrigationScheduled { state, .. } => state,
+        }
+    }
+
+    /// Why the event fired, from its event-specific detail
+    pub fn reason(&self) -> String {
+        match self {
+            AgriEvent::WateringStarted { .. } => "Moisture fell below the threshold".to_string(),
+            AgriEvent::MoistureStillLow { .. } => "Watering did not lift moisture above the threshold".to_string(),
+            AgriEvent::SensorError { .. } => "Sensor stopped giving valid readings".to_string(),
+            AgriEvent::ColdWaterStress { soil_temp_c, .. } => {
+                format!("Irrigation chilled the soil to {soil_temp_c:.1} °C")
+            }
+            AgriEvent::BackflowFault { bfp_type, .. } => format!("{bfp_type:?} backflow preventer failed its test"),
+            AgriEvent::WaterHammerAlert { surge_kpa, .. } => format!("{surge_kpa:.0} kPa surge on valve closure"),
+            AgriEvent::FrostAlert { air_temp_c, .. } => format!("Outside air at {air_temp_c:.1} °C"),
+            AgriEvent::DiseaseRiskAlert { disease_name, risk_level, .. } => {
+                format!("{disease_name} risk is {risk_level:?}")
+            }
+            AgriEvent::RainForecastWarning { trend_hpa_per_h, .. } => {
+                format!("Pressure changing {trend_hpa_per_h:.1} hPa/h")
+            }
+            AgriEvent::EnvironmentalRiskAlert { n_leached_kg_ha, .. } => {
+                format!("{n_leached_kg_ha:.1} kg/ha nitrate leached")
+            }
+            AgriEvent::MaintenanceWarning { component_id, interval_used_pct, .. } => {
+                format!("{component_id} at {interval_used_pct:.0}% of its service interval")
+            }
+            AgriEvent::GerminationComplete { heat_units, .. } => format!("{heat_units:.0} heat units accumulated"),
+            AgriEvent::MicronutrientDeficiency { element, .. } => format!("{element:?} below the critical level"),
+            AgriEvent::AlertAcknowledged { alert_id, alert_title, .. } => {
+                format!("Alert {alert_id} ({alert_title}) acknowledged")
+            }
+            AgriEvent::BudgetExceeded { used_mm, limit_mm, .. } => format!("{used_mm:.0} of {limit_mm:.0} mm used"),
+            AgriEvent::IrrigationScheduled { start_tick, .. } => format!("Watering accepted for tick {start_tick}"),
+        }
+    }
+}
+
+/// An event together with the tick it happened on
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedEvent {
+    pub tick: Tick,
+    pub event: AgriEvent,
+}
+
+/// Events of a run in the order they happened, addressable by tick for replay
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventLog {
+    entries: Vec<LoggedEvent>,
+}
+
+impl EventLog {
+    /// Appends an event that happened on `tick`
+    pub fn record(&mut self, tick: Tick, event: AgriEvent) {
+        self.entries.push(LoggedEvent { tick, event });
+    }
+
+    /// Every logged event, oldest first
+    pub fn entries(&self) -> &[LoggedEvent] {
+        &self.entries
+    }
+
+    /// Events that happened on exactly `tick`
+    pub fn at(&self, tick: Tick) -> impl Iterator<Item = &AgriEvent> {
+        self.entries.iter().filter(move |entry| entry.tick == tick).map(|entry| &entry.event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SoilMoistureSensor;
+
+    #[test]
+    fn event_log_is_addressable_by_tick() {
+        let mut sensor = SoilMoistureSensor::new(30.0, 31.0);
+        let mut log = EventLog::default();
+        for moisture in [30.5, 29.0, 29.0] {
+            let before = sensor.state;
+            sensor.step(moisture);
+            if let Some(event) = AgriEvent::from_transition(0, before, sensor.state, sensor.moisture_level) {
+                log.record(sensor.current_tick, event);
+            }
+        }
+        // The sensor's readings carry the same tick the log is keyed by
+        assert_eq!(sensor.reading().tick, Tick(3));
+        let watering: Vec<&AgriEvent> = log.at(Tick(2)).collect();
+        assert!(matches!(watering[..], [AgriEvent::WateringStarted { zone_id: 0, .. }]), "{watering:?}");
+        assert_eq!(log.at(Tick(1)).count(), 0);
+        assert!(log.entries().windows(2).all(|pair| pair[0].tick <= pair[1].tick));
+    }
+}
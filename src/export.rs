@@ -0,0 +1,484 @@
+//! Sensor readings written out to files in several formats at once
+//!
+//! Every `[[export]]` table registers one exporter with the [`DataExportManager`], which hands
+//! each reading to all of them in turn.
+
+use rusqlite::{params, Connection};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::agri_iot_core::delta::{self, DeltaEncoder};
+use crate::SensorReading;
+
+/// Header line the CSV export starts with
+pub const CSV_HEADER: &str = "tick,sensor_id,state,moisture_level";
+
+/// Moisture step the delta export keeps, in percentage points; as fine as the CSV export's
+pub const DELTA_PRECISION: f32 = 0.01;
+
+/// Why a reading could not be exported
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    Sqlite(rusqlite::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(err) => write!(f, "cannot write export file: {}", err),
+            ExportError::Sqlite(err) => write!(f, "cannot write export database: {}", err),
+            ExportError::Json(err) => write!(f, "cannot encode reading as JSON: {}", err),
+        }
+    }
+}
+
+impl Error for ExportError {}
+
+impl From<io::Error> for ExportError {
+    fn from(err: io::Error) -> Self {
+        ExportError::Io(err)
+    }
+}
+
+impl From<rusqlite::Error> for ExportError {
+    fn from(err: rusqlite::Error) -> Self {
+        ExportError::Sqlite(err)
+    }
+}
+
+impl From<serde_json::Error> for ExportError {
+    fn from(err: serde_json::Error) -> Self {
+        ExportError::Json(err)
+    }
+}
+
+/// A destination for sensor readings
+pub trait DataExporter {
+    /// Exports one reading; it may be buffered until [`flush`](Self::flush)
+    fn write(&mut self, reading: &SensorReading) -> Result<(), ExportError>;
+
+    /// Writes out every buffered reading
+    fn flush(&mut self) -> Result<(), ExportError>;
+}
+
+/// Format of an `[[export]]` table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    /// One JSON object per line
+    Json,
+    Sqlite,
+    /// Delta-encoded moisture readings, a block per sensor; see [`DeltaExporter`]
+    Delta,
+    /// Discards every reading
+    Null,
+}
+
+/// One line per reading under [`CSV_HEADER`]
+pub struct CsvExporter<W: Write> {
+    out: W,
+}
+
+impl CsvExporter<BufWriter<File>> {
+    /// Creates or truncates the file at `path` and writes the header
+    pub fn create(path: &Path) -> Result<Self, ExportError> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> CsvExporter<W> {
+    pub fn new(mut out: W) -> Result<Self, ExportError> {
+        writeln!(out, "{CSV_HEADER}")?;
+        Ok(Self { out })
+    }
+}
+
+impl<W: Write> DataExporter for CsvExporter<W> {
+    fn write(&mut self, reading: &SensorReading) -> Result<(), ExportError> {
+        let state = reading.state.name();
+        writeln!(self.out, "{},{},{state},{:.2}", reading.tick.0, reading.sensor_id, reading.moisture_level)?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ExportError> {
+        Ok(self.out.flush()?)
+    }
+}
+
+/// One JSON object per reading, a line each
+pub struct JsonExporter<W: Write> {
+    out: W,
+}
+
+impl JsonExporter<BufWriter<File>> {
+    /// Creates or truncates the file at `path`
+    pub fn create(path: &Path) -> Result<Self, ExportError> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> JsonExporter<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+}
+
+impl<W: Write> DataExporter for JsonExporter<W> {
+    fn write(&mut self, reading: &SensorReading) -> Result<(), ExportError> {
+        let object = serde_json::json!({
+            "tick": reading.tick.0,
+            "sensor_id": reading.sensor_id.to_string(),
+            "state": reading.state.name(),
+            "moisture_level": reading.moisture_level,
+        });
+        serde_json::to_writer(&mut self.out, &object)?;
+        writeln!(self.out)?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ExportError> {
+        Ok(self.out.flush()?)
+    }
+}
+
+/// Rows of a `readings` table, appended to any the database already holds
+pub struct SqliteExporter {
+    connection: Connection,
+}
+
+impl SqliteExporter {
+    /// Opens the database at `path`, creating it and its table if needed
+    pub fn open(path: &Path) -> Result<Self, ExportError> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS readings (
+                tick INTEGER NOT NULL,
+                sensor_id TEXT NOT NULL,
+                state TEXT NOT NULL,
+                moisture_level REAL NOT NULL
+            )",
+        )?;
+        Ok(Self { connection })
+    }
+}
+
+impl DataExporter for SqliteExporter {
+    fn write(&mut self, reading: &SensorReading) -> Result<(), ExportError> {
+        self.connection.execute(
+            "INSERT INTO readings (tick, sensor_id, state, moisture_level) VALUES (?1, ?2, ?3, ?4)",
+            params![reading.tick.0 as i64, reading.sensor_id.to_string(), reading.state.name(), reading.moisture_level],
+        )?;
+        Ok(())
+    }
+
+    /// Every insert is committed as it is made
+    fn flush(&mut self) -> Result<(), ExportError> {
+        Ok(())
+    }
+}
+
+/// One sensor's readings on consecutive ticks, as stored by [`DeltaExporter`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeltaBlock {
+    pub sensor_id: String,
+    pub first_tick: u64,
+    pub moisture_levels: Vec<f32>, // One per tick from `first_tick`
+}
+
+/// Moisture readings delta-encoded with [`DeltaEncoder`], a fraction of the size of the other
+/// formats; zone states are not kept
+///
+/// Each flush writes one block per sensor with readings since the last, and a gap in a sensor's
+/// ticks starts a new block. A block is the sensor id's length as a byte, the id, the first tick
+/// as a little-endian `u64`, the encoded length as a little-endian `u32` and the encoded bytes.
+pub struct DeltaExporter<W: Write> {
+    out: W,
+    open_blocks: BTreeMap<String, OpenBlock>, // By sensor id
+    closed: Vec<u8>, // Blocks ended by a gap, written at the next flush
+}
+
+/// A sensor's readings since the last flush or gap
+struct OpenBlock {
+    first_tick: u64,
+    next_tick: u64,
+    encoder: DeltaEncoder,
+}
+
+impl DeltaExporter<BufWriter<File>> {
+    /// Creates or truncates the file at `path`
+    pub fn create(path: &Path) -> Result<Self, ExportError> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> DeltaExporter<W> {
+    pub fn new(out: W) -> Self {
+        Self { out, open_blocks: BTreeMap::new(), closed: Vec::new() }
+    }
+
+    /// Sensor ids are at most 32 bytes, so the length fits the block's one-byte field
+    fn write_block(out: &mut Vec<u8>, sensor_id: &str, block: OpenBlock) {
+        let bytes = block.encoder.finish();
+        out.push(sensor_id.len() as u8);
+        out.extend_from_slice(sensor_id.as_bytes());
+        out.extend_from_slice(&block.first_tick.to_le_bytes());
+        out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(&bytes);
+    }
+}
+
+impl<W: Write> DataExporter for DeltaExporter<W> {
+    fn write(&mut self, reading: &SensorReading) -> Result<(), ExportError> {
+        let sensor_id = reading.sensor_id.to_string();
+        let tick = reading.tick.0;
+        if self.open_blocks.get(&sensor_id).is_some_and(|block| block.next_tick != tick) {
+            if let Some(block) = self.open_blocks.remove(&sensor_id) {
+                Self::write_block(&mut self.closed, &sensor_id, block);
+            }
+        }
+        let block = self.open_blocks.entry(sensor_id).or_insert_with(|| OpenBlock {
+            first_tick: tick,
+            next_tick: tick,
+            encoder: DeltaEncoder::new(DELTA_PRECISION),
+        });
+        block.encoder.push(reading.moisture_level);
+        block.next_tick = tick + 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ExportError> {
+        let mut blocks = std::mem::take(&mut self.closed);
+        for (sensor_id, block) in std::mem::take(&mut self.open_blocks) {
+            Self::write_block(&mut blocks, &sensor_id, block);
+        }
+        self.out.write_all(&blocks)?;
+        Ok(self.out.flush()?)
+    }
+}
+
+/// Reads back every block a [`DeltaExporter`] wrote
+pub fn read_delta_export(mut input: impl Read) -> Result<Vec<DeltaBlock>, Box<dyn Error>> {
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+    let mut blocks = Vec::new();
+    let mut rest = bytes.as_slice();
+    let truncated = || io::Error::new(io::ErrorKind::UnexpectedEof, "truncated delta export block");
+    while let Some((&id_len, after_len)) = rest.split_first() {
+        let (sensor_id, after_id) = after_len.split_at_checked(usize::from(id_len)).ok_or_else(truncated)?;
+        let (first_tick, after_tick) = after_id.split_first_chunk::<8>().ok_or_else(truncated)?;
+        let (encoded_len, after_header) = after_tick.split_first_chunk::<4>().ok_or_else(truncated)?;
+        let encoded_len = u32::from_le_bytes(*encoded_len) as usize;
+        let (encoded, after_block) = after_header.split_at_checked(encoded_len).ok_or_else(truncated)?;
+        blocks.push(DeltaBlock {
+            sensor_id: String::from_utf8(sensor_id.to_vec())?,
+            first_tick: u64::from_le_bytes(*first_tick),
+            moisture_levels: delta::decode(encoded, DELTA_PRECISION)?,
+        });
+        rest = after_block;
+    }
+    Ok(blocks)
+}
+
+/// Counts the readings it is given and keeps none
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NullExporter {
+    pub readings_written: u64,
+    pub flushes: u64,
+}
+
+impl DataExporter for NullExporter {
+    fn write(&mut self, _reading: &SensorReading) -> Result<(), ExportError> {
+        self.readings_written += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ExportError> {
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+/// Opens an exporter of `format` writing to `path`
+pub fn open(format: ExportFormat, path: &Path) -> Result<Box<dyn DataExporter>, ExportError> {
+    Ok(match format {
+        ExportFormat::Csv => Box::new(CsvExporter::create(path)?),
+        ExportFormat::Json => Box::new(JsonExporter::create(path)?),
+        ExportFormat::Sqlite => Box::new(SqliteExporter::open(path)?),
+        ExportFormat::Delta => Box::new(DeltaExporter::create(path)?),
+        ExportFormat::Null => Box::new(NullExporter::default()),
+    })
+}
+
+/// Every registered exporter, each given every reading
+#[derive(Default)]
+pub struct DataExportManager {
+    pub exporters: Vec<Box<dyn DataExporter>>,
+}
+
+impl DataExportManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, exporter: Box<dyn DataExporter>) {
+        self.exporters.push(exporter);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exporters.is_empty()
+    }
+
+    /// Hands `reading` to every exporter; one failing does not keep it from the rest, and the
+    /// first error is returned
+    pub fn write(&mut self, reading: &SensorReading) -> Result<(), ExportError> {
+        self.exporters.iter_mut().map(|exporter| exporter.write(reading)).fold(Ok(()), Result::and)
+    }
+
+    /// Flushes every exporter, returning the first error
+    pub fn flush(&mut self) -> Result<(), ExportError> {
+        self.exporters.iter_mut().map(|exporter| exporter.flush()).fold(Ok(()), Result::and)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::{DeviceState, SensorId, Tick};
+
+    /// Keeps every reading it is given where the test can still see it once registered
+    #[derive(Default)]
+    struct RecordingExporter {
+        readings: Rc<RefCell<Vec<SensorReading>>>,
+        flushes: Rc<RefCell<u32>>,
+    }
+
+    impl DataExporter for RecordingExporter {
+        fn write(&mut self, reading: &SensorReading) -> Result<(), ExportError> {
+            self.readings.borrow_mut().push(reading.clone());
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), ExportError> {
+            *self.flushes.borrow_mut() += 1;
+            Ok(())
+        }
+    }
+
+    /// Refuses every reading
+    struct FailingExporter;
+
+    impl DataExporter for FailingExporter {
+        fn write(&mut self, _reading: &SensorReading) -> Result<(), ExportError> {
+            Err(io::Error::other("disk full").into())
+        }
+
+        fn flush(&mut self) -> Result<(), ExportError> {
+            Err(io::Error::other("disk full").into())
+        }
+    }
+
+    fn reading(zone_id: u8, tick: u64, moisture_level: f32) -> SensorReading {
+        let sensor_id = SensorId::for_zone(zone_id);
+        SensorReading { sensor_id, tick: Tick(tick), state: DeviceState::Monitoring, moisture_level }
+    }
+
+    fn export(readings: &[SensorReading]) -> Vec<DeltaBlock> {
+        let mut out = Vec::new();
+        let mut exporter = DeltaExporter::new(&mut out);
+        for reading in readings {
+            exporter.write(reading).unwrap();
+        }
+        exporter.flush().unwrap();
+        drop(exporter);
+        read_delta_export(out.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn every_registered_exporter_receives_every_reading() {
+        let recorders: Vec<RecordingExporter> = (0..3).map(|_| RecordingExporter::default()).collect();
+        let seen: Vec<_> = recorders.iter().map(|recorder| recorder.readings.clone()).collect();
+        let flushes: Vec<_> = recorders.iter().map(|recorder| recorder.flushes.clone()).collect();
+        let mut manager = DataExportManager::new();
+        assert!(manager.is_empty());
+        manager.register(open(ExportFormat::Null, Path::new("unused")).unwrap());
+        for recorder in recorders {
+            manager.register(Box::new(recorder));
+        }
+        let readings: Vec<SensorReading> =
+            (0..20).flat_map(|tick| [reading(0, tick, 40.0), reading(1, tick, 55.5)]).collect();
+        for reading in &readings {
+            manager.write(reading).unwrap();
+        }
+        manager.flush().unwrap();
+        assert_eq!(manager.exporters.len(), 4);
+        assert!(seen.iter().all(|seen| *seen.borrow() == readings));
+        assert!(flushes.iter().all(|flushes| *flushes.borrow() == 1));
+    }
+
+    #[test]
+    fn a_failing_exporter_does_not_starve_the_rest() {
+        let recorder = RecordingExporter::default();
+        let (seen, flushes) = (recorder.readings.clone(), recorder.flushes.clone());
+        let mut manager = DataExportManager::new();
+        manager.register(Box::new(FailingExporter));
+        manager.register(Box::new(recorder));
+        let err = manager.write(&reading(0, 0, 30.0)).expect_err("the first exporter fails");
+        assert_eq!(err.to_string(), "cannot write export file: disk full");
+        assert!(manager.flush().is_err());
+        assert_eq!((seen.borrow().len(), *flushes.borrow()), (1, 1));
+    }
+
+    #[test]
+    fn null_exporter_counts_readings_and_flushes() {
+        let mut exporter = NullExporter::default();
+        for tick in 0..5 {
+            exporter.write(&reading(0, tick, 30.0)).unwrap();
+        }
+        exporter.flush().unwrap();
+        assert_eq!(exporter, NullExporter { readings_written: 5, flushes: 1 });
+    }
+
+    #[test]
+    fn delta_export_keeps_a_block_per_sensor() {
+        let readings: Vec<SensorReading> =
+            (0..50).flat_map(|tick| [reading(0, tick, 40.0 - tick as f32 * 0.1), reading(1, tick, 60.0)]).collect();
+        let blocks = export(&readings);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!((blocks[0].sensor_id.as_str(), blocks[0].first_tick), ("zone-0", 0));
+        assert_eq!(blocks[1].moisture_levels, vec![60.0; 50]);
+        let expected = readings.iter().filter(|reading| reading.sensor_id == SensorId::for_zone(0));
+        for (level, reading) in blocks[0].moisture_levels.iter().zip(expected) {
+            assert!((level - reading.moisture_level).abs() <= DELTA_PRECISION);
+        }
+    }
+
+    #[test]
+    fn a_gap_in_ticks_starts_a_new_block() {
+        let blocks = export(&[reading(0, 5, 30.0), reading(0, 6, 29.5), reading(0, 20, 45.0)]);
+        let starts: Vec<(u64, usize)> =
+            blocks.iter().map(|block| (block.first_tick, block.moisture_levels.len())).collect();
+        assert_eq!(starts, vec![(5, 2), (20, 1)]);
+    }
+
+    #[test]
+    fn truncated_delta_export_is_an_error() {
+        let mut out = Vec::new();
+        let mut exporter = DeltaExporter::new(&mut out);
+        exporter.write(&reading(0, 0, 30.0)).unwrap();
+        exporter.flush().unwrap();
+        drop(exporter);
+        assert!(read_delta_export(&out[..out.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! Greenhouse gas footprint of irrigating and fertilizing the field, for lifecycle assessment
+
+/// Cubic metres of water in 1 mm over one hectare
+pub const M3_PER_MM_HA: f32 = 10.0;
+
+/// What the emissions came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmissionSource {
+    Electricity, // Running the pump
+    Transport,   // Supplying and conveying the water pumped
+    Fertilizer,  // Making the nitrogen applied and the N2O it gives off
+}
+
+impl EmissionSource {
+    pub const ALL: [EmissionSource; 3] =
+        [EmissionSource::Electricity, EmissionSource::Transport, EmissionSource::Fertilizer];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EmissionSource::Electricity => "electricity",
+            EmissionSource::Transport => "water transport",
+            EmissionSource::Fertilizer => "fertilizer",
+        }
+    }
+}
+
+/// Emissions by source, in kg CO2e/ha
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EmissionsBreakdown {
+    pub electricity_kg_co2: f32,
+    pub transport_kg_co2: f32,
+    pub fertilizer_kg_co2: f32,
+}
+
+impl EmissionsBreakdown {
+    pub fn kg_co2(&self, source: EmissionSource) -> f32 {
+        match source {
+            EmissionSource::Electricity => self.electricity_kg_co2,
+            EmissionSource::Transport => self.transport_kg_co2,
+            EmissionSource::Fertilizer => self.fertilizer_kg_co2,
+        }
+    }
+
+    pub fn total_kg_co2e(&self) -> f32 {
+        self.electricity_kg_co2 + self.transport_kg_co2 + self.fertilizer_kg_co2
+    }
+
+    /// Fraction of the total that came from `source`, 0.0 before anything is emitted
+    pub fn share(&self, source: EmissionSource) -> f32 {
+        let total = self.total_kg_co2e();
+        if total > 0.0 {
+            self.kg_co2(source) / total
+        } else {
+            0.0
+        }
+    }
+
+    /// One line for the status bar, compared against `target_kg_co2e_ha` when there is one
+    pub fn summary(&self, target_kg_co2e_ha: Option<f32>) -> String {
+        let mut summary = format!(
+            "{:.1} kg CO2e/ha ({:.1} electricity, {:.1} water transport, {:.1} fertilizer)",
+            self.total_kg_co2e(),
+            self.electricity_kg_co2,
+            self.transport_kg_co2,
+            self.fertilizer_kg_co2
+        );
+        if let Some(target) = target_kg_co2e_ha {
+            let verdict = if self.total_kg_co2e() <= target { "within" } else { "over" };
+            summary += &format!(", {verdict} the {target:.0} target");
+        }
+        summary
+    }
+}
+
+/// Emission factors and this season's emissions so far
+#[derive(Debug, Clone, PartialEq)]
+pub struct GhgTracker {
+    pub electricity_emission_factor_kg_co2_kwh: f32, // Of the grid the pump draws from
+    pub water_transport_factor: f32,                 // kg CO2e per m³ of water pumped
+    pub fertilizer_factor_kg_co2_per_kg_n: f32,      // Manufacture plus field N2O, per kg N
+    pub field_area_ha: f32,                          // Served by the pump
+    pub target_kg_co2e_ha: Option<f32>,              // Per season
+    pub season: EmissionsBreakdown,                  // Since planting; reset at harvest
+}
+
+impl GhgTracker {
+    pub fn new(
+        electricity_emission_factor_kg_co2_kwh: f32,
+        water_transport_factor: f32,
+        fertilizer_factor_kg_co2_per_kg_n: f32,
+        field_area_ha: f32,
+        target_kg_co2e_ha: Option<f32>,
+    ) -> Self {
+        Self {
+            electricity_emission_factor_kg_co2_kwh,
+            water_transport_factor,
+            fertilizer_factor_kg_co2_per_kg_n,
+            field_area_ha,
+            target_kg_co2e_ha,
+            season: EmissionsBreakdown::default(),
+        }
+    }
+
+    /// Adds the emissions of `energy_kwh` drawn by the pump and returns them in kg CO2e/ha
+    pub fn record_electricity(&mut self, energy_kwh: f32) -> f32 {
+        let kg_co2 = energy_kwh * self.electricity_emission_factor_kg_co2_kwh / self.field_area_ha;
+        self.season.electricity_kg_co2 += kg_co2;
+        kg_co2
+    }
+
+    /// Adds the emissions of pumping `water_mm` over the field and returns them in kg CO2e/ha
+    pub fn record_water_pumped(&mut self, water_mm: f32) -> f32 {
+        let kg_co2 = water_mm * M3_PER_MM_HA * self.water_transport_factor;
+        self.season.transport_kg_co2 += kg_co2;
+        kg_co2
+    }
+
+    /// Adds the emissions of `n_kg_ha` of nitrogen fertilizer and returns them in kg CO2e/ha
+    pub fn record_fertilizer(&mut self, n_kg_ha: f32) -> f32 {
+        let kg_co2 = n_kg_ha * self.fertilizer_factor_kg_co2_per_kg_n;
+        self.season.fertilizer_kg_co2 += kg_co2;
+        kg_co2
+    }
+
+    /// Whether this season's emissions have passed the target
+    pub fn over_target(&self) -> bool {
+        self.target_kg_co2e_ha.is_some_and(|target| self.season.total_kg_co2e() > target)
+    }
+
+    /// Closes the season at harvest, starting the next from zero, and returns its emissions
+    pub fn close_season(&mut self) -> EmissionsBreakdown {
+        std::mem::take(&mut self.season)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    // 0.4 kg CO2/kWh grid, 0.05 kg CO2e/m³ of water, 5 kg CO2e/kg N, a 2 ha field and a 500 kg target
+    fn tracker() -> GhgTracker {
+        GhgTracker::new(0.4, 0.05, 5.0, 2.0, Some(500.0))
+    }
+
+    #[test]
+    fn electricity_is_spread_over_the_field() {
+        let mut ghg = tracker();
+        // 100 kWh at 0.4 kg/kWh over 2 ha
+        assert_close(ghg.record_electricity(100.0), 20.0, 1e-3);
+        assert_close(ghg.record_electricity(50.0), 10.0, 1e-3);
+        assert_close(ghg.season.electricity_kg_co2, 30.0, 1e-3);
+    }
+
+    #[test]
+    fn transport_follows_the_water_pumped() {
+        let mut ghg = tracker();
+        // 50 mm is 500 m³/ha
+        assert_close(ghg.record_water_pumped(50.0), 25.0, 1e-3);
+        assert_close(ghg.season.transport_kg_co2, 25.0, 1e-3);
+    }
+
+    #[test]
+    fn fertilizer_follows_the_nitrogen_applied() {
+        let mut ghg = tracker();
+        assert_close(ghg.record_fertilizer(60.0), 300.0, 1e-3);
+        assert_close(ghg.season.fertilizer_kg_co2, 300.0, 1e-3);
+    }
+
+    #[test]
+    fn season_total_is_compared_with_the_target() {
+        let mut ghg = tracker();
+        ghg.record_electricity(100.0);
+        ghg.record_water_pumped(50.0);
+        ghg.record_fertilizer(60.0);
+        assert_close(ghg.season.total_kg_co2e(), 345.0, 1e-3);
+        assert_close(ghg.season.share(EmissionSource::Fertilizer), 300.0 / 345.0, 1e-3);
+        assert!(!ghg.over_target());
+        assert!(ghg.season.summary(ghg.target_kg_co2e_ha).ends_with("within the 500 target"));
+        ghg.record_fertilizer(40.0);
+        assert!(ghg.over_target());
+        let season = ghg.close_season();
+        assert_eq!(
+            season.summary(Some(500.0)),
+            "545.0 kg CO2e/ha (20.0 electricity, 25.0 water transport, 500.0 fertilizer), over the 500 target"
+        );
+        assert_eq!(ghg.season, EmissionsBreakdown::default());
+        assert_eq!(ghg.season.share(EmissionSource::Electricity), 0.0);
+    }
+}
@@ -0,0 +1,340 @@
+//! Recirculating nutrient solution monitoring for hydroponic growing
+
+/// Possible states of the nutrient solution system
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HydroponicState {
+    Recirculating, // Pumping solution past the roots
+    Draining,      // Dumping depleted solution
+    Refilling,     // Mixing fresh solution into the reservoir
+    Flushing,      // Rinsing the system with plain water
+    Error,         // System error
+}
+
+/// Ion concentrations of the solution, in ppm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NpkSensor {
+    pub nitrate_ppm: f32,
+    pub phosphate_ppm: f32,
+    pub potassium_ppm: f32,
+}
+
+impl NpkSensor {
+    /// Concentrations of a freshly mixed Hoagland-type solution
+    pub const FRESH: NpkSensor = NpkSensor {
+        nitrate_ppm: 210.0,
+        phosphate_ppm: 50.0,
+        potassium_ppm: 235.0,
+    };
+
+    /// Ions removed from the solution by the crop each tick
+    pub const UPTAKE_PER_TICK: NpkSensor = NpkSensor {
+        nitrate_ppm: 4.0,
+        phosphate_ppm: 0.8,
+        potassium_ppm: 4.5,
+    };
+
+    /// Sum of all tracked ions
+    pub fn total_ppm(&self) -> f32 {
+        self.nitrate_ppm + self.phosphate_ppm + self.potassium_ppm
+    }
+}
+
+/// Dissolved salts other than N, P and K (calcium, magnesium, sulphate), in ppm
+const OTHER_IONS_PPM: f32 = 800.0;
+/// ppm of dissolved salts per dS/m of electrical conductivity
+const PPM_PER_DS_M: f32 = 640.0;
+/// Below this EC the solution is too depleted to keep recirculating
+pub const MIN_EC_DS_M: f32 = 1.5;
+/// Acceptable solution pH range
+pub const PH_RANGE: std::ops::RangeInclusive<f32> = 5.5..=6.5;
+
+/// Simulates the sensors of a recirculating hydroponic system
+pub struct NutrientSolutionMonitor {
+    pub state: HydroponicState,
+    pub ec_ds_m: f32,              // Electrical conductivity of the solution
+    pub ph: f32,
+    pub dissolved_oxygen_ppm: f32,
+    pub temp_c: f32,
+    pub flow_rate_lpm: f32,        // Pump flow through the channels
+    pub npk: NpkSensor,
+    pub uptake_per_tick: NpkSensor, // Drawn by the crop while recirculating
+    pub animation_frame: usize,    // Tracks blinking frames (0 or 1)
+}
+
+impl Default for NutrientSolutionMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NutrientSolutionMonitor {
+    /// Creates a system recirculating freshly mixed solution at 20 °C
+    pub fn new() -> Self {
+        let mut monitor = Self {
+            state: HydroponicState::Recirculating,
+            ec_ds_m: 0.0,
+            ph: 5.8,
+            dissolved_oxygen_ppm: 8.8,
+            temp_c: 20.0,
+            flow_rate_lpm: 4.0,
+            npk: NpkSensor::FRESH,
+            uptake_per_tick: NpkSensor::UPTAKE_PER_TICK,
+            animation_frame: 0,
+        };
+        monitor.update_ec();
+        monitor
+    }
+
+    /// Advances the nutrient cycle by one tick
+    pub fn step(&mut self) -> Option<String> {
+        self.animation_frame = (self.animation_frame + 1) % 2;
+        match self.state {
+            HydroponicState::Recirculating => {
+                self.flow_rate_lpm = 4.0;
+                let uptake = self.uptake_per_tick;
+                self.npk.nitrate_ppm = (self.npk.nitrate_ppm - uptake.nitrate_ppm).max(0.0);
+                self.npk.phosphate_ppm = (self.npk.phosphate_ppm - uptake.phosphate_ppm).max(0.0);
+                self.npk.potassium_ppm = (self.npk.potassium_ppm - uptake.potassium_ppm).max(0.0);
+                self.ph += 0.01; // Nitrate uptake releases hydroxide and raises pH
+                self.update_ec();
+                if !PH_RANGE.contains(&self.ph) {
+                    self.state = HydroponicState::Flushing;
+                    Some(format!("pH out of range ({:.2}), flushing...", self.ph))
+                } else if self.ec_ds_m < MIN_EC_DS_M {
+                    self.state = HydroponicState::Draining;
+                    Some(format!("Solution depleted (EC {:.2} dS/m), draining...", self.ec_ds_m))
+                } else {
+                    None
+                }
+            }
+            HydroponicState::Draining => {
+                self.flow_rate_lpm = 8.0;
+                self.state = HydroponicState::Refilling;
+                Some("Reservoir drained, refilling...".to_string())
+            }
+            HydroponicState::Flushing => {
+                self.flow_rate_lpm = 10.0;
+                self.npk = NpkSensor { nitrate_ppm: 0.0, phosphate_ppm: 0.0, potassium_ppm: 0.0 };
+                self.ph = 7.0;
+                self.update_ec();
+                self.state = HydroponicState::Refilling;
+                Some("System flushed, refilling...".to_string())
+            }
+            HydroponicState::Refilling => {
+                self.flow_rate_lpm = 8.0;
+                self.npk = NpkSensor::FRESH;
+                self.ph = 5.8;
+                self.update_ec();
+                self.state = HydroponicState::Recirculating;
+                Some(format!("Fresh solution mixed (EC {:.2} dS/m), recirculating", self.ec_ds_m))
+            }
+            HydroponicState::Error => {
+                self.flow_rate_lpm = 0.0;
+                Some("Error state, no transitions".to_string())
+            }
+        }
+    }
+
+    fn update_ec(&mut self) {
+        let other_ions = if self.npk.total_ppm() > 0.0 { OTHER_IONS_PPM } else { 0.0 };
+        self.ec_ds_m = (self.npk.total_ppm() + other_ions) / PPM_PER_DS_M;
+    }
+}
+
+/// Dissolved oxygen at the roots while the mist is on, near saturation at 20 °C, in ppm
+pub const MIST_DISSOLVED_OXYGEN_PPM: f32 = 9.0;
+/// Dissolved oxygen the roots draw the film down to between mists, in ppm
+pub const IDLE_DISSOLVED_OXYGEN_PPM: f32 = 6.5;
+/// Share of the gap to the target dissolved oxygen closed each tick
+const OXYGEN_EXCHANGE_PER_TICK: f32 = 0.5;
+
+/// What the misting nozzles are doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MistPhase {
+    Misting, // Spraying solution onto the hanging roots
+    Idle,    // Roots take up the film the last mist left
+}
+
+/// Cycles the nozzles of an aeroponic system, misting the roots briefly and leaving them to
+/// drain in the air in between
+#[derive(Debug, Clone, PartialEq)]
+pub struct AeroponicController {
+    pub on_duration_ticks: u32,
+    pub off_duration_ticks: u32,
+    pub mist_volume_ml_per_cycle: f32,
+    pub current_phase_ticks: u32, // Spent in the current phase so far
+    pub phase: MistPhase,
+}
+
+impl Default for AeroponicController {
+    /// 3 s of mist every 33 s
+    fn default() -> Self {
+        Self::new(3, 30, 50.0)
+    }
+}
+
+impl AeroponicController {
+    /// A controller starting at the beginning of a mist
+    pub fn new(on_duration_ticks: u32, off_duration_ticks: u32, mist_volume_ml_per_cycle: f32) -> Self {
+        Self {
+            on_duration_ticks,
+            off_duration_ticks,
+            mist_volume_ml_per_cycle,
+            current_phase_ticks: 0,
+            phase: MistPhase::Misting,
+        }
+    }
+
+    /// Ticks the current phase lasts in all
+    pub fn phase_duration_ticks(&self) -> u32 {
+        match self.phase {
+            MistPhase::Misting => self.on_duration_ticks,
+            MistPhase::Idle => self.off_duration_ticks,
+        }
+    }
+
+    /// Ticks left before the phase changes
+    pub fn ticks_remaining(&self) -> u32 {
+        self.phase_duration_ticks().saturating_sub(self.current_phase_ticks)
+    }
+
+    /// Nutrients the crop takes up per tick in the current phase: all of a cycle's uptake falls in
+    /// the idle gap, from the film the last mist left on the roots
+    pub fn uptake_per_tick(&self) -> NpkSensor {
+        let share = match self.phase {
+            MistPhase::Misting => 0.0,
+            MistPhase::Idle => {
+                (self.on_duration_ticks + self.off_duration_ticks) as f32 / self.off_duration_ticks as f32
+            }
+        };
+        let uptake = NpkSensor::UPTAKE_PER_TICK;
+        NpkSensor {
+            nitrate_ppm: uptake.nitrate_ppm * share,
+            phosphate_ppm: uptake.phosphate_ppm * share,
+            potassium_ppm: uptake.potassium_ppm * share,
+        }
+    }
+
+    /// Runs one tick of the current phase against `monitor`, after its own step, and returns the
+    /// next phase once this one has run its course. The mist aerates the roots and idling lets
+    /// them draw the oxygen down; `monitor` then takes up nutrients on the next tick's phase.
+    pub fn step(&mut self, monitor: &mut NutrientSolutionMonitor) -> Option<MistPhase> {
+        let target_do_ppm = match self.phase {
+            MistPhase::Misting => {
+                let litres_per_tick = self.mist_volume_ml_per_cycle / 1000.0 / self.on_duration_ticks as f32;
+                monitor.flow_rate_lpm = litres_per_tick * 60.0; // A tick is a second
+                MIST_DISSOLVED_OXYGEN_PPM
+            }
+            MistPhase::Idle => {
+                monitor.flow_rate_lpm = 0.0;
+                IDLE_DISSOLVED_OXYGEN_PPM
+            }
+        };
+        monitor.dissolved_oxygen_ppm += (target_do_ppm - monitor.dissolved_oxygen_ppm) * OXYGEN_EXCHANGE_PER_TICK;
+
+        self.current_phase_ticks += 1;
+        let changed = (self.current_phase_ticks >= self.phase_duration_ticks()).then(|| {
+            self.current_phase_ticks = 0;
+            self.phase = match self.phase {
+                MistPhase::Misting => MistPhase::Idle,
+                MistPhase::Idle => MistPhase::Misting,
+            };
+            self.phase
+        });
+        monitor.uptake_per_tick = self.uptake_per_tick();
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fresh_solution_conducts_from_all_its_ions() {
+        let monitor = NutrientSolutionMonitor::new();
+        assert_eq!(monitor.state, HydroponicState::Recirculating);
+        assert!((monitor.ec_ds_m - (495.0 + 800.0) / 640.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn uptake_depletes_the_solution_until_it_is_drained_and_refilled() {
+        let mut monitor = NutrientSolutionMonitor::new();
+        let mut last_ec = monitor.ec_ds_m;
+        // 9.3 ppm a tick takes the 495 ppm of N, P and K below the 160 ppm an EC of 1.5 allows on tick 37
+        for _ in 0..36 {
+            assert_eq!(monitor.step(), None);
+            assert!(monitor.ec_ds_m < last_ec);
+            last_ec = monitor.ec_ds_m;
+        }
+        assert!(monitor.npk.nitrate_ppm < NpkSensor::FRESH.nitrate_ppm);
+        assert!(monitor.step().is_some_and(|message| message.starts_with("Solution depleted")));
+        assert_eq!(monitor.state, HydroponicState::Draining);
+        assert!(monitor.ec_ds_m < MIN_EC_DS_M);
+
+        monitor.step();
+        assert_eq!(monitor.state, HydroponicState::Refilling);
+        monitor.step();
+        assert_eq!(monitor.state, HydroponicState::Recirculating);
+        assert_eq!(monitor.npk, NpkSensor::FRESH);
+        assert_eq!(monitor.ph, 5.8);
+        assert!(monitor.ec_ds_m > MIN_EC_DS_M);
+    }
+
+    #[test]
+    fn ph_drift_out_of_range_flushes_the_system() {
+        let mut monitor = NutrientSolutionMonitor::new();
+        monitor.uptake_per_tick = NpkSensor { nitrate_ppm: 0.0, phosphate_ppm: 0.0, potassium_ppm: 0.0 };
+        monitor.ph = 6.495;
+        assert!(monitor.step().is_some_and(|message| message.starts_with("pH out of range")));
+        assert_eq!(monitor.state, HydroponicState::Flushing);
+        monitor.step();
+        assert_eq!(monitor.ec_ds_m, 0.0);
+        assert_eq!(monitor.state, HydroponicState::Refilling);
+    }
+
+    #[test]
+    fn mist_and_idle_phases_alternate_on_time() {
+        let mut controller = AeroponicController::new(3, 5, 50.0);
+        let mut monitor = NutrientSolutionMonitor::new();
+        let mut changes = Vec::new();
+        for tick in 1..=16 {
+            if let Some(phase) = controller.step(&mut monitor) {
+                changes.push((tick, phase));
+            }
+        }
+        assert_eq!(
+            changes,
+            [(3, MistPhase::Idle), (8, MistPhase::Misting), (11, MistPhase::Idle), (16, MistPhase::Misting)]
+        );
+        // The countdown starts from the full phase and falls by one a tick
+        assert_eq!(controller.ticks_remaining(), 3);
+        controller.step(&mut monitor);
+        assert_eq!(controller.ticks_remaining(), 2);
+    }
+
+    #[test]
+    fn nutrients_are_taken_up_between_mists() {
+        let mut controller = AeroponicController::new(3, 5, 50.0);
+        let mut monitor = NutrientSolutionMonitor::new();
+        monitor.uptake_per_tick = controller.uptake_per_tick();
+        for _ in 0..3 {
+            monitor.step();
+            controller.step(&mut monitor);
+            assert_eq!(monitor.npk, NpkSensor::FRESH);
+            // 50 mL over 3 one-second ticks is 1 L/min through the nozzles
+            assert!((monitor.flow_rate_lpm - 1.0).abs() < 1e-4);
+        }
+        assert!(monitor.dissolved_oxygen_ppm > 8.8);
+        for _ in 0..5 {
+            monitor.step();
+            controller.step(&mut monitor);
+        }
+        assert_eq!(monitor.flow_rate_lpm, 0.0);
+        assert!(monitor.dissolved_oxygen_ppm < 7.0);
+        // The 5 idle ticks take up what 8 ticks of recirculation would
+        let (fresh, uptake) = (NpkSensor::FRESH, NpkSensor::UPTAKE_PER_TICK);
+        assert!((monitor.npk.nitrate_ppm - (fresh.nitrate_ppm - 8.0 * uptake.nitrate_ppm)).abs() < 1e-3);
+        assert!((monitor.npk.potassium_ppm - (fresh.potassium_ppm - 8.0 * uptake.potassium_ppm)).abs() < 1e-3);
+    }
+}
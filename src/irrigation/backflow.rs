@@ -0,0 +1,91 @@
+//! Backflow preventers keeping fertigation water out of the supply
+
+use rand::Rng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::Tick;
+
+/// Share of simulated tests a working preventer passes
+const TEST_PASS_PROBABILITY: f64 = 0.99;
+
+/// Kind of backflow prevention assembly
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BfpType {
+    /// Two check valves in series, for low-hazard connections
+    #[default]
+    DoubleCheckValve,
+    /// Check valves around a relief zone, required where chemicals are injected
+    ReducedPressure,
+}
+
+impl BfpType {
+    /// Name shown in the TUI and status messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            BfpType::DoubleCheckValve => "double check valve",
+            BfpType::ReducedPressure => "reduced pressure",
+        }
+    }
+}
+
+/// Outcome of one preventer test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackflowTest {
+    pub tick: Tick,
+    pub passed: bool,
+    pub manual: bool, // Triggered from the TUI rather than the test schedule
+}
+
+/// A backflow preventer on the irrigation mainline, tested on a fixed schedule
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackflowPreventer {
+    pub type_: BfpType,
+    pub test_frequency_ticks: u32,
+    pub last_test_tick: Tick,
+    pub is_functional: bool, // Irrigation stays blocked while false
+    pub history: Vec<BackflowTest>, // Oldest first
+}
+
+impl BackflowPreventer {
+    /// A working preventer, first tested `test_frequency_ticks` from now
+    pub fn new(type_: BfpType, test_frequency_ticks: u32) -> Self {
+        Self { type_, test_frequency_ticks, last_test_tick: Tick::ZERO, is_functional: true, history: Vec::new() }
+    }
+
+    /// Whether the scheduled test is due at `tick`
+    pub fn test_due(&self, tick: Tick) -> bool {
+        tick.0 >= self.last_test_tick.0 + u64::from(self.test_frequency_ticks)
+    }
+
+    /// Tests the preventer and records the result; returns whether it passed.
+    /// A failed preventer stays out of service until [`Self::acknowledge`].
+    pub fn run_test(&mut self, tick: Tick, manual: bool, rng: &mut impl Rng) -> bool {
+        let passed = rng.gen_bool(TEST_PASS_PROBABILITY);
+        self.is_functional &= passed;
+        self.last_test_tick = tick;
+        self.history.push(BackflowTest { tick, passed, manual });
+        passed
+    }
+
+    /// Runs the scheduled test if it is due, returning its result
+    pub fn step(&mut self, tick: Tick, rng: &mut impl Rng) -> Option<bool> {
+        self.test_due(tick).then(|| self.run_test(tick, false, rng))
+    }
+
+    /// Returns the preventer to service once an operator has dealt with the fault
+    pub fn acknowledge(&mut self) {
+        self.is_functional = true;
+    }
+
+    /// Whether irrigation must stay off
+    pub fn blocks_irrigation(&self) -> bool {
+        !self.is_functional
+    }
+
+    /// Tests run so far and how many of them failed
+    pub fn test_counts(&self) -> (usize, usize) {
+        (self.history.len(), self.history.iter().filter(|test| !test.passed).count())
+    }
+}
@@ -0,0 +1,116 @@
+//! A seasonal allowance of water drawn from the farm's supply
+//!
+//! Irrigation, fertigation and leaching water count against the budget, and once it is used up
+//! irrigation is refused for the rest of the season. Frost protection is an exception category: a
+//! frosty night cannot wait for next season's allowance, so its water is booked apart and never
+//! counts or is refused. Rain costs nothing, even when it was stored in the tank first.
+
+use crate::water_volume::WaterSource;
+
+/// How the budget treats water from a source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetCategory {
+    Counted,         // Drawn from the supply the budget caps
+    FrostProtection, // Exempt, booked apart
+    Free,            // Rain and harvested rainwater
+}
+
+impl BudgetCategory {
+    pub fn of(source: WaterSource) -> Self {
+        match source {
+            WaterSource::Irrigation | WaterSource::Fertigation | WaterSource::Leaching => BudgetCategory::Counted,
+            WaterSource::FrostProtection => BudgetCategory::FrostProtection,
+            WaterSource::Rain | WaterSource::Harvested => BudgetCategory::Free,
+        }
+    }
+}
+
+/// Water drawn this season against its limit, in mm over the field
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaterBudget {
+    pub seasonal_limit_mm: f32,
+    pub used_mm: f32,             // Counted water
+    pub frost_protection_mm: f32, // Exempt water, not part of `used_mm`
+}
+
+impl WaterBudget {
+    pub fn new(seasonal_limit_mm: f32) -> Self {
+        Self { seasonal_limit_mm, used_mm: 0.0, frost_protection_mm: 0.0 }
+    }
+
+    /// Books `mm` of water from `source` in its category. Returns whether this took the counted
+    /// water past the limit, which happens once a season.
+    pub fn record(&mut self, source: WaterSource, mm: f32) -> bool {
+        match BudgetCategory::of(source) {
+            BudgetCategory::Counted => {
+                let was_exhausted = self.is_exhausted();
+                self.used_mm += mm;
+                !was_exhausted && self.is_exhausted()
+            }
+            BudgetCategory::FrostProtection => {
+                self.frost_protection_mm += mm;
+                false
+            }
+            BudgetCategory::Free => false,
+        }
+    }
+
+    pub fn remaining_mm(&self) -> f32 {
+        (self.seasonal_limit_mm - self.used_mm).max(0.0)
+    }
+
+    /// Whether the limit is used up, so irrigation is refused; frost protection never is
+    pub fn is_exhausted(&self) -> bool {
+        self.used_mm >= self.seasonal_limit_mm
+    }
+
+    /// Starts the next season's allowance
+    pub fn reset_season(&mut self) {
+        self.used_mm = 0.0;
+        self.frost_protection_mm = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_fall_into_their_categories() {
+        assert_eq!(BudgetCategory::of(WaterSource::Irrigation), BudgetCategory::Counted);
+        assert_eq!(BudgetCategory::of(WaterSource::Fertigation), BudgetCategory::Counted);
+        assert_eq!(BudgetCategory::of(WaterSource::Leaching), BudgetCategory::Counted);
+        assert_eq!(BudgetCategory::of(WaterSource::FrostProtection), BudgetCategory::FrostProtection);
+        assert_eq!(BudgetCategory::of(WaterSource::Rain), BudgetCategory::Free);
+        assert_eq!(BudgetCategory::of(WaterSource::Harvested), BudgetCategory::Free);
+    }
+
+    #[test]
+    fn exhaustion_is_reported_once_a_season() {
+        let mut budget = WaterBudget::new(100.0);
+        assert!(!budget.record(WaterSource::Irrigation, 60.0));
+        assert_eq!(budget.remaining_mm(), 40.0);
+        assert!(budget.record(WaterSource::Fertigation, 40.0));
+        assert!(budget.is_exhausted());
+        assert!(!budget.record(WaterSource::Leaching, 10.0));
+        assert_eq!((budget.used_mm, budget.remaining_mm()), (110.0, 0.0));
+        budget.reset_season();
+        assert!(!budget.is_exhausted());
+        assert!(!budget.record(WaterSource::Irrigation, 50.0));
+    }
+
+    #[test]
+    fn frost_protection_and_rain_never_count() {
+        let mut budget = WaterBudget::new(100.0);
+        assert!(!budget.record(WaterSource::FrostProtection, 150.0));
+        assert!(!budget.record(WaterSource::Rain, 200.0));
+        assert!(!budget.record(WaterSource::Harvested, 30.0));
+        assert_eq!((budget.used_mm, budget.frost_protection_mm), (0.0, 150.0));
+        budget.record(WaterSource::Irrigation, 100.0);
+        // An exhausted budget still books frost protection
+        assert!(!budget.record(WaterSource::FrostProtection, 5.0));
+        assert_eq!(budget.frost_protection_mm, 155.0);
+        budget.reset_season();
+        assert_eq!(budget.frost_protection_mm, 0.0);
+    }
+}
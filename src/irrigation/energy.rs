@@ -0,0 +1,112 @@
+//! Electricity drawn by the pump and what it costs
+
+use std::io::{self, Write};
+
+/// Header line written by [`EnergyModel::write_hourly_csv`]
+pub const CSV_HEADER: &str = "hour,energy_kwh,cost";
+
+/// Electric pump rating and tariff; running totals are kept alongside
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnergyModel {
+    pub pump_power_kw: f32,            // Hydraulic power the pump delivers
+    pub pump_efficiency: f32,          // Wire-to-water efficiency (0, 1]
+    pub electricity_rate_per_kwh: f32, // Tariff in the local currency
+    pub total_energy_kwh: f32,
+    pub season_energy_kwh: f32, // Since planting; reset at harvest
+    hourly_kwh: Vec<f32>,       // Indexed by simulated hour since the start
+}
+
+impl EnergyModel {
+    pub fn new(pump_power_kw: f32, pump_efficiency: f32, electricity_rate_per_kwh: f32) -> Self {
+        Self {
+            pump_power_kw,
+            pump_efficiency,
+            electricity_rate_per_kwh,
+            total_energy_kwh: 0.0,
+            season_energy_kwh: 0.0,
+            hourly_kwh: Vec::new(),
+        }
+    }
+
+    /// Adds `duration_h` of pumping during simulated `hour` and returns the energy it drew
+    pub fn record_running(&mut self, hour: usize, duration_h: f32) -> f32 {
+        let energy_kwh = self.pump_power_kw * duration_h / self.pump_efficiency;
+        self.total_energy_kwh += energy_kwh;
+        self.season_energy_kwh += energy_kwh;
+        if self.hourly_kwh.len() <= hour {
+            self.hourly_kwh.resize(hour + 1, 0.0);
+        }
+        self.hourly_kwh[hour] += energy_kwh;
+        energy_kwh
+    }
+
+    /// Cost of `energy_kwh` at the configured rate
+    pub fn cost(&self, energy_kwh: f32) -> f32 {
+        energy_kwh * self.electricity_rate_per_kwh
+    }
+
+    /// Energy drawn during simulated `day`, counted from 0
+    pub fn day_energy_kwh(&self, day: usize) -> f32 {
+        self.hourly_kwh.iter().skip(day * 24).take(24).sum()
+    }
+
+    /// Starts a new season's total at harvest
+    pub fn reset_season(&mut self) {
+        self.season_energy_kwh = 0.0;
+    }
+
+    /// Writes the header and one row per simulated hour so far
+    pub fn write_hourly_csv(&self, mut out: impl Write) -> io::Result<()> {
+        writeln!(out, "{CSV_HEADER}")?;
+        for (hour, &energy_kwh) in self.hourly_kwh.iter().enumerate() {
+            writeln!(out, "{hour},{energy_kwh:.4},{:.4}", self.cost(energy_kwh))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn energy_accumulates_at_the_rated_power() {
+        // A 1.5 kW pump at 75% wire-to-water efficiency draws 2 kWh per hour of running
+        let mut energy = EnergyModel::new(1.5, 0.75, 0.2);
+        assert_close(energy.record_running(0, 1.0), 2.0, 1e-4);
+        // 3600 one-second ticks add up to the same hour
+        for _ in 0..3600 {
+            energy.record_running(1, 1.0 / 3600.0);
+        }
+        assert_close(energy.record_running(2, 0.5), 1.0, 1e-4);
+        assert!((energy.total_energy_kwh - 5.0).abs() < 1e-3);
+        assert!((energy.season_energy_kwh - 5.0).abs() < 1e-3);
+        assert_close(energy.cost(energy.total_energy_kwh), energy.total_energy_kwh * 0.2, 1e-4);
+    }
+
+    #[test]
+    fn day_totals_and_season_reset() {
+        let mut energy = EnergyModel::new(2.0, 1.0, 0.1);
+        energy.record_running(3, 1.0);
+        energy.record_running(23, 0.5);
+        energy.record_running(30, 0.25); // Second day
+        assert_close(energy.day_energy_kwh(0), 3.0, 1e-4);
+        assert_close(energy.day_energy_kwh(1), 0.5, 1e-4);
+        assert_close(energy.day_energy_kwh(2), 0.0, 1e-4);
+        energy.reset_season();
+        assert_close(energy.season_energy_kwh, 0.0, 1e-4);
+        assert_close(energy.total_energy_kwh, 3.5, 1e-4);
+    }
+
+    #[test]
+    fn hourly_csv_has_one_row_per_hour() {
+        let mut energy = EnergyModel::new(2.0, 1.0, 0.1);
+        energy.record_running(2, 1.0);
+        let mut out = Vec::new();
+        energy.write_hourly_csv(&mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows, [CSV_HEADER, "0,0.0000,0.0000", "1,0.0000,0.0000", "2,2.0000,0.2000"]);
+    }
+}
@@ -0,0 +1,90 @@
+//! Overhead sprinkling on frosty nights: water freezing on the crop releases its latent heat
+
+/// Rise above `activation_temp_c` the air must reach before sprinkling stops, in °C
+pub const DEACTIVATION_MARGIN_C: f32 = 0.5;
+
+/// Runs the overhead sprinklers continuously while the air is at risk of frost
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrostProtectionController {
+    pub activation_temp_c: f32,
+    pub required_application_rate_mm_h: f32, // 2-3 mm/h keeps a film of freezing water on the crop
+    pub active: bool,
+    pub total_applied_mm: f32, // Since the start, apart from irrigation
+}
+
+impl Default for FrostProtectionController {
+    fn default() -> Self {
+        Self::new(1.0, 2.5)
+    }
+}
+
+impl FrostProtectionController {
+    pub fn new(activation_temp_c: f32, required_application_rate_mm_h: f32) -> Self {
+        Self { activation_temp_c, required_application_rate_mm_h, active: false, total_applied_mm: 0.0 }
+    }
+
+    /// Starts sprinkling once `air_temp_c` drops below the activation temperature and stops once
+    /// it is [`DEACTIVATION_MARGIN_C`] above it; stopping any earlier lets the ice on the crop
+    /// draw its heat back out as it melts. Returns the new state when it changes.
+    pub fn update(&mut self, air_temp_c: f32) -> Option<bool> {
+        let active = if self.active {
+            air_temp_c < self.activation_temp_c + DEACTIVATION_MARGIN_C
+        } else {
+            air_temp_c < self.activation_temp_c
+        };
+        (active != self.active).then(|| {
+            self.active = active;
+            active
+        })
+    }
+
+    /// Sprinkles for `hours` if active and returns the depth applied, in mm
+    pub fn apply(&mut self, hours: f32) -> f32 {
+        if !self.active {
+            return 0.0;
+        }
+        let applied_mm = self.required_application_rate_mm_h * hours;
+        self.total_applied_mm += applied_mm;
+        applied_mm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sprinklers_start_just_below_the_activation_temperature() {
+        let mut frost = FrostProtectionController::default();
+        assert_eq!(frost.update(3.0), None);
+        assert_eq!(frost.update(1.0), None);
+        assert_eq!(frost.update(0.9), Some(true));
+        assert_eq!(frost.update(-2.0), None);
+        assert!(frost.active);
+    }
+
+    #[test]
+    fn sprinklers_stop_half_a_degree_above_it() {
+        let mut frost = FrostProtectionController::default();
+        frost.update(-1.0);
+        // Back above the activation temperature, but not yet by the margin
+        assert_eq!(frost.update(1.0), None);
+        assert_eq!(frost.update(1.4), None);
+        assert_eq!(frost.update(1.5), Some(false));
+        // Between the two thresholds a stopped controller stays stopped
+        assert_eq!(frost.update(1.2), None);
+        assert!(!frost.active);
+    }
+
+    #[test]
+    fn water_is_applied_only_while_active() {
+        let mut frost = FrostProtectionController::new(0.0, 3.0);
+        assert_eq!(frost.apply(1.0), 0.0);
+        frost.update(-0.5);
+        assert_eq!(frost.apply(2.0), 6.0);
+        assert_eq!(frost.apply(0.5), 1.5);
+        frost.update(0.5);
+        assert_eq!(frost.apply(1.0), 0.0);
+        assert_eq!(frost.total_applied_mm, 7.5);
+    }
+}
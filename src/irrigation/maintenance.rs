@@ -0,0 +1,169 @@
+//! Service intervals of the pump, valves and filters, counted in operating hours
+
+/// How close a component is to its next service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MaintenanceStatus {
+    Ok,
+    Due,     // Past the warning threshold
+    Overdue, // Past the whole interval
+}
+
+/// One component on a service interval
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaintenanceItem {
+    pub component_id: String, // `pump`, or `valve-<zone>` and `filter-<zone>` for a zone's parts
+    pub interval_operating_hours: f32,
+    pub last_service_hours: f32, // Operating hours at the last service
+    pub warning_threshold_pct: f32, // Share of the interval after which a warning is raised
+    pub operating_hours: f32,
+}
+
+impl MaintenanceItem {
+    pub fn new(component_id: impl Into<String>, interval_operating_hours: f32, warning_threshold_pct: f32) -> Self {
+        Self {
+            component_id: component_id.into(),
+            interval_operating_hours,
+            last_service_hours: 0.0,
+            warning_threshold_pct,
+            operating_hours: 0.0,
+        }
+    }
+
+    /// Share of the service interval used since the last service, in percent
+    pub fn interval_used_pct(&self) -> f32 {
+        (self.operating_hours - self.last_service_hours) / self.interval_operating_hours * 100.0
+    }
+
+    /// Operating hours left until the service is due, negative once overdue
+    pub fn hours_until_due(&self) -> f32 {
+        self.last_service_hours + self.interval_operating_hours - self.operating_hours
+    }
+
+    pub fn status(&self) -> MaintenanceStatus {
+        match self.interval_used_pct() {
+            pct if pct >= 100.0 => MaintenanceStatus::Overdue,
+            pct if pct > self.warning_threshold_pct => MaintenanceStatus::Due,
+            _ => MaintenanceStatus::Ok,
+        }
+    }
+
+    /// Zone whose part this is, from a `-<zone>` suffix; none for field-wide parts
+    pub fn zone_id(&self) -> Option<u8> {
+        self.component_id.rsplit_once('-').and_then(|(_, zone)| zone.parse().ok())
+    }
+
+    /// Records a service now, starting the next interval
+    pub fn service(&mut self) {
+        self.last_service_hours = self.operating_hours;
+    }
+}
+
+/// Every component's service interval and which one the TUI has selected
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaintenanceScheduler {
+    pub items: Vec<MaintenanceItem>,
+    pub selected: usize,
+}
+
+impl Default for MaintenanceScheduler {
+    /// The pump plus zone 0's valve and filter, warning at 80% of the interval
+    fn default() -> Self {
+        Self::new(vec![
+            MaintenanceItem::new("pump", 500.0, 80.0),
+            MaintenanceItem::new("valve-0", 1000.0, 80.0),
+            MaintenanceItem::new("filter-0", 250.0, 80.0),
+        ])
+    }
+}
+
+impl MaintenanceScheduler {
+    pub fn new(items: Vec<MaintenanceItem>) -> Self {
+        Self { items, selected: 0 }
+    }
+
+    /// Adds `hours` of runtime to every component `running` says ran, and returns the ones that
+    /// have just passed their warning threshold or their whole interval
+    pub fn record_runtime(&mut self, hours: f32, running: impl Fn(&MaintenanceItem) -> bool) -> Vec<&MaintenanceItem> {
+        let mut changed = Vec::new();
+        for (index, item) in self.items.iter_mut().enumerate() {
+            if running(item) {
+                let before = item.status();
+                item.operating_hours += hours;
+                if item.status() != before {
+                    changed.push(index);
+                }
+            }
+        }
+        changed.into_iter().map(|index| &self.items[index]).collect()
+    }
+
+    /// Moves the selection to the next item, wrapping around
+    pub fn select_next(&mut self) {
+        if !self.items.is_empty() {
+            self.selected = (self.selected + 1) % self.items.len();
+        }
+    }
+
+    /// Services the selected item and returns it
+    pub fn service_selected(&mut self) -> Option<&MaintenanceItem> {
+        let item = self.items.get_mut(self.selected)?;
+        item.service();
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due_date_counts_from_the_last_service() {
+        let mut filter = MaintenanceItem::new("filter-2", 200.0, 80.0);
+        filter.operating_hours = 150.0;
+        assert_eq!((filter.interval_used_pct(), filter.hours_until_due()), (75.0, 50.0));
+        assert_eq!(filter.status(), MaintenanceStatus::Ok);
+        // Exactly at the threshold is still fine; only past it is a warning raised
+        filter.operating_hours = 160.0;
+        assert_eq!(filter.status(), MaintenanceStatus::Ok);
+        filter.operating_hours = 180.0;
+        assert_eq!((filter.status(), filter.hours_until_due()), (MaintenanceStatus::Due, 20.0));
+        filter.operating_hours = 220.0;
+        assert_eq!((filter.status(), filter.hours_until_due()), (MaintenanceStatus::Overdue, -20.0));
+        filter.service();
+        assert_eq!((filter.last_service_hours, filter.hours_until_due()), (220.0, 200.0));
+        assert_eq!(filter.status(), MaintenanceStatus::Ok);
+        assert_eq!(filter.zone_id(), Some(2));
+        assert_eq!(MaintenanceItem::new("pump", 500.0, 80.0).zone_id(), None);
+    }
+
+    #[test]
+    fn runtime_reports_each_status_change_once() {
+        let mut scheduler = MaintenanceScheduler::default();
+        let pump_only = |item: &MaintenanceItem| item.component_id == "pump";
+        assert!(scheduler.record_runtime(400.0, pump_only).is_empty());
+        let changed = scheduler.record_runtime(50.0, pump_only);
+        assert_eq!(changed.len(), 1);
+        assert_eq!((changed[0].component_id.as_str(), changed[0].status()), ("pump", MaintenanceStatus::Due));
+        assert!(scheduler.record_runtime(40.0, pump_only).is_empty());
+        let changed = scheduler.record_runtime(10.0, pump_only);
+        assert_eq!(changed[0].status(), MaintenanceStatus::Overdue);
+        // Components that did not run keep their hours
+        assert_eq!(scheduler.items[1].operating_hours, 0.0);
+        assert_eq!(scheduler.items[0].operating_hours, 500.0);
+    }
+
+    #[test]
+    fn servicing_the_selected_item_starts_its_next_interval() {
+        let mut scheduler = MaintenanceScheduler::default();
+        scheduler.record_runtime(300.0, |_| true);
+        assert_eq!(scheduler.items[2].status(), MaintenanceStatus::Overdue);
+        scheduler.select_next();
+        scheduler.select_next();
+        let serviced = scheduler.service_selected().unwrap();
+        assert_eq!((serviced.component_id.as_str(), serviced.hours_until_due()), ("filter-0", 250.0));
+        scheduler.select_next();
+        assert_eq!(scheduler.selected, 0);
+        assert_eq!(scheduler.items[0].hours_until_due(), 200.0);
+        assert!(MaintenanceScheduler::new(Vec::new()).service_selected().is_none());
+    }
+}
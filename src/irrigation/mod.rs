@@ -0,0 +1,30 @@
+//! Irrigation hardware and scheduling strategies
+
+pub mod backflow;
+pub mod budget;
+pub mod emitter;
+pub mod energy;
+pub mod filter;
+pub mod frost;
+pub mod maintenance;
+pub mod optimizer;
+pub mod pipe_network;
+pub mod rainwater;
+pub mod strategy;
+pub mod surge;
+pub mod topology;
+pub mod water_hammer;
+
+pub use backflow::{BackflowPreventer, BfpType};
+pub use budget::WaterBudget;
+pub use energy::EnergyModel;
+pub use filter::FilterModel;
+pub use frost::FrostProtectionController;
+pub use maintenance::{MaintenanceItem, MaintenanceScheduler, MaintenanceStatus};
+pub use optimizer::IrrigationOptimizer;
+pub use pipe_network::{Pipe, PipeNetwork};
+pub use rainwater::RainwaterHarvester;
+pub use strategy::{DroughtStressTracker, IrrigationStrategy, WaterPump};
+pub use surge::{BasinModel, SurgeIrrigationController};
+pub use topology::{IrrigationScheduler, ZoneTopology};
+pub use water_hammer::WaterHammerModel;
@@ -0,0 +1,132 @@
+//! When to start the next watering, predicted from how fast the soil has been drying
+//!
+//! The drying rate is the least-squares slope of the readings since the soil was last wetted,
+//! or the crop's own evapotranspiration (ETc = Kc × ET0) over the root zone if that is faster.
+//! Watering is best started at the last tick before moisture falls below the threshold.
+
+use crate::agri_iot_core::history::ReadingHistory;
+use crate::crop::PlantProfile;
+use crate::simulation::ROOT_ZONE_DEPTH_MM;
+use crate::Tick;
+
+/// Moisture readings kept, one per tick
+pub const HISTORY_LEN: usize = 1000;
+
+/// Predicts the best tick for the next watering of one zone
+#[derive(Debug, Clone, Default)]
+pub struct IrrigationOptimizer {
+    pub history: ReadingHistory<f32, HISTORY_LEN>, // Moisture in %, one per tick
+    pub profile: PlantProfile,                     // Crop whose Kc sets the ET model
+    pub threshold: f32,                            // Moisture watering is due at, as last recorded
+    pub et0_mm_per_day: f32,
+    pub tick_days: f32,
+}
+
+impl IrrigationOptimizer {
+    pub fn new(profile: PlantProfile, et0_mm_per_day: f32, tick_days: f32) -> Self {
+        Self { history: ReadingHistory::new(), profile, threshold: 0.0, et0_mm_per_day, tick_days }
+    }
+
+    /// Adds the tick's moisture and the threshold it is watered at; call once per tick
+    pub fn record(&mut self, moisture_level: f32, threshold: f32) {
+        self.history.push(moisture_level);
+        self.threshold = threshold;
+    }
+
+    /// Moisture points the crop's evapotranspiration takes from the root zone each tick
+    pub fn et_pct_per_tick(&self) -> f32 {
+        self.profile.crop_coefficient * self.et0_mm_per_day * self.tick_days / ROOT_ZONE_DEPTH_MM * 100.0
+    }
+
+    /// Moisture points lost per tick over the readings since the last rise, by least squares;
+    /// `None` with fewer than two of them
+    pub fn trend_pct_per_tick(&self) -> Option<f32> {
+        let readings: Vec<f32> = self.history.iter().collect();
+        let rise = readings.windows(2).rposition(|pair| pair[1] > pair[0]);
+        let drying = &readings[rise.map_or(0, |index| index + 1)..];
+        if drying.len() < 2 {
+            return None;
+        }
+        let n = drying.len() as f32;
+        let mean_x = (n - 1.0) / 2.0;
+        let mean_y = drying.iter().sum::<f32>() / n;
+        let (covariance, variance) = drying.iter().enumerate().fold((0.0, 0.0), |(cov, var), (i, &y)| {
+            let dx = i as f32 - mean_x;
+            (cov + dx * (y - mean_y), var + dx * dx)
+        });
+        Some(-covariance / variance)
+    }
+
+    /// The faster of the observed drying and the crop's ET, in moisture points per tick
+    pub fn drying_pct_per_tick(&self) -> f32 {
+        self.trend_pct_per_tick().unwrap_or(0.0).max(self.et_pct_per_tick())
+    }
+
+    /// The last tick from `current_tick` on at which moisture is still at or above the
+    /// threshold, so watering then keeps the crop out of stress; `current_tick` once it is
+    /// already below, and `None` before any reading or when the soil is not drying
+    pub fn suggest_next_irrigation_tick(&self, current_tick: Tick) -> Option<Tick> {
+        let moisture_level = self.history.latest()?;
+        if moisture_level < self.threshold {
+            return Some(current_tick);
+        }
+        let drying = self.drying_pct_per_tick();
+        if drying <= 0.0 {
+            return None;
+        }
+        let ticks = ((moisture_level - self.threshold) / drying).floor() as u64;
+        Some(current_tick + Tick(ticks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts `suggested` is within 5 ticks of the hand-computed best tick
+    fn assert_within_5_ticks(suggested: Option<Tick>, best: Tick) {
+        let suggested = suggested.expect("a suggestion");
+        assert!(suggested.0.abs_diff(best.0) <= 5, "suggested {suggested:?}, best {best:?}");
+    }
+
+    #[test]
+    fn suggestion_follows_the_drying_since_the_last_watering() {
+        // Hourly ticks of maize at 5 mm/day ET0: ET alone takes 0.025 points a tick
+        let mut optimizer = IrrigationOptimizer::new(PlantProfile::default(), 5.0, 1.0 / 24.0);
+        for moisture_level in [36.0, 33.0] {
+            optimizer.record(moisture_level, 30.0);
+        }
+        // Watered to 70%, then drying 0.8 points a tick with ±0.3 of sensor noise
+        for tick in 0..=20 {
+            let noise = if tick % 2 == 0 { 0.3 } else { -0.3 };
+            optimizer.record(70.0 - 0.8 * tick as f32 + noise, 30.0);
+        }
+        assert!((optimizer.trend_pct_per_tick().unwrap() - 0.8).abs() < 0.05);
+        // The line crosses 30% 50 ticks after the watering, 30 after tick 20
+        assert_within_5_ticks(optimizer.suggest_next_irrigation_tick(Tick(1_020)), Tick(1_050));
+    }
+
+    #[test]
+    fn without_a_trend_the_crop_et_sets_the_pace() {
+        // A day per tick: 1.2 × 5 mm over the 1000 mm root zone is 0.6 points a tick
+        let mut optimizer = IrrigationOptimizer::new(PlantProfile::default(), 5.0, 1.0);
+        assert!((optimizer.et_pct_per_tick() - 0.6).abs() < 1e-5);
+        for _ in 0..10 {
+            optimizer.record(42.0, 30.0);
+        }
+        assert_eq!(optimizer.trend_pct_per_tick(), Some(0.0));
+        // 12 points above the threshold last 20 ticks
+        assert_within_5_ticks(optimizer.suggest_next_irrigation_tick(Tick(100)), Tick(120));
+    }
+
+    #[test]
+    fn dry_soil_is_due_now_and_wetting_soil_never() {
+        let mut optimizer = IrrigationOptimizer::new(PlantProfile::default(), 0.0, 1.0);
+        assert_eq!(optimizer.suggest_next_irrigation_tick(Tick(5)), None);
+        optimizer.record(40.0, 30.0);
+        optimizer.record(45.0, 30.0);
+        assert_eq!(optimizer.suggest_next_irrigation_tick(Tick(5)), None);
+        optimizer.record(25.0, 30.0);
+        assert_eq!(optimizer.suggest_next_irrigation_tick(Tick(5)), Some(Tick(5)));
+    }
+}
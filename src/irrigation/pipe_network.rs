@@ -0,0 +1,197 @@
+//! Friction losses from the pump to each zone, and the flow the zones get when they share the pump
+//!
+//! Each pipe runs from the pump's manifold to one zone. Its head loss follows the Hazen-Williams
+//! equation `hf = 10.67 · L · Q^1.852 / (C^1.852 · d^4.87)` in SI units, and the pump delivers
+//! less head the more it pumps.
+
+/// Halvings spent narrowing down each balanced head and flow
+const BISECTION_STEPS: usize = 40;
+
+/// One pipe from the manifold to a zone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pipe {
+    pub length_m: f32,
+    pub diameter_mm: f32, // Inside diameter
+    pub roughness: f32,   // Hazen-Williams C: 150 for PVC, 140 for PE, 100 for old steel
+    pub flow_rate_lps: f32,
+}
+
+impl Pipe {
+    pub fn new(length_m: f32, diameter_mm: f32, roughness: f32) -> Self {
+        Self { length_m, diameter_mm, roughness, flow_rate_lps: 0.0 }
+    }
+
+    /// Hazen-Williams friction loss at `flow_rate_lps`, in m of head
+    pub fn head_loss_m(&self, flow_rate_lps: f32) -> f32 {
+        let flow_m3_s = flow_rate_lps.max(0.0) / 1000.0;
+        let diameter_m = self.diameter_mm / 1000.0;
+        10.67 * self.length_m * flow_m3_s.powf(1.852) / (self.roughness.powf(1.852) * diameter_m.powf(4.87))
+    }
+}
+
+/// Pipes fed by one pump, `pipes[i]` leading to zone `i`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipeNetwork {
+    pub pipes: Vec<Pipe>,
+    pub pump_shutoff_head_m: f32, // Head with every valve closed
+    pub pump_max_flow_lps: f32,   // Flow at which the pump's head falls to nothing
+    pub required_head_m: f32,     // At a zone's inlet for its full design flow
+    pub zone_demand_lps: f32,     // Design flow of one watering zone
+    pub pump_head_m: f32,         // At the manifold, as last balanced
+    pub zone_heads_m: Vec<f32>,   // At each pipe's outlet, as last balanced
+}
+
+impl PipeNetwork {
+    pub fn new(
+        pipes: Vec<Pipe>,
+        pump_shutoff_head_m: f32,
+        pump_max_flow_lps: f32,
+        required_head_m: f32,
+        zone_demand_lps: f32,
+    ) -> Self {
+        let zone_heads_m = vec![pump_shutoff_head_m; pipes.len()];
+        Self {
+            pipes,
+            pump_shutoff_head_m,
+            pump_max_flow_lps,
+            required_head_m,
+            zone_demand_lps,
+            pump_head_m: pump_shutoff_head_m,
+            zone_heads_m,
+        }
+    }
+
+    /// Head the pump delivers at `total_flow_lps`, on a parabolic pump curve
+    pub fn pump_head_at(&self, total_flow_lps: f32) -> f32 {
+        let load = total_flow_lps / self.pump_max_flow_lps;
+        (self.pump_shutoff_head_m * (1.0 - load * load)).max(0.0)
+    }
+
+    /// Flow through `pipe` for `demand_lps` when the manifold is at `pump_head_m`: the flow at
+    /// which its emitters, flowing with the square root of their inlet head, take what the pipe
+    /// passes. Found by bisection, since more flow always means less head at the inlet.
+    fn pipe_flow_lps(&self, pipe: &Pipe, demand_lps: f32, pump_head_m: f32) -> f32 {
+        let emitter_flow_lps = |flow_lps: f32| {
+            let inlet_head_m = (pump_head_m - pipe.head_loss_m(flow_lps)).max(0.0);
+            demand_lps * (inlet_head_m / self.required_head_m).sqrt().min(1.0)
+        };
+        let (mut low, mut high) = (0.0, demand_lps);
+        for _ in 0..BISECTION_STEPS {
+            let mid = (low + high) / 2.0;
+            if emitter_flow_lps(mid) > mid {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        (low + high) / 2.0
+    }
+
+    /// Shares the pump between the zones drawing `demands_lps`, zero for zones that are off, and
+    /// returns the fraction of its demand each zone gets. A zone whose inlet is below the required
+    /// head flows as its emitters do, with the square root of the head; zones past the last pipe
+    /// get their full demand.
+    pub fn distribute(&mut self, demands_lps: &[f32]) -> Vec<f32> {
+        let demand = |zone: usize| demands_lps.get(zone).copied().unwrap_or(0.0);
+        let total_flow_lps = |head_m: f32| -> f32 {
+            self.pipes.iter().enumerate().map(|(zone, pipe)| self.pipe_flow_lps(pipe, demand(zone), head_m)).sum()
+        };
+        // The manifold settles where the pump's head matches the flow the pipes draw at that head
+        let (mut low, mut high) = (0.0, self.pump_shutoff_head_m);
+        for _ in 0..BISECTION_STEPS {
+            let mid = (low + high) / 2.0;
+            if self.pump_head_at(total_flow_lps(mid)) > mid {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        self.pump_head_m = (low + high) / 2.0;
+        let pump_head_m = self.pump_head_m;
+        let flows: Vec<f32> = (0..self.pipes.len())
+            .map(|zone| self.pipe_flow_lps(&self.pipes[zone], demand(zone), pump_head_m))
+            .collect();
+        for (pipe, flow_lps) in self.pipes.iter_mut().zip(flows) {
+            pipe.flow_rate_lps = flow_lps;
+        }
+        self.zone_heads_m = self.pipes.iter().map(|pipe| pump_head_m - pipe.head_loss_m(pipe.flow_rate_lps)).collect();
+        demands_lps
+            .iter()
+            .enumerate()
+            .map(|(zone, &demand_lps)| match self.pipes.get(zone) {
+                Some(pipe) if demand_lps > 0.0 => pipe.flow_rate_lps / demand_lps,
+                _ => 1.0,
+            })
+            .collect()
+    }
+
+    /// [`Self::distribute`] with every zone in `watering` drawing the design flow
+    pub fn balance(&mut self, watering: impl IntoIterator<Item = bool>) -> Vec<f32> {
+        let demands: Vec<f32> =
+            watering.into_iter().map(|on| if on { self.zone_demand_lps } else { 0.0 }).collect();
+        self.distribute(&demands)
+    }
+
+    /// Whether the zone at the end of pipe `zone` was short of the required head
+    pub fn low_pressure(&self, zone: usize) -> bool {
+        self.zone_heads_m.get(zone).is_some_and(|&head_m| head_m < self.required_head_m * (1.0 - 1e-3))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn head_loss_matches_hazen_williams_tables() {
+        // Loss per 100 m of pipe, as SI Hazen-Williams friction tables list it
+        assert_close(Pipe::new(100.0, 100.0, 130.0).head_loss_m(10.0), 1.902, 0.005);
+        assert_close(Pipe::new(100.0, 50.0, 150.0).head_loss_m(2.0), 2.166, 0.005);
+        assert_close(Pipe::new(100.0, 25.0, 140.0).head_loss_m(0.5), 5.522, 0.01);
+        assert_close(Pipe::new(100.0, 160.0, 150.0).head_loss_m(20.0), 0.534, 0.002);
+        assert_close(Pipe::new(1_000.0, 300.0, 120.0).head_loss_m(100.0), 7.446, 0.02);
+        assert_eq!(Pipe::new(100.0, 50.0, 150.0).head_loss_m(0.0), 0.0);
+    }
+
+    #[test]
+    fn head_loss_scales_with_flow_length_and_diameter() {
+        let pipe = Pipe::new(100.0, 50.0, 150.0);
+        let loss = pipe.head_loss_m(2.0);
+        assert_close(pipe.head_loss_m(4.0) / loss, 2f32.powf(1.852), 1e-3);
+        assert_close(Pipe::new(200.0, 50.0, 150.0).head_loss_m(2.0) / loss, 2.0, 1e-4);
+        assert_close(Pipe::new(100.0, 25.0, 150.0).head_loss_m(2.0) / loss, 2f32.powf(4.87), 0.05);
+        // A rougher pipe loses more
+        assert!(Pipe::new(100.0, 50.0, 100.0).head_loss_m(2.0) > loss);
+    }
+
+    // A 40 m pump that runs out of head at 10 L/s, feeding 2 L/s zones that need 20 m
+    fn network(zones: usize) -> PipeNetwork {
+        PipeNetwork::new(vec![Pipe::new(100.0, 50.0, 150.0); zones], 40.0, 10.0, 20.0, 2.0)
+    }
+
+    #[test]
+    fn one_zone_gets_its_full_flow() {
+        let mut network = network(5);
+        let factors = network.balance([true, false, false, false, false]);
+        assert_close(factors[0], 1.0, 1e-4);
+        assert!(factors[1..].iter().all(|&factor| factor == 1.0));
+        // 2 L/s off the pump curve, less 2.17 m of friction
+        assert_close(network.pump_head_m, 38.4, 0.01);
+        assert_close(network.zone_heads_m[0], 38.4 - 2.166, 0.01);
+        assert!(!network.low_pressure(0));
+    }
+
+    #[test]
+    fn zones_watering_together_share_the_pump() {
+        let mut network = network(5);
+        let factors = network.balance([true; 5]);
+        // 10 L/s would leave the pump with no head at all, so every zone is cut back alike
+        assert!(factors.iter().all(|&factor| factor < 1.0 && (factor - factors[0]).abs() < 1e-4));
+        assert!((0..5).all(|zone| network.low_pressure(zone)));
+        let total_lps: f32 = network.pipes.iter().map(|pipe| pipe.flow_rate_lps).sum();
+        assert_close(network.pump_head_at(total_lps), network.pump_head_m, 0.01);
+        // Emitters flow with the square root of their inlet head
+        assert_close(factors[0], (network.zone_heads_m[0] / 20.0).sqrt(), 1e-3);
+    }
+}
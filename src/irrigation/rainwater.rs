@@ -0,0 +1,84 @@
+//! Roof or field runoff captured in a tank and used ahead of the main supply
+
+use crate::WaterLevelSensor;
+
+/// Catchment draining into a storage tank
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RainwaterHarvester {
+    pub catchment_area_m2: f32,
+    pub runoff_coefficient: f32, // Share of the rain that reaches the tank, 0..=1
+    pub tank: WaterLevelSensor,
+    pub season_harvest_l: f32,  // Captured into the tank since planting
+    pub season_overflow_l: f32, // Lost because the tank was full
+}
+
+impl RainwaterHarvester {
+    pub fn new(catchment_area_m2: f32, runoff_coefficient: f32, tank: WaterLevelSensor) -> Self {
+        Self { catchment_area_m2, runoff_coefficient, tank, season_harvest_l: 0.0, season_overflow_l: 0.0 }
+    }
+
+    /// Runoff `rainfall_mm` sends to the tank: mm over m² is litres, scaled by the runoff coefficient
+    pub fn captured_volume_l(&self, rainfall_mm: f32) -> f32 {
+        rainfall_mm * self.catchment_area_m2 * self.runoff_coefficient
+    }
+
+    /// Fills the tank with the runoff of `rainfall_mm` and returns the litres stored
+    pub fn capture(&mut self, rainfall_mm: f32) -> f32 {
+        let captured = self.captured_volume_l(rainfall_mm);
+        let overflow = self.tank.fill(captured);
+        self.season_harvest_l += captured - overflow;
+        self.season_overflow_l += overflow;
+        captured - overflow
+    }
+
+    /// Serves as much of `demand_l` from the tank as it holds; the rest falls to the main supply
+    pub fn supply(&mut self, demand_l: f32) -> f32 {
+        self.tank.draw(demand_l)
+    }
+
+    /// Starts a new season's totals at harvest; the tank keeps its water
+    pub fn reset_season(&mut self) {
+        self.season_harvest_l = 0.0;
+        self.season_overflow_l = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn captured_volume_is_rain_times_area_times_coefficient() {
+        // 12 mm on a 200 m² metal roof shedding 85%: 12 × 200 × 0.85 / 1000 = 2.04 m³
+        let harvester = RainwaterHarvester::new(200.0, 0.85, WaterLevelSensor::new(10_000.0));
+        assert_close(harvester.captured_volume_l(12.0), 2_040.0, 1e-3);
+        assert_close(harvester.captured_volume_l(0.0), 0.0, 1e-3);
+        // 25 mm on a 1 ha field where 10% runs off: 25 m³
+        let field = RainwaterHarvester::new(10_000.0, 0.1, WaterLevelSensor::new(50_000.0));
+        assert_close(field.captured_volume_l(25.0), 25_000.0, 1e-3);
+    }
+
+    #[test]
+    fn capture_fills_the_tank_and_counts_the_overflow() {
+        let mut harvester = RainwaterHarvester::new(100.0, 0.8, WaterLevelSensor::new(1_000.0));
+        assert_close(harvester.capture(5.0), 400.0, 1e-3);
+        assert_close(harvester.tank.current_volume_liters, 400.0, 1e-3);
+        // 1000 L of runoff with 600 L of room left
+        assert_close(harvester.capture(12.5), 600.0, 1e-3);
+        assert_close(harvester.season_harvest_l, 1_000.0, 1e-3);
+        assert_close(harvester.season_overflow_l, 400.0, 1e-3);
+    }
+
+    #[test]
+    fn harvested_water_is_used_before_the_main_supply() {
+        let mut harvester = RainwaterHarvester::new(100.0, 0.8, WaterLevelSensor::new(1_000.0));
+        harvester.capture(5.0);
+        assert_close(harvester.supply(150.0), 150.0, 1e-3);
+        // Only 250 L left, so the main supply makes up the other 50 L
+        assert_close(harvester.supply(300.0), 250.0, 1e-3);
+        assert_close(harvester.supply(10.0), 0.0, 1e-3);
+        harvester.reset_season();
+        assert_close(harvester.season_harvest_l, 0.0, 1e-3);
+    }
+}
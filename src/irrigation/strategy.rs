@@ -0,0 +1,123 @@
+//! Full and deficit irrigation strategies and the crop stress they cause
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::crop::PlantProfile;
+
+/// How much of the crop's water demand each watering replaces
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IrrigationStrategy {
+    /// Replace the full ETc demand
+    #[default]
+    Full,
+    /// Replace only `fraction` (0.5 ..= 1.0) of the ETc demand, trading yield for water
+    Deficit { fraction: f32 },
+}
+
+impl IrrigationStrategy {
+    /// Share of the demand that is applied
+    pub fn fraction(self) -> f32 {
+        match self {
+            IrrigationStrategy::Full => 1.0,
+            IrrigationStrategy::Deficit { fraction } => fraction.clamp(0.5, 1.0),
+        }
+    }
+}
+
+/// Pump delivering water according to an [`IrrigationStrategy`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WaterPump {
+    pub strategy: IrrigationStrategy,
+}
+
+impl WaterPump {
+    pub fn new(strategy: IrrigationStrategy) -> Self {
+        Self { strategy }
+    }
+
+    /// Water actually applied for a demand of `et_demand_mm`
+    pub fn application_mm(&self, et_demand_mm: f32) -> f32 {
+        et_demand_mm * self.strategy.fraction()
+    }
+}
+
+/// Season totals of water demanded and withheld, for the yield penalty
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DroughtStressTracker {
+    pub etc_demand_mm: f32, // What full irrigation would have applied
+    pub deficit_mm: f32,    // Demand left unmet
+}
+
+impl DroughtStressTracker {
+    /// Adds one watering: the full demand and what was applied against it
+    pub fn record(&mut self, etc_demand_mm: f32, applied_mm: f32) {
+        self.etc_demand_mm += etc_demand_mm;
+        self.deficit_mm += (etc_demand_mm - applied_mm).max(0.0);
+    }
+
+    /// Water saved compared with full irrigation
+    pub fn water_saved_mm(&self) -> f32 {
+        self.deficit_mm
+    }
+
+    /// Relative ET deficit `1 - ETa/ETc` so far; 0 before any watering
+    pub fn stress_index(&self) -> f32 {
+        if self.etc_demand_mm <= 0.0 {
+            return 0.0;
+        }
+        self.deficit_mm / self.etc_demand_mm
+    }
+
+    /// Estimated relative yield loss `Ky * (1 - ETa/ETc)` for the season so far
+    pub fn yield_penalty(&self, profile: &PlantProfile) -> f32 {
+        profile.yield_loss(1.0 - self.stress_index())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A season of 20 waterings of 10 mm demand under `strategy`
+    fn season(strategy: IrrigationStrategy) -> DroughtStressTracker {
+        let pump = WaterPump::new(strategy);
+        let mut stress = DroughtStressTracker::default();
+        for _ in 0..20 {
+            stress.record(10.0, pump.application_mm(10.0));
+        }
+        stress
+    }
+
+    #[test]
+    fn deficit_pump_applies_a_fraction_of_the_demand() {
+        assert_eq!(WaterPump::new(IrrigationStrategy::Full).application_mm(8.0), 8.0);
+        assert_eq!(WaterPump::new(IrrigationStrategy::Deficit { fraction: 0.75 }).application_mm(8.0), 6.0);
+        // Fractions are held to 50-100% of the demand
+        assert_eq!(IrrigationStrategy::Deficit { fraction: 0.2 }.fraction(), 0.5);
+        assert_eq!(IrrigationStrategy::Deficit { fraction: 1.3 }.fraction(), 1.0);
+    }
+
+    #[test]
+    fn full_irrigation_causes_no_stress() {
+        let stress = season(IrrigationStrategy::Full);
+        assert_eq!(stress.etc_demand_mm, 200.0);
+        assert_eq!((stress.deficit_mm, stress.stress_index()), (0.0, 0.0));
+        assert_eq!(stress.yield_penalty(&PlantProfile::maize()), 0.0);
+        assert_eq!(DroughtStressTracker::default().stress_index(), 0.0);
+    }
+
+    #[test]
+    fn yield_penalty_increases_with_deficit() {
+        let maize = PlantProfile::maize();
+        let penalties: Vec<f32> = [0.9, 0.75, 0.5]
+            .map(|fraction| season(IrrigationStrategy::Deficit { fraction }).yield_penalty(&maize))
+            .into();
+        assert!(penalties.windows(2).all(|pair| pair[0] < pair[1]), "{penalties:?}");
+        // Maize's Ky of 1.25 at a 25% deficit
+        assert!((penalties[1] - 0.3125).abs() < 1e-4);
+        let saved = season(IrrigationStrategy::Deficit { fraction: 0.75 }).water_saved_mm();
+        assert!((saved - 50.0).abs() < 1e-3);
+    }
+}
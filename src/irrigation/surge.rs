@@ -0,0 +1,242 @@
+//! Surge (pulsed) flooding of a basin or furrow and the water it saves over continuous flow
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Share by which each earlier surge speeds up the next one's advance, as the wetted soil seals
+pub const CONDITIONING_GAIN: f32 = 0.5;
+
+/// Basin the inflow floods, advancing from the inlet towards the far end
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct BasinModel {
+    pub length_m: f32,                   // Inlet to far end
+    pub advance_velocity_m_per_min: f32, // Wetting front speed over dry soil
+    pub inflow_lps: f32,                 // Pump delivery while on
+}
+
+impl Default for BasinModel {
+    fn default() -> Self {
+        Self { length_m: 100.0, advance_velocity_m_per_min: 2.0, inflow_lps: 5.0 }
+    }
+}
+
+impl BasinModel {
+    /// Water continuous flow needs for the wetting front to reach the far end
+    pub fn continuous_water_l(&self) -> f32 {
+        self.length_m / self.advance_velocity_m_per_min * 60.0 * self.inflow_lps
+    }
+}
+
+/// Runs the pump in `n_surges` pulses of `surge_duration_s`, pausing `pause_s` between them
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurgeIrrigationController {
+    pub surge_duration_s: u32,
+    pub pause_s: u32,
+    pub n_surges: u8,
+    pub current_surge: u8, // 1-based surge running or just paused; 0 while idle
+    pub basin: BasinModel,
+    pub front_m: f32,              // Distance the wetting front has covered
+    pub surge_advance_m: Vec<f32>, // Advance made by each surge of the current cycle
+    pump_running: bool,
+    phase_elapsed_s: f32,
+    pump_on_s: f32,
+}
+
+impl SurgeIrrigationController {
+    pub fn new(surge_duration_s: u32, pause_s: u32, n_surges: u8, basin: BasinModel) -> Self {
+        Self {
+            surge_duration_s,
+            pause_s,
+            n_surges,
+            current_surge: 0,
+            basin,
+            front_m: 0.0,
+            surge_advance_m: Vec::new(),
+            pump_running: false,
+            phase_elapsed_s: 0.0,
+            pump_on_s: 0.0,
+        }
+    }
+
+    /// Starts a new cycle from a dry basin; ignored while a cycle is running
+    pub fn start(&mut self) -> bool {
+        if self.is_active() {
+            return false;
+        }
+        self.current_surge = 1;
+        self.front_m = 0.0;
+        self.surge_advance_m = vec![0.0];
+        self.pump_running = true;
+        self.phase_elapsed_s = 0.0;
+        self.pump_on_s = 0.0;
+        true
+    }
+
+    /// Whether a cycle is running, including its pauses
+    pub fn is_active(&self) -> bool {
+        self.current_surge > 0
+    }
+
+    /// Whether the pump is on right now
+    pub fn pump_running(&self) -> bool {
+        self.pump_running
+    }
+
+    /// Seconds left in the current surge or pause
+    pub fn phase_remaining_s(&self) -> f32 {
+        let phase = if self.pump_running { self.surge_duration_s } else { self.pause_s };
+        (phase as f32 - self.phase_elapsed_s).max(0.0)
+    }
+
+    /// Wetting front speed during 1-based `surge`; each earlier surge conditions the soil
+    pub fn advance_velocity_m_per_s(&self, surge: u8) -> f32 {
+        self.basin.advance_velocity_m_per_min / 60.0 * (1.0 + CONDITIONING_GAIN * surge.saturating_sub(1) as f32)
+    }
+
+    /// Advances the cycle by `dt_s`. Returns true on the step the cycle ends, which is
+    /// after the last surge or as soon as the front reaches the far end.
+    pub fn step(&mut self, dt_s: f32) -> bool {
+        let mut remaining_s = dt_s;
+        while self.is_active() && remaining_s > 0.0 {
+            let dt = remaining_s.min(self.phase_remaining_s());
+            remaining_s -= dt;
+            self.phase_elapsed_s += dt;
+            if self.pump_running {
+                let velocity = self.advance_velocity_m_per_s(self.current_surge);
+                let advance = (velocity * dt).min(self.basin.length_m - self.front_m);
+                self.front_m += advance;
+                if let Some(surge) = self.surge_advance_m.last_mut() {
+                    *surge += advance;
+                }
+                self.pump_on_s += dt;
+                let surge_over = self.phase_remaining_s() <= 0.0;
+                if self.reached_end() || surge_over && self.current_surge >= self.n_surges {
+                    self.current_surge = 0;
+                    self.pump_running = false;
+                    return true;
+                }
+                if surge_over {
+                    self.pump_running = false;
+                    self.phase_elapsed_s = 0.0;
+                }
+            } else if self.phase_remaining_s() <= 0.0 {
+                self.current_surge += 1;
+                self.surge_advance_m.push(0.0);
+                self.pump_running = true;
+                self.phase_elapsed_s = 0.0;
+            }
+        }
+        false
+    }
+
+    /// Water pumped so far in the current or last cycle
+    pub fn water_applied_l(&self) -> f32 {
+        self.pump_on_s * self.basin.inflow_lps
+    }
+
+    /// Whether the front has reached the far end, i.e. the whole basin was wetted
+    pub fn reached_end(&self) -> bool {
+        self.front_m >= self.basin.length_m
+    }
+
+    /// Share of the water continuous flow needs that the cycle saved; only a fair
+    /// comparison once the front has [reached the end](Self::reached_end)
+    pub fn water_saving_fraction(&self) -> f32 {
+        1.0 - self.water_applied_l() / self.basin.continuous_water_l()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    // Long enough that three 10 min surges never reach the far end
+    fn long_basin() -> BasinModel {
+        BasinModel { length_m: 1_000.0, ..BasinModel::default() }
+    }
+
+    #[test]
+    fn pump_alternates_between_surges_and_pauses() {
+        let mut controller = SurgeIrrigationController::new(600, 300, 3, long_basin());
+        assert!(!controller.is_active());
+        assert!(controller.start());
+        assert!(controller.pump_running());
+        for _ in 0..9 {
+            assert!(!controller.step(60.0));
+            assert!(controller.pump_running());
+        }
+        assert!(!controller.step(60.0));
+        assert!(!controller.pump_running());
+        assert_eq!(controller.current_surge, 1);
+        assert_close(controller.phase_remaining_s(), 300.0, 1e-3);
+
+        assert!(!controller.step(300.0));
+        assert!(controller.pump_running());
+        assert_eq!(controller.current_surge, 2);
+        assert_close(controller.phase_remaining_s(), 600.0, 1e-3);
+
+        // One step may span a whole surge and the pause after it
+        assert!(!controller.step(900.0));
+        assert!(controller.pump_running());
+        assert_eq!(controller.current_surge, 3);
+        assert!(controller.step(600.0));
+        assert!(!controller.is_active());
+        assert!(!controller.pump_running());
+        // Three surges of 600 s at 5 L/s; the pauses pump nothing
+        assert_close(controller.water_applied_l(), 9_000.0, 1e-2);
+    }
+
+    #[test]
+    fn start_is_ignored_while_a_cycle_runs() {
+        let mut controller = SurgeIrrigationController::new(600, 300, 2, long_basin());
+        assert!(controller.start());
+        controller.step(700.0);
+        assert!(!controller.start());
+        assert_eq!(controller.current_surge, 1);
+        assert!(controller.step(1_000.0));
+        assert!(controller.start());
+        assert_eq!(controller.surge_advance_m, vec![0.0]);
+    }
+
+    #[test]
+    fn later_surges_advance_faster_over_conditioned_soil() {
+        let mut controller = SurgeIrrigationController::new(600, 300, 3, long_basin());
+        // 2 m/min over dry soil, each earlier surge adding half of that again
+        assert_close(controller.advance_velocity_m_per_s(1), 2.0 / 60.0, 1e-6);
+        assert_close(controller.advance_velocity_m_per_s(2), 3.0 / 60.0, 1e-6);
+        assert_close(controller.advance_velocity_m_per_s(3), 4.0 / 60.0, 1e-6);
+        controller.start();
+        assert!(controller.step(3_000.0));
+        assert_eq!(controller.surge_advance_m.len(), 3);
+        for (advance, expected) in controller.surge_advance_m.iter().zip([20.0, 30.0, 40.0]) {
+            assert_close(*advance, expected, 1e-2);
+        }
+        assert_close(controller.front_m, 90.0, 1e-2);
+        assert!(!controller.reached_end());
+    }
+
+    #[test]
+    fn cycle_ends_when_the_front_reaches_the_far_end() {
+        let mut controller = SurgeIrrigationController::new(600, 300, 4, BasinModel::default());
+        controller.start();
+        let mut elapsed_s = 0.0;
+        while !controller.step(10.0) {
+            elapsed_s += 10.0;
+            assert!(elapsed_s < 10_000.0, "the cycle never ended");
+        }
+        elapsed_s += 10.0;
+        // Three surges cover 20 + 30 + 40 m; the fourth covers the last 10 m at 5 m/min in 120 s,
+        // 3 × 600 s of surges and 3 × 300 s of pauses after the start
+        assert!((2_820.0..=2_830.0).contains(&elapsed_s), "ended after {elapsed_s} s");
+        assert!(controller.reached_end());
+        assert_eq!(controller.current_surge, 0);
+        assert_eq!(controller.surge_advance_m.len(), 4);
+        // About 1920 s of pumping, give or take the last 10 s step, against the 3000 s continuous
+        // flow needs to cover 100 m at 2 m/min
+        assert_close(controller.basin.continuous_water_l(), 15_000.0, 1e-2);
+        assert_close(controller.water_applied_l(), 9_600.0, 60.0);
+        assert_close(controller.water_saving_fraction(), 0.36, 0.01);
+    }
+}
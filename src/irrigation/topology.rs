@@ -0,0 +1,158 @@
+//! Pipe segments shared between zones and the flow they can carry together
+
+use std::collections::{BTreeSet, VecDeque};
+
+/// Directed pipe network between zones: `(from_zone, to_zone, shared_pipe_capacity_lps)`.
+/// Zones that no pipe leads into are fed straight from the main supply.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ZoneTopology {
+    pub edges: Vec<(u8, u8, f32)>,
+}
+
+impl ZoneTopology {
+    /// Whether any pipe starts or ends at `zone`
+    pub fn contains(&self, zone: u8) -> bool {
+        self.edges.iter().any(|&(from, to, _)| from == zone || to == zone)
+    }
+
+    /// Most water the supply can deliver to `active_zones` at once, taking at
+    /// most `per_zone_lps` to each, found by Edmonds-Karp max flow
+    fn max_flow(&self, active_zones: &[u8], per_zone_lps: f32) -> f32 {
+        let zones: Vec<u8> = self
+            .edges
+            .iter()
+            .flat_map(|&(from, to, _)| [from, to])
+            .chain(active_zones.iter().copied())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let index = |zone: u8| zones.binary_search(&zone).expect("every zone is indexed");
+        let (source, sink) = (zones.len(), zones.len() + 1);
+        let mut capacity = vec![vec![0.0_f32; zones.len() + 2]; zones.len() + 2];
+        for &(from, to, capacity_lps) in &self.edges {
+            capacity[index(from)][index(to)] += capacity_lps;
+        }
+        for &zone in &zones {
+            if !self.edges.iter().any(|&(_, to, _)| to == zone) {
+                capacity[source][index(zone)] = f32::INFINITY;
+            }
+        }
+        for &zone in active_zones {
+            capacity[index(zone)][sink] = per_zone_lps;
+        }
+
+        let mut total = 0.0;
+        loop {
+            // Shortest augmenting path by breadth-first search over residual capacity
+            let mut parent = vec![None; capacity.len()];
+            let mut queue = VecDeque::from([source]);
+            while let Some(node) = queue.pop_front() {
+                for next in 0..capacity.len() {
+                    if next != source && parent[next].is_none() && capacity[node][next] > 0.0 {
+                        parent[next] = Some(node);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            if parent[sink].is_none() {
+                return total;
+            }
+            let mut bottleneck = f32::INFINITY;
+            let mut node = sink;
+            while let Some(previous) = parent[node] {
+                bottleneck = bottleneck.min(capacity[previous][node]);
+                node = previous;
+            }
+            if bottleneck.is_infinite() {
+                return f32::INFINITY;
+            }
+            let mut node = sink;
+            while let Some(previous) = parent[node] {
+                capacity[previous][node] -= bottleneck;
+                capacity[node][previous] += bottleneck;
+                node = previous;
+            }
+            total += bottleneck;
+        }
+    }
+}
+
+/// Total flow in L/s the pipes can carry to `active_zones` at the same time;
+/// infinite when an active zone is fed straight from the main supply
+pub fn maximum_simultaneous_flow(topology: &ZoneTopology, active_zones: &[u8]) -> f32 {
+    topology.max_flow(active_zones, f32::INFINITY)
+}
+
+/// Starts zones one after another when their shared pipes cannot feed them all at once
+#[derive(Debug, Clone, PartialEq)]
+pub struct IrrigationScheduler {
+    pub topology: ZoneTopology,
+    pub zone_demand_lps: f32, // Flow one watering zone draws
+}
+
+impl IrrigationScheduler {
+    /// Whether `zone` may start watering while the `running` zones are, i.e. the
+    /// pipes can give every one of them its full demand. Zones outside the
+    /// topology are never held back.
+    pub fn may_activate(&self, running: &[u8], zone: u8) -> bool {
+        if !self.topology.contains(zone) {
+            return true;
+        }
+        let active: Vec<u8> = running
+            .iter()
+            .copied()
+            .filter(|&other| other != zone && self.topology.contains(other))
+            .chain([zone])
+            .collect();
+        let demand = self.zone_demand_lps * active.len() as f32;
+        // Allow for rounding in the flow sums
+        self.topology.max_flow(&active, self.zone_demand_lps) >= demand * (1.0 - 1e-4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Main supply → 0, then 0 → 1 (5 L/s), 1 → 2 (3 L/s) and 0 → 3 (4 L/s)
+    fn branched() -> ZoneTopology {
+        ZoneTopology { edges: vec![(0, 1, 5.0), (1, 2, 3.0), (0, 3, 4.0)] }
+    }
+
+    #[test]
+    fn paths_are_limited_by_their_narrowest_pipe() {
+        let topology = branched();
+        assert_eq!(maximum_simultaneous_flow(&topology, &[1]), 5.0);
+        assert_eq!(maximum_simultaneous_flow(&topology, &[2]), 3.0);
+        // Zones 1 and 2 share the 5 L/s pipe into zone 1
+        assert_eq!(maximum_simultaneous_flow(&topology, &[1, 2]), 5.0);
+        // Separate branches add up
+        assert_eq!(maximum_simultaneous_flow(&topology, &[2, 3]), 7.0);
+        assert_eq!(maximum_simultaneous_flow(&topology, &[]), 0.0);
+    }
+
+    #[test]
+    fn zones_on_the_main_supply_are_unlimited() {
+        assert_eq!(maximum_simultaneous_flow(&branched(), &[0]), f32::INFINITY);
+        assert_eq!(maximum_simultaneous_flow(&ZoneTopology::default(), &[4]), f32::INFINITY);
+    }
+
+    #[test]
+    fn parallel_pipes_both_carry_flow() {
+        let topology = ZoneTopology { edges: vec![(0, 1, 2.0), (0, 2, 3.0), (1, 3, 2.0), (2, 3, 1.5)] };
+        assert_eq!(maximum_simultaneous_flow(&topology, &[3]), 3.5);
+    }
+
+    #[test]
+    fn scheduler_serializes_zones_a_shared_pipe_cannot_feed() {
+        let scheduler = IrrigationScheduler { topology: branched(), zone_demand_lps: 2.0 };
+        assert!(scheduler.may_activate(&[1], 2));
+        assert!(scheduler.may_activate(&[1, 2], 3));
+        assert!(scheduler.may_activate(&[1, 2, 3], 9), "zones outside the topology are never held back");
+
+        let thirsty = IrrigationScheduler { topology: branched(), zone_demand_lps: 3.0 };
+        assert!(thirsty.may_activate(&[], 2));
+        assert!(!thirsty.may_activate(&[1], 2), "6 L/s through the 5 L/s pipe");
+        assert!(thirsty.may_activate(&[1], 3));
+    }
+}
@@ -0,0 +1,92 @@
+//! Pressure surges from valve closure in irrigation mains
+//!
+//! A valve closing faster than the pipe's critical time `2L/a` sees the full
+//! Joukowsky surge `ΔP = ρ·a·ΔV`. Slower closure lets the reflected wave
+//! return first, cutting the surge by `(2L/a) / t_c` (Michaud's formula).
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Density of irrigation water, in kg/m³
+const WATER_DENSITY_KG_M3: f32 = 1000.0;
+
+/// How long a solenoid valve takes to close unless a slower closure is configured
+pub const VALVE_CLOSE_TIME_S: f32 = 0.5;
+
+/// A pipe carrying a steady flow that a valve at its end stops completely
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct WaterHammerModel {
+    /// Length from the valve to the nearest reflection point (usually the pump or a tank)
+    pub pipe_length_m: f32,
+    /// Pressure wave speed; around 400 m/s for PVC, 1200 m/s for steel
+    pub wave_speed_ms: f32,
+    /// Flow velocity before the valve closes
+    pub flow_velocity_ms: f32,
+}
+
+impl Default for WaterHammerModel {
+    fn default() -> Self {
+        Self { pipe_length_m: 200.0, wave_speed_ms: 400.0, flow_velocity_ms: 1.5 }
+    }
+}
+
+impl WaterHammerModel {
+    /// Closure time below which the full Joukowsky surge develops, in s
+    pub fn critical_closure_time_s(&self) -> f32 {
+        2.0 * self.pipe_length_m / self.wave_speed_ms
+    }
+
+    /// Peak surge above operating pressure when the valve closes in `closure_time_s`, in kPa
+    pub fn pressure_surge_kpa(&self, closure_time_s: f32) -> f32 {
+        let joukowsky_pa = WATER_DENSITY_KG_M3 * self.wave_speed_ms * self.flow_velocity_ms;
+        let critical = self.critical_closure_time_s();
+        let reduction = if closure_time_s <= critical { 1.0 } else { critical / closure_time_s };
+        joukowsky_pa * reduction / 1000.0
+    }
+
+    /// Shortest closure time that keeps the surge at or below `max_allowable_kpa`, in s
+    pub fn safe_closure_time_s(&self, max_allowable_kpa: f32) -> f32 {
+        let full_surge_kpa = self.pressure_surge_kpa(0.0);
+        if full_surge_kpa <= max_allowable_kpa {
+            0.0
+        } else {
+            self.critical_closure_time_s() * full_surge_kpa / max_allowable_kpa
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn fast_closure_gives_the_full_joukowsky_surge() {
+        // 1000 kg/m³ × 400 m/s × 1.5 m/s = 600 kPa, with a critical time of 2 × 200 m / 400 m/s = 1 s
+        let pvc = WaterHammerModel::default();
+        assert_close(pvc.critical_closure_time_s(), 1.0, 1e-2);
+        assert_close(pvc.pressure_surge_kpa(0.0), 600.0, 1e-2);
+        assert_close(pvc.pressure_surge_kpa(VALVE_CLOSE_TIME_S), 600.0, 1e-2);
+        // 1000 kg/m³ × 1200 m/s × 2 m/s = 2.4 MPa in a steel main
+        let steel = WaterHammerModel { pipe_length_m: 600.0, wave_speed_ms: 1200.0, flow_velocity_ms: 2.0 };
+        assert_close(steel.pressure_surge_kpa(0.2), 2400.0, 1e-2);
+    }
+
+    #[test]
+    fn slow_closure_cuts_the_surge() {
+        let pvc = WaterHammerModel::default();
+        // Michaud: 600 kPa × 1 s / 4 s
+        assert_close(pvc.pressure_surge_kpa(4.0), 150.0, 1e-2);
+        assert!(pvc.pressure_surge_kpa(10.0) < pvc.pressure_surge_kpa(2.0));
+    }
+
+    #[test]
+    fn safe_closure_time_keeps_the_surge_allowable() {
+        let pvc = WaterHammerModel::default();
+        let safe_s = pvc.safe_closure_time_s(300.0);
+        assert_close(safe_s, 2.0, 1e-2);
+        assert_close(pvc.pressure_surge_kpa(safe_s), 300.0, 1e-2);
+        assert_eq!(pvc.safe_closure_time_s(1000.0), 0.0);
+    }
+}
@@ -0,0 +1,115 @@
+//! Library API of the Agri-IoT simulator, for use from tests and research code.
+//!
+//! [`Simulation`] drives one or more irrigation zones without the TUI:
+//!
+//! ```no_run
+//! use agri_iot_simulator::Simulation;
+//!
+//! let mut simulation = Simulation::new(4, 30.0, 42);
+//! let report = simulation.run_for(500);
+//! println!("{:.0} L used", report.total_water_used_l);
+//! ```
+//!
+//! Without the default `std` feature only [`agri_iot_core`] is built, as a
+//! `no_std` + `alloc` library for microcontrollers (enable `embedded`), or
+//! with the core and a browser front end for `wasm32-unknown-unknown` (enable `wasm`).
+
+#![cfg_attr(not(any(feature = "std", feature = "wasm")), no_std)]
+
+#[cfg(all(feature = "std", feature = "embedded"))]
+compile_error!("`embedded` builds the no_std core; disable default features to use it");
+#[cfg(all(feature = "std", feature = "wasm"))]
+compile_error!("`wasm` builds the browser front end; disable default features to use it");
+
+extern crate alloc;
+
+pub mod agri_iot_core;
+#[cfg(feature = "std")]
+pub mod alerts;
+#[cfg(feature = "std")]
+pub mod api;
+#[cfg(feature = "std")]
+pub mod calibration;
+#[cfg(feature = "std")]
+pub mod climate;
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "std")]
+pub mod config;
+#[cfg(feature = "std")]
+pub mod cost_benefit;
+#[cfg(feature = "std")]
+pub mod crop;
+#[cfg(feature = "std")]
+pub mod diagnostics;
+#[cfg(feature = "std")]
+pub mod event_bus;
+#[cfg(feature = "std")]
+pub mod events;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "std")]
+pub mod ghg;
+#[cfg(feature = "std")]
+pub mod hydroponic;
+#[cfg(feature = "std")]
+pub mod irrigation;
+#[cfg(feature = "std")]
+pub mod network;
+#[cfg(feature = "std")]
+pub mod offline_cache;
+#[cfg(feature = "std")]
+pub mod plugin;
+#[cfg(feature = "std")]
+pub mod replay;
+#[cfg(feature = "std")]
+pub mod scenario;
+#[cfg(feature = "std")]
+pub mod simulation;
+#[cfg(feature = "std")]
+pub mod soil;
+#[cfg(feature = "std")]
+pub mod systemd;
+#[cfg(test)]
+pub(crate) mod test_util;
+#[cfg(feature = "std")]
+pub mod tui;
+#[cfg(feature = "std")]
+pub mod uniformity;
+#[cfg(feature = "std")]
+pub mod wal;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+pub mod water_volume;
+
+pub use agri_iot_core::sensor::{
+    DeviceState, ParseDeviceStateError, SensorReading, SoilMoistureSensor, StatusMessage, WATERING_BOOST,
+};
+pub use agri_iot_core::{
+    root_zone, tick, BarometricPressureSensor, CO2Sensor, HumiditySensor, LightSensor, PHSensor, RainfallSensor,
+    SensorId, SoilTemperatureSensor, TemperatureSensor, TensiometerSensor, Tick, WaterLevelSensor, WindSensor,
+};
+#[cfg(feature = "std")]
+pub use simulation::{IrrigationZone, Simulation, SimulationReport, ZoneState};
+
+#[cfg(feature = "std")]
+impl SoilMoistureSensor {
+    /// Transitions the sensor state based on moisture levels, then waits out the one-second tick
+    pub async fn transition(&mut self, new_moisture: f32) -> Option<StatusMessage> {
+        let message = self.step(new_moisture);
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        message
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl SoilMoistureSensor {
+    /// Transitions the sensor state based on moisture levels, then waits out the one-second tick on a browser timer
+    pub async fn transition(&mut self, new_moisture: f32) -> Option<StatusMessage> {
+        let message = self.step(new_moisture);
+        wasm::sleep(1000).await;
+        message
+    }
+}
+
@@ -0,0 +1,128 @@
+//! Simulated field gateway uplink: packet loss, latency, limited bandwidth and outages
+//!
+//! Readings and events are passed through a [`NetworkLink`] before they reach
+//! the broker. Readings that do not get through are gone; events wait in a
+//! [`QosBuffer`] and are retried until they are delivered.
+
+use rand::Rng;
+use std::collections::VecDeque;
+
+use crate::events::AgriEvent;
+
+/// Size of one zone reading on the wire: the JSON payload plus MQTT and TCP/IP headers
+pub const READING_PAYLOAD_BYTES: u64 = 120;
+
+/// Size of one event on the wire
+pub const EVENT_PAYLOAD_BYTES: u64 = 160;
+
+/// Uplink from the gateway to the broker
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkLink {
+    pub packet_loss_rate: f64, // Chance that any one packet is lost, 0 to 1
+    pub latency_ms: u64,       // One-way delay of an otherwise idle link
+    pub bandwidth_bps: u64,
+    pub connected: bool,
+    pub packets_sent: u64, // Every packet put on the link, lost or not
+    pub packets_lost: u64,
+    pub total_latency_ms: u64, // Summed over the delivered packets
+    backlog_bits: u64,         // Sent but not yet serialized onto the link
+}
+
+impl NetworkLink {
+    pub fn new(packet_loss_rate: f64, latency_ms: u64, bandwidth_bps: u64) -> Self {
+        Self {
+            packet_loss_rate,
+            latency_ms,
+            bandwidth_bps,
+            connected: true,
+            packets_sent: 0,
+            packets_lost: 0,
+            total_latency_ms: 0,
+            backlog_bits: 0,
+        }
+    }
+
+    /// Whether the gateway can reach the broker
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Brings the link down or back up
+    pub fn set_connected(&mut self, connected: bool) {
+        self.connected = connected;
+    }
+
+    /// Lets `elapsed_ms` pass, serializing that much of the queued traffic
+    pub fn advance(&mut self, elapsed_ms: u64) {
+        self.backlog_bits = self.backlog_bits.saturating_sub(self.bandwidth_bps * elapsed_ms / 1000);
+    }
+
+    /// Sends a packet of `payload_bytes`. It waits behind the traffic the bandwidth has not
+    /// carried yet, then is lost with the link's loss rate. Returns the delay it arrived
+    /// after, or `None` if it was lost or the link is down.
+    pub fn transmit(&mut self, payload_bytes: u64, rng: &mut impl Rng) -> Option<u64> {
+        if !self.connected {
+            return None;
+        }
+        self.packets_sent += 1;
+        self.backlog_bits += payload_bytes * 8;
+        if rng.gen_bool(self.packet_loss_rate.clamp(0.0, 1.0)) {
+            self.packets_lost += 1;
+            return None;
+        }
+        let queueing_ms = self.backlog_bits * 1000 / self.bandwidth_bps.max(1);
+        let latency_ms = self.latency_ms + queueing_ms;
+        self.total_latency_ms += latency_ms;
+        Some(latency_ms)
+    }
+
+    /// Share of the packets sent that were lost
+    pub fn loss_fraction(&self) -> f64 {
+        if self.packets_sent == 0 {
+            return 0.0;
+        }
+        self.packets_lost as f64 / self.packets_sent as f64
+    }
+
+    /// Mean delay of the delivered packets, in ms
+    pub fn mean_latency_ms(&self) -> f64 {
+        let delivered = self.packets_sent - self.packets_lost;
+        if delivered == 0 {
+            return 0.0;
+        }
+        self.total_latency_ms as f64 / delivered as f64
+    }
+}
+
+/// Events held at the gateway until the broker acknowledges them (MQTT QoS 1)
+#[derive(Debug, Clone, PartialEq)]
+pub struct QosBuffer {
+    pub max_size: usize,
+    pub queue: VecDeque<AgriEvent>, // Oldest first
+    pub dropped: u64,               // Discarded to make room while full
+}
+
+impl QosBuffer {
+    pub fn new(max_size: usize) -> Self {
+        Self { max_size, queue: VecDeque::new(), dropped: 0 }
+    }
+
+    /// Queues `event`, discarding the oldest one when the buffer is full
+    pub fn push(&mut self, event: AgriEvent) {
+        if self.queue.len() >= self.max_size {
+            self.queue.pop_front();
+            self.dropped += 1;
+        }
+        self.queue.push_back(event);
+    }
+
+    /// Sends queued events in order while `link` is up. A lost event stays at the front
+    /// to be retried on the next flush. Returns the events that got through, oldest first.
+    pub fn flush(&mut self, link: &mut NetworkLink, rng: &mut impl Rng) -> Vec<AgriEvent> {
+        let mut delivered = Vec::new();
+        while !self.queue.is_empty() && link.transmit(EVENT_PAYLOAD_BYTES, rng).is_some() {
+            delivered.extend(self.queue.pop_front());
+        }
+        delivered
+    }
+}
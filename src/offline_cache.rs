@@ -0,0 +1,216 @@
+//! SQLite store for readings taken while the uplink is down, replayed when it returns
+//!
+//! The database runs in WAL mode so a store on every tick of an outage costs
+//! one append rather than a rewrite of the page it lands on.
+
+use rusqlite::{params, Connection};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{SoilMoistureSensor, Tick};
+
+/// One reading as it was taken
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedReading {
+    pub tick: Tick,
+    pub timestamp_ms: u64, // Wall clock time of the reading, since the Unix epoch
+    pub zone_id: usize,
+    pub sensor_id: String,
+    pub moisture_level: f32,
+    pub state: String,
+    pub water_pump_running: bool,
+}
+
+impl CachedReading {
+    /// `sensor`'s current reading, stamped with the time now
+    pub fn now(zone_id: usize, sensor: &SoilMoistureSensor) -> Self {
+        Self {
+            tick: sensor.current_tick,
+            timestamp_ms: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_millis() as u64),
+            zone_id,
+            sensor_id: sensor.sensor_id.to_string(),
+            moisture_level: sensor.moisture_level,
+            state: sensor.state.name().to_string(),
+            water_pump_running: sensor.water_running(),
+        }
+    }
+}
+
+/// Readings through the cache since it was opened
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub entries_buffered: u64, // Stored while offline
+    pub entries_flushed: u64,  // Handed back for publishing
+    pub entries_expired: u64,  // Discarded, oldest first, to stay within `max_entries`
+}
+
+/// Readings held on disk until the broker can be reached, oldest discarded beyond `max_entries`
+pub struct OfflineCache {
+    pub db_path: PathBuf,
+    pub max_entries: usize,
+    pub stats: CacheStats,
+    connection: Connection,
+}
+
+impl OfflineCache {
+    /// Opens the database at `db_path`, creating it if needed. Readings left by an earlier
+    /// run stay cached and are flushed with the next ones.
+    pub fn open(db_path: impl Into<PathBuf>, max_entries: usize) -> rusqlite::Result<Self> {
+        let db_path = db_path.into();
+        let connection = Connection::open(&db_path)?;
+        connection.pragma_update(None, "journal_mode", "WAL")?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS readings (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                tick INTEGER NOT NULL,
+                timestamp_ms INTEGER NOT NULL,
+                zone_id INTEGER NOT NULL,
+                sensor_id TEXT NOT NULL,
+                moisture_level REAL NOT NULL,
+                state TEXT NOT NULL,
+                water_pump_running INTEGER NOT NULL
+            )",
+        )?;
+        Ok(Self { db_path, max_entries, stats: CacheStats::default(), connection })
+    }
+
+    /// Readings waiting to be flushed
+    pub fn len(&self) -> rusqlite::Result<usize> {
+        self.connection.query_row("SELECT COUNT(*) FROM readings", [], |row| row.get(0))
+    }
+
+    pub fn is_empty(&self) -> rusqlite::Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Stores `reading`, expiring the oldest ones beyond `max_entries`
+    pub fn store(&mut self, reading: &CachedReading) -> rusqlite::Result<()> {
+        self.connection.execute(
+            "INSERT INTO readings (tick, timestamp_ms, zone_id, sensor_id, moisture_level, state, water_pump_running)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                reading.tick.0 as i64,
+                reading.timestamp_ms as i64,
+                reading.zone_id as i64,
+                reading.sensor_id,
+                f64::from(reading.moisture_level),
+                reading.state,
+                reading.water_pump_running,
+            ],
+        )?;
+        self.stats.entries_buffered += 1;
+        let expired = self.connection.execute(
+            "DELETE FROM readings WHERE id NOT IN (SELECT id FROM readings ORDER BY id DESC LIMIT ?1)",
+            params![self.max_entries as i64],
+        )?;
+        self.stats.entries_expired += expired as u64;
+        Ok(())
+    }
+
+    /// Removes up to `limit` of the oldest cached readings and returns them in the order they were taken
+    pub fn flush(&mut self, limit: usize) -> rusqlite::Result<Vec<CachedReading>> {
+        let transaction = self.connection.transaction()?;
+        let readings = {
+            let mut statement = transaction.prepare(
+                "SELECT tick, timestamp_ms, zone_id, sensor_id, moisture_level, state, water_pump_running
+                 FROM readings ORDER BY id LIMIT ?1",
+            )?;
+            let rows = statement.query_map(params![limit as i64], |row| {
+                Ok(CachedReading {
+                    tick: Tick(row.get::<_, i64>(0)? as u64),
+                    timestamp_ms: row.get::<_, i64>(1)? as u64,
+                    zone_id: row.get::<_, i64>(2)? as usize,
+                    sensor_id: row.get(3)?,
+                    moisture_level: row.get::<_, f64>(4)? as f32,
+                    state: row.get(5)?,
+                    water_pump_running: row.get(6)?,
+                })
+            })?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        transaction.execute(
+            "DELETE FROM readings WHERE id IN (SELECT id FROM readings ORDER BY id LIMIT ?1)",
+            params![limit as i64],
+        )?;
+        transaction.commit()?;
+        self.stats.entries_flushed += readings.len() as u64;
+        Ok(readings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::NetworkLink;
+    use crate::simulation::Simulation;
+    use std::path::Path;
+
+    fn temp_db(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("agri-offline-{name}-{}.db", std::process::id()))
+    }
+
+    fn remove_db(path: &Path) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+    }
+
+    #[test]
+    fn outage_readings_are_flushed_in_order_on_reconnect() {
+        let path = temp_db("outage");
+        let mut cache = OfflineCache::open(&path, 1_000).unwrap();
+        let mut simulation = Simulation::new(2, 30.0, 9);
+        let mut link = NetworkLink::new(0.0, 50, 250_000);
+        link.set_connected(false);
+        let mut stored = Vec::new();
+        for _ in 0..100 {
+            simulation.run_for(1);
+            assert!(!link.is_connected());
+            for (zone_id, zone) in simulation.zones.iter().enumerate() {
+                let reading = CachedReading::now(zone_id, &zone.sensor);
+                cache.store(&reading).unwrap();
+                stored.push(reading);
+            }
+        }
+        assert_eq!(cache.len().unwrap(), 200);
+
+        link.set_connected(true);
+        let mut flushed = Vec::new();
+        while !cache.is_empty().unwrap() {
+            flushed.extend(cache.flush(64).unwrap());
+        }
+        remove_db(&path);
+        // Every reading keeps the tick and wall clock time it was taken at
+        assert_eq!(flushed, stored);
+        assert!(flushed.windows(2).all(|pair| (pair[0].tick, pair[0].zone_id) < (pair[1].tick, pair[1].zone_id)));
+        assert_eq!((flushed[0].tick, flushed[199].tick), (Tick(1), Tick(100)));
+        assert_eq!(cache.stats, CacheStats { entries_buffered: 200, entries_flushed: 200, entries_expired: 0 });
+    }
+
+    #[test]
+    fn oldest_readings_expire_beyond_the_limit() {
+        let path = temp_db("expiry");
+        let mut cache = OfflineCache::open(&path, 10).unwrap();
+        let mut sensor = SoilMoistureSensor::new(30.0, 50.0);
+        for _ in 0..15 {
+            sensor.step(sensor.moisture_level - 1.0);
+            cache.store(&CachedReading::now(0, &sensor)).unwrap();
+        }
+        let ticks: Vec<u64> = cache.flush(100).unwrap().iter().map(|reading| reading.tick.0).collect();
+        assert_eq!(ticks, (6..=15).collect::<Vec<_>>());
+        assert_eq!(cache.stats, CacheStats { entries_buffered: 15, entries_flushed: 10, entries_expired: 5 });
+        remove_db(&path);
+    }
+
+    #[test]
+    fn readings_survive_a_restart() {
+        let path = temp_db("restart");
+        let sensor = SoilMoistureSensor::new(30.0, 42.0);
+        OfflineCache::open(&path, 10).unwrap().store(&CachedReading::now(3, &sensor)).unwrap();
+        let mut reopened = OfflineCache::open(&path, 10).unwrap();
+        let readings = reopened.flush(10).unwrap();
+        remove_db(&path);
+        assert_eq!(readings.len(), 1);
+        assert_eq!((readings[0].zone_id, readings[0].moisture_level), (3, 42.0));
+    }
+}
@@ -0,0 +1,133 @@
+//! Sensors added at run time from dynamic libraries
+//!
+//! Every `[[plugins]]` table names a library built as a `cdylib` that exports
+//! `fn agri_plugin_entry() -> Box<dyn Plugin>` under that unmangled name. The loader opens the library,
+//! asks its plugin for one sensor built from the table's `config` and reads that sensor every tick.
+//! Trait objects cross the library boundary with the Rust ABI, so a plugin must be built with the
+//! same compiler and the same version of this crate as the simulator loading it;
+//! `plugins/example_plugin` is one.
+
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use libloading::Library;
+
+/// Symbol every plugin library exports
+pub const PLUGIN_ENTRY_SYMBOL: &str = "agri_plugin_entry";
+
+/// Signature of [`PLUGIN_ENTRY_SYMBOL`]
+pub type PluginEntry = fn() -> Box<dyn Plugin>;
+
+/// Something that takes one reading of type `T` per tick
+pub trait Sensor<T>: Send {
+    /// Shown next to the readings, e.g. "Leaf wetness"
+    fn name(&self) -> &str;
+    /// Unit the readings are in, e.g. "%"
+    fn unit(&self) -> &str;
+    /// Takes the next reading
+    fn read(&mut self) -> T;
+}
+
+/// A library's entry point, which builds its sensors
+pub trait Plugin: Send {
+    fn name(&self) -> &str;
+    /// Major, minor and patch version
+    fn version(&self) -> (u32, u32, u32);
+    /// Builds a sensor from its `[[plugins]]` table's `config`, an empty table when none is set
+    fn create_sensor(&self, config: &toml::Value) -> Box<dyn Sensor<f32>>;
+}
+
+/// Why a plugin could not be loaded
+#[derive(Debug)]
+pub enum PluginError {
+    /// The library could not be opened or does not export [`PLUGIN_ENTRY_SYMBOL`]
+    Library { path: PathBuf, err: libloading::Error },
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginError::Library { path, err } => write!(f, "cannot load plugin {}: {}", path.display(), err),
+        }
+    }
+}
+
+impl Error for PluginError {}
+
+/// A plugin, the sensor it built and the library both came from
+pub struct LoadedPlugin {
+    // Fields drop in order, so the sensor and plugin go before the code behind them is unloaded
+    pub sensor: Box<dyn Sensor<f32>>,
+    pub plugin: Box<dyn Plugin>,
+    pub last_reading: Option<f32>,
+    _library: Library,
+}
+
+impl LoadedPlugin {
+    /// Takes the sensor's next reading and keeps it for the TUI
+    pub fn read(&mut self) -> f32 {
+        let reading = self.sensor.read();
+        self.last_reading = Some(reading);
+        reading
+    }
+
+    /// "name major.minor.patch"
+    pub fn label(&self) -> String {
+        let (major, minor, patch) = self.plugin.version();
+        format!("{} {major}.{minor}.{patch}", self.plugin.name())
+    }
+}
+
+/// Every plugin loaded so far, kept loaded until the loader is dropped
+#[derive(Default)]
+pub struct PluginLoader {
+    plugins: Vec<LoadedPlugin>,
+}
+
+impl fmt::Debug for PluginLoader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.plugins.iter().map(LoadedPlugin::label)).finish()
+    }
+}
+
+impl PluginLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens the library at `path`, calls its entry point and builds one sensor from `config`
+    pub fn load(&mut self, path: &Path, config: &toml::Value) -> Result<&mut LoadedPlugin, PluginError> {
+        let library_error = |err| PluginError::Library { path: path.to_path_buf(), err };
+        // SAFETY: loading runs the library's initializers and trusts its entry point to have the
+        // `PluginEntry` signature; plugins are configured by the operator, like the simulator itself
+        let library = unsafe { Library::new(path) }.map_err(library_error)?;
+        let entry = unsafe { library.get::<PluginEntry>(PLUGIN_ENTRY_SYMBOL.as_bytes()) }.map_err(library_error)?;
+        let plugin = entry();
+        let sensor = plugin.create_sensor(config);
+        self.plugins.push(LoadedPlugin { sensor, plugin, last_reading: None, _library: library });
+        Ok(self.plugins.last_mut().expect("just pushed"))
+    }
+
+    pub fn plugins(&self) -> &[LoadedPlugin] {
+        &self.plugins
+    }
+
+    pub fn plugins_mut(&mut self) -> &mut [LoadedPlugin] {
+        &mut self.plugins
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_library_is_an_error() {
+        let mut loader = PluginLoader::new();
+        let config = toml::Value::Table(toml::Table::new());
+        let err = loader.load(Path::new("/nonexistent/libno_such_plugin.so"), &config).err().expect("no library");
+        assert!(err.to_string().starts_with("cannot load plugin /nonexistent/libno_such_plugin.so"));
+        assert!(loader.plugins().is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+//! Playback of a recorded session from the JSON lines export
+//!
+//! An `[[export]]` with `format = "json"` writes one reading per line. Played back, the
+//! recording advances a tick per [`TICK_INTERVAL`] at 1× speed, with every reading of that
+//! tick applied at once.
+
+use serde::{Deserialize, Deserializer};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::simulation::TICK_INTERVAL;
+use crate::{DeviceState, Tick};
+
+/// Playback speeds `]` and `[` step through
+pub const SPEEDS: [f32; 4] = [1.0, 2.0, 4.0, 8.0];
+/// Entries the arrow keys jump by
+pub const SEEK_ENTRIES: usize = 10;
+
+/// One line of the recording, a reading as [`JsonExporter`](crate::export::JsonExporter)
+/// wrote it
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NdjsonEntry {
+    pub tick: Tick,
+    pub sensor_id: String,
+    #[serde(deserialize_with = "state_from_name")]
+    pub state: DeviceState,
+    pub moisture_level: f32,
+}
+
+fn state_from_name<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DeviceState, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    name.parse().map_err(serde::de::Error::custom)
+}
+
+/// Where playback is in the recording and how fast it runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayController {
+    pub ndjson_path: PathBuf,
+    pub entries: Vec<NdjsonEntry>, // In recorded order, so by tick
+    pub current_index: usize,      // Last entry shown
+    pub speed: f32,                // One of `SPEEDS`
+    pub paused: bool,
+    pending: Duration, // Playback time not yet spent on a tick
+}
+
+impl ReplayController {
+    /// Reads the recording at `ndjson_path`, skipping blank lines
+    pub fn load(ndjson_path: impl Into<PathBuf>) -> io::Result<Self> {
+        let ndjson_path = ndjson_path.into();
+        let text = fs::read_to_string(&ndjson_path)?;
+        let entries = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|err| {
+                    let message = format!("{} line {}: {err}", ndjson_path.display(), index + 1);
+                    io::Error::new(io::ErrorKind::InvalidData, message)
+                })
+            })
+            .collect::<io::Result<Vec<NdjsonEntry>>>()?;
+        if entries.is_empty() {
+            let message = format!("{} holds no readings to replay", ndjson_path.display());
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        }
+        Ok(Self::new(ndjson_path, entries))
+    }
+
+    /// Playback of `entries` at 1×, showing the first tick
+    pub fn new(ndjson_path: PathBuf, entries: Vec<NdjsonEntry>) -> Self {
+        let mut replay =
+            Self { ndjson_path, entries, current_index: 0, speed: SPEEDS[0], paused: false, pending: Duration::ZERO };
+        replay.current_index = replay.end_of_tick(0);
+        replay
+    }
+
+    /// Last index of the tick `index` is in
+    fn end_of_tick(&self, index: usize) -> usize {
+        let Some(tick) = self.entries.get(index).map(|entry| entry.tick) else {
+            return index;
+        };
+        let rest = &self.entries[index..];
+        index + rest.iter().take_while(|entry| entry.tick == tick).count() - 1
+    }
+
+    /// Doubles the speed, up to the fastest of [`SPEEDS`]
+    pub fn faster(&mut self) {
+        self.speed = SPEEDS.into_iter().find(|&speed| speed > self.speed).unwrap_or(SPEEDS[SPEEDS.len() - 1]);
+    }
+
+    /// Halves the speed, down to 1×
+    pub fn slower(&mut self) {
+        self.speed = SPEEDS.into_iter().rev().find(|&speed| speed < self.speed).unwrap_or(SPEEDS[0]);
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Moves `entries` forward, or back when negative, stopping at either end
+    pub fn seek(&mut self, entries: isize) {
+        let last = self.entries.len().saturating_sub(1);
+        self.current_index = self.current_index.saturating_add_signed(entries).min(last);
+        self.pending = Duration::ZERO;
+    }
+
+    /// Spends `elapsed` wall time at the current speed, a tick per [`TICK_INTERVAL`]; nothing
+    /// while paused or at the end
+    pub fn advance(&mut self, elapsed: Duration) {
+        if self.paused {
+            return;
+        }
+        self.pending += elapsed.mul_f32(self.speed);
+        while self.pending >= TICK_INTERVAL && !self.is_finished() {
+            self.pending -= TICK_INTERVAL;
+            self.current_index = self.end_of_tick(self.current_index + 1);
+        }
+        if self.is_finished() {
+            self.pending = Duration::ZERO;
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current_index + 1 >= self.entries.len()
+    }
+
+    pub fn current_tick(&self) -> Tick {
+        self.entries.get(self.current_index).map_or(Tick::ZERO, |entry| entry.tick)
+    }
+
+    /// The latest reading of `sensor_id` up to the current entry
+    pub fn latest(&self, sensor_id: &str) -> Option<&NdjsonEntry> {
+        let shown = self.entries.get(..=self.current_index).unwrap_or_default();
+        shown.iter().rev().find(|entry| entry.sensor_id == sensor_id)
+    }
+
+    /// Every sensor in the recording, in the order each first appears
+    pub fn sensor_ids(&self) -> Vec<&str> {
+        let mut sensor_ids = Vec::new();
+        for entry in &self.entries {
+            if !sensor_ids.contains(&entry.sensor_id.as_str()) {
+                sensor_ids.push(entry.sensor_id.as_str());
+            }
+        }
+        sensor_ids
+    }
+
+    /// Playback time from the start of the recording to the current tick, at 1×
+    pub fn position(&self) -> Duration {
+        self.since_start(self.current_tick())
+    }
+
+    /// Playback time of the whole recording, at 1×
+    pub fn duration(&self) -> Duration {
+        self.since_start(self.entries.last().map_or(Tick::ZERO, |entry| entry.tick))
+    }
+
+    fn since_start(&self, tick: Tick) -> Duration {
+        let first = self.entries.first().map_or(Tick::ZERO, |entry| entry.tick);
+        TICK_INTERVAL * tick.0.saturating_sub(first.0) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{DataExporter, JsonExporter};
+    use crate::{SensorId, SensorReading};
+
+    /// Two sensors read on ticks 100 to 129, as the JSON export writes them
+    fn recording() -> ReplayController {
+        let path = std::env::temp_dir().join(format!("agri-replay-{}.ndjson", std::process::id()));
+        let mut exporter = JsonExporter::create(&path).unwrap();
+        for tick in 100..130 {
+            for zone_id in 0..2 {
+                let moisture_level = 40.0 + tick as f32 - 100.0 + zone_id as f32 * 0.5;
+                let sensor_id = SensorId::for_zone(zone_id);
+                let state = DeviceState::Monitoring;
+                exporter.write(&SensorReading { sensor_id, tick: Tick(tick), state, moisture_level }).unwrap();
+            }
+        }
+        exporter.flush().unwrap();
+        drop(exporter);
+        let replay = ReplayController::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        replay
+    }
+
+    #[test]
+    fn loads_the_json_export_and_shows_the_first_tick() {
+        let replay = recording();
+        assert_eq!(replay.entries.len(), 60);
+        assert_eq!((replay.current_index, replay.current_tick()), (1, Tick(100)));
+        assert_eq!(replay.sensor_ids(), ["zone-0", "zone-1"]);
+        assert_eq!(replay.latest("zone-1").unwrap().moisture_level, 40.5);
+        assert_eq!((replay.position(), replay.duration()), (Duration::ZERO, TICK_INTERVAL * 29));
+    }
+
+    #[test]
+    fn speed_steps_through_the_presets_and_stops_at_either_end() {
+        let mut replay = recording();
+        replay.slower();
+        assert_eq!(replay.speed, 1.0);
+        let mut speeds = Vec::new();
+        for _ in 0..4 {
+            replay.faster();
+            speeds.push(replay.speed);
+        }
+        assert_eq!(speeds, [2.0, 4.0, 8.0, 8.0]);
+        replay.slower();
+        replay.slower();
+        assert_eq!(replay.speed, 2.0);
+    }
+
+    #[test]
+    fn playback_covers_a_tick_per_interval_at_the_speed() {
+        let mut replay = recording();
+        replay.advance(TICK_INTERVAL);
+        assert_eq!((replay.current_tick(), replay.current_index), (Tick(101), 3));
+        replay.faster();
+        replay.faster();
+        // Half an interval at 4× is two ticks, with the rest kept for the next call
+        replay.advance(TICK_INTERVAL / 2);
+        assert_eq!(replay.current_tick(), Tick(103));
+        replay.toggle_pause();
+        replay.advance(TICK_INTERVAL * 10);
+        assert_eq!(replay.current_tick(), Tick(103));
+        replay.toggle_pause();
+        replay.advance(TICK_INTERVAL / 8);
+        replay.advance(TICK_INTERVAL / 8);
+        assert_eq!(replay.current_tick(), Tick(104));
+        assert_eq!(replay.position(), TICK_INTERVAL * 4);
+        replay.advance(TICK_INTERVAL * 100);
+        assert!(replay.is_finished());
+        assert_eq!(replay.current_tick(), Tick(129));
+    }
+
+    #[test]
+    fn seeking_stops_at_the_first_and_last_entries() {
+        let mut replay = recording();
+        replay.seek(SEEK_ENTRIES as isize);
+        assert_eq!((replay.current_index, replay.current_tick()), (11, Tick(105)));
+        // Only readings up to the current entry are shown
+        assert_eq!(replay.latest("zone-0").unwrap().tick, Tick(105));
+        assert_eq!(replay.latest("zone-1").unwrap().tick, Tick(105));
+        replay.seek(-(SEEK_ENTRIES as isize) * 2);
+        assert_eq!(replay.current_index, 0);
+        assert!(replay.latest("zone-1").is_none());
+        for _ in 0..7 {
+            replay.seek(SEEK_ENTRIES as isize);
+        }
+        assert_eq!(replay.current_index, 59);
+        assert!(replay.is_finished());
+        replay.seek(-(SEEK_ENTRIES as isize));
+        assert_eq!((replay.current_index, replay.is_finished()), (49, false));
+    }
+
+    #[test]
+    fn bad_lines_and_empty_recordings_are_errors() {
+        let path = std::env::temp_dir().join(format!("agri-replay-bad-{}.ndjson", std::process::id()));
+        let reading = r#"{"tick":1,"sensor_id":"zone-0","state":"Monitoring","moisture_level":40.0}"#;
+        fs::write(&path, format!("{reading}\n\n{{\"tick\":2}}\n")).unwrap();
+        let err = ReplayController::load(&path).unwrap_err();
+        assert!(err.to_string().ends_with("line 3: missing field `sensor_id` at line 1 column 10"), "{err}");
+        fs::write(&path, "\n").unwrap();
+        assert!(ReplayController::load(&path).unwrap_err().to_string().ends_with("holds no readings to replay"));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,284 @@
+//! Scripted weather and field-operation events for reproducible simulation runs.
+//!
+//! Scenarios are normally written with [`ScenarioBuilder`] and saved as TOML
+//! fixtures rather than edited by hand, then played through a
+//! [`Simulation`](crate::simulation::Simulation):
+//!
+//! ```no_run
+//! use agri_iot_simulator::scenario::ScenarioBuilder;
+//! use std::path::Path;
+//!
+//! let scenario = ScenarioBuilder::new()
+//!     .drought_period(0, 200)
+//!     .rain_event(201, 30.0)
+//!     .fertilize(50, 10.0)
+//!     .freeze_event(100, -3.0)
+//!     .build()
+//!     .expect("no conflicting events");
+//! scenario.save_toml(Path::new("fixtures/drought.toml")).unwrap();
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// One scripted event, keyed by simulation tick
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScenarioEvent {
+    /// No rain and no irrigation water for ticks `start..start + ticks`
+    Drought { start: u64, ticks: u64 },
+    /// Rainfall of `mm` millimetres at `tick`
+    Rain { tick: u64, mm: f32 },
+    /// Nitrogen fertilizer application of `n_kg_ha` kg/ha at `tick`
+    Fertilize { tick: u64, n_kg_ha: f32 },
+    /// Air temperature drop to `temp_c` °C at `tick`
+    Freeze { tick: u64, temp_c: f32 },
+}
+
+/// Two events that cannot both happen as scripted
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScenarioConflict {
+    /// Rain and a freeze scheduled for the same tick
+    RainDuringFreeze { tick: u64 },
+    /// Rain scheduled inside a drought period
+    RainDuringDrought { tick: u64, drought_start: u64, drought_ticks: u64 },
+}
+
+impl fmt::Display for ScenarioConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioConflict::RainDuringFreeze { tick } => {
+                write!(f, "rain and freeze both scheduled at tick {}", tick)
+            }
+            ScenarioConflict::RainDuringDrought { tick, drought_start, drought_ticks } => write!(
+                f,
+                "rain at tick {} falls inside the drought at ticks {}..{}",
+                tick,
+                drought_start,
+                drought_start + drought_ticks
+            ),
+        }
+    }
+}
+
+/// A validated, ordered list of scripted events
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Scenario {
+    pub events: Vec<ScenarioEvent>,
+}
+
+impl Scenario {
+    /// Writes the scenario as a TOML file with one `[[events]]` table per event
+    pub fn save_toml(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Reads a scenario written by [`Self::save_toml`]
+    pub fn load_toml(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Whether `tick` falls inside a drought
+    pub fn in_drought(&self, tick: u64) -> bool {
+        self.events.iter().any(|event| {
+            matches!(*event, ScenarioEvent::Drought { start, ticks } if (start..start + ticks).contains(&tick))
+        })
+    }
+
+    /// Rain falling at `tick`, in mm
+    pub fn rain_mm(&self, tick: u64) -> f32 {
+        self.events
+            .iter()
+            .map(|event| match *event {
+                ScenarioEvent::Rain { tick: at, mm } if at == tick => mm,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Nitrogen spread at `tick`, in kg/ha
+    pub fn fertilizer_n_kg_ha(&self, tick: u64) -> f32 {
+        self.events
+            .iter()
+            .map(|event| match *event {
+                ScenarioEvent::Fertilize { tick: at, n_kg_ha } if at == tick => n_kg_ha,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Air temperature a freeze holds `tick` at, the coldest if several are scripted
+    pub fn freeze_temp_c(&self, tick: u64) -> Option<f32> {
+        self.events
+            .iter()
+            .filter_map(|event| match *event {
+                ScenarioEvent::Freeze { tick: at, temp_c } if at == tick => Some(temp_c),
+                _ => None,
+            })
+            .reduce(f32::min)
+    }
+}
+
+/// Fluent builder that checks events for conflicts before producing a [`Scenario`]
+#[derive(Debug, Clone, Default)]
+pub struct ScenarioBuilder {
+    events: Vec<ScenarioEvent>,
+}
+
+impl ScenarioBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a drought lasting `ticks` from tick `start`
+    pub fn drought_period(mut self, start: u64, ticks: u64) -> Self {
+        self.events.push(ScenarioEvent::Drought { start, ticks });
+        self
+    }
+
+    /// Adds `mm` millimetres of rain at `tick`
+    pub fn rain_event(mut self, tick: u64, mm: f32) -> Self {
+        self.events.push(ScenarioEvent::Rain { tick, mm });
+        self
+    }
+
+    /// Adds a nitrogen application of `n` kg/ha at `tick`
+    pub fn fertilize(mut self, tick: u64, n: f32) -> Self {
+        self.events.push(ScenarioEvent::Fertilize { tick, n_kg_ha: n });
+        self
+    }
+
+    /// Adds a freeze down to `temp_c` °C at `tick`
+    pub fn freeze_event(mut self, tick: u64, temp_c: f32) -> Self {
+        self.events.push(ScenarioEvent::Freeze { tick, temp_c });
+        self
+    }
+
+    /// Every pair of events that cannot both happen as scripted
+    pub fn conflicts(&self) -> Vec<ScenarioConflict> {
+        let mut conflicts = Vec::new();
+        for rain_tick in self.events.iter().filter_map(|event| match event {
+            ScenarioEvent::Rain { tick, .. } => Some(*tick),
+            _ => None,
+        }) {
+            for event in &self.events {
+                match *event {
+                    ScenarioEvent::Freeze { tick, .. } if tick == rain_tick => {
+                        conflicts.push(ScenarioConflict::RainDuringFreeze { tick });
+                    }
+                    ScenarioEvent::Drought { start, ticks } if (start..start + ticks).contains(&rain_tick) => {
+                        conflicts.push(ScenarioConflict::RainDuringDrought {
+                            tick: rain_tick,
+                            drought_start: start,
+                            drought_ticks: ticks,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Produces the scenario with events sorted by tick, or every conflict found
+    pub fn build(self) -> Result<Scenario, Vec<ScenarioConflict>> {
+        let conflicts = self.conflicts();
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+        let mut events = self.events;
+        events.sort_by_key(|event| match *event {
+            ScenarioEvent::Drought { start, .. } => start,
+            ScenarioEvent::Rain { tick, .. }
+            | ScenarioEvent::Fertilize { tick, .. }
+            | ScenarioEvent::Freeze { tick, .. } => tick,
+        });
+        Ok(Scenario { events })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::Simulation;
+
+    #[test]
+    fn rain_and_freeze_on_the_same_tick_conflict() {
+        let conflicts = ScenarioBuilder::new().rain_event(100, 30.0).freeze_event(100, -3.0).build().unwrap_err();
+        assert_eq!(conflicts, vec![ScenarioConflict::RainDuringFreeze { tick: 100 }]);
+    }
+
+    #[test]
+    fn rain_inside_a_drought_conflicts() {
+        let conflicts = ScenarioBuilder::new().drought_period(50, 100).rain_event(120, 10.0).build().unwrap_err();
+        assert_eq!(
+            conflicts,
+            vec![ScenarioConflict::RainDuringDrought { tick: 120, drought_start: 50, drought_ticks: 100 }]
+        );
+    }
+
+    #[test]
+    fn rain_just_after_a_drought_does_not_conflict() {
+        let scenario = ScenarioBuilder::new().drought_period(0, 200).rain_event(200, 30.0).build().unwrap();
+        assert_eq!(scenario.events.len(), 2);
+    }
+
+    #[test]
+    fn every_conflict_is_reported() {
+        let conflicts = ScenarioBuilder::new()
+            .drought_period(0, 10)
+            .rain_event(5, 1.0)
+            .freeze_event(5, -1.0)
+            .rain_event(20, 1.0)
+            .freeze_event(20, -2.0)
+            .build()
+            .unwrap_err();
+        assert_eq!(conflicts.len(), 3);
+    }
+
+    #[test]
+    fn build_sorts_events_by_tick() {
+        let scenario =
+            ScenarioBuilder::new().rain_event(201, 30.0).fertilize(50, 10.0).drought_period(0, 200).build().unwrap();
+        let first_ticks: Vec<u64> = scenario
+            .events
+            .iter()
+            .map(|event| match *event {
+                ScenarioEvent::Drought { start, .. } => start,
+                ScenarioEvent::Rain { tick, .. }
+                | ScenarioEvent::Fertilize { tick, .. }
+                | ScenarioEvent::Freeze { tick, .. } => tick,
+            })
+            .collect();
+        assert_eq!(first_ticks, vec![0, 50, 201]);
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        let scenario = ScenarioBuilder::new().drought_period(0, 200).rain_event(201, 30.0).build().unwrap();
+        let path = std::env::temp_dir().join(format!("scenario-{}.toml", std::process::id()));
+        scenario.save_toml(&path).unwrap();
+        let loaded = Scenario::load_toml(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), scenario);
+    }
+
+    #[test]
+    fn drought_holds_irrigation_back_in_a_simulation() {
+        let scenario = ScenarioBuilder::new().drought_period(0, 500).build().unwrap();
+        let report = Simulation::new(2, 30.0, 7).with_scenario(scenario).run_for(500);
+        assert_eq!(report.total_water_used_l, 0.0);
+    }
+
+    #[test]
+    fn rain_and_fertilizer_reach_the_simulation() {
+        let scenario = ScenarioBuilder::new().rain_event(3, 30.0).fertilize(4, 10.0).build().unwrap();
+        let report = Simulation::new(1, 30.0, 7).with_scenario(scenario).run_for(10);
+        assert_eq!(report.water_by_source[0].rain_mm, 30.0);
+        assert_eq!(report.fertilizer_n_kg_ha, 10.0);
+    }
+}
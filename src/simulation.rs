@@ -0,0 +1,256 @@
+//! Headless, deterministic simulation runs for library users
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::agri_iot_core::sensor_id::{ensure_unique, DuplicateSensorId};
+use crate::agri_iot_core::trend::TrendDetector;
+use crate::crop::fusion::SensorFusion;
+use crate::crop::wue::WueTracker;
+use crate::crop::yield_estimator::YieldEstimator;
+use crate::crop::PlantProfile;
+use crate::events::{AgriEvent, EventLog};
+use crate::water_volume::{WaterSource, WaterVolumeTracker};
+use crate::irrigation::{
+    DroughtStressTracker, FilterModel, IrrigationScheduler, IrrigationStrategy, PipeNetwork, WaterPump,
+};
+use crate::scenario::Scenario;
+use crate::{DeviceState, SensorId, SoilMoistureSensor, WATERING_BOOST};
+
+/// Simulated time between ticks
+pub const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Depth of every zone's root zone; a 1000 L zone is 1 m² of soil
+pub const ROOT_ZONE_DEPTH_MM: f32 = 1000.0;
+
+/// One irrigated area monitored by its own soil moisture sensor
+pub struct IrrigationZone {
+    pub id: u8,
+    pub sensor: SoilMoistureSensor,
+    pub soil_volume_l: f32, // Root-zone soil volume; 1% moisture is 1% of this in water
+    pub trend: TrendDetector, // Fed one reading per tick
+    pub wue: WueTracker,
+    pub water: WaterVolumeTracker, // Water received, by source
+    pub stress: DroughtStressTracker, // Demand withheld by deficit irrigation
+    pub filter: FilterModel, // Clogs with the water it passes
+    pub fusion: Option<SensorFusion>, // Soil and canopy stress combined, when a canopy sensor is fitted
+}
+
+impl IrrigationZone {
+    /// Current state, moisture and zone id
+    pub fn snapshot(&self) -> ZoneState {
+        ZoneState {
+            zone_id: self.id,
+            state: self.sensor.state,
+            moisture_level: self.sensor.moisture_level,
+        }
+    }
+
+    /// Water depth in mm that `moisture_points` percentage points of moisture hold in the root zone
+    pub fn water_depth_mm(&self, moisture_points: f32) -> f32 {
+        moisture_points / 100.0 * ROOT_ZONE_DEPTH_MM
+    }
+
+    /// Litres of water one watering cycle puts into this zone
+    pub fn watering_volume_l(&self) -> f32 {
+        self.sensor.watering_boost / 100.0 * self.soil_volume_l
+    }
+}
+
+/// Snapshot of one zone, as reported at the end of a run or logged to the WAL
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ZoneState {
+    pub zone_id: u8,
+    pub state: DeviceState,
+    pub moisture_level: f32,
+}
+
+/// Summary of a [`Simulation::run_for`] call
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    pub final_states: Vec<ZoneState>,
+    pub total_water_used_l: f32,
+    pub transition_counts: HashMap<(DeviceState, DeviceState), u32>, // (from, to) state changes
+    pub events: EventLog,
+    pub water_use_efficiency: Vec<Option<f32>>, // g/L per zone, indexed like final_states
+    pub water_by_source: Vec<WaterVolumeTracker>, // Indexed like final_states
+    pub stress_index: Vec<f32>, // Relative ET deficit per zone, indexed like final_states
+    pub yield_t_ha: Vec<f32>, // A whole season at each zone's stress so far, indexed like final_states
+    pub fertilizer_n_kg_ha: f32, // Spread by the scenario during the run
+}
+
+/// Drives every zone's state machine synchronously, one tick per iteration
+pub struct Simulation {
+    pub zones: Vec<IrrigationZone>,
+    pub evaporation: Range<f32>, // Moisture lost per tick, in percentage points
+    pub crop: PlantProfile,
+    pub air_temp_c: f32, // Drives growing degree days
+    pub scheduler: Option<IrrigationScheduler>, // Holds zones back while their shared pipes are full
+    pub pipe_network: Option<PipeNetwork>, // Cuts the flow of zones the pump cannot fully pressurize
+    pub strategy: IrrigationStrategy, // Share of the demand each watering replaces
+    pub scenario: Scenario, // Scripted weather and field operations, keyed by the ticks run so far
+    ticks_run: u64,
+    rng: StdRng,
+}
+
+impl Simulation {
+    /// Creates `zone_count` identical 1 m³ zones; the same `seed` always gives the same run
+    pub fn new(zone_count: u8, threshold: f32, seed: u64) -> Self {
+        let sensor_ids = (0..zone_count).map(SensorId::for_zone).collect();
+        Self::with_sensor_ids(sensor_ids, threshold, seed).expect("zone-N sensor ids are unique")
+    }
+
+    /// Like [`Self::new`], with one zone per sensor id in order; the ids must be unique
+    pub fn with_sensor_ids(sensor_ids: Vec<SensorId>, threshold: f32, seed: u64) -> Result<Self, DuplicateSensorId> {
+        ensure_unique(&sensor_ids)?;
+        let zones = sensor_ids
+            .into_iter()
+            .zip(0..)
+            .map(|(sensor_id, id)| IrrigationZone {
+                id,
+                sensor: SoilMoistureSensor::new(threshold, 50.0).with_sensor_id(sensor_id),
+                soil_volume_l: 1000.0,
+                trend: TrendDetector::new(TICK_INTERVAL),
+                wue: WueTracker::default(),
+                water: WaterVolumeTracker::default(),
+                stress: DroughtStressTracker::default(),
+                filter: FilterModel::default(),
+                fusion: None,
+            })
+            .collect();
+        Ok(Self {
+            zones,
+            evaporation: 0.5..2.0,
+            crop: PlantProfile::default(),
+            air_temp_c: 25.0,
+            scheduler: None,
+            pipe_network: None,
+            strategy: IrrigationStrategy::default(),
+            scenario: Scenario::default(),
+            ticks_run: 0,
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+
+    /// Plays `scenario` from the next tick run: droughts hold irrigation back, rain wets every zone,
+    /// freezes hold the air at their temperature and fertilizer is totalled in the report
+    pub fn with_scenario(mut self, scenario: Scenario) -> Self {
+        self.scenario = scenario;
+        self
+    }
+
+    /// Runs exactly `ticks` ticks without sleeping and reports what happened
+    pub fn run_for(&mut self, ticks: u64) -> SimulationReport {
+        let mut total_water_used_l = 0.0;
+        let mut transition_counts = HashMap::new();
+        let mut events = EventLog::default();
+        let mut fertilizer_n_kg_ha = 0.0;
+        let boost = WaterPump::new(self.strategy).application_mm(WATERING_BOOST);
+
+        for _ in 0..ticks {
+            let tick = self.ticks_run;
+            self.ticks_run += 1;
+            let drought = self.scenario.in_drought(tick);
+            let rain_mm = if drought { 0.0 } else { self.scenario.rain_mm(tick) };
+            let air_temp_c = self.scenario.freeze_temp_c(tick).unwrap_or(self.air_temp_c);
+            let gdd_per_tick = self.crop.growing_degree_days(air_temp_c, TICK_INTERVAL.as_secs_f32() / 86_400.0);
+            fertilizer_n_kg_ha += self.scenario.fertilizer_n_kg_ha(tick);
+            let mut running: Vec<u8> =
+                self.zones.iter().filter(|zone| zone.sensor.water_running()).map(|zone| zone.id).collect();
+            let watering = self.zones.iter().map(|zone| zone.sensor.state == DeviceState::Activating);
+            let flow_factors = self.pipe_network.as_mut().map(|network| network.balance(watering));
+            for zone in &mut self.zones {
+                let pipes_full = self
+                    .scheduler
+                    .as_ref()
+                    .is_some_and(|scheduler| !zone.sensor.water_running() && !scheduler.may_activate(&running, zone.id));
+                zone.sensor.irrigation_blocked = drought || pipes_full;
+                let before = zone.sensor.state;
+                let mut applied_mm = 0.0;
+                let flow_factor = flow_factors.as_ref().and_then(|factors| factors.get(zone.id as usize).copied());
+                zone.sensor.watering_boost = boost * flow_factor.unwrap_or(1.0);
+                if before == DeviceState::Activating {
+                    total_water_used_l += zone.watering_volume_l();
+                    zone.filter.record_flow(zone.watering_volume_l());
+                    applied_mm = zone.water_depth_mm(zone.sensor.watering_boost);
+                    zone.stress.record(zone.water_depth_mm(WATERING_BOOST), applied_mm);
+                }
+                let drop = self.rng.gen_range(self.evaporation.clone());
+                let rain_points = rain_mm / zone.water_depth_mm(1.0);
+                zone.water.record(WaterSource::Rain, rain_mm);
+                zone.sensor.step((zone.sensor.moisture_level + rain_points - drop).clamp(0.0, 100.0));
+                zone.trend.record(zone.sensor.moisture_level);
+                running.retain(|&id| id != zone.id);
+                if zone.sensor.water_running() {
+                    running.push(zone.id);
+                }
+                let et_mm = zone.water_depth_mm(drop);
+                zone.wue.record(&self.crop, gdd_per_tick, et_mm, applied_mm);
+                zone.water.record(WaterSource::Irrigation, applied_mm);
+                if zone.sensor.state != before {
+                    *transition_counts.entry((before, zone.sensor.state)).or_insert(0) += 1;
+                }
+                if let Some(event) =
+                    AgriEvent::from_transition(zone.id, before, zone.sensor.state, zone.sensor.moisture_level)
+                {
+                    events.record(zone.sensor.current_tick, event);
+                }
+            }
+        }
+
+        let estimator = YieldEstimator::from(&self.crop);
+        let stress_index: Vec<f32> = self.zones.iter().map(|zone| zone.stress.stress_index()).collect();
+        SimulationReport {
+            final_states: self.zones.iter().map(IrrigationZone::snapshot).collect(),
+            total_water_used_l,
+            transition_counts,
+            events,
+            water_use_efficiency: self.zones.iter().map(|zone| zone.wue.wue_g_per_l()).collect(),
+            water_by_source: self.zones.iter().map(|zone| zone.water).collect(),
+            yield_t_ha: stress_index.iter().map(|&stress| estimator.estimate_yield(stress, 1.0)).collect(),
+            stress_index,
+            fertilizer_n_kg_ha,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tick;
+
+    #[test]
+    fn run_for_runs_exactly_the_ticks_asked() {
+        let mut simulation = Simulation::new(3, 30.0, 42);
+        let report = simulation.run_for(500);
+        assert_eq!(report.final_states.len(), 3);
+        assert!(simulation.zones.iter().all(|zone| zone.sensor.current_tick == Tick(500)));
+        let report = simulation.run_for(20);
+        assert!(simulation.zones.iter().all(|zone| zone.sensor.current_tick == Tick(520)));
+        assert_eq!(report.final_states, simulation.zones.iter().map(IrrigationZone::snapshot).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_report() {
+        let report = |seed| Simulation::new(4, 30.0, seed).run_for(1_000);
+        assert_eq!(report(7), report(7));
+        assert_ne!(report(7).final_states, report(8).final_states);
+    }
+
+    #[test]
+    fn zones_dry_out_and_are_watered() {
+        let report = Simulation::new(2, 30.0, 1).run_for(1_000);
+        let watering_started = report.transition_counts.get(&(DeviceState::Monitoring, DeviceState::Activating));
+        assert!(watering_started.is_some_and(|&count| count > 0));
+        // Every watering cycle pumps one boost's worth of water into its 1000 L zone
+        let cycles = report.transition_counts.iter().filter(|((_, to), _)| *to == DeviceState::Activating);
+        let cycles: u32 = cycles.map(|(_, count)| count).sum();
+        let per_cycle_l = WATERING_BOOST / 100.0 * 1000.0;
+        assert!((report.total_water_used_l - cycles as f32 * per_cycle_l).abs() < 1e-3 * report.total_water_used_l);
+        assert!(report.water_by_source.iter().all(|water| water.irrigation_mm > 0.0));
+    }
+}
@@ -0,0 +1,111 @@
+//! Biochar soil amendment raising water holding capacity and storing carbon
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::van_genuchten::VanGenuchten;
+
+/// Feedstock the biochar was pyrolysed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CharType {
+    WoodChar,
+    StrawChar,
+    ManureChar,
+}
+
+impl CharType {
+    /// Relative increase of saturated water content per t/ha applied
+    pub fn retention_enhancement_per_t_ha(&self) -> f32 {
+        match self {
+            CharType::WoodChar => 0.004,
+            CharType::StrawChar => 0.005,
+            CharType::ManureChar => 0.003,
+        }
+    }
+
+    /// Mass fraction of the char that is stable carbon
+    pub fn carbon_fraction(&self) -> f32 {
+        match self {
+            CharType::WoodChar => 0.75,
+            CharType::StrawChar => 0.55,
+            CharType::ManureChar => 0.35,
+        }
+    }
+}
+
+/// One application of biochar to the field
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BiocharAmendment {
+    /// Tonnes of biochar per hectare
+    pub application_rate_t_ha: f32,
+    /// Feedstock of the char
+    pub char_type: CharType,
+}
+
+impl BiocharAmendment {
+    /// Factor `theta_s` is multiplied by, capped at +50% for very heavy applications
+    pub fn enhancement_factor(&self) -> f32 {
+        (1.0 + self.char_type.retention_enhancement_per_t_ha() * self.application_rate_t_ha).min(1.5)
+    }
+
+    /// Stable carbon added to the soil, in t C/ha
+    pub fn carbon_t_ha(&self) -> f32 {
+        self.char_type.carbon_fraction() * self.application_rate_t_ha
+    }
+}
+
+/// Combined `theta_s` multiplier of several applications
+pub fn retention_factor(applications: &[BiocharAmendment]) -> f32 {
+    applications.iter().map(BiocharAmendment::enhancement_factor).product()
+}
+
+/// The retention curve of `soil` after all `applications`, with `theta_s` kept at or below 1.0
+pub fn amend(soil: VanGenuchten, applications: &[BiocharAmendment]) -> VanGenuchten {
+    VanGenuchten {
+        theta_s: (soil.theta_s * retention_factor(applications)).min(1.0),
+        ..soil
+    }
+}
+
+/// Cumulative biochar carbon stock of all applications, in t C/ha
+pub fn carbon_stock_t_ha(applications: &[BiocharAmendment]) -> f32 {
+    applications.iter().map(BiocharAmendment::carbon_t_ha).fold(0.0, |total, carbon| total + carbon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WOOD_10_T: BiocharAmendment = BiocharAmendment { application_rate_t_ha: 10.0, char_type: CharType::WoodChar };
+
+    #[test]
+    fn amended_soil_holds_more_water_than_the_control() {
+        let control = VanGenuchten::loam();
+        let amended = amend(control, &[WOOD_10_T]);
+        assert!((amended.theta_s - 0.43 * 1.04).abs() < 1e-5);
+        for suction_cm in [0.0, 100.0, 330.0, 15_000.0] {
+            assert!(amended.water_content(suction_cm) > control.water_content(suction_cm), "at {suction_cm} cm");
+        }
+        assert_eq!(amend(control, &[]), control);
+    }
+
+    #[test]
+    fn char_types_enhance_retention_differently() {
+        let factor = |char_type| BiocharAmendment { application_rate_t_ha: 10.0, char_type }.enhancement_factor();
+        assert!(factor(CharType::StrawChar) > factor(CharType::WoodChar));
+        assert!(factor(CharType::WoodChar) > factor(CharType::ManureChar));
+        let heavy = BiocharAmendment { application_rate_t_ha: 1_000.0, char_type: CharType::StrawChar };
+        assert_eq!(heavy.enhancement_factor(), 1.5);
+        assert_eq!(amend(VanGenuchten::loam(), &[heavy, heavy]).theta_s, 0.43 * 2.25);
+        assert!(amend(VanGenuchten { theta_s: 0.6, ..VanGenuchten::loam() }, &[heavy, heavy]).theta_s <= 1.0);
+    }
+
+    #[test]
+    fn carbon_stock_accumulates_over_applications() {
+        let straw = BiocharAmendment { application_rate_t_ha: 4.0, char_type: CharType::StrawChar };
+        assert!((carbon_stock_t_ha(&[WOOD_10_T, straw]) - (7.5 + 2.2)).abs() < 1e-5);
+        assert_eq!(carbon_stock_t_ha(&[]), 0.0);
+    }
+}
@@ -0,0 +1,121 @@
+//! Subsurface tile drains that carry water above field capacity out of a waterlogged soil
+
+use super::nitrogen::NitrogenPool;
+
+/// Parallel lateral drains laid under the field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DrainageTile {
+    pub depth_cm: f32,           // Below the surface
+    pub spacing_m: f32,          // Between neighbouring laterals
+    pub flow_coefficient: f32,   // Saturated hydraulic conductivity K, in m/day
+    pub field_capacity_pct: f32, // Moisture level below which the soil holds all its water
+}
+
+impl DrainageTile {
+    pub fn new(depth_cm: f32, spacing_m: f32, flow_coefficient: f32, field_capacity_pct: f32) -> Self {
+        Self { depth_cm, spacing_m, flow_coefficient, field_capacity_pct }
+    }
+
+    /// Height of the perched water table above the drains midway between them, rising from
+    /// zero at field capacity to the drain depth when the soil is saturated
+    pub fn water_table_height_m(&self, moisture_level: f32) -> f32 {
+        let excess = (moisture_level - self.field_capacity_pct).max(0.0) / (100.0 - self.field_capacity_pct);
+        excess.min(1.0) * self.depth_cm / 100.0
+    }
+
+    /// Drain discharge by the Hooghoudt equation `q = (8·K·d·h + 4·K·h²) / L²`, in m/day,
+    /// simplified to take the drain depth as the equivalent depth `d` of the flow region
+    pub fn drain_flux_m_per_day(&self, water_table_height_m: f32) -> f32 {
+        let k = self.flow_coefficient;
+        let d = self.depth_cm / 100.0;
+        let h = water_table_height_m;
+        (8.0 * k * d * h + 4.0 * k * h * h) / (self.spacing_m * self.spacing_m)
+    }
+
+    /// Water the drains remove from a zone at `moisture_level` over `days`, in mm. Never
+    /// more than the excess over field capacity, held at `mm_per_point` per moisture point;
+    /// nothing drains at or below field capacity.
+    pub fn drain_mm(&self, moisture_level: f32, mm_per_point: f32, days: f32) -> f32 {
+        let excess_mm = (moisture_level - self.field_capacity_pct).max(0.0) * mm_per_point;
+        let flux_mm = self.drain_flux_m_per_day(self.water_table_height_m(moisture_level)) * 1000.0 * days;
+        flux_mm.min(excess_mm)
+    }
+}
+
+/// Water the drains have carried off and the nitrate that went with it
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DrainageVolume {
+    pub total_mm: f32,
+    pub last_flow_mm_per_day: f32, // Drain discharge over the last step
+    pub nitrate_leached_kg_ha: f32,
+}
+
+impl DrainageVolume {
+    /// Records `drained_mm` over `days` out of `soil_water_mm` of soil water. Mineral nitrogen
+    /// dissolved in it leaves in proportion, moving from `nitrogen` to the leaching losses.
+    pub fn record(&mut self, drained_mm: f32, days: f32, soil_water_mm: f32, nitrogen: &mut NitrogenPool) {
+        self.total_mm += drained_mm;
+        self.last_flow_mm_per_day = if days > 0.0 { drained_mm / days } else { 0.0 };
+        if drained_mm > 0.0 && soil_water_mm > 0.0 {
+            let leached = nitrogen.mineral_n * (drained_mm / soil_water_mm).min(1.0);
+            nitrogen.mineral_n -= leached;
+            self.nitrate_leached_kg_ha += leached;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    // Laterals 1 m deep and 20 m apart in a soil with K = 1 m/day and a 35% field capacity
+    fn tile() -> DrainageTile {
+        DrainageTile::new(100.0, 20.0, 1.0, 35.0)
+    }
+
+    #[test]
+    fn nothing_drains_at_or_below_field_capacity() {
+        let tile = tile();
+        for moisture_level in [0.0, 20.0, 34.9, 35.0] {
+            assert_eq!(tile.water_table_height_m(moisture_level), 0.0);
+            assert_eq!(tile.drain_mm(moisture_level, 10.0, 1.0), 0.0);
+            assert_eq!(tile.drain_mm(moisture_level, 10.0, 365.0), 0.0);
+        }
+        let mut volume = DrainageVolume::default();
+        let mut nitrogen = NitrogenPool { organic_n: 50.0, mineral_n: 30.0 };
+        volume.record(0.0, 1.0, 350.0, &mut nitrogen);
+        assert_eq!(volume, DrainageVolume::default());
+        assert_eq!(nitrogen.mineral_n, 30.0);
+    }
+
+    #[test]
+    fn hooghoudt_flux_above_field_capacity() {
+        let tile = tile();
+        // Halfway to saturation the water table stands 0.5 m over the drains:
+        // (8 × 1 × 1 × 0.5 + 4 × 1 × 0.25) / 20² = 0.0125 m/day
+        assert_close(tile.water_table_height_m(67.5), 0.5, 1e-4);
+        assert_close(tile.drain_flux_m_per_day(0.5), 0.0125, 1e-4);
+        assert_close(tile.drain_mm(67.5, 10.0, 1.0), 12.5, 1e-4);
+        assert_close(tile.water_table_height_m(100.0), 1.0, 1e-4);
+    }
+
+    #[test]
+    fn drains_never_take_more_than_the_excess() {
+        // 1 point over field capacity holds 10 mm, however long the drains run
+        assert_close(tile().drain_mm(36.0, 10.0, 100.0), 10.0, 1e-4);
+        assert!(tile().drain_mm(36.0, 10.0, 1.0) < 10.0);
+    }
+
+    #[test]
+    fn drained_water_carries_its_share_of_the_nitrate() {
+        let mut volume = DrainageVolume::default();
+        let mut nitrogen = NitrogenPool { organic_n: 50.0, mineral_n: 30.0 };
+        volume.record(35.0, 0.5, 350.0, &mut nitrogen);
+        assert_close(volume.total_mm, 35.0, 1e-4);
+        assert_close(volume.last_flow_mm_per_day, 70.0, 1e-4);
+        assert_close(volume.nitrate_leached_kg_ha, 3.0, 1e-4);
+        assert_close(nitrogen.mineral_n, 27.0, 1e-4);
+        assert_close(nitrogen.organic_n, 50.0, 1e-4);
+    }
+}
@@ -0,0 +1,16 @@
+//! Soil physics models layered on top of the moisture state machine
+
+pub mod biochar;
+pub mod carbon;
+pub mod drainage;
+pub mod nitrogen;
+pub mod nutrient_leaching;
+pub mod organic_matter;
+pub mod respiration;
+pub mod salinity;
+pub mod sampling;
+pub mod trace_elements;
+pub mod water_temperature;
+pub mod wetting_front;
+
+pub use crate::agri_iot_core::van_genuchten;
@@ -0,0 +1,15 @@
+//! Soil nitrogen held in organic matter and in plant-available mineral forms
+
+/// Nitrogen in the topsoil of one hectare
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NitrogenPool {
+    pub organic_n: f32, // In residues and organic matter, in kg/ha
+    pub mineral_n: f32, // Nitrate and ammonium the crop can take up, in kg/ha
+}
+
+impl NitrogenPool {
+    /// Nitrogen in both forms, in kg/ha
+    pub fn total_n(&self) -> f32 {
+        self.organic_n + self.mineral_n
+    }
+}
@@ -0,0 +1,89 @@
+//! Mobile nutrients washed out of the root zone by irrigation water
+
+use crate::crop::nitrogen::SOIL_MASS_KG_HA;
+use crate::hydroponic::NpkSensor;
+
+/// Cumulative nitrate leaching, in kg N/ha, above which the losses put nearby water at risk
+pub const DEFAULT_N_RISK_THRESHOLD_KG_HA: f32 = 30.0;
+
+/// Nutrients one irrigation washed out, in kg/ha
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NutrientLoss {
+    pub n_kg_ha: f32,
+    pub k_kg_ha: f32,
+}
+
+/// Loss per irrigation of `applied_water_mm · soil_concentration · leach_coeff`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LeachingModel {
+    pub leach_coeff_n: f32, // Share of the soil nitrate lost per mm of water applied
+    pub leach_coeff_k: f32, // Potassium is held on clay and leaches far less
+}
+
+impl Default for LeachingModel {
+    /// A 10 mm irrigation washes out 2% of the nitrate and 0.3% of the potassium
+    fn default() -> Self {
+        Self { leach_coeff_n: 0.002, leach_coeff_k: 0.0003 }
+    }
+}
+
+impl LeachingModel {
+    /// Leaches `sensor`'s nitrate and potassium for `applied_water_mm` of irrigation, deducting
+    /// them from its readings, and returns what was lost
+    pub fn leach(&self, sensor: &mut NpkSensor, applied_water_mm: f32) -> NutrientLoss {
+        let loss_ppm = |ppm: f32, coeff: f32| (applied_water_mm * ppm * coeff).clamp(0.0, ppm);
+        let n_ppm = loss_ppm(sensor.nitrate_ppm, self.leach_coeff_n);
+        let k_ppm = loss_ppm(sensor.potassium_ppm, self.leach_coeff_k);
+        sensor.nitrate_ppm -= n_ppm;
+        sensor.potassium_ppm -= k_ppm;
+        NutrientLoss { n_kg_ha: n_ppm * SOIL_MASS_KG_HA / 1e6, k_kg_ha: k_ppm * SOIL_MASS_KG_HA / 1e6 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+    use crate::water_volume::WaterVolumeTracker;
+
+    fn soil() -> NpkSensor {
+        NpkSensor { nitrate_ppm: 50.0, phosphate_ppm: 20.0, potassium_ppm: 200.0 }
+    }
+
+    #[test]
+    fn loss_is_water_times_concentration_times_coefficient() {
+        let mut sensor = soil();
+        let loss = LeachingModel::default().leach(&mut sensor, 10.0);
+        // 10 mm × 50 ppm × 0.002 = 1 ppm of nitrate and 10 × 200 × 0.0003 = 0.6 ppm of potassium,
+        // over 3900 t/ha of topsoil
+        assert_close(loss.n_kg_ha, 3.9, 1e-3);
+        assert_close(loss.k_kg_ha, 2.34, 1e-3);
+        assert_close(sensor.nitrate_ppm, 49.0, 1e-3);
+        assert_close(sensor.potassium_ppm, 199.4, 1e-3);
+        assert_eq!(sensor.phosphate_ppm, 20.0);
+    }
+
+    #[test]
+    fn no_more_leaches_than_the_soil_holds() {
+        let mut sensor = soil();
+        let loss = LeachingModel::default().leach(&mut sensor, 1_000.0);
+        assert_close(loss.n_kg_ha, 50.0 * 3.9, 1e-3);
+        assert_eq!(sensor.nitrate_ppm, 0.0);
+        assert_eq!(LeachingModel::default().leach(&mut sensor, 0.0), NutrientLoss::default());
+    }
+
+    #[test]
+    fn cumulative_nitrate_loss_crosses_the_risk_threshold() {
+        let model = LeachingModel::default();
+        let mut sensor = soil();
+        let mut water = WaterVolumeTracker::default();
+        // Each 10 mm takes 2% of what is left: 50 × (1 − 0.98⁸) ppm is 29.1 kg/ha, one more is 32.4
+        for _ in 0..8 {
+            water.record_nutrient_loss(model.leach(&mut sensor, 10.0));
+        }
+        assert!(water.n_leached_kg_ha < DEFAULT_N_RISK_THRESHOLD_KG_HA);
+        water.record_nutrient_loss(model.leach(&mut sensor, 10.0));
+        assert!(water.n_leached_kg_ha > DEFAULT_N_RISK_THRESHOLD_KG_HA);
+        assert!((water.n_leached_kg_ha - 50.0 * (1.0 - 0.98f32.powi(9)) * 3.9).abs() < 1e-2);
+    }
+}
@@ -0,0 +1,86 @@
+//! CO2 efflux from soil microbial respiration, following temperature by Q10 and peaking in moist soil
+
+/// Temperature at which respiration runs at its basal rate, in °C
+pub const REFERENCE_TEMP_C: f32 = 10.0;
+
+/// Moisture, as a fraction of field capacity, at which microbes respire fastest
+pub const OPTIMUM_MOISTURE_FRACTION: f32 = 0.6;
+
+/// Respiration relative to its rate at [`OPTIMUM_MOISTURE_FRACTION`] of field capacity.
+/// Stops in dry soil and falls off again towards saturation as the pores fill with water.
+pub fn optimum_moisture_modifier(fraction_of_field_capacity: f32) -> f32 {
+    let offset = (fraction_of_field_capacity - OPTIMUM_MOISTURE_FRACTION) / OPTIMUM_MOISTURE_FRACTION;
+    (1.0 - offset * offset).clamp(0.0, 1.0)
+}
+
+/// Soil CO2 flux `R = basal · Q10^((T - 10) / 10) · f(θ)`
+#[derive(Debug, Clone, Copy)]
+pub struct SoilRespirationModel {
+    pub basal_rate_g_co2_m2_h: f32, // At 10 °C and the optimum moisture
+    pub q10: f32,                   // Factor the rate rises by per 10 °C
+    pub moisture_modifier_fn: fn(f32) -> f32, // Of moisture as a fraction of field capacity
+    pub field_capacity_pct: f32,
+}
+
+impl Default for SoilRespirationModel {
+    /// About 2 µmol CO2/m²/s from an arable loam at 10 °C, doubling every 10 °C
+    fn default() -> Self {
+        Self::new(0.3, 2.0, 60.0)
+    }
+}
+
+impl SoilRespirationModel {
+    /// A model with the moisture response peaking at [`OPTIMUM_MOISTURE_FRACTION`] of field capacity
+    pub fn new(basal_rate_g_co2_m2_h: f32, q10: f32, field_capacity_pct: f32) -> Self {
+        Self { basal_rate_g_co2_m2_h, q10, moisture_modifier_fn: optimum_moisture_modifier, field_capacity_pct }
+    }
+
+    /// Respiration relative to the reference temperature, `Q10^((T - 10) / 10)`
+    pub fn temperature_modifier(&self, temp_c: f32) -> f32 {
+        self.q10.powf((temp_c - REFERENCE_TEMP_C) / 10.0)
+    }
+
+    /// CO2 flux out of soil at `temp_c` holding `moisture_pct`, in g CO2/m²/h
+    pub fn flux_g_co2_m2_h(&self, temp_c: f32, moisture_pct: f32) -> f32 {
+        let moisture_modifier = (self.moisture_modifier_fn)(moisture_pct / self.field_capacity_pct);
+        self.basal_rate_g_co2_m2_h * self.temperature_modifier(temp_c) * moisture_modifier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn respiration_doubles_with_every_ten_degrees() {
+        let model = SoilRespirationModel::default();
+        // 36% moisture is 0.6 of the 60% field capacity, the optimum
+        assert_close(model.flux_g_co2_m2_h(10.0, 36.0), 0.3, 1e-5);
+        for temp_c in [0.0, 5.0, 10.0, 15.0, 25.0] {
+            let ratio = model.flux_g_co2_m2_h(temp_c + 10.0, 36.0) / model.flux_g_co2_m2_h(temp_c, 36.0);
+            assert_close(ratio, 2.0, 1e-5);
+        }
+        assert_close(model.flux_g_co2_m2_h(30.0, 36.0), 1.2, 1e-5);
+        assert_close(model.flux_g_co2_m2_h(0.0, 36.0), 0.15, 1e-5);
+    }
+
+    #[test]
+    fn other_q10_values_scale_the_same_way() {
+        let model = SoilRespirationModel::new(0.3, 3.0, 60.0);
+        assert_close(model.temperature_modifier(20.0), 3.0, 1e-5);
+        assert_close(model.temperature_modifier(15.0), 3f32.sqrt(), 1e-5);
+    }
+
+    #[test]
+    fn moisture_response_peaks_at_sixty_percent_of_field_capacity() {
+        assert_close(optimum_moisture_modifier(0.6), 1.0, 1e-5);
+        assert_close(optimum_moisture_modifier(0.3), 0.75, 1e-5);
+        assert_close(optimum_moisture_modifier(0.9), 0.75, 1e-5);
+        assert_close(optimum_moisture_modifier(0.0), 0.0, 1e-5);
+        assert_close(optimum_moisture_modifier(1.5), 0.0, 1e-5);
+        let model = SoilRespirationModel::default();
+        assert!(model.flux_g_co2_m2_h(20.0, 36.0) > model.flux_g_co2_m2_h(20.0, 54.0));
+        assert!(model.flux_g_co2_m2_h(20.0, 36.0) > model.flux_g_co2_m2_h(20.0, 18.0));
+    }
+}
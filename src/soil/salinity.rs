@@ -0,0 +1,99 @@
+//! Leaching requirement for irrigating with saline water (FAO Irrigation and Drainage Paper 29)
+
+use crate::Tick;
+
+/// Share of the applied water that must drain below the root zone to keep the soil at
+/// `ec_threshold_ds_m`: `LR = ECw / (5·ECe − ECw)`. Clamped to 0..=1, where 1 means the
+/// water is too saline to be managed by leaching.
+pub fn leaching_requirement(ec_water_ds_m: f32, ec_threshold_ds_m: f32) -> f32 {
+    let denominator = 5.0 * ec_threshold_ds_m - ec_water_ds_m;
+    if denominator <= 0.0 {
+        return 1.0;
+    }
+    (ec_water_ds_m / denominator).clamp(0.0, 1.0)
+}
+
+/// Schedules flushing irrigation that carries accumulated salt out of the root zone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LeachingManager {
+    pub lr: f32, // Leaching requirement, 0..1
+    pub last_leach_tick: Tick,
+    pub leach_interval_ticks: u32,
+    pending_irrigation_l: f32, // Applied since the last flush
+}
+
+impl LeachingManager {
+    pub fn new(lr: f32, leach_interval_ticks: u32) -> Self {
+        Self { lr, last_leach_tick: Tick::ZERO, leach_interval_ticks, pending_irrigation_l: 0.0 }
+    }
+
+    /// Counts `litres` of irrigation towards the next flush
+    pub fn record_irrigation(&mut self, litres: f32) {
+        self.pending_irrigation_l += litres;
+    }
+
+    /// Whether a flush is due on tick `now`
+    pub fn leach_due(&self, now: Tick) -> bool {
+        now.0.saturating_sub(self.last_leach_tick.0) >= u64::from(self.leach_interval_ticks)
+    }
+
+    /// Extra water the irrigation since the last flush calls for: with `I = ETc / (1 − LR)`
+    /// applied in all, the flush is `LR / (1 − LR)` of what went on for the crop
+    pub fn leaching_volume_l(&self) -> f32 {
+        if self.lr >= 1.0 {
+            return 0.0;
+        }
+        self.pending_irrigation_l * self.lr / (1.0 - self.lr)
+    }
+
+    /// Flushes on tick `now` and returns the litres applied
+    pub fn leach(&mut self, now: Tick) -> f32 {
+        let volume_l = self.leaching_volume_l();
+        self.pending_irrigation_l = 0.0;
+        self.last_leach_tick = now;
+        volume_l
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn matches_fao_29_leaching_requirements() {
+        // FAO-29 example water of 1.2 dS/m against the Table 4 thresholds for bean, maize,
+        // alfalfa, tomato and wheat
+        assert_close(leaching_requirement(1.2, 1.0), 0.316, 1e-3);
+        assert_close(leaching_requirement(1.2, 1.7), 0.164, 1e-3);
+        assert_close(leaching_requirement(1.2, 2.0), 0.136, 1e-3);
+        assert_close(leaching_requirement(1.2, 2.5), 0.106, 1e-3);
+        assert_close(leaching_requirement(1.2, 6.0), 0.042, 1e-3);
+        // A saltier 3 dS/m well on wheat and on tomato
+        assert_close(leaching_requirement(3.0, 6.0), 0.111, 1e-3);
+        assert_close(leaching_requirement(3.0, 2.5), 0.316, 1e-3);
+        assert_close(leaching_requirement(0.0, 2.5), 0.0, 1e-3);
+    }
+
+    #[test]
+    fn water_too_saline_to_leach_needs_everything() {
+        assert_eq!(leaching_requirement(5.0, 1.0), 1.0);
+        assert_eq!(leaching_requirement(8.0, 1.0), 1.0);
+        assert_eq!(LeachingManager::new(1.0, 10).leaching_volume_l(), 0.0);
+    }
+
+    #[test]
+    fn flush_is_due_every_interval_and_replaces_the_leaching_fraction() {
+        let mut manager = LeachingManager::new(0.2, 100);
+        assert!(!manager.leach_due(Tick(99)));
+        assert!(manager.leach_due(Tick(100)));
+        manager.record_irrigation(300.0);
+        manager.record_irrigation(100.0);
+        // 400 L for the crop is 80% of what goes on in all, so the flush is another 100 L
+        assert_close(manager.leach(Tick(100)), 100.0, 1e-3);
+        assert_eq!(manager.last_leach_tick, Tick(100));
+        assert!(!manager.leach_due(Tick(150)));
+        assert!(manager.leach_due(Tick(200)));
+        assert_close(manager.leaching_volume_l(), 0.0, 1e-3);
+    }
+}
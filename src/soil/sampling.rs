@@ -0,0 +1,161 @@
+//! Lab analysis of a soil sample: a snapshot of the soil models and the fertilizer it calls for
+
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::nitrogen::NitrogenPool;
+use super::respiration::SoilRespirationModel;
+use crate::crop::nitrogen::SOIL_MASS_KG_HA;
+use crate::crop::PlantProfile;
+use crate::simulation::IrrigationZone;
+use crate::Tick;
+
+/// Ratio of the saturated-paste ECe to the irrigation water's ECw at a 15-20% leaching fraction (FAO-29)
+pub const ECE_PER_ECW: f32 = 1.5;
+
+/// Field-level soil state the zone itself does not hold
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoilChemistry {
+    pub ec_water_ds_m: Option<f32>, // Irrigation water salinity, when it is modelled
+    pub ph: f32,
+    pub nitrogen: NitrogenPool,
+    pub p_ppm: f32,
+    pub k_ppm: f32,
+    pub som_pct: f32,
+    pub soil_temp_c: f32,
+}
+
+/// Fertilizer to apply for the crop's demand, in kg/ha of each nutrient
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct FertilizerRecommendation {
+    pub n_kg_ha: f32,
+    pub p_kg_ha: f32,
+    pub k_kg_ha: f32,
+}
+
+/// What the lab reports for one sample
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SoilSampleReport {
+    pub zone_id: u8,
+    pub timestamp: Tick,
+    pub date: String, // Simulated calendar date, `YYYY-MM-DD`
+    pub moisture_pct: f32,
+    pub ec_ds_m: Option<f32>, // Saturated-paste ECe; absent when salinity is not modelled
+    pub ph: f32,
+    pub n_ppm: f32, // Mineral (plant-available) nitrogen
+    pub p_ppm: f32,
+    pub k_ppm: f32,
+    pub som_pct: f32,
+    pub co2_flux_g_m2_h: f32, // Soil respiration at the time of sampling
+    pub crop: String,
+    pub recommendation: FertilizerRecommendation,
+}
+
+impl SoilSampleReport {
+    /// `<zone_id>_<tick>.json` under `directory`
+    pub fn path(&self, directory: &Path) -> PathBuf {
+        directory.join(format!("{}_{}.json", self.zone_id, self.timestamp.0))
+    }
+
+    /// Writes the report as JSON under `directory`, creating it if needed, and returns the file's path
+    pub fn write_json(&self, directory: &Path) -> io::Result<PathBuf> {
+        fs::create_dir_all(directory)?;
+        let path = self.path(directory);
+        fs::write(&path, serde_json::to_string_pretty(self).map_err(io::Error::other)?)?;
+        Ok(path)
+    }
+}
+
+/// Kilograms per hectare of a nutrient at `ppm` in the topsoil
+fn ppm_to_kg_ha(ppm: f32) -> f32 {
+    ppm * SOIL_MASS_KG_HA / 1e6
+}
+
+/// Samples `zone` at `tick`: its moisture plus the field's soil chemistry, with the
+/// fertilizer that makes up the difference between `crop`'s demand and what the soil holds.
+/// The CO2 flux is `respiration`'s for the zone's moisture at the soil temperature.
+pub fn take_soil_sample(
+    zone: &IrrigationZone,
+    tick: Tick,
+    date: String,
+    soil: &SoilChemistry,
+    respiration: &SoilRespirationModel,
+    crop: &PlantProfile,
+) -> SoilSampleReport {
+    let n_ppm = soil.nitrogen.mineral_n / SOIL_MASS_KG_HA * 1e6;
+    let shortfall = |demand_kg_ha: f32, ppm: f32| (demand_kg_ha - ppm_to_kg_ha(ppm)).max(0.0);
+    SoilSampleReport {
+        zone_id: zone.id,
+        timestamp: tick,
+        date,
+        moisture_pct: zone.sensor.moisture_level,
+        ec_ds_m: soil.ec_water_ds_m.map(|ecw| ecw * ECE_PER_ECW),
+        ph: soil.ph,
+        n_ppm,
+        p_ppm: soil.p_ppm,
+        k_ppm: soil.k_ppm,
+        som_pct: soil.som_pct,
+        co2_flux_g_m2_h: respiration.flux_g_co2_m2_h(soil.soil_temp_c, zone.sensor.moisture_level),
+        crop: crop.name.clone(),
+        recommendation: FertilizerRecommendation {
+            n_kg_ha: shortfall(crop.n_demand_kg_ha, n_ppm),
+            p_kg_ha: shortfall(crop.p_demand_kg_ha, soil.p_ppm),
+            k_kg_ha: shortfall(crop.k_demand_kg_ha, soil.k_ppm),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+    use crate::simulation::Simulation;
+
+    fn sample() -> SoilSampleReport {
+        let simulation = Simulation::new(2, 30.0, 3);
+        let soil = SoilChemistry {
+            ec_water_ds_m: Some(1.2),
+            ph: 6.5,
+            nitrogen: NitrogenPool { organic_n: 2_000.0, mineral_n: 78.0 },
+            p_ppm: 5.0,
+            k_ppm: 50.0,
+            som_pct: 2.0,
+            soil_temp_c: 18.0,
+        };
+        let respiration = SoilRespirationModel::default();
+        let date = "2024-05-01".to_string();
+        take_soil_sample(&simulation.zones[1], Tick(720), date, &soil, &respiration, &PlantProfile::maize())
+    }
+
+    #[test]
+    fn recommendation_makes_up_the_crop_demand() {
+        let report = sample();
+        assert_eq!((report.zone_id, report.timestamp), (1, Tick(720)));
+        assert_close(report.moisture_pct, 50.0, 1e-3);
+        // 78 kg/ha of mineral N in 3900 t of topsoil
+        assert_close(report.n_ppm, 20.0, 1e-3);
+        assert_close(report.ec_ds_m.unwrap(), 1.8, 1e-3);
+        // Maize wants 180 kg N, 35 kg P and 150 kg K; 5 ppm P is 19.5 kg/ha and 50 ppm K is 195 kg/ha
+        assert_close(report.recommendation.n_kg_ha, 102.0, 1e-3);
+        assert_close(report.recommendation.p_kg_ha, 15.5, 1e-3);
+        assert_close(report.recommendation.k_kg_ha, 0.0, 1e-3);
+    }
+
+    #[test]
+    fn report_file_is_created_under_the_directory() {
+        let directory = std::env::temp_dir().join(format!("agri-soil-samples-{}", std::process::id()));
+        let report = sample();
+        let path = report.write_json(&directory).unwrap();
+        assert_eq!(path, directory.join("1_720.json"));
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(json["zone_id"], 1);
+        assert_eq!(json["timestamp"], 720);
+        assert_eq!(json["date"], "2024-05-01");
+        assert_eq!(json["crop"], "Maize");
+        assert_eq!(json["ph"], 6.5);
+        assert!(json["recommendation"]["n_kg_ha"].as_f64().is_some_and(|n| (n - 102.0).abs() < 1e-3));
+    }
+}
@@ -0,0 +1,130 @@
+//! Plant-available micronutrients in the topsoil and how soil pH locks them up
+//!
+//! Iron and manganese are held as insoluble oxides in alkaline soil and dissolve freely in
+//! acid soil, so above pH 7.5 they dwindle and below pH 5.5 they build up. Zinc and boron only
+//! change with foliar sprays here.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// pH above which iron and manganese become unavailable
+pub const ALKALINE_PH: f32 = 7.5;
+/// pH below which iron and manganese dissolve in excess
+pub const ACID_PH: f32 = 5.5;
+/// Manganese changes this share as fast as iron with pH
+pub const MN_RATE_SHARE: f32 = 0.5;
+
+/// A micronutrient the monitor reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceElement {
+    Iron,
+    Zinc,
+    Manganese,
+    Boron,
+}
+
+impl TraceElement {
+    pub const ALL: [TraceElement; 4] =
+        [TraceElement::Iron, TraceElement::Zinc, TraceElement::Manganese, TraceElement::Boron];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TraceElement::Iron => "iron",
+            TraceElement::Zinc => "zinc",
+            TraceElement::Manganese => "manganese",
+            TraceElement::Boron => "boron",
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TraceElement::Iron => "Fe",
+            TraceElement::Zinc => "Zn",
+            TraceElement::Manganese => "Mn",
+            TraceElement::Boron => "B",
+        }
+    }
+
+    /// Rise in the reading from one foliar spray, in ppm
+    pub fn foliar_spray_ppm(self) -> f32 {
+        match self {
+            TraceElement::Iron => 2.0,
+            TraceElement::Zinc => 0.5,
+            TraceElement::Manganese => 1.0,
+            TraceElement::Boron => 0.3,
+        }
+    }
+}
+
+/// Extractable micronutrients in the topsoil, in ppm (DTPA for the metals, hot water for boron)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceElementMonitor {
+    pub fe_ppm: f32,
+    pub zn_ppm: f32,
+    pub mn_ppm: f32,
+    pub b_ppm: f32,
+    pub fe_rate_ppm_per_day: f32, // Iron lost above `ALKALINE_PH`, and gained below `ACID_PH`
+}
+
+impl TraceElementMonitor {
+    pub fn ppm(&self, element: TraceElement) -> f32 {
+        match element {
+            TraceElement::Iron => self.fe_ppm,
+            TraceElement::Zinc => self.zn_ppm,
+            TraceElement::Manganese => self.mn_ppm,
+            TraceElement::Boron => self.b_ppm,
+        }
+    }
+
+    fn ppm_mut(&mut self, element: TraceElement) -> &mut f32 {
+        match element {
+            TraceElement::Iron => &mut self.fe_ppm,
+            TraceElement::Zinc => &mut self.zn_ppm,
+            TraceElement::Manganese => &mut self.mn_ppm,
+            TraceElement::Boron => &mut self.b_ppm,
+        }
+    }
+
+    /// Elements below the crop's `minimum_ppm`; those without a minimum never are
+    pub fn deficient(&self, minimum_ppm: &HashMap<TraceElement, f32>) -> Vec<TraceElement> {
+        TraceElement::ALL
+            .into_iter()
+            .filter(|element| minimum_ppm.get(element).is_some_and(|&minimum| self.ppm(*element) < minimum))
+            .collect()
+    }
+
+    /// Lets soil at `ph_level` act on iron and manganese for `days` and returns the elements that
+    /// have just fallen below the crop's `minimum_ppm`
+    pub fn update(&mut self, ph_level: f32, days: f32, minimum_ppm: &HashMap<TraceElement, f32>) -> Vec<TraceElement> {
+        let before = self.deficient(minimum_ppm);
+        let fe_rate_ppm_per_day = if ph_level > ALKALINE_PH {
+            -self.fe_rate_ppm_per_day
+        } else if ph_level < ACID_PH {
+            self.fe_rate_ppm_per_day
+        } else {
+            0.0
+        };
+        let change = fe_rate_ppm_per_day * days.max(0.0);
+        self.fe_ppm = (self.fe_ppm + change).max(0.0);
+        self.mn_ppm = (self.mn_ppm + change * MN_RATE_SHARE).max(0.0);
+        self.deficient(minimum_ppm).into_iter().filter(|element| !before.contains(element)).collect()
+    }
+
+    /// Sprays a micronutrient mix on the leaves, raising every reading by its element's dose
+    pub fn foliar_spray(&mut self) {
+        for element in TraceElement::ALL {
+            *self.ppm_mut(element) += element.foliar_spray_ppm();
+        }
+    }
+
+    /// `Fe 6.0 Zn 1.5 Mn 3.0 B 0.8`
+    pub fn summary(&self) -> String {
+        TraceElement::ALL
+            .into_iter()
+            .map(|element| format!("{} {:.1}", element.symbol(), self.ppm(element)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
@@ -0,0 +1,107 @@
+//! Soil cooling or warming caused by the temperature of irrigation water
+
+use crate::events::AgriEvent;
+use crate::SoilMoistureSensor;
+
+/// Volumetric heat capacity of liquid water, in kJ/(L·K)
+pub const WATER_HEAT_CAPACITY: f32 = 4.18;
+
+/// One irrigation event mixing water of one temperature into soil of another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaterTemperatureModel {
+    pub water_temp_c: f32,
+    pub soil_temp_before: f32,   // Root-zone soil temperature before irrigation, in °C
+    pub soil_volume_l: f32,
+    pub water_volume_l: f32,
+    pub soil_specific_heat: f32, // Volumetric heat capacity of the soil, in kJ/(L·K)
+}
+
+impl WaterTemperatureModel {
+    /// Soil temperature once water and soil reach thermal equilibrium, ignoring losses to air and subsoil
+    pub fn post_irrigation_soil_temp(&self) -> f32 {
+        let water_heat = self.water_volume_l * WATER_HEAT_CAPACITY;
+        let soil_heat = self.soil_volume_l * self.soil_specific_heat;
+        if water_heat + soil_heat <= 0.0 {
+            return self.soil_temp_before;
+        }
+        (water_heat * self.water_temp_c + soil_heat * self.soil_temp_before) / (water_heat + soil_heat)
+    }
+
+    /// A `ColdWaterStress` event for the zone `sensor` is in if irrigation leaves the soil below the
+    /// crop's germination threshold
+    pub fn cold_water_stress(
+        &self,
+        zone_id: u8,
+        sensor: &SoilMoistureSensor,
+        germination_threshold_c: f32,
+    ) -> Option<AgriEvent> {
+        let soil_temp_c = self.post_irrigation_soil_temp();
+        (soil_temp_c < germination_threshold_c).then_some(AgriEvent::ColdWaterStress {
+            zone_id,
+            moisture_level: sensor.moisture_level,
+            state: sensor.state,
+            soil_temp_c,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(water_temp_c: f32, water_volume_l: f32) -> WaterTemperatureModel {
+        WaterTemperatureModel {
+            water_temp_c,
+            soil_temp_before: 20.0,
+            soil_volume_l: 1000.0,
+            water_volume_l,
+            soil_specific_heat: 2.5,
+        }
+    }
+
+    #[test]
+    fn equal_temperatures_leave_the_soil_unchanged() {
+        let mut equal = model(20.0, 150.0);
+        assert_eq!(equal.post_irrigation_soil_temp(), 20.0);
+        equal.water_volume_l = 0.0;
+        assert_eq!(equal.post_irrigation_soil_temp(), 20.0);
+    }
+
+    #[test]
+    fn energy_balance_mixes_by_heat_capacity() {
+        // 150 L × 4.18 = 627 kJ/K of water at 8 °C into 1000 L × 2.5 = 2500 kJ/K of soil at 20 °C
+        let expected = (627.0 * 8.0 + 2500.0 * 20.0) / 3127.0;
+        assert!((model(8.0, 150.0).post_irrigation_soil_temp() - expected).abs() < 1e-4);
+    }
+
+    #[test]
+    fn large_water_volume_pulls_the_soil_towards_the_water_temperature() {
+        let flooded = model(8.0, 1_000_000.0).post_irrigation_soil_temp();
+        assert!((flooded - 8.0).abs() < 0.01, "{flooded}");
+    }
+
+    #[test]
+    fn small_water_volume_barely_changes_the_soil() {
+        let misted = model(8.0, 1.0).post_irrigation_soil_temp();
+        assert!(misted < 20.0 && misted > 19.95, "{misted}");
+    }
+
+    #[test]
+    fn cold_water_stress_only_below_the_germination_threshold() {
+        let sensor = SoilMoistureSensor::new(30.0, 28.0);
+        let chilled = model(2.0, 2000.0);
+        let soil_temp = chilled.post_irrigation_soil_temp();
+        let event = chilled.cold_water_stress(3, &sensor, 10.0);
+        assert_eq!(
+            event,
+            Some(AgriEvent::ColdWaterStress {
+                zone_id: 3,
+                moisture_level: 28.0,
+                state: sensor.state,
+                soil_temp_c: soil_temp,
+            })
+        );
+        assert_eq!(chilled.cold_water_stress(3, &sensor, soil_temp), None);
+        assert_eq!(model(8.0, 150.0).cold_water_stress(3, &sensor, 10.0), None);
+    }
+}
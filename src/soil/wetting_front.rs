@@ -0,0 +1,186 @@
+//! How deep each irrigation's water has soaked into the soil
+//!
+//! Infiltrating water fills the pore space below it, so a watering of `I` cm comes to rest
+//! `I / (θs · (1 − S))` cm down, where `S` is the share of the pores already full: the wetter the
+//! soil, the deeper. Its front covers that distance in the soil's redistribution time, quickly in
+//! sand and slowly in clay.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::van_genuchten::VanGenuchten;
+
+/// Least pore space left to fill, so that watering saturated soil sends its front only so deep
+const MIN_FILLABLE_PORE_SPACE: f32 = 0.02;
+
+/// Soil texture of the field
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SoilType {
+    Sand,
+    #[default]
+    Loam,
+    Clay,
+}
+
+impl SoilType {
+    /// Retention curve of the texture class, from Carsel and Parrish (1988)
+    pub fn retention(self) -> VanGenuchten {
+        match self {
+            SoilType::Sand => VanGenuchten { theta_r: 0.045, theta_s: 0.43, alpha_per_cm: 0.145, n: 2.68 },
+            SoilType::Loam => VanGenuchten::loam(),
+            SoilType::Clay => VanGenuchten { theta_r: 0.068, theta_s: 0.38, alpha_per_cm: 0.008, n: 1.09 },
+        }
+    }
+
+    /// Hours a watering's front takes to come to rest
+    pub fn redistribution_hours(self) -> f32 {
+        match self {
+            SoilType::Sand => 2.0,
+            SoilType::Loam => 12.0,
+            SoilType::Clay => 48.0,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SoilType::Sand => "sand",
+            SoilType::Loam => "loam",
+            SoilType::Clay => "clay",
+        }
+    }
+}
+
+/// The leading edge of one watering's water on its way down
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WettingFront {
+    pub depth_cm: f32,
+    pub advance_rate_cm_per_h: f32,
+    pub soil_type: SoilType,
+    pub final_depth_cm: f32, // Where the water comes to rest
+}
+
+impl WettingFront {
+    /// A front at the surface carrying `applied_mm` into soil whose pores are `moisture_pct` full
+    pub fn new(soil_type: SoilType, applied_mm: f32, moisture_pct: f32) -> Self {
+        let saturation = (moisture_pct / 100.0).clamp(0.0, 1.0);
+        let fillable = (soil_type.retention().theta_s * (1.0 - saturation)).max(MIN_FILLABLE_PORE_SPACE);
+        let final_depth_cm = applied_mm.max(0.0) / 10.0 / fillable;
+        Self {
+            depth_cm: 0.0,
+            advance_rate_cm_per_h: final_depth_cm / soil_type.redistribution_hours(),
+            soil_type,
+            final_depth_cm,
+        }
+    }
+
+    /// Moves the front down for `hours`, stopping where its water comes to rest
+    pub fn advance(&mut self, hours: f32) {
+        self.depth_cm = (self.depth_cm + self.advance_rate_cm_per_h * hours.max(0.0)).min(self.final_depth_cm);
+    }
+
+    /// Whether the front has come to rest
+    pub fn settled(&self) -> bool {
+        self.depth_cm >= self.final_depth_cm
+    }
+}
+
+/// Every front still moving, oldest first; each watering starts a new one at the surface
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WettingFrontTracker {
+    pub soil_type: SoilType,
+    pub fronts: VecDeque<WettingFront>,
+    pub last_settled_depth_cm: Option<f32>, // Where the latest front to come to rest stopped
+}
+
+impl WettingFrontTracker {
+    pub fn new(soil_type: SoilType) -> Self {
+        Self { soil_type, ..Self::default() }
+    }
+
+    /// Starts a front for a watering of `applied_mm` into soil at `moisture_pct`
+    pub fn start(&mut self, applied_mm: f32, moisture_pct: f32) {
+        self.fronts.push_back(WettingFront::new(self.soil_type, applied_mm, moisture_pct));
+    }
+
+    /// Moves every front down for `hours` and drops those that have come to rest; every front
+    /// takes the soil's redistribution time, so they come to rest oldest first
+    pub fn advance(&mut self, hours: f32) {
+        for front in &mut self.fronts {
+            front.advance(hours);
+        }
+        while let Some(front) = self.fronts.front().filter(|front| front.settled()) {
+            self.last_settled_depth_cm = Some(front.depth_cm);
+            self.fronts.pop_front();
+        }
+    }
+
+    /// Depth of the deepest front still moving
+    pub fn deepest_cm(&self) -> Option<f32> {
+        self.fronts.iter().map(|front| front.depth_cm).max_by(f32::total_cmp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn advance_rate_is_proportional_to_the_water_applied() {
+        let rate = |applied_mm| WettingFront::new(SoilType::Loam, applied_mm, 50.0).advance_rate_cm_per_h;
+        // 2 cm of water fills half of loam's 0.43 pore space over 9.30 cm, reached in 12 h
+        assert_close(rate(20.0), 2.0 / 0.215 / 12.0, 1e-3);
+        assert_close(rate(40.0), 2.0 * rate(20.0), 1e-3);
+        assert_close(rate(5.0), rate(20.0) / 4.0, 1e-3);
+        assert_eq!(rate(0.0), 0.0);
+    }
+
+    #[test]
+    fn sand_is_fast_and_clay_slow() {
+        let rate = |soil_type| WettingFront::new(soil_type, 20.0, 50.0).advance_rate_cm_per_h;
+        assert!(rate(SoilType::Sand) > rate(SoilType::Loam) && rate(SoilType::Loam) > rate(SoilType::Clay));
+        // Sand and loam share a pore space, so only the redistribution time tells them apart
+        assert_close(rate(SoilType::Sand), 6.0 * rate(SoilType::Loam), 1e-3);
+    }
+
+    #[test]
+    fn wetter_soil_sends_the_front_deeper() {
+        let depth = |moisture_pct| WettingFront::new(SoilType::Loam, 20.0, moisture_pct).final_depth_cm;
+        assert_close(depth(75.0), 2.0 * depth(50.0), 1e-3);
+        // Saturated soil still takes the water, over the least fillable pore space
+        assert_close(depth(100.0), 2.0 / MIN_FILLABLE_PORE_SPACE, 1e-3);
+    }
+
+    #[test]
+    fn front_stops_where_its_water_comes_to_rest() {
+        let mut front = WettingFront::new(SoilType::Sand, 20.0, 50.0);
+        front.advance(1.0);
+        assert_close(front.depth_cm, front.final_depth_cm / 2.0, 1e-3);
+        assert!(!front.settled());
+        front.advance(5.0);
+        assert_eq!(front.depth_cm, front.final_depth_cm);
+        assert!(front.settled());
+    }
+
+    #[test]
+    fn tracker_follows_each_watering_and_drops_settled_fronts() {
+        let mut tracker = WettingFrontTracker::new(SoilType::Loam);
+        tracker.start(20.0, 50.0);
+        tracker.advance(6.0);
+        tracker.start(40.0, 50.0);
+        tracker.advance(3.0);
+        assert_eq!(tracker.fronts.len(), 2);
+        // The second front carries twice the water, so after 3 h it is as deep as the first was after 6 h
+        let first_final = tracker.fronts[0].final_depth_cm;
+        assert_close(tracker.fronts[1].depth_cm, first_final / 2.0, 1e-3);
+        assert_close(tracker.deepest_cm().unwrap(), first_final * 0.75, 1e-3);
+        tracker.advance(3.0);
+        assert_eq!(tracker.fronts.len(), 1);
+        assert_eq!(tracker.last_settled_depth_cm, Some(first_final));
+        tracker.advance(6.0);
+        assert!(tracker.fronts.is_empty() && tracker.deepest_cm().is_none());
+        assert_close(tracker.last_settled_depth_cm.unwrap(), 2.0 * first_final, 1e-3);
+    }
+}
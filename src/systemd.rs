@@ -0,0 +1,105 @@
+//! systemd unit generation for running the simulator as a service
+
+use std::path::Path;
+
+/// Unit name the generated file should be saved as under `/etc/systemd/system/`
+pub const UNIT_NAME: &str = "agri_iot_simulator.service";
+
+/// A complete unit file starting `exe` with `args` in `working_dir`
+///
+/// The TUI needs a terminal, so the service is attached to virtual console 8,
+/// where the dashboard can be watched with Ctrl+Alt+F8. Relative paths among
+/// `args` resolve against `working_dir`, as they did where the unit was generated.
+pub fn unit_file(exe: &Path, working_dir: &Path, args: &[String]) -> String {
+    let working_dir = escape_specifiers(&working_dir.display().to_string());
+    let mut exec_start = quote(&exe.display().to_string());
+    for arg in args {
+        exec_start.push(' ');
+        exec_start.push_str(&quote(arg));
+    }
+    format!(
+        "[Unit]
+Description=Agri-IoT soil moisture simulator
+After=network.target
+
+[Service]
+Type=simple
+User=agri
+SupplementaryGroups=tty
+WorkingDirectory={working_dir}
+ExecStart={exec_start}
+Restart=on-failure
+RestartSec=5
+Environment=RUST_LOG=info
+StandardInput=tty
+StandardOutput=tty
+TTYPath=/dev/tty8
+TTYReset=yes
+TTYVHangup=yes
+
+[Install]
+WantedBy=multi-user.target
+"
+    )
+}
+
+/// Doubles `%`, which systemd would otherwise expand as a specifier such as `%h`
+fn escape_specifiers(value: &str) -> String {
+    value.replace('%', "%%")
+}
+
+/// Quotes a command-line word for `ExecStart` when it contains spaces or quotes, and escapes the
+/// `%` specifiers and `$` variables systemd would otherwise expand in it
+fn quote(word: &str) -> String {
+    let word = escape_specifiers(word).replace('$', "$$");
+    if word.is_empty() || word.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\' || c == '\'') {
+        format!("\"{}\"", word.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        word
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service_section(unit: &str) -> Vec<(&str, &str)> {
+        unit.lines()
+            .skip_while(|line| *line != "[Service]")
+            .skip(1)
+            .take_while(|line| !line.starts_with('['))
+            .filter(|line| !line.is_empty())
+            .map(|line| line.split_once('=').expect("every line is key=value"))
+            .collect()
+    }
+
+    #[test]
+    fn unit_file_is_well_formed_ini() {
+        let unit = unit_file(Path::new("/usr/bin/agri"), Path::new("/srv/farm"), &["--http-port".into(), "8080".into()]);
+        let sections: Vec<&str> = unit.lines().filter(|line| line.starts_with('[')).collect();
+        assert_eq!(sections, ["[Unit]", "[Service]", "[Install]"]);
+        for line in unit.lines().filter(|line| !line.is_empty() && !line.starts_with('[')) {
+            let (key, _) = line.split_once('=').unwrap_or_else(|| panic!("{line:?} is not key=value"));
+            assert!(!key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric()), "bad key in {line:?}");
+        }
+        let service = service_section(&unit);
+        assert!(service.contains(&("ExecStart", "/usr/bin/agri --http-port 8080")));
+        assert!(service.contains(&("WorkingDirectory", "/srv/farm")));
+        assert!(unit.ends_with("WantedBy=multi-user.target\n"));
+    }
+
+    #[test]
+    fn quote_wraps_words_with_spaces_and_escapes_quotes() {
+        assert_eq!(quote("plain"), "plain");
+        assert_eq!(quote("my farm.toml"), "\"my farm.toml\"");
+        assert_eq!(quote("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(quote(""), "\"\"");
+    }
+
+    #[test]
+    fn quote_escapes_specifiers_and_variables() {
+        assert_eq!(quote("100%"), "100%%");
+        assert_eq!(quote("$HOME/farm"), "$$HOME/farm");
+        assert_eq!(escape_specifiers("/srv/%i"), "/srv/%%i");
+    }
+}
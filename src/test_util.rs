@@ -0,0 +1,7 @@
+//! Helpers shared by the unit tests
+
+/// Asserts that `actual` is within `tolerance` of `expected`
+#[track_caller]
+pub(crate) fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+    assert!((actual - expected).abs() <= tolerance, "{actual} is not within {tolerance} of {expected}");
+}
@@ -0,0 +1,81 @@
+//! ASCII art panels and their state-dependent coloring
+
+use ratatui::prelude::*;
+
+use super::Theme;
+use crate::hydroponic::HydroponicState;
+use crate::DeviceState;
+
+/// Neon flower shown for the soil moisture sensor
+pub static FLOWER_BASE: &str = "            .--. 
+      .-\"-:`    `:-\"-.
+   .-/     '.  .'     \\-.
+  ;__|      _::_      |__;
+ /`   '.  /` \\/` \\  .'   `\\
+ |      _ \\      / _      |
+ \\    /` '.'.  .'.' `\\    /
+/ '-._'.  _'./\\.'_  .'_.-' \\
+\\ .-' .'`  .'\\//'.  `'. '-. /
+ /    \\._.'.'  '.'._./    \\
+ |        /      \\        |
+ \\.__ .'  \\._/\\_.//  '. __./
+  ;  |       ::       |  ;
+   '-\\     .'  '.     /-'
+ jgs  '-.-:_    _:-.-'
+            '--'";
+
+/// Colors one flower line for the sensor state
+// Add lifetime 'a to tie Line to the borrowed line
+pub fn style_line<'a>(index: usize, line: &'a str, blink_frame: usize, state: &DeviceState) -> Line<'a> {
+    match state {
+        DeviceState::Monitoring => Line::from(line).style(Style::default().fg(Color::Yellow)),
+        DeviceState::Activating => {
+            let color = if blink_frame == 0 { Color::Blue } else { Color::Black };
+            Line::from(line).style(Style::default().fg(color))
+        }
+        DeviceState::Adjusting => Line::from(line).style(Style::default().fg(Color::Cyan)),
+        DeviceState::Idle => {
+            if index == 11 || ((7..=9).contains(&index) && line.contains(" / ")) {
+                Line::from(line).style(Style::default().fg(Color::White)) // Center
+            } else {
+                Line::from(line).style(Style::default().fg(Color::Rgb(255, 165, 0))) // Petals
+            }
+        }
+        DeviceState::Error => {
+            let color = if blink_frame == 0 { Color::Red } else { Color::Black };
+            Line::from(line).style(Style::default().fg(color))
+        }
+    }
+}
+
+/// Root system shown in hydroponic mode
+pub static ROOTS_BASE: &str = "      \\  |  | //
+       \\ |  |//
+     ~~~~|~~|~~~~
+        /|  |\\
+       / |  | \\
+      /  /  \\  \\
+     / ./    \\. \\
+    ' /  \\  /  \\ '
+     /    \\/    \\
+    '   ./  \\.   '
+       '      '";
+
+/// Colors one root line for the nutrient solution state
+pub fn style_root_line(line: &str, blink_frame: usize, state: HydroponicState, theme: Theme) -> Line<'_> {
+    let color = match (theme, state) {
+        (Theme::HighContrast, HydroponicState::Recirculating) => Color::White,
+        (Theme::HighContrast, HydroponicState::Error) => Color::Red,
+        (Theme::HighContrast, _) => Color::Yellow,
+        (Theme::Neon, HydroponicState::Recirculating) => Color::Green,
+        (Theme::Neon, HydroponicState::Draining) => Color::Yellow,
+        (Theme::Neon, HydroponicState::Refilling) => Color::Cyan,
+        (Theme::Neon, HydroponicState::Flushing) => {
+            if blink_frame == 0 { Color::Blue } else { Color::White }
+        }
+        (Theme::Neon, HydroponicState::Error) => {
+            if blink_frame == 0 { Color::Red } else { Color::Black }
+        }
+    };
+    Line::from(line).style(Style::default().fg(color))
+}
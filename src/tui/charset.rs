@@ -0,0 +1,228 @@
+//! Unicode or pure-ASCII glyphs for terminals that cannot render Unicode
+
+use ratatui::symbols::{bar, border};
+use ratatui::widgets::block::Title;
+use ratatui::widgets::{Block, Borders};
+
+use crate::agri_iot_core::barometer::PressureTrend;
+use crate::agri_iot_core::trend::Trend;
+use crate::ghg::EmissionSource;
+use crate::water_volume::WaterSource;
+use crate::DeviceState;
+
+/// Borders drawn with `+`, `-` and `|`
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+/// Bars drawn with `#`, with `:` and `.` for partial cells
+const ASCII_BARS: bar::Set = bar::Set {
+    full: "#",
+    seven_eighths: "#",
+    three_quarters: "#",
+    five_eighths: ":",
+    half: ":",
+    three_eighths: ":",
+    one_quarter: ".",
+    one_eighth: ".",
+    empty: " ",
+};
+
+/// Which characters the TUI may draw with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Charset {
+    #[default]
+    Unicode,
+    /// Only codepoints below 128, for old hardware and limited SSH terminals
+    Ascii,
+}
+
+impl Charset {
+    /// A titled panel with borders drawn in this charset
+    pub fn block<'a>(self, title: impl Into<Title<'a>>) -> Block<'a> {
+        let block = Block::default().title(title).borders(Borders::ALL);
+        match self {
+            Charset::Unicode => block,
+            Charset::Ascii => block.border_set(ASCII_BORDER),
+        }
+    }
+
+    /// An untitled bordered panel
+    pub fn plain_block(self) -> Block<'static> {
+        self.block("")
+    }
+
+    /// Symbols used by bar charts
+    pub fn bar_set(self) -> bar::Set {
+        match self {
+            Charset::Unicode => bar::NINE_LEVELS,
+            Charset::Ascii => ASCII_BARS,
+        }
+    }
+
+    /// A fully filled cell
+    pub fn full_block(self) -> char {
+        match self {
+            Charset::Unicode => '█',
+            Charset::Ascii => '#',
+        }
+    }
+
+    /// An unfilled cell of a drawing grid
+    pub fn empty_cell(self) -> char {
+        match self {
+            Charset::Unicode => '·',
+            Charset::Ascii => '.',
+        }
+    }
+
+    /// Cell whose density tracks a moisture percentage
+    pub fn shade(self, moisture: f32) -> char {
+        let level = match moisture {
+            m if m >= 75.0 => 3,
+            m if m >= 50.0 => 2,
+            m if m >= 25.0 => 1,
+            m if m > 0.0 => 0,
+            _ => return ' ',
+        };
+        match self {
+            Charset::Unicode => ['░', '▒', '▓', '█'][level],
+            Charset::Ascii => ['.', '/', '%', '#'][level],
+        }
+    }
+
+    /// Symbol shown after a state's name
+    pub fn state_symbol(self, state: DeviceState) -> &'static str {
+        match self {
+            Charset::Unicode => state.emoji(),
+            Charset::Ascii => match state {
+                DeviceState::Monitoring => "?",
+                DeviceState::Activating => "~",
+                DeviceState::Adjusting => "~~",
+                DeviceState::Idle => "[OK]",
+                DeviceState::Error => "[X]",
+            },
+        }
+    }
+
+    /// Arrow for a moisture trend; steep trends point straight up or down
+    pub fn trend_arrow(self, trend: Trend) -> &'static str {
+        let index = match trend {
+            Trend::Rising(_) if trend.is_steep() => 0,
+            Trend::Rising(_) => 1,
+            Trend::Stable => 2,
+            Trend::Falling(_) if !trend.is_steep() => 3,
+            Trend::Falling(_) => 4,
+        };
+        match self {
+            Charset::Unicode => ["↑", "↗", "→", "↘", "↓"][index],
+            Charset::Ascii => ["^", "/", "-", "\\", "v"][index],
+        }
+    }
+
+    /// Arrow for the barometer's pressure tendency
+    pub fn pressure_arrow(self, trend: PressureTrend) -> &'static str {
+        let index = match trend {
+            PressureTrend::Rising => 0,
+            PressureTrend::Stable => 1,
+            PressureTrend::Falling => 2,
+        };
+        match self {
+            Charset::Unicode => ["↑", "→", "↓"][index],
+            Charset::Ascii => ["^", "-", "v"][index],
+        }
+    }
+
+    /// Fill character for one slice of the emissions pie chart
+    pub fn emission_glyph(self, source: EmissionSource) -> char {
+        match (self, source) {
+            (Charset::Unicode, EmissionSource::Electricity) => '█',
+            (Charset::Unicode, EmissionSource::Transport) => '▒',
+            (Charset::Unicode, EmissionSource::Fertilizer) => '░',
+            (Charset::Ascii, EmissionSource::Electricity) => '#',
+            (Charset::Ascii, EmissionSource::Transport) => '=',
+            (Charset::Ascii, EmissionSource::Fertilizer) => '.',
+        }
+    }
+
+    /// Fill character for one water source in the stats panel's breakdown bar
+    pub fn water_source_glyph(self, source: WaterSource) -> char {
+        match (self, source) {
+            (Charset::Unicode, WaterSource::Rain) => '█',
+            (Charset::Unicode, WaterSource::Irrigation) => '▓',
+            (Charset::Unicode, WaterSource::Harvested) => '▒',
+            (Charset::Unicode, WaterSource::Fertigation) => '░',
+            (Charset::Unicode, WaterSource::Leaching) => '▚',
+            (Charset::Unicode, WaterSource::FrostProtection) => '▞',
+            (Charset::Ascii, WaterSource::Rain) => '#',
+            (Charset::Ascii, WaterSource::Irrigation) => '=',
+            (Charset::Ascii, WaterSource::Harvested) => '+',
+            (Charset::Ascii, WaterSource::Fertigation) => '.',
+            (Charset::Ascii, WaterSource::Leaching) => '~',
+            (Charset::Ascii, WaterSource::FrostProtection) => '*',
+        }
+    }
+
+    /// Dot drawn in front of a colored status
+    pub fn indicator(self) -> &'static str {
+        match self {
+            Charset::Unicode => "●",
+            Charset::Ascii => "*",
+        }
+    }
+
+    /// Key names for the arrow keys, in the order left/right and up/down
+    pub fn arrows(self) -> (&'static str, &'static str) {
+        match self {
+            Charset::Unicode => ("←/→", "↑/↓"),
+            Charset::Ascii => ("Left/Right", "Up/Down"),
+        }
+    }
+
+    /// Unit suffix for degrees Celsius
+    pub fn celsius(self) -> &'static str {
+        match self {
+            Charset::Unicode => "°C",
+            Charset::Ascii => "C",
+        }
+    }
+
+    /// Unit of heat units and growing degree days
+    pub fn degree_days(self) -> &'static str {
+        match self {
+            Charset::Unicode => "°C·days",
+            Charset::Ascii => "C-days",
+        }
+    }
+
+    /// Unit suffix for an angle in degrees, such as a wind direction
+    pub fn degrees(self) -> &'static str {
+        match self {
+            Charset::Unicode => "°",
+            Charset::Ascii => " deg",
+        }
+    }
+
+    /// Multiplication sign, as in a weighting
+    pub fn times(self) -> &'static str {
+        match self {
+            Charset::Unicode => "×",
+            Charset::Ascii => "x",
+        }
+    }
+
+    /// Unit suffix for per square metre, as in `W/m²`
+    pub fn per_m2(self) -> &'static str {
+        match self {
+            Charset::Unicode => "/m²",
+            Charset::Ascii => "/m2",
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! Bottom-panel visualizations selectable with `c` and `1`-`7`
+
+use std::collections::VecDeque;
+
+use ratatui::prelude::*;
+use ratatui::symbols;
+use ratatui::widgets::{Axis, Bar, BarChart, BarGroup, Chart, Dataset, GraphType, Paragraph};
+
+use super::{Charset, Theme};
+use crate::ghg::{EmissionSource, GhgTracker};
+use crate::soil::organic_matter::SomTracker;
+use crate::{DeviceState, IrrigationZone, Tick};
+
+/// Ticks of history kept per zone for the charts
+pub const HISTORY_LEN: usize = 120;
+
+/// Ticks covered by the state timeline
+pub const TIMELINE_TICKS: u64 = 60;
+
+/// A stretch of time a zone spent in one state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateTimelineEntry {
+    pub state: DeviceState,
+    pub entered_at: Tick,
+    pub exited_at: Option<Tick>, // None while the zone is still in this state
+}
+
+impl StateTimelineEntry {
+    /// Whether the zone was in this state during `tick`
+    fn covers(&self, tick: Tick) -> bool {
+        self.entered_at <= tick && self.exited_at.is_none_or(|exited| tick < exited)
+    }
+}
+
+/// Recent samples of one zone, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct ZoneHistory {
+    pub moisture: VecDeque<f32>,
+    pub timeline: VecDeque<StateTimelineEntry>,
+    pub current_tick: Tick,
+}
+
+impl ZoneHistory {
+    /// Appends the zone's current reading, dropping samples older than the charts show
+    pub fn record(&mut self, zone: &IrrigationZone) {
+        self.moisture.push_back(zone.sensor.moisture_level);
+        if self.moisture.len() > HISTORY_LEN {
+            self.moisture.pop_front();
+        }
+
+        let tick = zone.sensor.current_tick;
+        self.current_tick = tick;
+        match self.timeline.back_mut() {
+            Some(entry) if entry.state == zone.sensor.state => {}
+            last => {
+                if let Some(entry) = last {
+                    entry.exited_at = Some(tick);
+                }
+                self.timeline.push_back(StateTimelineEntry {
+                    state: zone.sensor.state,
+                    entered_at: tick,
+                    exited_at: None,
+                });
+            }
+        }
+        let window_start = self.window_start();
+        while self.timeline.front().is_some_and(|entry| entry.exited_at.is_some_and(|exited| exited <= window_start)) {
+            self.timeline.pop_front();
+        }
+    }
+
+    /// First tick of the timeline window
+    fn window_start(&self) -> Tick {
+        Tick(self.current_tick.0.saturating_sub(TIMELINE_TICKS - 1))
+    }
+
+    /// State the zone was in during `tick`, if still recorded
+    fn state_at(&self, tick: Tick) -> Option<DeviceState> {
+        self.timeline.iter().rev().find(|entry| entry.covers(tick)).map(|entry| entry.state)
+    }
+}
+
+/// Moisture samples as chart points, oldest at x = 0
+pub fn moisture_points(history: &ZoneHistory) -> Vec<(f64, f64)> {
+    history
+        .moisture
+        .iter()
+        .enumerate()
+        .map(|(i, &moisture)| (i as f64, f64::from(moisture)))
+        .collect()
+}
+
+/// Horizontal line at the zone's watering threshold spanning the chart
+pub fn threshold_points(zone: &IrrigationZone) -> Vec<(f64, f64)> {
+    let level = f64::from(zone.sensor.threshold);
+    vec![(0.0, level), ((HISTORY_LEN - 1) as f64, level)]
+}
+
+/// Moisture history plotted with `*` for samples and `-` for the threshold, for ASCII-only terminals
+pub fn ascii_moisture_history(zone: &IrrigationZone, history: &ZoneHistory, area: Rect) -> Paragraph<'static> {
+    let rows = area.height.saturating_sub(2).max(1) as usize;
+    let columns = area.width.saturating_sub(2) as usize;
+    let samples: Vec<f32> = history.moisture.iter().skip(history.moisture.len().saturating_sub(columns)).copied().collect();
+    let row_of = |moisture: f32| ((100.0 - moisture.clamp(0.0, 100.0)) / 100.0 * (rows - 1) as f32).round() as usize;
+    let threshold_row = row_of(zone.sensor.threshold);
+    let lines: Vec<Line> = (0..rows)
+        .map(|row| {
+            let line: String = samples
+                .iter()
+                .map(|&moisture| match row {
+                    _ if row_of(moisture) == row => '*',
+                    _ if row == threshold_row => '-',
+                    _ => ' ',
+                })
+                .collect();
+            Line::from(line)
+        })
+        .collect();
+    Paragraph::new(lines).block(Charset::Ascii.block("Moisture History (0-100%)"))
+}
+
+/// Line chart of a zone's moisture with its watering threshold
+pub fn moisture_history<'a>(moisture: &'a [(f64, f64)], threshold: &'a [(f64, f64)], theme: Theme) -> Chart<'a> {
+    let datasets = vec![
+        Dataset::default()
+            .name("moisture")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.moisture_line_color()))
+            .data(moisture),
+        Dataset::default()
+            .name("threshold")
+            .marker(symbols::Marker::Dot)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Red))
+            .data(threshold),
+    ];
+    Chart::new(datasets)
+        .block(Charset::Unicode.block("Moisture History"))
+        .x_axis(Axis::default().title("tick").bounds([0.0, (HISTORY_LEN - 1) as f64]))
+        .y_axis(
+            Axis::default()
+                .title("%")
+                .bounds([0.0, 100.0])
+                .labels(vec!["0".into(), "50".into(), "100".into()]),
+        )
+}
+
+/// One bar per zone showing its current moisture
+pub fn sensor_bars(zones: &[IrrigationZone], theme: Theme, charset: Charset) -> BarChart<'static> {
+    let bars: Vec<Bar> = zones
+        .iter()
+        .map(|zone| {
+            Bar::default()
+                .label(format!("Zone {}", zone.id).into())
+                .value(zone.sensor.moisture_level.round() as u64)
+                .style(Style::default().fg(theme.state_color(zone.sensor.state)))
+        })
+        .collect();
+    BarChart::default()
+        .block(charset.block("Sensor Bars"))
+        .bar_set(charset.bar_set())
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(9)
+        .bar_gap(2)
+        .max(100)
+}
+
+/// Soil organic matter at the end of each simulated year, then now; bars are in hundredths of a percent
+pub fn som_trend(som: &SomTracker, theme: Theme, charset: Charset) -> BarChart<'static> {
+    let years = som.annual_som_pct.iter().enumerate().map(|(year, &pct)| (format!("Year {}", year + 1), pct));
+    let bars: Vec<Bar> = years
+        .chain(std::iter::once(("Now".to_string(), som.som_pct)))
+        .map(|(label, pct)| {
+            Bar::default()
+                .label(label.into())
+                .value((pct * 100.0).round() as u64)
+                .text_value(format!("{pct:.2}%"))
+                .style(Style::default().fg(theme.som_color(pct >= som.initial_som_pct)))
+        })
+        .collect();
+    BarChart::default()
+        .block(charset.block(format!("Soil Organic Matter (started at {:.2}%)", som.initial_som_pct)))
+        .bar_set(charset.bar_set())
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(9)
+        .bar_gap(2)
+}
+
+/// Text pie chart of the season's emissions by source, with a legend beside it
+pub fn emissions_pie(ghg: Option<&GhgTracker>, charset: Charset) -> Paragraph<'static> {
+    const RADIUS: i32 = 4; // In rows; columns are half as tall, so the pie is twice as wide
+    let block = charset.block("Season Greenhouse Gas Emissions");
+    let Some(ghg) = ghg else {
+        return Paragraph::new("Add a [ghg] table to the config to track emissions").block(block);
+    };
+    let season = ghg.season;
+    let mut legend: Vec<String> = EmissionSource::ALL
+        .iter()
+        .map(|&source| {
+            let glyph = charset.emission_glyph(source);
+            let share = season.share(source) * 100.0;
+            format!("{glyph} {:<16}{:>8.1} kg ({share:.0}%)", source.name(), season.kg_co2(source))
+        })
+        .collect();
+    legend.push(String::new());
+    legend.push(format!("Total {:.1} kg CO2e/ha", season.total_kg_co2e()));
+    if let Some(target) = ghg.target_kg_co2e_ha {
+        let verdict = if ghg.over_target() { "over" } else { "within" };
+        legend.push(format!("Target {target:.0} kg CO2e/ha: {verdict}"));
+    }
+    let lines: Vec<Line> = (-RADIUS..=RADIUS)
+        .enumerate()
+        .map(|(row_index, row)| {
+            let pie: String = (-2 * RADIUS..=2 * RADIUS)
+                .map(|column| {
+                    let (x, y) = (column as f32 / 2.0, row as f32);
+                    if x * x + y * y > (RADIUS as f32 + 0.5).powi(2) {
+                        return ' ';
+                    }
+                    if season.total_kg_co2e() <= 0.0 {
+                        return if charset == Charset::Ascii { '-' } else { '·' };
+                    }
+                    // Share of a clockwise turn from twelve o'clock
+                    let turn = (x.atan2(-y) / std::f32::consts::TAU).rem_euclid(1.0);
+                    let mut start = 0.0;
+                    let source = EmissionSource::ALL
+                        .into_iter()
+                        .find(|&source| {
+                            start += season.share(source);
+                            turn < start
+                        })
+                        .unwrap_or(EmissionSource::Fertilizer);
+                    charset.emission_glyph(source)
+                })
+                .collect();
+            let legend_line = legend.get(row_index).cloned().unwrap_or_default();
+            Line::from(format!("{pie}   {legend_line}"))
+        })
+        .collect();
+    Paragraph::new(lines).block(block)
+}
+
+/// Gantt chart of the last [`TIMELINE_TICKS`] ticks: one row per state, then tick labels and time shares
+pub fn state_timeline(history: &ZoneHistory, width: u16, theme: Theme, charset: Charset) -> Paragraph<'static> {
+    const LABEL_WIDTH: usize = 12;
+    let columns = (width.saturating_sub(2) as usize).saturating_sub(LABEL_WIDTH) as u64;
+    let first_recorded = history.timeline.front().map_or(history.current_tick, |entry| entry.entered_at);
+    let first_visible = Tick(history.current_tick.0.saturating_sub(columns.saturating_sub(1)));
+    let start = history.window_start().max(first_recorded).max(first_visible);
+    let ticks: Vec<Tick> = (start.0..=history.current_tick.0).map(Tick).collect();
+    let states: Vec<Option<DeviceState>> = ticks.iter().map(|&tick| history.state_at(tick)).collect();
+
+    let mut lines: Vec<Line> = DeviceState::ALL
+        .into_iter()
+        .map(|state| {
+            let bar: String = states.iter().map(|&s| if s == Some(state) { charset.full_block() } else { ' ' }).collect();
+            Line::from(vec![
+                Span::raw(format!("{:<LABEL_WIDTH$}", state.name())),
+                Span::styled(bar, Style::default().fg(theme.state_color(state))),
+            ])
+        })
+        .collect();
+
+    let first = start.0.to_string();
+    let last = history.current_tick.0.to_string();
+    let gap = ticks.len().saturating_sub(first.len() + last.len()).max(1);
+    lines.push(Line::from(format!("{:<LABEL_WIDTH$}{first}{}{last}", "tick", " ".repeat(gap))));
+    lines.push(Line::default());
+
+    let recorded = states.iter().flatten().count().max(1) as f32;
+    let shares: Vec<Span> = DeviceState::ALL
+        .into_iter()
+        .map(|state| {
+            let share = states.iter().filter(|&&s| s == Some(state)).count() as f32 / recorded * 100.0;
+            Span::styled(format!("{} {share:.0}%  ", state.name()), Style::default().fg(theme.state_color(state)))
+        })
+        .collect();
+    lines.push(Line::from(shares));
+
+    Paragraph::new(lines).block(charset.block("State Timeline"))
+}
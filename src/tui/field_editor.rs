@@ -0,0 +1,238 @@
+//! Drawing the field's zones cell by cell on a grid, north at the top
+
+use ratatui::prelude::*;
+use ratatui::widgets::Widget;
+
+use super::Charset;
+use crate::crop::yield_map::{FieldLayout, GeoPoint};
+
+/// Cells across the grid, west to east
+pub const GRID_COLUMNS: u16 = 40;
+/// Cells down the grid, north to south
+pub const GRID_ROWS: u16 = 20;
+/// South-west corner of a field drawn without a configured layout, the yield map's default origin
+pub const DEFAULT_ORIGIN: GeoPoint = GeoPoint { latitude: 52.0, longitude: 5.0 };
+/// Side of a cell of such a field, in degrees (about 14 m east-west by 22 m north-south)
+pub const DEFAULT_CELL_DEG: f64 = 0.0002;
+
+/// Color a zone's cells are drawn in; ten, one per digit key
+pub fn zone_color(zone_id: u8) -> Color {
+    const COLORS: [Color; 10] = [
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::LightRed,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightBlue,
+    ];
+    COLORS[zone_id as usize % COLORS.len()]
+}
+
+/// The grid being drawn and where the cursor is on it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldBoundaryEditor {
+    pub cells: Vec<Option<u8>>, // Zone of each cell, row by row from the north-west corner
+    pub cursor: (u16, u16),     // Column and row
+    pub current_zone: u8,       // Zone `Space` assigns
+    pub south_west: GeoPoint,
+    pub north_east: GeoPoint,
+}
+
+impl Default for FieldBoundaryEditor {
+    fn default() -> Self {
+        let north_east = GeoPoint {
+            latitude: DEFAULT_ORIGIN.latitude + GRID_ROWS as f64 * DEFAULT_CELL_DEG,
+            longitude: DEFAULT_ORIGIN.longitude + GRID_COLUMNS as f64 * DEFAULT_CELL_DEG,
+        };
+        Self {
+            cells: vec![None; GRID_COLUMNS as usize * GRID_ROWS as usize],
+            cursor: (0, 0),
+            current_zone: 0,
+            south_west: DEFAULT_ORIGIN,
+            north_east,
+        }
+    }
+}
+
+impl FieldBoundaryEditor {
+    /// A grid over the bounding box of `layout`, with its zones when it was drawn on a
+    /// grid of this size
+    pub fn from_layout(layout: &FieldLayout) -> Self {
+        let mut editor = Self { south_west: layout.south_west, north_east: layout.north_east, ..Self::default() };
+        let same_grid = layout.rows == GRID_ROWS as u32 && layout.columns == GRID_COLUMNS as u32;
+        if same_grid && layout.cell_zones.len() == editor.cells.len() {
+            // The layout runs from the south, the grid from the north
+            for (row, zones) in layout.cell_zones.chunks(GRID_COLUMNS as usize).enumerate() {
+                let start = (GRID_ROWS as usize - 1 - row) * GRID_COLUMNS as usize;
+                editor.cells[start..start + zones.len()].copy_from_slice(zones);
+            }
+        }
+        editor
+    }
+
+    /// Moves the cursor by whole cells, stopping at the edges
+    pub fn move_cursor(&mut self, d_column: i32, d_row: i32) {
+        let (column, row) = self.cursor;
+        self.cursor = (
+            (column as i32 + d_column).clamp(0, GRID_COLUMNS as i32 - 1) as u16,
+            (row as i32 + d_row).clamp(0, GRID_ROWS as i32 - 1) as u16,
+        );
+    }
+
+    /// Zone of the cell at `column` and `row`, if one was assigned
+    pub fn cell(&self, column: u16, row: u16) -> Option<u8> {
+        self.cells.get(row as usize * GRID_COLUMNS as usize + column as usize).copied().flatten()
+    }
+
+    fn cursor_cell(&mut self) -> &mut Option<u8> {
+        let (column, row) = self.cursor;
+        &mut self.cells[row as usize * GRID_COLUMNS as usize + column as usize]
+    }
+
+    /// Assigns the cell under the cursor to the current zone, or clears it if it already is
+    pub fn toggle(&mut self) {
+        let zone_id = self.current_zone;
+        let cell = self.cursor_cell();
+        *cell = if *cell == Some(zone_id) { None } else { Some(zone_id) };
+    }
+
+    /// Makes `zone_id` the current zone and assigns the cell under the cursor to it
+    pub fn assign(&mut self, zone_id: u8) {
+        self.current_zone = zone_id;
+        *self.cursor_cell() = Some(zone_id);
+    }
+
+    /// The grid as a layout over the bounding box, rows from the south
+    pub fn layout(&self) -> FieldLayout {
+        let cell_zones = self.cells.chunks(GRID_COLUMNS as usize).rev().flatten().copied().collect();
+        FieldLayout {
+            south_west: self.south_west,
+            north_east: self.north_east,
+            rows: GRID_ROWS as u32,
+            columns: GRID_COLUMNS as u32,
+            cell_zones,
+        }
+    }
+}
+
+/// The editor's grid: unassigned cells dotted, assigned ones filled in their zone's color and
+/// the cursor reversed
+pub struct FieldGrid<'a> {
+    editor: &'a FieldBoundaryEditor,
+    charset: Charset,
+}
+
+impl<'a> FieldGrid<'a> {
+    pub fn new(editor: &'a FieldBoundaryEditor) -> Self {
+        Self { editor, charset: Charset::default() }
+    }
+
+    /// Draws with `charset` instead of Unicode dots and blocks
+    pub fn charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+}
+
+impl Widget for FieldGrid<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = format!("Field zones: zone {} (Space, 0-9, Enter to save, Esc)", self.editor.current_zone);
+        let block = self.charset.block(title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        for row in 0..GRID_ROWS.min(inner.height) {
+            for column in 0..GRID_COLUMNS.min(inner.width) {
+                let (symbol, mut style) = match self.editor.cell(column, row) {
+                    Some(zone_id) => (self.charset.full_block(), Style::default().fg(zone_color(zone_id))),
+                    None => (self.charset.empty_cell(), Style::default().fg(Color::DarkGray)),
+                };
+                if (column, row) == self.editor.cursor {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                buf.get_mut(inner.x + column, inner.y + row).set_char(symbol).set_style(style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_moves_by_cells_and_stops_at_the_edges() {
+        let mut editor = FieldBoundaryEditor::default();
+        editor.move_cursor(-1, -1);
+        assert_eq!(editor.cursor, (0, 0));
+        editor.move_cursor(3, 2);
+        assert_eq!(editor.cursor, (3, 2));
+        editor.move_cursor(100, 100);
+        assert_eq!(editor.cursor, (GRID_COLUMNS - 1, GRID_ROWS - 1));
+        editor.move_cursor(1, 0);
+        assert_eq!(editor.cursor, (GRID_COLUMNS - 1, GRID_ROWS - 1));
+        editor.move_cursor(-(GRID_COLUMNS as i32), 0);
+        assert_eq!(editor.cursor, (0, GRID_ROWS - 1));
+    }
+
+    #[test]
+    fn space_toggles_the_current_zone_and_digits_assign_another() {
+        let mut editor = FieldBoundaryEditor::default();
+        editor.move_cursor(2, 1);
+        editor.toggle();
+        assert_eq!(editor.cell(2, 1), Some(0));
+        editor.toggle();
+        assert_eq!(editor.cell(2, 1), None);
+        editor.assign(7);
+        assert_eq!((editor.cell(2, 1), editor.current_zone), (Some(7), 7));
+        // A digit overwrites another zone's cell, and Space then toggles the new zone
+        editor.assign(3);
+        assert_eq!(editor.cell(2, 1), Some(3));
+        editor.toggle();
+        assert_eq!(editor.cell(2, 1), None);
+        editor.move_cursor(1, 0);
+        editor.toggle();
+        assert_eq!(editor.cell(3, 1), Some(3));
+        assert_eq!(editor.cells.iter().flatten().count(), 1);
+    }
+
+    #[test]
+    fn layout_runs_from_the_south_and_reads_back() {
+        let mut editor = FieldBoundaryEditor::default();
+        editor.assign(1); // North-west corner
+        editor.move_cursor(GRID_COLUMNS as i32, GRID_ROWS as i32);
+        editor.assign(4); // South-east corner
+        let layout = editor.layout();
+        assert_eq!((layout.rows, layout.columns), (GRID_ROWS as u32, GRID_COLUMNS as u32));
+        assert_eq!(layout.cell_zones[GRID_COLUMNS as usize - 1], Some(4));
+        assert_eq!(layout.cell_zones[(GRID_ROWS as usize - 1) * GRID_COLUMNS as usize], Some(1));
+        assert_eq!(FieldBoundaryEditor::from_layout(&layout).cells, editor.cells);
+        // A layout drawn on another grid keeps only its bounding box
+        let coarse = FieldLayout { rows: 2, columns: 2, cell_zones: vec![Some(0); 4], ..layout };
+        assert!(FieldBoundaryEditor::from_layout(&coarse).cells.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn drawn_cells_serialize_as_geojson_polygons() {
+        let mut editor = FieldBoundaryEditor::default();
+        editor.assign(2);
+        let geojson = editor.layout().geojson();
+        assert_eq!(geojson["type"], "FeatureCollection");
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 1);
+        assert_eq!(features[0]["properties"]["zone_id"], 2);
+        let ring = features[0]["geometry"]["coordinates"][0].as_array().unwrap();
+        let corner = |index: usize| (ring[index][0].as_f64().unwrap(), ring[index][1].as_f64().unwrap());
+        // The north-west cell: its south-west corner is a cell's side below the north edge
+        let (west, south) = corner(0);
+        assert!((west - DEFAULT_ORIGIN.longitude).abs() < 1e-9);
+        assert!((south - (DEFAULT_ORIGIN.latitude + (GRID_ROWS - 1) as f64 * DEFAULT_CELL_DEG)).abs() < 1e-9);
+        let (east, north) = corner(2);
+        assert!((east - west - DEFAULT_CELL_DEG).abs() < 1e-9 && (north - south - DEFAULT_CELL_DEG).abs() < 1e-9);
+        assert_eq!(ring[0], ring[4]);
+    }
+}
@@ -0,0 +1,114 @@
+//! Vertical gauge for the pressure differential across a zone's filter
+
+use ratatui::prelude::*;
+use ratatui::widgets::Widget;
+
+use super::{Charset, Theme};
+
+/// Differential at which a filter should be cleaned soon, in kPa
+pub const YELLOW_KPA: f32 = 20.0;
+/// Differential at which a filter needs cleaning now, in kPa
+pub const RED_KPA: f32 = 50.0;
+/// Differential shown by a full bar, in kPa
+const FULL_SCALE_KPA: f32 = 70.0;
+
+/// Green below [`YELLOW_KPA`], yellow up to [`RED_KPA`], red above
+pub fn pressure_color(kpa: f32) -> Color {
+    match kpa {
+        kpa if kpa >= RED_KPA => Color::Red,
+        kpa if kpa >= YELLOW_KPA => Color::Yellow,
+        _ => Color::Green,
+    }
+}
+
+/// Bar filling upwards with a filter's pressure differential, over a kPa label
+pub struct PressureGauge {
+    kpa: f32,
+    blink_on: bool, // Whether the over-limit `!` is drawn this frame
+    theme: Theme,
+    charset: Charset,
+}
+
+impl PressureGauge {
+    pub fn new(kpa: f32) -> Self {
+        Self { kpa, blink_on: true, theme: Theme::default(), charset: Charset::default() }
+    }
+
+    /// Draws the `!` above the red threshold only when `on`, so it blinks across frames
+    pub fn blink(mut self, on: bool) -> Self {
+        self.blink_on = on;
+        self
+    }
+
+    /// Colors the bar with `theme` instead of green, yellow and red
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Draws with `charset` instead of Unicode blocks
+    pub fn charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+}
+
+impl Widget for PressureGauge {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = self.charset.block("dP");
+        let inner = block.inner(area);
+        block.render(area, buf);
+        if inner.height < 2 || inner.width == 0 {
+            return;
+        }
+
+        // The bottom row holds the label; every row above it covers an equal slice of the scale
+        let rows = inner.height - 1;
+        let filled = (self.kpa / FULL_SCALE_KPA * rows as f32).round().clamp(0.0, rows as f32) as u16;
+        let bar_x = inner.x + inner.width.saturating_sub(1) / 2;
+        for row in 0..rows {
+            let row_kpa = (row as f32 + 0.5) / rows as f32 * FULL_SCALE_KPA;
+            let y = inner.y + rows - 1 - row;
+            let (symbol, style) = if row < filled {
+                (self.charset.full_block(), Style::default().fg(self.theme.pressure_color(row_kpa)))
+            } else {
+                (self.charset.shade(1.0), Style::default().fg(Color::DarkGray))
+            };
+            buf.get_mut(bar_x, y).set_char(symbol).set_style(style);
+        }
+
+        let alarm = if self.kpa >= RED_KPA && self.blink_on { "!" } else { "" };
+        let label = format!("{:.0}{alarm}", self.kpa);
+        let label_style = Style::default().fg(self.theme.pressure_color(self.kpa));
+        buf.set_stringn(inner.x, inner.y + rows, label, inner.width as usize, label_style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered(gauge: PressureGauge) -> String {
+        let area = Rect::new(0, 0, 6, 12);
+        let mut buf = Buffer::empty(area);
+        gauge.render(area, &mut buf);
+        buf.content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn color_zones_change_at_their_boundaries() {
+        assert_eq!(pressure_color(0.0), Color::Green);
+        assert_eq!(pressure_color(19.9), Color::Green);
+        assert_eq!(pressure_color(YELLOW_KPA), Color::Yellow);
+        assert_eq!(pressure_color(49.9), Color::Yellow);
+        assert_eq!(pressure_color(RED_KPA), Color::Red);
+        assert_eq!(pressure_color(120.0), Color::Red);
+    }
+
+    #[test]
+    fn alarm_blinks_above_the_red_threshold() {
+        assert!(rendered(PressureGauge::new(55.0).blink(true)).contains("55!"));
+        assert!(!rendered(PressureGauge::new(55.0).blink(false)).contains('!'));
+        assert!(!rendered(PressureGauge::new(35.0).blink(true)).contains('!'));
+    }
+}
@@ -0,0 +1,134 @@
+//! Grid of zones shaded and colored by moisture, with a hover tooltip
+
+use ratatui::layout::Position;
+use ratatui::prelude::*;
+use ratatui::widgets::{Clear, Paragraph, Widget};
+
+use super::{Charset, Theme};
+use crate::IrrigationZone;
+
+/// Color for a moisture percentage, from red when dry through to blue when wet
+pub fn moisture_color(moisture: f32) -> Color {
+    let wet = moisture.clamp(0.0, 100.0) / 100.0;
+    Color::Rgb((255.0 * (1.0 - wet)).round() as u8, 0, (255.0 * wet).round() as u8)
+}
+
+/// Heatmap of every zone laid out in a near-square grid that fills the area
+pub struct ZoneHeatmap<'a> {
+    zones: &'a [IrrigationZone],
+    hovered: Option<(u16, u16)>, // Terminal column and row under the mouse
+    theme: Theme,
+    charset: Charset,
+}
+
+impl<'a> ZoneHeatmap<'a> {
+    pub fn new(zones: &'a [IrrigationZone]) -> Self {
+        Self {
+            zones,
+            hovered: None,
+            theme: Theme::default(),
+            charset: Charset::default(),
+        }
+    }
+
+    /// Shows a tooltip for the cell under this terminal position, if any
+    pub fn hovered(mut self, position: Option<(u16, u16)>) -> Self {
+        self.hovered = position;
+        self
+    }
+
+    /// Colors cells with `theme` instead of the default neon gradient
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Draws with `charset` instead of Unicode shades
+    pub fn charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Screen rectangle of every zone's cell inside `inner`
+    fn cells(&self, inner: Rect) -> Vec<Rect> {
+        if self.zones.is_empty() || inner.width == 0 || inner.height == 0 {
+            return Vec::new();
+        }
+        let columns = (self.zones.len() as f64).sqrt().ceil() as u16;
+        let rows = (self.zones.len() as u16).div_ceil(columns);
+        let cell_width = (inner.width / columns).max(1);
+        let cell_height = (inner.height / rows).max(1);
+        (0..self.zones.len() as u16)
+            .map(|i| {
+                let x = inner.x + (i % columns) * cell_width;
+                let y = inner.y + (i / columns) * cell_height;
+                Rect::new(x, y, cell_width, cell_height).intersection(inner)
+            })
+            .collect()
+    }
+}
+
+impl Widget for ZoneHeatmap<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = self.charset.block("Zone Heatmap");
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let cells = self.cells(inner);
+        for (zone, cell) in self.zones.iter().zip(&cells) {
+            let moisture = zone.sensor.moisture_level;
+            let style = Style::default().fg(self.theme.moisture_color(moisture));
+            let shade = self.charset.shade(moisture).to_string();
+            // Leave a one-character gutter so neighbouring cells stay distinguishable
+            for y in cell.top()..cell.bottom().saturating_sub(u16::from(cell.height > 1)) {
+                for x in cell.left()..cell.right().saturating_sub(u16::from(cell.width > 1)) {
+                    buf.get_mut(x, y).set_symbol(&shade).set_style(style);
+                }
+            }
+            let label = format!("{}", zone.id);
+            buf.set_string(cell.x, cell.y, label, Style::default().fg(Color::White).bg(Color::Black));
+        }
+
+        let Some((column, row)) = self.hovered else {
+            return;
+        };
+        let hovered_zone = self.zones.iter().zip(&cells).find(|(_, cell)| cell.contains(Position { x: column, y: row }));
+        let Some((zone, _)) = hovered_zone else {
+            return;
+        };
+        let text = format!(
+            "Zone {}\nMoisture: {:.1}%\nState: {}",
+            zone.id,
+            zone.sensor.moisture_level,
+            zone.sensor.state.name()
+        );
+        let width = 22.min(inner.width);
+        let height = 5.min(inner.height);
+        let x = (column + 1).min(inner.right().saturating_sub(width)).max(inner.x);
+        let y = (row + 1).min(inner.bottom().saturating_sub(height)).max(inner.y);
+        let tooltip = Rect::new(x, y, width, height);
+        Clear.render(tooltip, buf);
+        Paragraph::new(text)
+            .block(self.charset.plain_block())
+            .style(Style::default().fg(Color::White))
+            .render(tooltip, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moisture_color_runs_from_red_to_blue() {
+        assert_eq!(moisture_color(0.0), Color::Rgb(255, 0, 0));
+        assert_eq!(moisture_color(50.0), Color::Rgb(128, 0, 128));
+        assert_eq!(moisture_color(100.0), Color::Rgb(0, 0, 255));
+    }
+
+    #[test]
+    fn moisture_color_clamps_out_of_range_readings() {
+        assert_eq!(moisture_color(-5.0), moisture_color(0.0));
+        assert_eq!(moisture_color(140.0), moisture_color(100.0));
+    }
+}
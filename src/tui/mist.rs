@@ -0,0 +1,81 @@
+//! Countdown of the aeroponic mister's current phase
+
+use ratatui::prelude::*;
+use ratatui::widgets::Widget;
+
+use super::{Charset, Theme};
+use crate::hydroponic::{AeroponicController, MistPhase};
+
+/// Phase label over a bar that empties as the phase runs out
+pub struct MistCountdown {
+    phase: MistPhase,
+    remaining_ticks: u32,
+    duration_ticks: u32,
+    theme: Theme,
+    charset: Charset,
+}
+
+impl MistCountdown {
+    pub fn new(controller: &AeroponicController) -> Self {
+        Self {
+            phase: controller.phase,
+            remaining_ticks: controller.ticks_remaining(),
+            duration_ticks: controller.phase_duration_ticks(),
+            theme: Theme::default(),
+            charset: Charset::default(),
+        }
+    }
+
+    /// Colors the bar with `theme` instead of cyan and grey
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Draws with `charset` instead of Unicode blocks
+    pub fn charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    fn color(&self) -> Color {
+        match (self.theme, self.phase) {
+            (Theme::Neon, MistPhase::Misting) => Color::Cyan,
+            (Theme::Neon, MistPhase::Idle) => Color::Gray,
+            (Theme::HighContrast, MistPhase::Misting) => Color::Yellow,
+            (Theme::HighContrast, MistPhase::Idle) => Color::White,
+        }
+    }
+}
+
+impl Widget for MistCountdown {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = self.charset.block("Aeroponic mister");
+        let inner = block.inner(area);
+        block.render(area, buf);
+        if inner.height == 0 || inner.width == 0 {
+            return;
+        }
+
+        let style = Style::default().fg(self.color());
+        let label = match self.phase {
+            MistPhase::Misting => format!("Misting, {} s left", self.remaining_ticks),
+            MistPhase::Idle => format!("Idle, next mist in {} s", self.remaining_ticks),
+        };
+        buf.set_stringn(inner.x, inner.y, label, inner.width as usize, style);
+        if inner.height < 2 {
+            return;
+        }
+
+        let share_left = self.remaining_ticks as f32 / self.duration_ticks.max(1) as f32;
+        let filled = (share_left * inner.width as f32).round() as u16;
+        for column in 0..inner.width {
+            let (symbol, style) = if column < filled {
+                (self.charset.full_block(), style)
+            } else {
+                (self.charset.shade(1.0), Style::default().fg(Color::DarkGray))
+            };
+            buf.get_mut(inner.x + column, inner.y + 1).set_char(symbol).set_style(style);
+        }
+    }
+}
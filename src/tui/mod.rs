@@ -0,0 +1,1130 @@
+//! Terminal UI state, drawing and key handling for the soil moisture simulator
+
+pub mod art;
+pub mod charset;
+pub mod charts;
+pub mod field_editor;
+pub mod gauge;
+pub mod heatmap;
+pub mod mist;
+pub mod profile;
+pub mod theme;
+
+use std::collections::VecDeque;
+use std::io;
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDateTime, TimeDelta};
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::prelude::*;
+use ratatui::widgets::{Cell, Clear, List, ListItem, Paragraph, Row, Table};
+
+use crate::agri_iot_core::moisture_profile::MoistureProfiler;
+use crate::alerts::history::AlertHistory;
+use crate::api::gateway::GatewayStatus;
+use crate::calibration::{MultiSensorCalibrationWizard, SensorCalibration};
+use crate::climate::forecast::{DailyForecast, RAIN_SKIP_THRESHOLD_MM};
+use crate::climate::{DaylightModel, MicroclimateModel, NightVentilationController, WeatherStation};
+use crate::diagnostics::SensorDiagnostics;
+use crate::crop::cover_crop::CoverCropModel;
+use crate::crop::rotation::CropRotation;
+use crate::crop::cwsi::{crop_water_stress_index, estimate_canopy_temp, is_water_stressed};
+use crate::crop::disease::PestDiseaseRiskCalculator;
+use crate::crop::et::EtAccumulator;
+use crate::crop::germination::GerminationModel;
+use crate::crop::nitrogen::NitrogenFixationModel;
+use crate::crop::yield_map::FieldLayout;
+use crate::crop::{GrowthStage, PlantProfile};
+use crate::ghg::GhgTracker;
+use crate::irrigation::emitter::EmitterArray;
+use crate::irrigation::optimizer::IrrigationOptimizer;
+use crate::irrigation::{
+    BackflowPreventer, EnergyModel, FrostProtectionController, IrrigationStrategy, MaintenanceScheduler,
+    MaintenanceStatus, PipeNetwork, RainwaterHarvester, SurgeIrrigationController, WaterBudget,
+};
+use crate::network::{NetworkLink, QosBuffer};
+use crate::offline_cache::CacheStats;
+use crate::plugin::PluginLoader;
+use crate::replay::{ReplayController, SEEK_ENTRIES};
+use crate::soil::carbon::CarbonTracker;
+use crate::soil::drainage::{DrainageTile, DrainageVolume};
+use crate::soil::nutrient_leaching::LeachingModel;
+use crate::soil::respiration::SoilRespirationModel;
+use crate::soil::sampling::SoilSampleReport;
+use crate::soil::trace_elements::TraceElementMonitor;
+use crate::soil::wetting_front::WettingFrontTracker;
+use crate::soil::nitrogen::NitrogenPool;
+use crate::soil::organic_matter::SomTracker;
+use crate::soil::salinity::LeachingManager;
+use crate::uniformity::{distribution_uniformity, distribution_uniformity_low_quarter};
+use crate::water_volume::{WaterSource, WaterVolumeTracker};
+use crate::{
+    DeviceState, IrrigationZone, PHSensor, RainfallSensor, SoilTemperatureSensor, TemperatureSensor,
+    TensiometerSensor, Tick,
+};
+use art::{style_line, FLOWER_BASE};
+use charts::ZoneHistory;
+use field_editor::{FieldBoundaryEditor, FieldGrid};
+use gauge::PressureGauge;
+use heatmap::ZoneHeatmap;
+use profile::SoilProfile;
+pub use charset::Charset;
+pub use theme::Theme;
+
+/// Simulated field weather used for the canopy temperature reading
+pub const AIR_TEMP_C: f32 = 28.0;
+pub const VPD_KPA: f32 = 2.0;
+pub const ET0_MM_PER_DAY: f32 = 5.0; // Reference evapotranspiration
+pub const RAIN_SHOWER_MM: f32 = 10.0; // Rain the `r` key makes fall
+pub const COLD_SNAP_TEMP_C: f32 = -2.0; // Outside air during a cold snap from the `f` key
+pub const COLD_SNAP_HOURS: i64 = 6;
+
+/// Cells in the stats panel's water source bar
+const WATER_BAR_WIDTH: usize = 20;
+
+/// Least wall time between two ticks in step mode, so holding `t` runs at most 10 ticks a second
+const MIN_STEP_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Status messages kept for the event log panel
+const EVENT_LOG_CAPACITY: usize = 100;
+
+/// Visualization shown in the bottom panel
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChartType {
+    #[default]
+    FlowerAnimation,
+    MoistureHistory,
+    SensorBars,
+    ZoneHeatmap,
+    StateTimeline,
+    SomTrend,
+    Emissions,
+}
+
+impl ChartType {
+    /// Every chart, in the order `c` cycles through and `1`-`7` select
+    pub const ALL: [ChartType; 7] = [
+        ChartType::FlowerAnimation,
+        ChartType::MoistureHistory,
+        ChartType::SensorBars,
+        ChartType::ZoneHeatmap,
+        ChartType::StateTimeline,
+        ChartType::SomTrend,
+        ChartType::Emissions,
+    ];
+
+    /// The chart after this one, wrapping around
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&chart| chart == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// Everything the TUI needs to remember between frames
+#[derive(Debug, Default)]
+pub struct UiState {
+    pub selected_zone: usize,
+    pub show_help: bool,
+    pub show_stats: bool,          // Uniformity panel
+    pub show_diagnostics: bool,    // Per-sensor statistics over the charts
+    pub diagnostics: Vec<SensorDiagnostics>,
+    pub event_log_scroll: u16,     // Lines scrolled back from the newest message
+    pub active_chart: ChartType,
+    pub theme: Theme,
+    pub charset: Charset,
+    pub last_key: Option<KeyCode>,
+    pub mouse_position: Option<(u16, u16)>, // Column and row of the last mouse move
+    pub status_message: String,
+    pub simulated_now: NaiveDateTime, // Shown in the status panel title
+    pub event_log: VecDeque<String>, // Newest message first
+    pub carbon_stock_t_ha: f32,      // Biochar carbon shown in the status panel
+    pub history: Vec<ZoneHistory>,   // Indexed like the zones slice
+    pub crop: PlantProfile,          // Crop grown in every zone
+    pub growth_stage: GrowthStage,
+    pub et: EtAccumulator,           // Crop evapotranspiration of the field
+    pub estimated_yield_t_ha: f32,   // Refreshed once per simulated day
+    pub disease: PestDiseaseRiskCalculator,
+    pub strategy: IrrigationStrategy,
+    pub emitters: Option<EmitterArray>, // Drip lateral whose uniformity the stats panel shows
+    pub backflow: Option<BackflowPreventer>,
+    pub pipe_network: Option<PipeNetwork>, // Pressures at the pump and zone inlets shown in the stats panel
+    pub maintenance: Option<MaintenanceScheduler>, // Listed in a sidebar; `m` selects and `M` services
+    pub moisture_profile: Option<MoistureProfiler>, // Under zone 0, drawn beside its status
+    pub root_distribution: Vec<f32>, // Share of the roots at each of the profile's depths
+    pub tensiometer: Option<TensiometerSensor>, // In zone 0
+    pub wetting_front: Option<WettingFrontTracker>, // Zone 0's waterings on their way down
+    pub germination: Option<GerminationModel>, // Holds the crop in the germination stage until met
+    pub seedbed: Option<SoilTemperatureSensor>, // At the seeding depth; read by `germination`
+    pub soil_ph: PHSensor,
+    pub trace_elements: Option<TraceElementMonitor>, // `T` sprays them on the leaves
+    pub surge: Option<SurgeIrrigationController>, // Pulses the pump while watering when configured
+    pub frost: Option<FrostProtectionController>,
+    pub water_budget: Option<WaterBudget>, // Blocks irrigation once used up; frost protection is exempt
+    pub cold_snap_until: Option<NaiveDateTime>, // The `f` key holds the outside air at a frost until then
+    pub energy: Option<EnergyModel>,
+    pub rainwater: Option<RainwaterHarvester>,
+    pub rain_gauge: RainfallSensor,
+    pub rain_forecast: Option<DailyForecast>, // Tomorrow's, when a forecast file covers it
+    pub leaching: Option<LeachingManager>,
+    pub nutrient_leaching: Option<LeachingModel>, // Nitrate and potassium washed out by irrigation
+    pub soil_k_ppm: f32,                          // Potassium left in the topsoil
+    pub microclimate: Option<MicroclimateModel>, // Growing structure; open field when none
+    pub outside_temp_c: f32,
+    pub weather_station: Option<WeatherStation>, // Drives the outside air and rain when fitted
+    pub rain_warning_until: Option<NaiveDateTime>, // Irrigation is cut back until then after a falling barometer
+    pub air_temp: TemperatureSensor, // Inside the structure, if any
+    pub daylight: DaylightModel,
+    pub night_ventilation: Option<NightVentilationController>,
+    pub cover_crop: Option<CoverCropModel>,
+    pub nitrogen_fixation: Option<NitrogenFixationModel>, // Companion legume crediting mineral N
+    pub nitrogen: NitrogenPool,
+    pub som: SomTracker,
+    pub respiration: SoilRespirationModel,
+    pub rotation: Option<CropRotation>, // Crop sequence; `crop` is its current crop
+    pub carbon: Option<CarbonTracker>,
+    pub drainage: Option<DrainageTile>,
+    pub drained: DrainageVolume, // Field average since the start
+    pub network: Option<NetworkLink>, // Gateway uplink; a perfect one when none
+    pub qos_buffer: Option<QosBuffer>, // Events waiting for the uplink
+    pub gateway: Option<GatewayStatus>, // Redundant brokers, when configured
+    pub cache_stats: Option<CacheStats>, // Readings through the offline cache, when there is one
+    pub backflow_test_requested: bool, // Set by `b`, run by the simulation loop
+    pub harvest_requested: bool,       // Set by `H`, run by the simulation loop
+    pub soil_sample_requested: bool,   // Set by `S`, run by the simulation loop
+    pub fertilize_requested: bool,     // Set by `F`: apply the last sample's nitrogen advice
+    pub pause_requested: bool,         // Set by `p`: pause or resume the simulation loop
+    pub paused: bool,                  // No ticks run; the TUI still draws and takes keys
+    pub step_mode: bool,               // Set by `t`, cleared by `g`: each tick waits for a `t`
+    pub step_requested: bool,          // Set by `t` in step mode: run one tick
+    pub last_step: Option<Instant>,    // When `t` last asked for a tick
+    pub alert_history: AlertHistory,
+    pub probes: Vec<SensorCalibration>, // Each zone's uncalibrated response to the true moisture
+    pub calibration_wizard: Option<MultiSensorCalibrationWizard>, // Opened by `C`
+    pub field_editor: Option<FieldBoundaryEditor>, // Opened by F1; takes every key while open
+    pub field_layout: Option<FieldLayout>,         // Last saved from the editor, or configured
+    pub replay: Option<ReplayController>,          // Set by `--replay`: the zones show the recording
+    pub optimizer: IrrigationOptimizer,            // Fed the first zone's moisture every tick
+    pub scheduled_irrigation: Option<Tick>,        // Accepted suggestion, watered at that tick
+    pub irrigation_accept_requested: bool,         // Set by `A`: schedule the suggested watering
+    pub alert_ack_requested: bool,     // Set by `a`: acknowledge the oldest open alert
+    pub ghg: Option<GhgTracker>,
+    pub plugins: PluginLoader, // Sensors from `[[plugins]]` libraries, read every tick
+    pub last_soil_sample: Option<SoilSampleReport>,
+    pub quit: bool,
+}
+
+impl UiState {
+    /// Shows `message` as the current status and prepends it to the event log
+    pub fn push_status(&mut self, message: String) {
+        self.event_log.push_front(message.clone());
+        self.event_log.truncate(EVENT_LOG_CAPACITY);
+        self.status_message = message;
+    }
+
+    /// Samples every zone for the history charts; call once per tick
+    pub fn record_tick(&mut self, zones: &[IrrigationZone]) {
+        self.history.resize_with(zones.len(), ZoneHistory::default);
+        for (history, zone) in self.history.iter_mut().zip(zones) {
+            history.record(zone);
+        }
+    }
+}
+
+/// Draws one frame
+pub fn render(ui: &UiState, zones: &[IrrigationZone], terminal: &mut Terminal<impl Backend>) -> io::Result<()> {
+    terminal.draw(|f| {
+        let constraints: &[Constraint] = if ui.show_stats {
+            &[Constraint::Percentage(30), Constraint::Length(22), Constraint::Min(0)]
+        } else {
+            &[Constraint::Percentage(30), Constraint::Percentage(70)]
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints(constraints)
+            .split(f.size());
+        let top = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(chunks[0]);
+
+        if let Some(zone) = zones.get(ui.selected_zone) {
+            let sidebar_width = if ui.maintenance.is_some() { 22 } else { 0 };
+            let profile_width = if ui.moisture_profile.is_some() || ui.wetting_front.is_some() { 22 } else { 0 };
+            let zone_area = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Min(0),
+                    Constraint::Length(profile_width),
+                    Constraint::Length(sidebar_width),
+                    Constraint::Length(6),
+                ])
+                .split(top[0]);
+            f.render_widget(status_panel(ui, zone), zone_area[0]);
+            if profile_width > 0 {
+                let mut profile = SoilProfile::new().theme(ui.theme).charset(ui.charset);
+                if let Some(profiler) = &ui.moisture_profile {
+                    profile = profile.profiler(profiler, &ui.root_distribution);
+                }
+                if let Some(tracker) = &ui.wetting_front {
+                    profile = profile.wetting_fronts(tracker);
+                }
+                f.render_widget(profile, zone_area[1]);
+            }
+            if let Some(maintenance) = &ui.maintenance {
+                f.render_widget(maintenance_panel(ui, maintenance), zone_area[2]);
+            }
+            let gauge = PressureGauge::new(zone.filter.pressure_differential_kpa())
+                .blink(zone.sensor.current_tick.0 % 2 == 0)
+                .theme(ui.theme)
+                .charset(ui.charset);
+            f.render_widget(gauge, zone_area[3]);
+        }
+        let log_area = match &ui.weather_station {
+            Some(station) => {
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                    .split(top[1]);
+                f.render_widget(weather_panel(ui, station), columns[1]);
+                columns[0]
+            }
+            None => top[1],
+        };
+        let open_alerts = ui.alert_history.unacknowledged().count() as u16;
+        let log_area = if open_alerts > 0 {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(open_alerts.min(4) + 2), Constraint::Min(3)])
+                .split(log_area);
+            let blink_on = zones.first().is_some_and(|zone| zone.sensor.current_tick.0 % 2 == 0);
+            f.render_widget(alerts_panel(ui, blink_on), rows[0]);
+            rows[1]
+        } else {
+            log_area
+        };
+        f.render_widget(event_log_panel(ui, log_area.height), log_area);
+
+        if ui.show_stats {
+            f.render_widget(stats_panel(ui, zones), chunks[1]);
+        }
+
+        if let Some(zone) = zones.get(ui.selected_zone) {
+            let chart_area = chunks[chunks.len() - 1];
+            let empty = ZoneHistory::default();
+            let history = ui.history.get(ui.selected_zone).unwrap_or(&empty);
+            match ui.active_chart {
+                ChartType::FlowerAnimation if ui.theme == Theme::HighContrast => {
+                    f.render_widget(plain_status_panel(ui, zone), chart_area)
+                }
+                ChartType::FlowerAnimation => f.render_widget(flower_panel(zone, ui.charset), chart_area),
+                ChartType::MoistureHistory if ui.charset == Charset::Ascii => {
+                    f.render_widget(charts::ascii_moisture_history(zone, history, chart_area), chart_area)
+                }
+                ChartType::MoistureHistory => {
+                    let moisture = charts::moisture_points(history);
+                    let threshold = charts::threshold_points(zone);
+                    f.render_widget(charts::moisture_history(&moisture, &threshold, ui.theme), chart_area);
+                }
+                ChartType::SensorBars => f.render_widget(charts::sensor_bars(zones, ui.theme, ui.charset), chart_area),
+                ChartType::ZoneHeatmap => {
+                    f.render_widget(ZoneHeatmap::new(zones).hovered(ui.mouse_position).theme(ui.theme).charset(ui.charset), chart_area)
+                }
+                ChartType::StateTimeline => {
+                    f.render_widget(charts::state_timeline(history, chart_area.width, ui.theme, ui.charset), chart_area)
+                }
+                ChartType::SomTrend => f.render_widget(charts::som_trend(&ui.som, ui.theme, ui.charset), chart_area),
+                ChartType::Emissions => f.render_widget(charts::emissions_pie(ui.ghg.as_ref(), ui.charset), chart_area),
+            }
+        }
+
+        if ui.show_diagnostics {
+            let current_tick = zones.first().map_or(Tick::ZERO, |zone| zone.sensor.current_tick);
+            let area = centered(f.size(), 76, ui.diagnostics.len() as u16 + 3);
+            f.render_widget(Clear, area);
+            f.render_widget(diagnostics_panel(ui, current_tick), area);
+        }
+
+        if let Some(wizard) = &ui.calibration_wizard {
+            let width = 24 * wizard.zones.len().max(1) as u16 + 2;
+            let area = centered(f.size(), width, 9);
+            f.render_widget(Clear, area);
+            let title = format!("Calibration: {} (Enter)", wizard.step.instructions());
+            let block = ui.charset.block(title);
+            let panes = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Constraint::Ratio(1, wizard.zones.len().max(1) as u32); wizard.zones.len()])
+                .split(block.inner(area));
+            f.render_widget(block, area);
+            for (index, &zone_id) in wizard.zones.iter().enumerate() {
+                f.render_widget(calibration_pane(ui, zones, wizard, index, zone_id), panes[index]);
+            }
+        }
+
+        if let Some(editor) = &ui.field_editor {
+            let area = centered(f.size(), 62, field_editor::GRID_ROWS + 2);
+            f.render_widget(Clear, area);
+            f.render_widget(FieldGrid::new(editor).charset(ui.charset), area);
+        }
+
+        if ui.show_help {
+            let area = centered(f.size(), 48, 28);
+            f.render_widget(Clear, area);
+            f.render_widget(help_panel(ui), area);
+        }
+    })?;
+    Ok(())
+}
+
+/// Applies one key press to the UI state and the zones
+pub fn handle_input(event: KeyEvent, ui: &mut UiState, zones: &mut [IrrigationZone]) {
+    ui.last_key = Some(event.code);
+    if ui.field_editor.is_some() {
+        edit_field(event.code, ui);
+        return;
+    }
+    if control_replay(event.code, ui) {
+        return;
+    }
+    match event.code {
+        KeyCode::Char('q') => ui.quit = true,
+        KeyCode::F(1) => {
+            let layout = ui.field_layout.as_ref();
+            ui.field_editor = Some(layout.map_or_else(FieldBoundaryEditor::default, FieldBoundaryEditor::from_layout));
+        }
+        // Trigger the Error state for testing
+        KeyCode::Char('e') => {
+            if let Some(zone) = zones.get_mut(ui.selected_zone) {
+                zone.sensor.state = DeviceState::Error;
+            }
+        }
+        KeyCode::Char('b') if ui.backflow.is_some() => ui.backflow_test_requested = true,
+        KeyCode::Char('a') => {
+            if let Some(backflow) = ui.backflow.as_mut().filter(|backflow| backflow.blocks_irrigation()) {
+                backflow.acknowledge();
+                ui.push_status("Backflow fault acknowledged, irrigation resumed".to_string());
+            } else {
+                ui.alert_ack_requested = true;
+            }
+        }
+        KeyCode::Char('H') => ui.harvest_requested = true,
+        KeyCode::Char('S') => ui.soil_sample_requested = true,
+        KeyCode::Char('F') => ui.fertilize_requested = true,
+        KeyCode::Char('p') => ui.pause_requested = true,
+        KeyCode::Char('t') if !ui.step_mode => {
+            ui.step_mode = true;
+            ui.push_status("Step mode: t runs the next tick, g goes back to running".to_string());
+        }
+        // Key repeat while `t` is held comes faster than this, and the extra presses are dropped
+        KeyCode::Char('t') if ui.last_step.is_none_or(|last| last.elapsed() >= MIN_STEP_INTERVAL) => {
+            ui.step_requested = true;
+            ui.last_step = Some(Instant::now());
+        }
+        KeyCode::Char('g') if ui.step_mode => {
+            ui.step_mode = false;
+            ui.step_requested = false;
+            ui.push_status("Step mode off, running".to_string());
+        }
+        KeyCode::Char('A') => ui.irrigation_accept_requested = true,
+        KeyCode::Char('T') => {
+            if let Some(trace_elements) = &mut ui.trace_elements {
+                trace_elements.foliar_spray();
+                let message = format!("Micronutrient foliar spray: {} ppm", trace_elements.summary());
+                ui.push_status(message);
+            }
+        }
+        KeyCode::Char('n') => {
+            if let Some(link) = ui.network.as_mut() {
+                link.set_connected(!link.is_connected());
+                let message = if link.is_connected() { "Uplink restored" } else { "Uplink down, buffering events" };
+                ui.push_status(message.to_string());
+            }
+        }
+        KeyCode::Char('r') => ui.rain_gauge.record(RAIN_SHOWER_MM),
+        KeyCode::Char('f') => {
+            ui.cold_snap_until = Some(ui.simulated_now + TimeDelta::hours(COLD_SNAP_HOURS));
+            let celsius = ui.charset.celsius();
+            ui.push_status(format!("Cold snap: {COLD_SNAP_TEMP_C:.0} {celsius} for {COLD_SNAP_HOURS} h"));
+        }
+        KeyCode::Char('m') => {
+            if let Some(maintenance) = &mut ui.maintenance {
+                maintenance.select_next();
+            }
+        }
+        KeyCode::Char('M') => {
+            if let Some(item) = ui.maintenance.as_mut().and_then(MaintenanceScheduler::service_selected) {
+                let message = format!("{} serviced at {:.0} operating hours", item.component_id, item.operating_hours);
+                ui.push_status(message);
+            }
+        }
+        KeyCode::Char('u') => ui.show_stats = !ui.show_stats,
+        KeyCode::Char('d') => ui.show_diagnostics = !ui.show_diagnostics,
+        KeyCode::Char('C') if ui.calibration_wizard.is_none() => {
+            let zone_ids = zones.iter().map(|zone| zone.id).collect();
+            ui.calibration_wizard = Some(MultiSensorCalibrationWizard::new(zone_ids));
+        }
+        KeyCode::Enter => advance_calibration(ui, zones),
+        KeyCode::Char('c') => ui.active_chart = ui.active_chart.next(),
+        KeyCode::Char(digit @ '1'..='7') => {
+            ui.active_chart = ChartType::ALL[digit as usize - '1' as usize];
+        }
+        KeyCode::Char('h') | KeyCode::Char('?') => ui.show_help = !ui.show_help,
+        KeyCode::Esc => ui.show_help = false,
+        KeyCode::Tab | KeyCode::Right if !zones.is_empty() => {
+            ui.selected_zone = (ui.selected_zone + 1) % zones.len();
+        }
+        KeyCode::BackTab | KeyCode::Left if !zones.is_empty() => {
+            ui.selected_zone = (ui.selected_zone + zones.len() - 1) % zones.len();
+        }
+        KeyCode::Up => {
+            let max_scroll = ui.event_log.len().saturating_sub(1) as u16;
+            ui.event_log_scroll = (ui.event_log_scroll + 1).min(max_scroll);
+        }
+        KeyCode::Down => ui.event_log_scroll = ui.event_log_scroll.saturating_sub(1),
+        _ => {}
+    }
+}
+
+/// Applies a key to the open field editor: arrows move, Space and digits assign, Enter saves
+/// and Esc or F1 closes without saving
+fn edit_field(code: KeyCode, ui: &mut UiState) {
+    let Some(editor) = &mut ui.field_editor else {
+        return;
+    };
+    match code {
+        KeyCode::Left => editor.move_cursor(-1, 0),
+        KeyCode::Right => editor.move_cursor(1, 0),
+        KeyCode::Up => editor.move_cursor(0, -1),
+        KeyCode::Down => editor.move_cursor(0, 1),
+        KeyCode::Char(' ') => editor.toggle(),
+        KeyCode::Char(digit @ '0'..='9') => editor.assign(digit as u8 - b'0'),
+        KeyCode::Enter => {
+            let layout = editor.layout();
+            let assigned = layout.cell_zones.iter().flatten().count();
+            let mut zone_ids: Vec<u8> = layout.cell_zones.iter().flatten().copied().collect();
+            zone_ids.sort_unstable();
+            zone_ids.dedup();
+            ui.field_layout = Some(layout);
+            ui.field_editor = None;
+            ui.push_status(format!("Field layout saved: {assigned} cells in {} zones", zone_ids.len()));
+        }
+        KeyCode::Esc | KeyCode::F(1) => ui.field_editor = None,
+        _ => {}
+    }
+}
+
+/// Applies a playback key while replaying: `]` and `[` change speed, Space pauses and the
+/// arrows seek; returns whether the key was one of them
+fn control_replay(code: KeyCode, ui: &mut UiState) -> bool {
+    let Some(replay) = &mut ui.replay else {
+        return false;
+    };
+    match code {
+        KeyCode::Char(']') => replay.faster(),
+        KeyCode::Char('[') => replay.slower(),
+        KeyCode::Char(' ') => replay.toggle_pause(),
+        KeyCode::Right => replay.seek(SEEK_ENTRIES as isize),
+        KeyCode::Left => replay.seek(-(SEEK_ENTRIES as isize)),
+        _ => return false,
+    }
+    if matches!(code, KeyCode::Char(']' | '[')) {
+        let speed = replay.speed;
+        ui.push_status(format!("Replay speed {speed}x"));
+    }
+    true
+}
+
+/// Tracks the mouse for hover tooltips
+pub fn handle_mouse(event: MouseEvent, ui: &mut UiState) {
+    if let MouseEventKind::Moved = event.kind {
+        ui.mouse_position = Some((event.column, event.row));
+    }
+}
+
+fn status_panel<'a>(ui: &'a UiState, zone: &IrrigationZone) -> Paragraph<'a> {
+    let mut title = format!("Agri-IoT Simulator - Zone {} - {}", zone.id, ui.simulated_now.format("%Y-%m-%d %H:%M"));
+    if let Some(replay) = &ui.replay {
+        title += &format!(" [REPLAY {}/{}]", minutes_seconds(replay.position()), minutes_seconds(replay.duration()));
+        if replay.paused {
+            title += " [PAUSED]";
+        }
+    } else if ui.paused {
+        title += " [PAUSED]";
+    } else if ui.step_mode {
+        title += &format!(" [STEP MODE - Tick {}]", zone.sensor.current_tick.0);
+    }
+    if let Some(rotation) = &ui.rotation {
+        let years = rotation.sequence.len();
+        title += &format!(" - Rotation year {} of {years}: {}", rotation.rotation_year(), ui.crop.name);
+    }
+    let sensor = &zone.sensor;
+    // The canopy heats up as the soil dries below the threshold
+    let soil_stress = (sensor.threshold - sensor.moisture_level) / sensor.threshold;
+    let canopy_temp = estimate_canopy_temp(AIR_TEMP_C, VPD_KPA, soil_stress);
+    let cwsi = zone.fusion.map_or_else(
+        || crop_water_stress_index(AIR_TEMP_C, canopy_temp, VPD_KPA),
+        |fusion| fusion.cwsi_calculator.cwsi,
+    );
+    let fusion = zone.fusion.map_or_else(String::new, |fusion| {
+        let times = ui.charset.times();
+        format!(
+            "\nFused stress: {:.2} (soil deficit {:.2} {times} {}, CWSI {times} {}){}",
+            fusion.fused_stress_index(),
+            fusion.soil_water_deficit,
+            fusion.fusion_weight_soil,
+            fusion.fusion_weight_cwsi,
+            if fusion.is_stressed() { " (water stress)" } else { "" }
+        )
+    });
+    let tensiometer = ui.tensiometer.as_ref().filter(|_| zone.id == 0).map_or_else(String::new, |tensiometer| {
+        format!(
+            "\nTensiometer ({:.0} cm): {:.1} kPa, {}",
+            tensiometer.depth_cm,
+            tensiometer.matric_potential_kpa,
+            tensiometer.state().name()
+        )
+    });
+    let stage = match ui.germination.filter(|germination| !germination.is_complete()) {
+        Some(germination) => format!(
+            "{}, {:.0}/{:.0} {}",
+            ui.growth_stage.name(),
+            germination.accumulated_heat_units,
+            germination.germination_threshold_hu,
+            ui.charset.degree_days()
+        ),
+        None => ui.growth_stage.name().to_string(),
+    };
+    let micronutrients = ui.trace_elements.as_ref().map_or_else(String::new, |trace_elements| {
+        let deficient = trace_elements.deficient(&ui.crop.micronutrient_min_ppm);
+        let deficient: Vec<_> = deficient.iter().map(|element| element.symbol()).collect();
+        let deficient = if deficient.is_empty() { String::new() } else { format!(" ({} low)", deficient.join(", ")) };
+        format!("\nMicronutrients: {} ppm{deficient}", trace_elements.summary())
+    });
+    let backflow = ui.backflow.as_ref().map_or_else(String::new, |backflow| {
+        let (tests, failed) = backflow.test_counts();
+        if backflow.blocks_irrigation() {
+            "\nBackflow: FAULT, irrigation blocked (a to acknowledge)".to_string()
+        } else {
+            format!("\nBackflow: {} ok, {tests} tests ({failed} failed)", backflow.type_.name())
+        }
+    });
+    let suggestion = ui.optimizer.suggest_next_irrigation_tick(sensor.current_tick).filter(|_| zone.id == 0);
+    let irrigation = match (ui.scheduled_irrigation, suggestion) {
+        (Some(tick), _) if zone.id == 0 => format!("\nIrrigation scheduled for tick {}", tick.0),
+        (_, Some(tick)) if tick <= sensor.current_tick => {
+            "\nNext irrigation recommended: now (A to accept)".to_string()
+        }
+        (_, Some(tick)) => {
+            let ticks = tick.0 - sensor.current_tick.0;
+            format!("\nNext irrigation recommended: in {ticks} ticks (A to accept)")
+        }
+        _ => String::new(),
+    };
+    let text = format!(
+        "State: {}\nMoisture: {:.1}% {} (threshold {:.1}%)\nCrop: {} ({}), ET {:.1} mm today, {:.0} mm season\nCWSI: {:.2}{}{}\nEstimated yield: {:.1} t/ha",
+        ui.theme.state_label(sensor.state, ui.charset),
+        sensor.moisture_level,
+        ui.charset.trend_arrow(zone.trend.detect()),
+        sensor.threshold,
+        ui.crop.name,
+        stage,
+        ui.et.daily_et_mm,
+        ui.et.season_total_mm(),
+        cwsi,
+        if is_water_stressed(cwsi) { " (water stress)" } else { "" },
+        fusion,
+        ui.estimated_yield_t_ha,
+    );
+    let mut text = Text::from(text);
+    if let Some((model, level)) = ui.disease.highest_risk() {
+        text.lines.push(Line::from(vec![
+            Span::raw("Disease risk: "),
+            Span::styled(ui.charset.indicator(), Style::default().fg(ui.theme.risk_color(level))),
+            Span::raw(format!(" {} ({})", level.name(), model.name)),
+        ]));
+    }
+    let broker = ui.gateway.as_ref().map_or_else(String::new, |gateway| {
+        let up = gateway.brokers.iter().filter(|broker| broker.connected).count();
+        format!(
+            "\nBroker: {} ({}, {up} of {} up)",
+            gateway.active_broker.as_deref().unwrap_or("none yet"),
+            gateway.strategy.name(),
+            gateway.brokers.len()
+        )
+    });
+    text.extend(Text::from(format!(
+        "Biochar carbon: {:.1} t C/ha{tensiometer}{micronutrients}{}{irrigation}\nStatus: {}{broker}",
+        ui.carbon_stock_t_ha, backflow, ui.status_message
+    )));
+    Paragraph::new(text)
+        .block(ui.charset.block(title))
+        .style(Style::default().fg(Color::White))
+}
+
+fn event_log_panel(ui: &UiState, height: u16) -> List<'_> {
+    let visible = height.saturating_sub(2) as usize;
+    let items: Vec<ListItem> = ui
+        .event_log
+        .iter()
+        .skip(ui.event_log_scroll as usize)
+        .take(visible)
+        .map(|message| ListItem::new(message.as_str()))
+        .collect();
+    List::new(items)
+        .block(ui.charset.block("Events"))
+        .style(Style::default().fg(Color::White))
+}
+
+/// Alerts not yet acknowledged, oldest first, in red on every other tick so they blink
+fn alerts_panel(ui: &UiState, blink_on: bool) -> List<'static> {
+    let items: Vec<ListItem> = ui
+        .alert_history
+        .unacknowledged()
+        .map(|alert| {
+            ListItem::new(format!("{} zone {}: {} (tick {})", alert.id, alert.zone, alert.message, alert.fired_at.0))
+        })
+        .collect();
+    let color = if blink_on { ui.theme.state_color(DeviceState::Error) } else { Color::White };
+    List::new(items)
+        .block(ui.charset.block("Alerts (a to acknowledge)"))
+        .style(Style::default().fg(color))
+}
+
+/// Every reading of the weather station
+fn weather_panel(ui: &UiState, station: &WeatherStation) -> Paragraph<'static> {
+    let data = station.atmospheric_data();
+    let (celsius, degrees, per_m2) = (ui.charset.celsius(), ui.charset.degrees(), ui.charset.per_m2());
+    let mut text = format!("Sky: {}", data.condition.name());
+    text += &format!("\nAir: {:.1} {celsius}, {:.0}% RH", data.temperature_c, data.relative_humidity_pct);
+    text += &format!("\nWind: {:.1} m/s from {:.0}{degrees}", data.wind_speed_m_s, data.wind_direction_deg);
+    text += &format!("\nRain: {:.1} mm/h", data.rain_rate_mm_h);
+    text += &format!("\nSolar: {:.0} W{per_m2}", data.solar_radiation_w_m2);
+    text += &format!("\nCO2: {:.0} ppm", data.co2_ppm);
+    let arrow = ui.charset.pressure_arrow(station.pressure.trend());
+    text += &format!("\nPressure: {:.1} hPa {arrow}", data.pressure_hpa);
+    text += &format!("\nTendency: {:+.1} hPa/h", data.pressure_trend_hpa_per_h);
+    if let Some(until) = ui.rain_warning_until {
+        text += &format!("\nRain likely, half volume to {}", until.format("%H:%M"));
+    }
+    Paragraph::new(text)
+        .block(ui.charset.block("Weather station"))
+        .style(Style::default().fg(Color::White))
+}
+
+/// Uniformity across zones and the selected zone's water use efficiency
+fn stats_panel(ui: &UiState, zones: &[IrrigationZone]) -> Paragraph<'static> {
+    let moistures: Vec<f32> = zones.iter().map(|zone| zone.sensor.moisture_level).collect();
+    let mut text = format!(
+        "CU: {:.1}%\nDU (low quarter): {:.1}%",
+        distribution_uniformity(&moistures),
+        distribution_uniformity_low_quarter(&moistures)
+    );
+    if let Some(emitters) = &ui.emitters {
+        text += &format!(
+            "\nEmitters: CU {:.1}% over a {:.0} m rise, {:.1} L/h total",
+            emitters.uniformity_coefficient(),
+            emitters.elevation_diff_m,
+            emitters.total_flow_lph()
+        );
+    }
+    if let Some(network) = &ui.pipe_network {
+        let zone_heads: Vec<String> = (0..network.zone_heads_m.len())
+            .map(|zone| {
+                let low = if network.low_pressure(zone) { "!" } else { "" };
+                format!("{:.1}{low}", network.zone_heads_m[zone])
+            })
+            .collect();
+        text += &format!(
+            "\nPressure: pump {:.1} m, zones {} m ({:.0} m needed)",
+            network.pump_head_m,
+            zone_heads.join(" / "),
+            network.required_head_m
+        );
+    }
+    if let Some(surge) = &ui.surge {
+        text += &surge_summary(surge);
+    }
+    if let Some(microclimate) = &ui.microclimate {
+        let celsius = ui.charset.celsius();
+        text += &format!(
+            "\nAir: {:.1} {celsius} in the {}, {:.1} {celsius} outside",
+            ui.air_temp.temperature_c,
+            microclimate.structure_type.name(),
+            ui.outside_temp_c
+        );
+        if let Some(night) = &ui.night_ventilation {
+            let vents = if night.vent_actuator.open { "open" } else { "shut" };
+            text += &format!(", night vents {vents} (target {:.0} {celsius})", night.target_night_temp_c);
+        }
+    }
+    if let Some(cover) = &ui.cover_crop {
+        text += &if cover.incorporated {
+            format!(
+                "\nCover crop: {} incorporated, {:.0} kg N/ha organic, field capacity +{:.0}%",
+                cover.species,
+                ui.nitrogen.organic_n,
+                (cover.retention_factor() - 1.0) * 100.0
+            )
+        } else {
+            format!("\nCover crop: {} growing until {}", cover.species, cover.termination_tick)
+        };
+    }
+    if let Some(legume) = &ui.nitrogen_fixation {
+        text += &format!(
+            "\nCompanion {}: {:.1} kg N/ha fixed this season",
+            legume.legume_profile.name, legume.season_fixed_n_kg_ha
+        );
+    }
+    for plugin in ui.plugins.plugins() {
+        let sensor = &plugin.sensor;
+        let reading = plugin.last_reading.map_or_else(|| "no reading yet".to_string(), |value| format!("{value:.1}"));
+        text += &format!("\nPlugin {}: {} {reading} {}", plugin.label(), sensor.name(), sensor.unit());
+    }
+    if let Some(harvester) = &ui.rainwater {
+        text += &format!(
+            "\nRainwater tank: {:.0}/{:.0} L ({:.0}%), {:.0} L harvested this season",
+            harvester.tank.current_volume_liters,
+            harvester.tank.capacity_liters,
+            harvester.tank.level_pct(),
+            harvester.season_harvest_l
+        );
+    }
+    if let Some(leaching) = &ui.leaching {
+        let flushed_mm = zones.get(ui.selected_zone).map_or(0.0, |zone| zone.water.leaching_mm);
+        text += &format!("\nLeaching requirement: {:.0}%, {flushed_mm:.0} mm flushed", leaching.lr * 100.0);
+    }
+    if let Some(forecast) = &ui.rain_forecast {
+        let withheld = if forecast.expected_rain_mm > RAIN_SKIP_THRESHOLD_MM { ", irrigation withheld" } else { "" };
+        text += &format!(
+            "\nForecast {}: {:.0} mm rain, {:.0}/{:.0} {}{withheld}",
+            forecast.date,
+            forecast.expected_rain_mm,
+            forecast.max_temp_c,
+            forecast.min_temp_c,
+            ui.charset.celsius()
+        );
+    }
+    if ui.drainage.is_some() {
+        text += &format!(
+            "\nTile drains: {:.1} mm/day, {:.0} mm drained, {:.1} kg N/ha nitrate leached",
+            ui.drained.last_flow_mm_per_day, ui.drained.total_mm, ui.drained.nitrate_leached_kg_ha
+        );
+    }
+    if let Some(link) = &ui.network {
+        let buffered = ui.qos_buffer.as_ref().map_or(0, |buffer| buffer.queue.len());
+        text += &format!(
+            "\nNetwork: {}, lost {}/{} packets ({:.1}%), mean latency {:.0} ms, {buffered} events buffered",
+            if link.is_connected() { "connected" } else { "OFFLINE" },
+            link.packets_lost,
+            link.packets_sent,
+            link.loss_fraction() * 100.0,
+            link.mean_latency_ms()
+        );
+    }
+    if let Some(gateway) = &ui.gateway {
+        let brokers: Vec<String> = gateway
+            .brokers
+            .iter()
+            .map(|broker| {
+                let down = if broker.connected { "" } else { " down" };
+                let errors = broker.error_rate() * 100.0;
+                format!("{}{down} {} sent, {errors:.1}% errors", broker.address, broker.messages_sent)
+            })
+            .collect();
+        text += &format!("\nBrokers: {}", brokers.join("; "));
+    }
+    if let Some(stats) = &ui.cache_stats {
+        text += &format!(
+            "\nOffline cache: {} readings buffered, {} flushed, {} expired",
+            stats.entries_buffered, stats.entries_flushed, stats.entries_expired
+        );
+    }
+    if let Some(sample) = &ui.last_soil_sample {
+        let advice = sample.recommendation;
+        text += &format!(
+            "\nLast soil sample: {} (zone {}), N {:.1} ppm; add {:.0}/{:.0}/{:.0} kg/ha N/P/K",
+            sample.date, sample.zone_id, sample.n_ppm, advice.n_kg_ha, advice.p_kg_ha, advice.k_kg_ha
+        );
+    }
+    if let Some(carbon) = &ui.carbon {
+        let year = carbon.current_balance();
+        text += &format!(
+            "\nSoil carbon: {:.1} t C/ha, year {} net {:+.2} ({:.2} in, {:.2} respired)",
+            year.soil_carbon_t_ha,
+            year.year,
+            year.net_sequestration_t_ha(),
+            year.input_carbon_t_ha,
+            year.respiration_loss_t_ha
+        );
+        text += &format!("\nSoil CO2 efflux today: {:.1} g{}", carbon.co2_efflux_today_g_m2, ui.charset.per_m2());
+    }
+    if let Some(frost) = &ui.frost {
+        let state = if frost.active { "sprinkling" } else { "standby" };
+        text += &format!("\nFrost protection: {state}, {:.1} mm applied", frost.total_applied_mm);
+    }
+    if let Some(budget) = &ui.water_budget {
+        text += &format!(
+            "\nWater budget: {:.0} of {:.0} mm used{}, {:.1} mm frost protection exempt",
+            budget.used_mm,
+            budget.seasonal_limit_mm,
+            if budget.is_exhausted() { ", irrigation stopped" } else { "" },
+            budget.frost_protection_mm
+        );
+    }
+    if let Some(energy) = &ui.energy {
+        text += &format!(
+            "\nPump energy: {:.2} kWh this season, cost {:.2} ({:.2} in total)",
+            energy.season_energy_kwh,
+            energy.cost(energy.season_energy_kwh),
+            energy.cost(energy.total_energy_kwh)
+        );
+    }
+    if let Some(zone) = zones.get(ui.selected_zone) {
+        let wue = zone.wue.wue_g_per_l().map_or_else(|| "-".to_string(), |wue| format!("{wue:.2}"));
+        text += &format!("\nWUE: {wue} g/L (target {:.2})", ui.crop.target_wue_g_per_l);
+        if zone.wue.below_target(&ui.crop) {
+            text += "\nBelow target: consider deficit irrigation or a longer interval";
+        }
+        text += &format!("\nWater: {}", water_breakdown(ui.charset, &zone.water));
+        if let IrrigationStrategy::Deficit { fraction } = ui.strategy {
+            text += &format!(
+                "\nDeficit {:.0}%: saved {:.0} mm vs full, est. yield penalty {:.0}%",
+                fraction * 100.0,
+                zone.stress.water_saved_mm(),
+                zone.stress.yield_penalty(&ui.crop) * 100.0
+            );
+        }
+    }
+    Paragraph::new(text)
+        .block(ui.charset.block("Stats"))
+        .style(Style::default().fg(Color::White))
+}
+
+/// Progress of a running surge cycle, or how the last one compared with continuous flow
+fn surge_summary(surge: &SurgeIrrigationController) -> String {
+    let front = format!("front {:.0}/{:.0} m", surge.front_m, surge.basin.length_m);
+    if surge.is_active() {
+        let pump = if surge.pump_running() { "pump on" } else { "paused" };
+        format!(
+            "\nSurge {}/{}: {pump} {:.0} s more, {front}",
+            surge.current_surge,
+            surge.n_surges,
+            surge.phase_remaining_s()
+        )
+    } else if surge.surge_advance_m.is_empty() {
+        "\nSurge: idle".to_string()
+    } else {
+        format!(
+            "\nSurge: {:.0} L vs {:.0} L continuous ({:.0}% saved), {front}",
+            surge.water_applied_l(),
+            surge.basin.continuous_water_l(),
+            surge.water_saving_fraction() * 100.0
+        )
+    }
+}
+
+/// Stacked bar of water received by source, with each source's share
+fn water_breakdown(charset: Charset, water: &WaterVolumeTracker) -> String {
+    let cells = water.cell_widths(WATER_BAR_WIDTH);
+    let mut text: String = WaterSource::ALL
+        .iter()
+        .zip(cells)
+        .flat_map(|(&source, count)| std::iter::repeat_n(charset.water_source_glyph(source), count))
+        .collect();
+    for source in WaterSource::ALL {
+        text += &format!(" {}{} {:.0}%", charset.water_source_glyph(source), source.name(), water.share(source) * 100.0);
+    }
+    text + &format!(" ({:.0} mm)", water.total_mm())
+}
+
+fn flower_panel(zone: &IrrigationZone, charset: Charset) -> Paragraph<'static> {
+    let animation_lines: Vec<Line> = FLOWER_BASE
+        .lines()
+        .enumerate()
+        .map(|(i, line)| style_line(i, line, zone.sensor.animation_frame, &zone.sensor.state))
+        .collect();
+    Paragraph::new(Text::from(animation_lines)).block(charset.block("Neon Flower"))
+}
+
+/// Stand-in for the flower in high-contrast mode
+fn plain_status_panel(ui: &UiState, zone: &IrrigationZone) -> Paragraph<'static> {
+    let sensor = &zone.sensor;
+    let theme = ui.theme;
+    let text = format!(
+        "{}\nMoisture {:.1}% (threshold {:.1}%)\nWater pump: {}",
+        theme.state_label(sensor.state, ui.charset),
+        sensor.moisture_level,
+        sensor.threshold,
+        if sensor.water_running() { "ON" } else { "off" }
+    );
+    Paragraph::new(text)
+        .block(ui.charset.block("Zone Status"))
+        .style(Style::default().fg(theme.state_color(sensor.state)).add_modifier(Modifier::BOLD))
+}
+
+/// Every maintained component with the share of its interval used, due ones in yellow and
+/// overdue ones in red, the selected one marked
+fn maintenance_panel(ui: &UiState, maintenance: &MaintenanceScheduler) -> List<'static> {
+    let items: Vec<ListItem> = maintenance
+        .items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let marker = if index == maintenance.selected { ">" } else { " " };
+            let style = match item.status() {
+                MaintenanceStatus::Ok => Style::default().fg(Color::White),
+                MaintenanceStatus::Due => Style::default().fg(Color::Yellow),
+                MaintenanceStatus::Overdue => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            };
+            ListItem::new(format!("{marker}{:<12}{:>4.0}%", item.component_id, item.interval_used_pct())).style(style)
+        })
+        .collect();
+    List::new(items).block(ui.charset.block("Maintenance"))
+}
+
+fn help_panel(ui: &UiState) -> Paragraph<'static> {
+    let last_key = ui.last_key.map_or_else(String::new, |key| format!("{key:?}"));
+    let (left_right, up_down) = ui.charset.arrows();
+    let text = format!(
+        "q               quit\n\
+         p               pause / resume\n\
+         t / g           step mode, next tick / go\n\
+         e               trigger Error state\n\
+         u               toggle stats panel\n\
+         d               toggle sensor diagnostics\n\
+         C / Enter       calibrate probes / next step\n\
+         F1              draw field zones\n\
+         [ / ] / Space   replay speed / pause\n\
+         b               test backflow preventer\n\
+         a               acknowledge alert / backflow\n\
+         H               harvest now\n\
+         S               take soil samples\n\
+         F               apply recommended N\n\
+         T               micronutrient foliar spray\n\
+         A               accept irrigation suggestion\n\
+         n               toggle network outage\n\
+         r               rain shower\n\
+         f               cold snap\n\
+         m / M           select / service part\n\
+         c / 1-7         switch chart\n\
+         Tab/{left_right:<10} select zone\n\
+         {up_down:<15} scroll events\n\
+         h/?             toggle this help\n\
+         \n\
+         Last key: {last_key}"
+    );
+    Paragraph::new(text)
+        .block(ui.charset.block("Help"))
+        .style(Style::default().fg(Color::White))
+}
+
+/// One row per sensor, red once errors pass 1% of its updates, `STALE` without a good reading in
+/// over 10 ticks
+fn diagnostics_panel(ui: &UiState, current_tick: Tick) -> Table<'static> {
+    let header = Row::new(["Sensor", "Updates", "Errors", "Last tick", "Min %", "Max %", "Rate Hz", ""])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let range = |reading: f32| if reading.is_finite() { format!("{reading:.1}") } else { "-".to_string() };
+    let rows = ui.diagnostics.iter().map(|sensor| {
+        let stale = if sensor.is_stale(current_tick) {
+            Cell::from("STALE").style(Style::default().add_modifier(Modifier::REVERSED))
+        } else {
+            Cell::from("")
+        };
+        let row = Row::new([
+            Cell::from(sensor.sensor_id.to_string()),
+            Cell::from(sensor.update_count.to_string()),
+            Cell::from(sensor.error_count.to_string()),
+            Cell::from(sensor.last_update_tick.0.to_string()),
+            Cell::from(range(sensor.min_reading)),
+            Cell::from(range(sensor.max_reading)),
+            Cell::from(format!("{:.2}", sensor.reading_rate_hz)),
+            stale,
+        ]);
+        if sensor.is_faulty() {
+            row.style(Style::default().fg(ui.theme.state_color(DeviceState::Error)))
+        } else {
+            row
+        }
+    });
+    let widths = [
+        Constraint::Length(12),
+        Constraint::Length(8),
+        Constraint::Length(7),
+        Constraint::Length(10),
+        Constraint::Length(7),
+        Constraint::Length(7),
+        Constraint::Length(8),
+        Constraint::Length(6),
+    ];
+    Table::new(rows, widths).header(header).block(ui.charset.block("Sensor diagnostics (d to close)"))
+}
+
+/// Reads every probe in the current reference medium and moves the wizard on, closing it once
+/// it is done
+fn advance_calibration(ui: &mut UiState, zones: &[IrrigationZone]) {
+    let Some(wizard) = &mut ui.calibration_wizard else {
+        return;
+    };
+    if wizard.is_done() {
+        let summary: Vec<String> = wizard
+            .zones
+            .iter()
+            .zip(&wizard.results)
+            .filter_map(|(zone_id, result)| {
+                let calibration = result.calibration?;
+                Some(format!("zone {zone_id} gain {:.3} offset {:+.2}", calibration.gain, calibration.offset))
+            })
+            .collect();
+        ui.calibration_wizard = None;
+        ui.push_status(format!("Probes calibrated: {}", summary.join(", ")));
+        return;
+    }
+    if let Some(reference_pct) = wizard.step.reference_pct() {
+        wizard.advance(|zone_id| probe_raw(&ui.probes, zones, zone_id, reference_pct));
+    }
+}
+
+/// What the probe of zone `zone_id` reads in a medium at `moisture_pct`
+fn probe_raw(probes: &[SensorCalibration], zones: &[IrrigationZone], zone_id: u8, moisture_pct: f32) -> f32 {
+    let index = zones.iter().position(|zone| zone.id == zone_id);
+    index.and_then(|index| probes.get(index)).copied().unwrap_or_default().apply(moisture_pct)
+}
+
+/// One zone's reference readings and, once both are in, its calibration
+fn calibration_pane(
+    ui: &UiState,
+    zones: &[IrrigationZone],
+    wizard: &MultiSensorCalibrationWizard,
+    index: usize,
+    zone_id: u8,
+) -> Paragraph<'static> {
+    let result = wizard.results.get(index).copied().unwrap_or_default();
+    let reading = |raw: Option<f32>| raw.map_or_else(|| "-".to_string(), |raw| format!("{raw:.1}%"));
+    let mut text = match wizard.step.reference_pct() {
+        Some(reference_pct) => format!("Raw now: {:.1}%", probe_raw(&ui.probes, zones, zone_id, reference_pct)),
+        None => String::new(),
+    };
+    text += &format!("\nDry: {}\nWet: {}", reading(result.dry_raw), reading(result.wet_raw));
+    if let Some(calibration) = result.calibration {
+        text += &format!("\nGain: {:.3}\nOffset: {:+.2}", calibration.gain, calibration.offset);
+    }
+    Paragraph::new(text).block(ui.charset.block(format!("Zone {zone_id}")))
+}
+
+/// `m:ss` of `duration`
+fn minutes_seconds(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// A `width` x `height` rectangle centered in `area`, shrunk to fit
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
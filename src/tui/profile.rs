@@ -0,0 +1,108 @@
+//! Soil profile diagram of the moisture at each buried sensor's depth and how far down the
+//! latest watering has soaked
+
+use ratatui::prelude::*;
+use ratatui::widgets::Widget;
+
+use super::{Charset, Theme};
+use crate::agri_iot_core::moisture_profile::MoistureProfiler;
+use crate::soil::wetting_front::WettingFrontTracker;
+
+/// One horizontal moisture bar per depth, shallowest at the top, over the root-weighted moisture
+/// and the wetting front; each part is left out when its model is not fitted
+#[derive(Default)]
+pub struct SoilProfile {
+    layers: Vec<(u8, f32)>, // (depth in cm, moisture %)
+    root_weighted_moisture: Option<f32>,
+    front_depth_cm: Option<f32>, // Of the deepest moving front, or where the last one stopped
+    moving_fronts: Option<usize>,
+    theme: Theme,
+    charset: Charset,
+}
+
+impl SoilProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draws a bar per sensor of `profiler` and its moisture weighted by `root_distribution`
+    pub fn profiler(mut self, profiler: &MoistureProfiler, root_distribution: &[f32]) -> Self {
+        self.layers = profiler.sensors.iter().map(|(depth_cm, sensor)| (*depth_cm, sensor.moisture_level)).collect();
+        self.root_weighted_moisture = Some(profiler.root_weighted_moisture(root_distribution));
+        self
+    }
+
+    /// Shows how deep the tracker's fronts are and marks the sensors they have passed
+    pub fn wetting_fronts(mut self, tracker: &WettingFrontTracker) -> Self {
+        self.front_depth_cm = tracker.deepest_cm().or(tracker.last_settled_depth_cm);
+        self.moving_fronts = Some(tracker.fronts.len());
+        self
+    }
+
+    /// Colors the bars with `theme` instead of blue
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Draws with `charset` instead of Unicode blocks
+    pub fn charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    fn color(&self) -> Color {
+        match self.theme {
+            Theme::Neon => Color::Blue,
+            Theme::HighContrast => Color::White,
+        }
+    }
+}
+
+impl Widget for SoilProfile {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = self.charset.block("Soil profile");
+        let inner = block.inner(area);
+        block.render(area, buf);
+        // Depth label, bar and moisture need a few columns each
+        if inner.width < 12 || inner.height == 0 {
+            return;
+        }
+
+        let style = Style::default().fg(self.color());
+        let mut footer = Vec::new();
+        if let Some(moving) = self.moving_fronts {
+            footer.push(match self.front_depth_cm {
+                Some(depth_cm) => format!("Front: {depth_cm:.0} cm ({moving})"),
+                None => "Front: none yet".to_string(),
+            });
+        }
+        if let Some(moisture) = self.root_weighted_moisture {
+            footer.push(format!("Roots: {moisture:.1}%"));
+        }
+        let bar_rows = (inner.height as usize).saturating_sub(footer.len());
+        let bar_width = inner.width - 10;
+        for (row, &(depth_cm, moisture)) in self.layers.iter().enumerate().take(bar_rows) {
+            let y = inner.y + row as u16;
+            // Sensors the water has soaked down to are labelled in the bar's color
+            let wetted = self.front_depth_cm.is_some_and(|front_cm| front_cm >= depth_cm as f32);
+            let label_style = if wetted { style } else { Style::default().fg(Color::Gray) };
+            buf.set_string(inner.x, y, format!("{depth_cm:>2}cm"), label_style);
+            let filled = (moisture.clamp(0.0, 100.0) / 100.0 * bar_width as f32).round() as u16;
+            for column in 0..bar_width {
+                let (symbol, style) = if column < filled {
+                    (self.charset.full_block(), style)
+                } else {
+                    (self.charset.shade(1.0), Style::default().fg(Color::DarkGray))
+                };
+                buf.get_mut(inner.x + 5 + column, y).set_char(symbol).set_style(style);
+            }
+            buf.set_string(inner.x + 5 + bar_width, y, format!("{moisture:>4.0}%"), style);
+        }
+        let style = style.add_modifier(Modifier::BOLD);
+        let footer_y = inner.y + inner.height - footer.len().min(inner.height as usize) as u16;
+        for (row, line) in footer.into_iter().enumerate().take(inner.height as usize) {
+            buf.set_stringn(inner.x, footer_y + row as u16, line, inner.width as usize, style);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Color schemes and state labels of the TUI
+
+use ratatui::style::Color;
+
+use super::{gauge, heatmap};
+use super::Charset;
+use crate::crop::disease::RiskLevel;
+use crate::DeviceState;
+
+/// Color scheme of the whole TUI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Theme {
+    /// Full-color neon look
+    #[default]
+    Neon,
+    /// Only white, black, yellow and red, with text labels so state never relies on color alone
+    HighContrast,
+}
+
+impl Theme {
+    /// Color used for a state in charts and bars
+    pub fn state_color(self, state: DeviceState) -> Color {
+        match self {
+            Theme::Neon => match state {
+                DeviceState::Monitoring => Color::Yellow,
+                DeviceState::Activating => Color::Blue,
+                DeviceState::Adjusting => Color::Cyan,
+                DeviceState::Idle => Color::Rgb(255, 165, 0),
+                DeviceState::Error => Color::Red,
+            },
+            Theme::HighContrast => match state {
+                DeviceState::Monitoring | DeviceState::Activating | DeviceState::Adjusting => Color::Yellow,
+                DeviceState::Idle => Color::White,
+                DeviceState::Error => Color::Red,
+            },
+        }
+    }
+
+    /// How a state is written in the status panel
+    pub fn state_label(self, state: DeviceState, charset: Charset) -> String {
+        match self {
+            Theme::Neon => format!("{} {}", state.name(), charset.state_symbol(state)),
+            Theme::HighContrast => {
+                let tag = match state {
+                    DeviceState::Idle => "[OK]",
+                    DeviceState::Monitoring => "[WARN]",
+                    DeviceState::Activating | DeviceState::Adjusting => "[ACT]",
+                    DeviceState::Error => "[ERR]",
+                };
+                format!("{tag} {}", state.name())
+            }
+        }
+    }
+
+    /// Heatmap color for a moisture percentage
+    pub fn moisture_color(self, moisture: f32) -> Color {
+        match self {
+            Theme::Neon => heatmap::moisture_color(moisture),
+            Theme::HighContrast if moisture < 30.0 => Color::Red,
+            Theme::HighContrast if moisture < 60.0 => Color::Yellow,
+            Theme::HighContrast => Color::White,
+        }
+    }
+
+    /// Color for a filter pressure differential in kPa
+    pub fn pressure_color(self, kpa: f32) -> Color {
+        match self {
+            Theme::Neon => gauge::pressure_color(kpa),
+            Theme::HighContrast if kpa >= gauge::RED_KPA => Color::Red,
+            Theme::HighContrast if kpa >= gauge::YELLOW_KPA => Color::Yellow,
+            Theme::HighContrast => Color::White,
+        }
+    }
+
+    /// Color of the disease risk indicator
+    pub fn risk_color(self, level: RiskLevel) -> Color {
+        match (self, level) {
+            (Theme::Neon, RiskLevel::Low) => Color::Green,
+            (Theme::HighContrast, RiskLevel::Low) => Color::White,
+            (_, RiskLevel::Moderate) => Color::Yellow,
+            (_, RiskLevel::High) => Color::Red,
+        }
+    }
+
+    /// Color of a soil organic matter bar, by whether it is at or above the starting level
+    pub fn som_color(self, at_or_above_start: bool) -> Color {
+        match (self, at_or_above_start) {
+            (Theme::Neon, true) => Color::Green,
+            (Theme::HighContrast, true) => Color::White,
+            (_, false) => Color::Yellow,
+        }
+    }
+
+    /// Color of the moisture line in the history chart
+    pub fn moisture_line_color(self) -> Color {
+        match self {
+            Theme::Neon => Color::Cyan,
+            Theme::HighContrast => Color::White,
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! How evenly water is spread across zones, from irrigation engineering practice
+
+/// Christiansen's Uniformity Coefficient in percent: `100 * (1 - mean_deviation / mean)`
+///
+/// An empty or all-dry field counts as perfectly uniform.
+pub fn distribution_uniformity(zone_moistures: &[f32]) -> f32 {
+    let Some(mean) = mean(zone_moistures).filter(|&mean| mean > 0.0) else {
+        return 100.0;
+    };
+    let mean_deviation = zone_moistures.iter().map(|m| (m - mean).abs()).sum::<f32>() / zone_moistures.len() as f32;
+    (100.0 * (1.0 - mean_deviation / mean)).max(0.0)
+}
+
+/// Low-quarter Distribution Uniformity (DU_lq) in percent: mean of the driest quarter over the overall mean
+pub fn distribution_uniformity_low_quarter(zone_moistures: &[f32]) -> f32 {
+    let Some(mean) = mean(zone_moistures).filter(|&mean| mean > 0.0) else {
+        return 100.0;
+    };
+    let mut sorted = zone_moistures.to_vec();
+    sorted.sort_by(f32::total_cmp);
+    let low_quarter = &sorted[..sorted.len().div_ceil(4)];
+    100.0 * low_quarter.iter().sum::<f32>() / low_quarter.len() as f32 / mean
+}
+
+fn mean(values: &[f32]) -> Option<f32> {
+    (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn christiansen_coefficient_of_a_catch_can_test() {
+        // Mean 13 mm, mean absolute deviation 2 mm
+        assert_close(distribution_uniformity(&[10.0, 12.0, 14.0, 16.0]), 84.62, 0.01);
+        // Mean 20, deviations 4, 2, 0, 2, 4: CU = 100 (1 - 2.4 / 20)
+        assert_close(distribution_uniformity(&[16.0, 18.0, 20.0, 22.0, 24.0]), 88.0, 0.01);
+    }
+
+    #[test]
+    fn low_quarter_uses_the_driest_quarter_of_the_zones() {
+        assert_close(distribution_uniformity_low_quarter(&[10.0, 12.0, 14.0, 16.0]), 76.92, 0.01);
+        // The driest 2 of 8 average 10.5 against a mean of 15
+        let zones = [18.0, 11.0, 16.0, 10.0, 17.0, 14.0, 12.0, 22.0];
+        assert_close(distribution_uniformity_low_quarter(&zones), 100.0 * (10.5 / 15.0), 0.01);
+        // A partial quarter rounds up to a whole zone
+        assert_close(distribution_uniformity_low_quarter(&[20.0, 30.0, 40.0]), 100.0 * (20.0 / 30.0), 0.01);
+    }
+
+    #[test]
+    fn perfectly_uniform_field_scores_100() {
+        assert_eq!(distribution_uniformity(&[35.0; 16]), 100.0);
+        assert_eq!(distribution_uniformity_low_quarter(&[35.0; 16]), 100.0);
+        assert_eq!(distribution_uniformity(&[]), 100.0);
+        assert_eq!(distribution_uniformity_low_quarter(&[0.0, 0.0]), 100.0);
+    }
+
+    #[test]
+    fn maximally_non_uniform_field_scores_0() {
+        // All the water in one zone of four; the deviation exceeds the mean, so CU is floored at 0
+        assert_eq!(distribution_uniformity(&[0.0, 0.0, 0.0, 100.0]), 0.0);
+        assert_eq!(distribution_uniformity_low_quarter(&[0.0, 0.0, 0.0, 100.0]), 0.0);
+    }
+}
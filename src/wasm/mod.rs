@@ -0,0 +1,169 @@
+//! Browser build of the simulator, for `wasm32-unknown-unknown`
+//!
+//! Built with `make wasm`, which packages the module into `www/pkg/` with `wasm-bindgen`.
+//! Only the core sensor state machine is simulated: tokio's timers give way to browser timers through
+//! `wasm-bindgen-futures`, and [`terminal`] stands in for crossterm, so the page owns the display.
+
+pub mod terminal;
+
+use alloc::vec::Vec;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::agri_iot_core::SensorId;
+use crate::{DeviceState, SoilMoistureSensor};
+
+/// Moisture each zone loses per tick, in percentage points, as in the headless simulation
+pub const EVAPORATION: (f32, f32) = (0.5, 2.0);
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &js_sys::Function, ms: i32) -> JsValue;
+}
+
+/// Resolves after `ms` milliseconds on a browser timer, in place of `tokio::time::sleep`
+pub async fn sleep(ms: i32) {
+    let timer = js_sys::Promise::new(&mut |resolve, _reject| {
+        set_timeout(&resolve, ms);
+    });
+    // setTimeout never rejects
+    let _ = JsFuture::from(timer).await;
+}
+
+/// One zone as `tick()` reports it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ZoneJson {
+    pub zone_id: u8,
+    pub sensor_id: alloc::string::String,
+    pub state: DeviceState,
+    pub moisture_level: f32,
+    pub tick: u64,
+}
+
+/// Zones the page drives one tick at a time
+#[wasm_bindgen]
+pub struct SimulatorHandle {
+    zones: Vec<SoilMoistureSensor>,
+    rng: u64, // xorshift64 state; the same seed always gives the same run
+}
+
+#[wasm_bindgen]
+impl SimulatorHandle {
+    /// `zone_count` zones starting at 50% moisture, watered below `threshold`
+    #[wasm_bindgen(constructor)]
+    pub fn new(zone_count: u8, threshold: f32, seed: u32) -> Self {
+        let zones = (0..zone_count)
+            .map(|zone| SoilMoistureSensor::new(threshold, 50.0).with_sensor_id(SensorId::for_zone(zone)))
+            .collect();
+        Self { zones, rng: splitmix64(u64::from(seed)) }
+    }
+
+    /// Runs one tick and returns every zone's state as a JSON array
+    pub fn tick(&mut self) -> JsValue {
+        self.step();
+        JsValue::from_str(&self.states_json())
+    }
+
+    /// Runs `ticks` ticks `interval_ms` apart in the background, writing each tick's JSON to the console
+    pub fn run(mut self, ticks: u32, interval_ms: i32) {
+        wasm_bindgen_futures::spawn_local(async move {
+            for _ in 0..ticks {
+                self.step();
+                terminal::write_line(&self.states_json());
+                sleep(interval_ms).await;
+            }
+        });
+    }
+}
+
+impl SimulatorHandle {
+    /// Dries every zone by a random evaporation and steps its state machine
+    pub fn step(&mut self) {
+        for index in 0..self.zones.len() {
+            let drop = EVAPORATION.0 + (EVAPORATION.1 - EVAPORATION.0) * self.next_unit();
+            let zone = &mut self.zones[index];
+            zone.step((zone.moisture_level - drop).clamp(0.0, 100.0));
+        }
+    }
+
+    pub fn states(&self) -> Vec<ZoneJson> {
+        self.zones
+            .iter()
+            .zip(0..)
+            .map(|(sensor, zone_id)| ZoneJson {
+                zone_id,
+                sensor_id: sensor.sensor_id.as_str().into(),
+                state: sensor.state,
+                moisture_level: sensor.moisture_level,
+                tick: sensor.current_tick.0,
+            })
+            .collect()
+    }
+
+    pub fn states_json(&self) -> alloc::string::String {
+        serde_json::to_string(&self.states()).expect("zone states always serialize")
+    }
+
+    /// Uniform in 0..1
+    fn next_unit(&mut self) -> f32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// Spreads a small seed over all 64 bits, which xorshift needs to start well; never zero
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (z ^ (z >> 31)).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zones_dry_until_they_are_watered() {
+        let mut handle = SimulatorHandle::new(2, 30.0, 7);
+        for _ in 0..40 {
+            handle.step();
+        }
+        let states = handle.states();
+        assert_eq!(states.len(), 2);
+        assert!(states.iter().all(|zone| zone.tick == 40));
+        assert!(states.iter().all(|zone| zone.moisture_level < 50.0));
+        let mut handle = SimulatorHandle::new(1, 30.0, 7);
+        let watered = (0..200).any(|_| {
+            handle.step();
+            handle.states()[0].state == DeviceState::Activating
+        });
+        assert!(watered);
+    }
+
+    #[test]
+    fn states_are_a_json_array() {
+        let mut handle = SimulatorHandle::new(1, 30.0, 1);
+        handle.step();
+        let json: serde_json::Value = serde_json::from_str(&handle.states_json()).unwrap();
+        assert_eq!(json[0]["zone_id"], 0);
+        assert_eq!(json[0]["sensor_id"], "zone-0");
+        assert_eq!(json[0]["state"], "Monitoring");
+        assert_eq!(json[0]["tick"], 1);
+    }
+
+    #[test]
+    fn same_seed_same_run() {
+        let run = |seed| {
+            let mut handle = SimulatorHandle::new(3, 30.0, seed);
+            (0..50).for_each(|_| handle.step());
+            handle.states_json()
+        };
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(1), run(2));
+    }
+}
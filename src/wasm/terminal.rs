@@ -0,0 +1,30 @@
+//! Stub of the crossterm calls the TUI makes, for the browser build
+//!
+//! The page draws the zones itself, so there is no raw mode to enter and output goes to the console.
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console, js_name = log)]
+    fn console_log(line: &str);
+}
+
+/// Nothing to do: the browser has no terminal
+pub fn enable_raw_mode() -> Result<(), JsValue> {
+    Ok(())
+}
+
+pub fn disable_raw_mode() -> Result<(), JsValue> {
+    Ok(())
+}
+
+/// Columns and rows reported to layout code, those of a classic terminal
+pub fn size() -> Result<(u16, u16), JsValue> {
+    Ok((80, 24))
+}
+
+/// Writes `line` to the browser console
+pub fn write_line(line: &str) {
+    console_log(line);
+}
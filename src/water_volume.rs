@@ -0,0 +1,222 @@
+//! Cumulative water received by a zone, broken down by source
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::scenario::ScenarioEvent;
+use crate::soil::nutrient_leaching::NutrientLoss;
+use crate::Tick;
+
+/// Header line written by [`append_csv`]
+pub const CSV_HEADER: &str =
+    "date,zone_id,rain_mm,irrigation_mm,harvested_mm,fertigation_mm,leaching_mm,frost_protection_mm,total_mm,\
+     daily_et_mm,n_leached_kg_ha,k_leached_kg_ha";
+
+/// Where a zone's water came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WaterSource {
+    Rain,
+    Irrigation,      // From the main supply
+    Harvested,       // Irrigation water drawn from the rainwater tank
+    Fertigation,     // Irrigation water carrying dissolved fertilizer
+    Leaching,        // Extra water that flushes salt below the root zone
+    FrostProtection, // Overhead sprinkling on frosty nights, kept apart from irrigation
+}
+
+impl WaterSource {
+    pub const ALL: [WaterSource; 6] = [
+        WaterSource::Rain,
+        WaterSource::Irrigation,
+        WaterSource::Harvested,
+        WaterSource::Fertigation,
+        WaterSource::Leaching,
+        WaterSource::FrostProtection,
+    ];
+
+    /// Short label for legends and reports
+    pub fn name(self) -> &'static str {
+        match self {
+            WaterSource::Rain => "rain",
+            WaterSource::Irrigation => "irrigation",
+            WaterSource::Harvested => "harvested",
+            WaterSource::Fertigation => "fertigation",
+            WaterSource::Leaching => "leaching",
+            WaterSource::FrostProtection => "frost",
+        }
+    }
+}
+
+/// Millimetres of water received from each source since `start_tick`, and the nutrients it washed out
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WaterVolumeTracker {
+    pub rain_mm: f32,
+    pub irrigation_mm: f32,
+    pub harvested_mm: f32,
+    pub fertigation_mm: f32,
+    pub leaching_mm: f32,
+    pub frost_protection_mm: f32,
+    pub n_leached_kg_ha: f32, // Nitrate washed below the root zone by irrigation
+    pub k_leached_kg_ha: f32,
+    pub start_tick: Tick,
+}
+
+impl WaterVolumeTracker {
+    /// An empty tracker counting from `start_tick`
+    pub fn new(start_tick: Tick) -> Self {
+        Self { start_tick, ..Self::default() }
+    }
+
+    /// Adds `mm` of water from `source`
+    pub fn record(&mut self, source: WaterSource, mm: f32) {
+        match source {
+            WaterSource::Rain => self.rain_mm += mm,
+            WaterSource::Irrigation => self.irrigation_mm += mm,
+            WaterSource::Harvested => self.harvested_mm += mm,
+            WaterSource::Fertigation => self.fertigation_mm += mm,
+            WaterSource::Leaching => self.leaching_mm += mm,
+            WaterSource::FrostProtection => self.frost_protection_mm += mm,
+        }
+    }
+
+    /// Adds the nutrients one irrigation leached
+    pub fn record_nutrient_loss(&mut self, loss: NutrientLoss) {
+        self.n_leached_kg_ha += loss.n_kg_ha;
+        self.k_leached_kg_ha += loss.k_kg_ha;
+    }
+
+    /// Adds the rain from a scripted rainfall event; other events bring no water
+    pub fn record_scenario_event(&mut self, event: &ScenarioEvent) {
+        if let ScenarioEvent::Rain { mm, .. } = *event {
+            self.record(WaterSource::Rain, mm);
+        }
+    }
+
+    /// Millimetres received from `source`
+    pub fn mm(&self, source: WaterSource) -> f32 {
+        match source {
+            WaterSource::Rain => self.rain_mm,
+            WaterSource::Irrigation => self.irrigation_mm,
+            WaterSource::Harvested => self.harvested_mm,
+            WaterSource::Fertigation => self.fertigation_mm,
+            WaterSource::Leaching => self.leaching_mm,
+            WaterSource::FrostProtection => self.frost_protection_mm,
+        }
+    }
+
+    /// Millimetres received from every source
+    pub fn total_mm(&self) -> f32 {
+        self.rain_mm
+            + self.irrigation_mm
+            + self.harvested_mm
+            + self.fertigation_mm
+            + self.leaching_mm
+            + self.frost_protection_mm
+    }
+
+    /// Fraction of the total that came from `source`, 0.0 when nothing has been received
+    pub fn share(&self, source: WaterSource) -> f32 {
+        let total = self.total_mm();
+        if total > 0.0 {
+            self.mm(source) / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Whole cells out of `width` for each source, in [`WaterSource::ALL`] order,
+    /// rounded so they always add up to `width` once any water is recorded
+    pub fn cell_widths(&self, width: usize) -> [usize; 6] {
+        let exact = WaterSource::ALL.map(|source| self.share(source) * width as f32);
+        let mut cells = exact.map(|cells| cells.floor() as usize);
+        if self.total_mm() > 0.0 {
+            // Largest remainder: hand leftover cells to the sources that lost the most to rounding
+            let mut order = [0, 1, 2, 3, 4, 5];
+            order.sort_by(|&a, &b| (exact[b] - cells[b] as f32).total_cmp(&(exact[a] - cells[a] as f32)));
+            let leftover = width - cells.iter().sum::<usize>();
+            for &index in order.iter().take(leftover) {
+                cells[index] += 1;
+            }
+        }
+        cells
+    }
+}
+
+/// Appends one row per zone to the CSV file at `path`, writing the header if the file is new.
+/// `daily_et_mm` is the crop evapotranspiration of the simulated day being logged.
+pub fn append_csv(path: &Path, date: &str, daily_et_mm: f32, zones: &[(u8, &WaterVolumeTracker)]) -> io::Result<()> {
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(file, "{CSV_HEADER}")?;
+    }
+    for (zone_id, water) in zones {
+        writeln!(
+            file,
+            "{date},{zone_id},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{daily_et_mm:.2},{:.2},{:.2}",
+            water.rain_mm,
+            water.irrigation_mm,
+            water.harvested_mm,
+            water.fertigation_mm,
+            water.leaching_mm,
+            water.frost_protection_mm,
+            water.total_mm(),
+            water.n_leached_kg_ha,
+            water.k_leached_kg_ha
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rain_and_irrigation_increment_their_own_counters() {
+        let mut water = WaterVolumeTracker::new(Tick(10));
+        water.record(WaterSource::Rain, 12.0);
+        assert_eq!((water.rain_mm, water.irrigation_mm), (12.0, 0.0));
+        water.record(WaterSource::Irrigation, 5.0);
+        water.record(WaterSource::Irrigation, 3.0);
+        assert_eq!((water.rain_mm, water.irrigation_mm), (12.0, 8.0));
+        water.record_scenario_event(&ScenarioEvent::Rain { tick: 11, mm: 4.0 });
+        water.record_scenario_event(&ScenarioEvent::Freeze { tick: 12, temp_c: -2.0 });
+        assert_eq!(water.rain_mm, 16.0);
+        assert_eq!(water.total_mm(), 24.0);
+        assert_eq!(water.start_tick, Tick(10));
+        assert_eq!(WaterSource::ALL.map(|source| water.mm(source)), [16.0, 8.0, 0.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn breakdown_cells_always_fill_the_width() {
+        let mut water = WaterVolumeTracker::default();
+        assert_eq!(water.cell_widths(20), [0; 6]);
+        for source in [WaterSource::Rain, WaterSource::Irrigation, WaterSource::Fertigation] {
+            water.record(source, 1.0);
+        }
+        // Thirds of 20 round to 7, 7 and 6
+        let cells = water.cell_widths(20);
+        assert_eq!(cells.iter().sum::<usize>(), 20);
+        assert_eq!(cells, [7, 7, 0, 6, 0, 0]);
+        assert!((water.share(WaterSource::Rain) - 1.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn csv_has_one_row_per_zone_under_a_single_header() {
+        let path = std::env::temp_dir().join(format!("water-volume-{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut water = WaterVolumeTracker::default();
+        water.record(WaterSource::Rain, 2.5);
+        append_csv(&path, "2026-05-01", 4.0, &[(0, &water), (1, &WaterVolumeTracker::default())]).unwrap();
+        append_csv(&path, "2026-05-02", 4.5, &[(0, &water)]).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "2026-05-01,0,2.5,0.0,0.0,0.0,0.0,0.0,2.5,4.00,0.00,0.00");
+        assert!(lines[2].starts_with("2026-05-01,1,0.0,"));
+        assert!(lines[3].starts_with("2026-05-02,0,2.5,"));
+        assert_eq!(lines.len(), 4);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,109 @@
+//! Every panel the TUI draws with `--ascii-only` stays within 7-bit ASCII.
+
+use agri_iot_simulator::climate::forecast::DailyForecast;
+use agri_iot_simulator::climate::{
+    DaylightModel, MicroclimateModel, NightVentilationController, StructureType, VentilationFan, WeatherCondition,
+    WeatherEngine, WeatherStation,
+};
+use agri_iot_simulator::crop::cwsi::{CwsiBaselines, CwsiCalculator};
+use agri_iot_simulator::crop::fusion::SensorFusion;
+use agri_iot_simulator::crop::germination::GerminationModel;
+use agri_iot_simulator::simulation::Simulation;
+use agri_iot_simulator::soil::carbon::CarbonTracker;
+use agri_iot_simulator::tui::{self, Charset, ChartType, UiState};
+use chrono::NaiveDate;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+/// Draws one frame into an off-screen terminal tall enough for every panel's lines and
+/// returns every character on it
+fn frame(ui: &UiState, simulation: &Simulation) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(200, 120)).unwrap();
+    tui::render(ui, &simulation.zones, &mut terminal).unwrap();
+    terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect()
+}
+
+fn assert_ascii(screen: &str, what: &str) {
+    let offending: String = screen.chars().filter(|&c| c as u32 > 127).collect();
+    assert!(offending.is_empty(), "{what} drew non-ASCII characters {offending:?}");
+}
+
+#[test]
+fn every_chart_is_pure_ascii() {
+    let mut simulation = Simulation::new(9, 30.0, 3);
+    let mut ui = UiState { charset: Charset::Ascii, status_message: "Monitoring...".to_string(), ..UiState::default() };
+    for _ in 0..120 {
+        simulation.run_for(1);
+        ui.record_tick(&simulation.zones);
+    }
+    for chart in ChartType::ALL {
+        ui.active_chart = chart;
+        assert_ascii(&frame(&ui, &simulation), &format!("{chart:?}"));
+    }
+}
+
+#[test]
+fn help_stats_and_heatmap_tooltip_are_pure_ascii() {
+    let simulation = Simulation::new(4, 30.0, 3);
+    let mut ui = UiState {
+        charset: Charset::Ascii,
+        show_help: true,
+        show_stats: true,
+        active_chart: ChartType::ZoneHeatmap,
+        mouse_position: Some((20, 40)),
+        weather_station: Some(WeatherStation::new(WeatherEngine::new(WeatherCondition::Clear))),
+        ..UiState::default()
+    };
+    ui.record_tick(&simulation.zones);
+    let screen = frame(&ui, &simulation);
+    assert!(screen.contains("W/m2"), "the weather panel is drawn");
+    assert_ascii(&screen, "help, stats, heatmap and weather");
+}
+
+#[test]
+fn optional_panels_status_and_event_log_are_pure_ascii() {
+    let mut simulation = Simulation::new(2, 30.0, 3);
+    let fusion = SensorFusion::new(CwsiCalculator::new(CwsiBaselines::default()), 0.5, 0.5, 0.6);
+    simulation.zones.iter_mut().for_each(|zone| zone.fusion = Some(fusion));
+    let mut ui = UiState {
+        charset: Charset::Ascii,
+        show_stats: true,
+        weather_station: Some(WeatherStation::new(WeatherEngine::new(WeatherCondition::Clear))),
+        microclimate: Some(MicroclimateModel::for_structure(StructureType::Polytunnel)),
+        night_ventilation: Some(NightVentilationController::new(
+            18.0,
+            VentilationFan::new(0.05),
+            DaylightModel::default(),
+        )),
+        rain_forecast: Some(DailyForecast {
+            date: NaiveDate::from_ymd_opt(2024, 5, 2).unwrap(),
+            expected_rain_mm: 8.0,
+            max_temp_c: 21.0,
+            min_temp_c: 9.0,
+            solar_rad: 18.0,
+        }),
+        carbon: Some(CarbonTracker::new(2.0, 1.3, 30.0, 0.8)),
+        germination: Some(GerminationModel::new(10.0, 80.0)),
+        ..UiState::default()
+    };
+    simulation.run_for(1);
+    ui.record_tick(&simulation.zones);
+    // The cold snap key reports its temperature in the status line and the event log
+    tui::handle_input(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE), &mut ui, &mut simulation.zones);
+    let screen = frame(&ui, &simulation);
+    let units = ["Cold snap: -2 C", "W/m2", " deg", "g/m2", "C-days", " x 0.5"];
+    for drawn in units.into_iter().chain(["polytunnel", "night vents", "Forecast"]) {
+        assert!(screen.contains(drawn), "{drawn:?} is drawn");
+    }
+    assert_ascii(&screen, "optional panels");
+    let lines = ui.event_log.iter().chain([&ui.status_message]);
+    lines.for_each(|line| assert_ascii(line, "the status and event log"));
+}
+
+#[test]
+fn unicode_charset_is_caught() {
+    let simulation = Simulation::new(1, 30.0, 3);
+    let screen = frame(&UiState::default(), &simulation);
+    assert!(screen.chars().any(|c| c as u32 > 127), "the default borders are box-drawing characters");
+}
@@ -0,0 +1,149 @@
+//! `GatewayManager` routing across mock brokers that accept every client and count its publishes.
+
+use agri_iot_simulator::api::gateway::{GatewayManager, LoadBalancingStrategy};
+use agri_iot_simulator::config::BrokerConfig;
+use agri_iot_simulator::SoilMoistureSensor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+/// MQTT control packet types, from the high nibble of the fixed header
+const CONNECT: u8 = 1;
+const PUBLISH: u8 = 3;
+
+/// A broker the test can count the publishes of and take down
+struct MockBroker {
+    config: BrokerConfig,
+    publishes: Arc<AtomicUsize>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockBroker {
+    fn publishes(&self) -> usize {
+        self.publishes.load(Ordering::Relaxed)
+    }
+
+    /// Drops the client's connection and stops listening, as a crashed broker would
+    fn go_down(&mut self) {
+        self.shutdown.take().expect("broker is up").send(()).unwrap();
+    }
+}
+
+/// Reads one packet and returns its type
+async fn read_packet(socket: &mut TcpStream) -> std::io::Result<u8> {
+    let header = socket.read_u8().await?;
+    let (mut remaining, mut shift) = (0usize, 0);
+    loop {
+        let byte = socket.read_u8().await?;
+        remaining |= ((byte & 0x7f) as usize) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+    let mut body = vec![0; remaining];
+    socket.read_exact(&mut body).await?;
+    Ok(header >> 4)
+}
+
+/// Accepts one client, lets it in and counts the PUBLISH packets it sends until shut down
+async fn mock_broker() -> MockBroker {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let publishes = Arc::new(AtomicUsize::new(0));
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+    let counter = Arc::clone(&publishes);
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        assert_eq!(read_packet(&mut socket).await.unwrap(), CONNECT);
+        socket.write_all(&[0x20, 0x02, 0x00, 0x00]).await.unwrap();
+        loop {
+            tokio::select! {
+                packet = read_packet(&mut socket) => match packet {
+                    Ok(PUBLISH) => {
+                        counter.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Ok(_) => {}
+                    Err(_) => break,
+                },
+                _ = &mut shutdown_rx => break,
+            }
+        }
+        // The socket and the listener drop here, so reconnecting fails too
+    });
+    let config = BrokerConfig { host: "127.0.0.1".to_string(), port, ..BrokerConfig::default() };
+    MockBroker { config, publishes, shutdown: Some(shutdown_tx) }
+}
+
+/// Polls `condition` for up to 5 s
+async fn wait_for(mut condition: impl FnMut() -> bool) {
+    for _ in 0..250 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("timed out");
+}
+
+async fn gateway(brokers: &[MockBroker], strategy: LoadBalancingStrategy) -> GatewayManager {
+    let configs: Vec<BrokerConfig> = brokers.iter().map(|broker| broker.config.clone()).collect();
+    let gateway = GatewayManager::connect(&configs, strategy).unwrap();
+    wait_for(|| gateway.status().brokers.iter().all(|broker| broker.connected)).await;
+    gateway
+}
+
+#[tokio::test]
+async fn round_robin_distributes_evenly() {
+    let brokers = vec![mock_broker().await, mock_broker().await, mock_broker().await];
+    let mut gateway = gateway(&brokers, LoadBalancingStrategy::RoundRobin).await;
+    let sensor = SoilMoistureSensor::new(30.0, 50.0);
+    for zone_id in 0..30 {
+        gateway.publish_reading(zone_id % 4, &sensor);
+    }
+    let status = gateway.status();
+    assert!(status.brokers.iter().all(|broker| broker.messages_sent == 10 && broker.errors == 0));
+    // The last message went to the third broker, having started from the first
+    assert_eq!(status.active_broker.as_deref(), Some(status.brokers[2].address.as_str()));
+    wait_for(|| brokers.iter().all(|broker| broker.publishes() == 10)).await;
+}
+
+#[tokio::test]
+async fn failover_switches_to_the_next_broker_on_disconnect() {
+    let mut brokers = vec![mock_broker().await, mock_broker().await];
+    let mut gateway = gateway(&brokers, LoadBalancingStrategy::FailoverPrimary).await;
+    let sensor = SoilMoistureSensor::new(30.0, 50.0);
+    for _ in 0..3 {
+        gateway.publish_reading(0, &sensor);
+    }
+    let primary = brokers[0].config.port.to_string();
+    assert!(gateway.active_broker().is_some_and(|address| address.ends_with(&primary)));
+    wait_for(|| brokers[0].publishes() == 3).await;
+
+    brokers[0].go_down();
+    wait_for(|| !gateway.status().brokers[0].connected).await;
+    // The very next publish goes to the backup
+    gateway.publish_reading(0, &sensor);
+    let backup = brokers[1].config.port.to_string();
+    assert!(gateway.active_broker().is_some_and(|address| address.ends_with(&backup)));
+    gateway.publish_reading(0, &sensor);
+    let status = gateway.status();
+    assert_eq!((status.brokers[0].messages_sent, status.brokers[1].messages_sent), (3, 2));
+    wait_for(|| brokers[1].publishes() == 2).await;
+    assert_eq!(brokers[0].publishes(), 3);
+}
+
+#[tokio::test]
+async fn broadcast_reaches_every_broker() {
+    let brokers = vec![mock_broker().await, mock_broker().await];
+    let mut gateway = gateway(&brokers, LoadBalancingStrategy::BroadcastAll).await;
+    let sensor = SoilMoistureSensor::new(30.0, 50.0);
+    for _ in 0..4 {
+        gateway.publish_reading(1, &sensor);
+    }
+    assert!(gateway.status().brokers.iter().all(|broker| broker.messages_sent == 4));
+    wait_for(|| brokers.iter().all(|broker| broker.publishes() == 4)).await;
+}
@@ -0,0 +1,53 @@
+//! Streams zone readings from a running `GrpcServer` through the generated tonic client.
+
+use agri_iot_simulator::api::grpc::proto::sensor_service_client::SensorServiceClient;
+use agri_iot_simulator::api::grpc::proto::ZoneRequest;
+use agri_iot_simulator::api::grpc::GrpcServer;
+use agri_iot_simulator::SoilMoistureSensor;
+use std::time::Duration;
+use tokio_stream::StreamExt;
+
+const UPDATES: usize = 5;
+
+#[tokio::test]
+async fn stream_sensor_data_delivers_five_updates() {
+    let server = GrpcServer::start(0).await.unwrap();
+    let mut sensor = SoilMoistureSensor::new(30.0, 50.0);
+    server.update(&[&sensor]);
+    let url = format!("http://127.0.0.1:{}", server.local_addr().port());
+    let mut client = SensorServiceClient::connect(url).await.unwrap();
+
+    let zone = client.get_zone_state(ZoneRequest { zone_id: 0 }).await.unwrap().into_inner();
+    assert_eq!((zone.zone_id, zone.moisture_level), (0, 50.0));
+    let mut updates = client.stream_sensor_data(ZoneRequest { zone_id: 0 }).await.unwrap().into_inner();
+
+    // Keep ticking until the client has seen enough; the stream carries the latest tick only
+    let ticker = tokio::spawn(async move {
+        loop {
+            sensor.step(sensor.moisture_level - 0.5);
+            server.update(&[&sensor]);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    });
+    let mut received = Vec::new();
+    while received.len() < UPDATES {
+        let update = tokio::time::timeout(Duration::from_secs(5), updates.next()).await.unwrap();
+        received.push(update.expect("stream ended early").unwrap());
+    }
+    ticker.abort();
+
+    assert!(received.iter().all(|update| update.zone.as_ref().is_some_and(|zone| zone.zone_id == 0)));
+    let levels: Vec<f32> = received.iter().map(|update| update.zone.as_ref().unwrap().moisture_level).collect();
+    assert!(levels.windows(2).all(|pair| pair[1] < pair[0]), "readings should keep drying: {levels:?}");
+    assert!(received.windows(2).all(|pair| pair[0].timestamp_ms <= pair[1].timestamp_ms));
+}
+
+#[tokio::test]
+async fn unknown_zone_is_not_found() {
+    let server = GrpcServer::start(0).await.unwrap();
+    server.update(&[&SoilMoistureSensor::new(30.0, 50.0)]);
+    let url = format!("http://127.0.0.1:{}", server.local_addr().port());
+    let mut client = SensorServiceClient::connect(url).await.unwrap();
+    let status = client.stream_sensor_data(ZoneRequest { zone_id: 7 }).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::NotFound);
+}
@@ -0,0 +1,65 @@
+//! Status codes of the `/health` probes before and after the simulation's first tick.
+
+use agri_iot_simulator::api::http::HttpServer;
+use agri_iot_simulator::config::{ApiConfig, AuthConfig};
+use agri_iot_simulator::{DeviceState, SoilMoistureSensor};
+use serde_json::Value;
+
+async fn start(config: &ApiConfig) -> (HttpServer, String) {
+    let server = HttpServer::start(0, config).await.unwrap();
+    let base = format!("http://127.0.0.1:{}", server.local_addr().port());
+    (server, base)
+}
+
+async fn get(url: String) -> (u16, Value) {
+    let response = reqwest::get(url).await.unwrap();
+    (response.status().as_u16(), response.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn live_but_not_ready_before_the_first_tick() {
+    let (server, base) = start(&ApiConfig::default()).await;
+    let (status, live) = get(format!("{base}/health/live")).await;
+    assert_eq!(status, 200);
+    assert_eq!(live["status"], "ok");
+    assert!(live["uptime_secs"].is_u64());
+    assert_eq!(get(format!("{base}/health/ready")).await.0, 503);
+    assert_eq!(get(format!("{base}/health/zones")).await, (200, Value::Array(Vec::new())));
+
+    // Publishing zones that have not ticked yet does not make the server ready
+    let sensor = SoilMoistureSensor::new(30.0, 50.0);
+    server.update(&[(&sensor, "Starting...")]);
+    assert_eq!(get(format!("{base}/health/ready")).await, (503, serde_json::json!({ "status": "starting" })));
+}
+
+#[tokio::test]
+async fn ready_once_every_zone_has_ticked() {
+    let (server, base) = start(&ApiConfig::default()).await;
+    let mut ticked = SoilMoistureSensor::new(30.0, 50.0);
+    ticked.step(45.0);
+    let waiting = SoilMoistureSensor::new(30.0, 50.0);
+    server.update(&[(&ticked, "Monitoring..."), (&waiting, "Starting...")]);
+    assert_eq!(get(format!("{base}/health/ready")).await.0, 503);
+
+    let mut failed = SoilMoistureSensor::new(30.0, 50.0);
+    failed.step(45.0);
+    failed.state = DeviceState::Error;
+    server.update(&[(&ticked, "Monitoring..."), (&failed, "Sensor error")]);
+    assert_eq!(get(format!("{base}/health/ready")).await, (200, serde_json::json!({ "status": "ready" })));
+
+    let (status, zones) = get(format!("{base}/health/zones")).await;
+    assert_eq!(status, 200);
+    assert_eq!(zones[0]["healthy"], true);
+    assert_eq!(zones[0]["ticks_completed"], 1);
+    assert_eq!(zones[1]["healthy"], false);
+    assert_eq!(zones[1]["state"], DeviceState::Error.name());
+}
+
+#[tokio::test]
+async fn health_routes_skip_authentication() {
+    let config = ApiConfig { auth: AuthConfig { enabled: true, keys: Vec::new() }, ..ApiConfig::default() };
+    let (_server, base) = start(&config).await;
+    assert_eq!(reqwest::get(format!("{base}/api/zones")).await.unwrap().status(), 401);
+    assert_eq!(get(format!("{base}/health/live")).await.0, 200);
+    assert_eq!(get(format!("{base}/health/ready")).await.0, 503);
+}
@@ -0,0 +1,50 @@
+//! Smoke tests of the dashboard and zone API served by `HttpServer`.
+
+use agri_iot_simulator::api::http::HttpServer;
+use agri_iot_simulator::config::ApiConfig;
+use agri_iot_simulator::SoilMoistureSensor;
+use serde_json::Value;
+
+async fn start() -> (HttpServer, String) {
+    let server = HttpServer::start(0, &ApiConfig::default()).await.unwrap();
+    let base = format!("http://127.0.0.1:{}", server.local_addr().port());
+    (server, base)
+}
+
+#[tokio::test]
+async fn index_is_served_as_html() {
+    let (_server, base) = start().await;
+    let response = reqwest::get(format!("{base}/")).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/html"));
+    assert!(response.text().await.unwrap().contains("<title>Agri-IoT Simulator</title>"));
+
+    for (path, content_type) in [("/dashboard.css", "text/css"), ("/dashboard.js", "text/javascript")] {
+        let response = reqwest::get(format!("{base}{path}")).await.unwrap();
+        assert_eq!(response.status(), 200, "{path}");
+        assert!(response.headers()["content-type"].to_str().unwrap().starts_with(content_type), "{path}");
+    }
+}
+
+#[tokio::test]
+async fn zones_api_returns_the_latest_readings() {
+    let (server, base) = start().await;
+    let mut sensor = SoilMoistureSensor::new(30.0, 50.0);
+    sensor.step(12.5);
+    server.update(&[(&sensor, "Moisture low (12.5%), activating...")]);
+
+    let zones: Value = reqwest::get(format!("{base}/api/zones")).await.unwrap().json().await.unwrap();
+    assert_eq!(zones.as_array().unwrap().len(), 1);
+    let zone = &zones[0];
+    assert_eq!(zone["zone_id"], 0);
+    assert_eq!(zone["sensor_id"], "zone-0");
+    assert_eq!(zone["moisture_level"], 12.5);
+    assert_eq!(zone["state"], "Activating");
+    assert_eq!(zone["water_pump_running"], true);
+    assert_eq!(zone["status_message"], "Moisture low (12.5%), activating...");
+
+    let sensor = reqwest::get(format!("{base}/api/sensors/zone-0")).await.unwrap();
+    assert_eq!(sensor.status(), 200);
+    let missing = reqwest::get(format!("{base}/api/sensors/zone-9")).await.unwrap();
+    assert_eq!(missing.status(), 404);
+}
@@ -0,0 +1,43 @@
+//! The dashboard served over HTTPS with a freshly generated self-signed certificate.
+
+use agri_iot_simulator::api::http::HttpServer;
+use agri_iot_simulator::config::{ApiConfig, TlsConfig};
+use std::path::PathBuf;
+
+/// Writes a certificate for `localhost` and its key to PEM files unique to this test run
+fn self_signed(name: &str) -> (PathBuf, reqwest::Certificate, TlsConfig) {
+    let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let dir = std::env::temp_dir().join(format!("agri_iot_simulator-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let cert_pem = certified.cert.pem();
+    let tls = TlsConfig { cert_path: dir.join("cert.pem"), key_path: dir.join("key.pem") };
+    std::fs::write(&tls.cert_path, &cert_pem).unwrap();
+    std::fs::write(&tls.key_path, certified.key_pair.serialize_pem()).unwrap();
+    (dir, reqwest::Certificate::from_pem(cert_pem.as_bytes()).unwrap(), tls)
+}
+
+#[tokio::test]
+async fn client_trusting_the_certificate_connects() {
+    let (dir, certificate, tls) = self_signed("https-trusted");
+    let server = HttpServer::start(0, &ApiConfig { tls: Some(tls), ..ApiConfig::default() }).await.unwrap();
+    let url = format!("https://localhost:{}/api/zones", server.local_addr().port());
+
+    let client = reqwest::Client::builder().add_root_certificate(certificate).build().unwrap();
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().await.unwrap(), "[]");
+
+    // Plain HTTP is not served on the TLS port, and clients without the certificate refuse it
+    assert!(reqwest::get(url.replace("https://", "http://")).await.is_err());
+    assert!(reqwest::get(&url).await.is_err());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn missing_certificate_fails_to_start() {
+    let (dir, _, mut tls) = self_signed("https-missing");
+    tls.cert_path = dir.join("absent.pem");
+    let started = HttpServer::start(0, &ApiConfig { tls: Some(tls), ..ApiConfig::default() }).await;
+    assert!(started.is_err());
+    std::fs::remove_dir_all(dir).unwrap();
+}
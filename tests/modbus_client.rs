@@ -0,0 +1,42 @@
+//! Reads the register map of a running `ModbusTcpServer` through a tokio-modbus client.
+
+use agri_iot_simulator::api::modbus::ModbusTcpServer;
+use agri_iot_simulator::{DeviceState, SoilMoistureSensor};
+use std::net::SocketAddr;
+use tokio_modbus::client::{tcp, Reader};
+use tokio_modbus::ExceptionCode;
+
+#[tokio::test]
+async fn registers_match_the_simulator_state() {
+    let server = ModbusTcpServer::start(0).await.unwrap();
+    let idle = SoilMoistureSensor::new(30.0, 42.5);
+    let mut watering = SoilMoistureSensor::new(30.0, 31.0);
+    watering.step(12.34);
+    assert_eq!(watering.state, DeviceState::Activating);
+    server.update(&[&idle, &watering]);
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], server.local_addr().port()));
+    let mut client = tcp::connect(addr).await.unwrap();
+    let holding = client.read_holding_registers(0, 2).await.unwrap().unwrap();
+    assert_eq!(holding, [4250, 1234]);
+    let coils = client.read_coils(0, 2).await.unwrap().unwrap();
+    assert_eq!(coils, [false, true]);
+
+    // Each tick refreshes the image the next read sees
+    let mut idle = idle;
+    idle.step(40.0);
+    server.update(&[&idle, &watering]);
+    assert_eq!(client.read_holding_registers(0, 1).await.unwrap().unwrap(), [4000]);
+}
+
+#[tokio::test]
+async fn reads_past_the_last_zone_are_rejected() {
+    let server = ModbusTcpServer::start(0).await.unwrap();
+    server.update(&[&SoilMoistureSensor::new(30.0, 50.0)]);
+    let addr = SocketAddr::from(([127, 0, 0, 1], server.local_addr().port()));
+    let mut client = tcp::connect(addr).await.unwrap();
+    let exception = client.read_holding_registers(0, 2).await.unwrap().unwrap_err();
+    assert_eq!(exception, ExceptionCode::IllegalDataAddress);
+    let exception = client.read_coils(1, 1).await.unwrap().unwrap_err();
+    assert_eq!(exception, ExceptionCode::IllegalDataAddress);
+}
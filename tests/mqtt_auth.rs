@@ -0,0 +1,105 @@
+//! `MqttPublisher` against a mock broker that only accepts one username and password.
+
+use agri_iot_simulator::api::mqtt::MqttPublisher;
+use agri_iot_simulator::config::{MqttAuthConfig, MqttConfig};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+const USERNAME: &str = "agri";
+const PASSWORD: &str = "s3cret";
+
+/// CONNACK return codes of MQTT 3.1.1
+const ACCEPTED: u8 = 0;
+const NOT_AUTHORIZED: u8 = 5;
+
+/// Reads an MQTT length-prefixed string at `*at` and moves past it
+fn read_string(bytes: &[u8], at: &mut usize) -> String {
+    let len = u16::from_be_bytes([bytes[*at], bytes[*at + 1]]) as usize;
+    let value = String::from_utf8(bytes[*at + 2..*at + 2 + len].to_vec()).unwrap();
+    *at += 2 + len;
+    value
+}
+
+/// Reads one CONNECT packet and returns the username and password it carries, if any
+async fn read_connect(socket: &mut TcpStream) -> (Option<String>, Option<String>) {
+    assert_eq!(socket.read_u8().await.unwrap(), 0x10, "first packet is CONNECT");
+    let (mut remaining, mut shift) = (0usize, 0);
+    loop {
+        let byte = socket.read_u8().await.unwrap();
+        remaining |= ((byte & 0x7f) as usize) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+    let mut bytes = vec![0; remaining];
+    socket.read_exact(&mut bytes).await.unwrap();
+
+    let mut at = 0;
+    assert_eq!(read_string(&bytes, &mut at), "MQTT");
+    let flags = bytes[at + 1];
+    at += 4; // Protocol level, connect flags and keep alive
+    read_string(&bytes, &mut at); // Client id
+    if flags & 0x04 != 0 {
+        read_string(&bytes, &mut at); // Will topic
+        read_string(&bytes, &mut at); // Will message
+    }
+    let username = (flags & 0x80 != 0).then(|| read_string(&bytes, &mut at));
+    let password = (flags & 0x40 != 0).then(|| read_string(&bytes, &mut at));
+    (username, password)
+}
+
+/// Accepts one client, answers its CONNECT and reports whether it was let in
+async fn mock_broker() -> (u16, oneshot::Receiver<bool>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (accepted_tx, accepted_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let (username, password) = read_connect(&mut socket).await;
+        let accepted = username.as_deref() == Some(USERNAME) && password.as_deref() == Some(PASSWORD);
+        let code = if accepted { ACCEPTED } else { NOT_AUTHORIZED };
+        socket.write_all(&[0x20, 0x02, 0x00, code]).await.unwrap();
+        accepted_tx.send(accepted).unwrap();
+        // Hold the connection open until the client goes away
+        let _ = tokio::io::copy(&mut socket, &mut tokio::io::sink()).await;
+    });
+    (port, accepted_rx)
+}
+
+/// Connects with `auth` and returns whether the broker accepted and the publisher reports it connected
+async fn connect(auth: Option<MqttAuthConfig>) -> (bool, bool) {
+    let (port, accepted) = mock_broker().await;
+    let config = MqttConfig { host: "127.0.0.1".to_string(), port, auth, ..MqttConfig::default() };
+    let publisher = MqttPublisher::connect(&config).unwrap();
+    let accepted = tokio::time::timeout(Duration::from_secs(5), accepted).await.unwrap().unwrap();
+    // Give the event loop time to handle the CONNACK
+    for _ in 0..50 {
+        if publisher.is_connected() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    (accepted, publisher.is_connected())
+}
+
+fn credentials(username: &str, password: &str) -> Option<MqttAuthConfig> {
+    Some(MqttAuthConfig { username: username.to_string(), password: password.to_string() })
+}
+
+#[tokio::test]
+async fn valid_credentials_are_accepted() {
+    assert_eq!(connect(credentials(USERNAME, PASSWORD)).await, (true, true));
+}
+
+#[tokio::test]
+async fn connection_without_credentials_is_rejected() {
+    assert_eq!(connect(None).await, (false, false));
+}
+
+#[tokio::test]
+async fn wrong_password_is_rejected() {
+    assert_eq!(connect(credentials(USERNAME, "guess")).await, (false, false));
+}
@@ -0,0 +1,75 @@
+//! Reads the moisture node of a running `OpcUaServer` through an opcua client session.
+
+use agri_iot_simulator::api::opcua::OpcUaServer;
+use agri_iot_simulator::SoilMoistureSensor;
+use opcua::client::prelude::*;
+use opcua::sync::RwLock;
+use std::sync::Arc;
+use std::time::Duration;
+
+// OPC-UA endpoints carry their port, so the server cannot take any free one
+const PORT: u16 = 48_401;
+
+const NAMESPACE_URI: &str = "urn:agri-iot-simulator";
+
+/// Opens an anonymous, unsecured session, retrying while the server starts up
+fn connect() -> Arc<RwLock<Session>> {
+    let mut client = ClientBuilder::new()
+        .application_name("Agri-IoT test client")
+        .application_uri("urn:agri-iot-test-client")
+        .pki_dir(std::env::temp_dir().join("agri_iot_simulator-test-client-pki"))
+        .create_sample_keypair(true)
+        .trust_server_certs(true)
+        .session_retry_limit(0)
+        .client()
+        .unwrap();
+    let url = format!("opc.tcp://127.0.0.1:{PORT}/");
+    let endpoint: EndpointDescription =
+        (url.as_str(), SecurityPolicy::None.to_str(), MessageSecurityMode::None, UserTokenPolicy::anonymous()).into();
+    for _ in 0..50 {
+        if let Ok(session) = client.connect_to_endpoint(endpoint.clone(), IdentityToken::Anonymous) {
+            return session;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    panic!("cannot connect to the OPC-UA server at {url}");
+}
+
+fn read(session: &Session, node: NodeId) -> Variant {
+    let results = session.read(&[ReadValueId::from(node)], TimestampsToReturn::Neither, 0.0).unwrap();
+    results[0].value.clone().expect("node has a value")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn client_reads_the_moisture_node() {
+    let server = OpcUaServer::start(PORT, 2).unwrap();
+    let other = SoilMoistureSensor::new(30.0, 60.0);
+    let mut sensor = SoilMoistureSensor::new(30.0, 42.5);
+    server.update(&[&other, &sensor]);
+
+    // The opcua client runs and drops its own runtime, so it must stay off this test's threads
+    let client = std::thread::spawn(move || {
+        let session = connect();
+        let session = session.read();
+        let Variant::Array(namespaces) = read(&session, VariableId::Server_NamespaceArray.into()) else {
+            panic!("NamespaceArray is an array");
+        };
+        let namespace = namespaces.values.iter().position(|uri| *uri == Variant::from(NAMESPACE_URI));
+        let namespace = namespace.expect("simulator namespace is registered") as u16;
+        let moisture = NodeId::new(namespace, "Zone1.Moisture");
+        let state = NodeId::new(namespace, "Zone1.State");
+        let pump = NodeId::new(namespace, "Zone1.WaterPumpRunning");
+        assert_eq!(read(&session, moisture.clone()), Variant::Float(42.5));
+        assert_eq!(read(&session, state.clone()), Variant::from("Monitoring"));
+        assert_eq!(read(&session, pump.clone()), Variant::Boolean(false));
+
+        // The next tick's readings replace the node values
+        sensor.step(12.5);
+        server.update(&[&other, &sensor]);
+        assert_eq!(read(&session, moisture), Variant::Float(12.5));
+        assert_eq!(read(&session, state), Variant::from("Activating"));
+        assert_eq!(read(&session, pump), Variant::Boolean(true));
+        session.disconnect();
+    });
+    tokio::task::spawn_blocking(move || client.join()).await.unwrap().unwrap();
+}